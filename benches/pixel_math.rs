@@ -0,0 +1,140 @@
+//! Compare floating-point and fixed-point per-pixel math.
+//!
+//! Run with `cargo bench --bench pixel_math`. Each benchmark processes a
+//! 96×96 RGBA tile, the largest common key size.
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use image::{Rgba, RgbaImage};
+use verandah_plugin_utils::image::pixel_math::{
+    blend_f32, blend_fixed, blend_linear_f32, blend_linear_fixed, greyscale_f32, greyscale_fixed,
+    scale_linear_f32, scale_linear_fixed, to_q8,
+};
+
+fn tile() -> RgbaImage {
+    RgbaImage::from_fn(96, 96, |x, y| {
+        Rgba([(x * 2) as u8, (y * 2) as u8, ((x + y) % 256) as u8, 255])
+    })
+}
+
+fn bench_brightness(c: &mut Criterion) {
+    let src = tile();
+    let mut group = c.benchmark_group("brightness");
+    group.bench_function("f32", |b| {
+        b.iter(|| {
+            let mut img = src.clone();
+            let factor = black_box(0.55);
+            for p in img.pixels_mut() {
+                p[0] = scale_linear_f32(p[0], factor);
+                p[1] = scale_linear_f32(p[1], factor);
+                p[2] = scale_linear_f32(p[2], factor);
+            }
+            img
+        })
+    });
+    group.bench_function("fixed", |b| {
+        b.iter(|| {
+            let mut img = src.clone();
+            let q8 = to_q8(black_box(0.55));
+            for p in img.pixels_mut() {
+                p[0] = scale_linear_fixed(p[0], q8);
+                p[1] = scale_linear_fixed(p[1], q8);
+                p[2] = scale_linear_fixed(p[2], q8);
+            }
+            img
+        })
+    });
+    group.finish();
+}
+
+fn bench_greyscale(c: &mut Criterion) {
+    let src = tile();
+    let mut group = c.benchmark_group("greyscale");
+    group.bench_function("f32", |b| {
+        b.iter(|| {
+            src.pixels()
+                .map(|p| greyscale_f32(p[0], p[1], p[2]) as u32)
+                .sum::<u32>()
+        })
+    });
+    group.bench_function("fixed", |b| {
+        b.iter(|| {
+            src.pixels()
+                .map(|p| greyscale_fixed(p[0], p[1], p[2]) as u32)
+                .sum::<u32>()
+        })
+    });
+    group.finish();
+}
+
+fn bench_blend(c: &mut Criterion) {
+    let a = tile();
+    let other = RgbaImage::from_pixel(96, 96, Rgba([30, 60, 90, 255]));
+    let mut group = c.benchmark_group("blend");
+    group.bench_function("f32", |b| {
+        b.iter(|| {
+            let t = black_box(0.3);
+            let mut out = a.clone();
+            for (o, p) in out.pixels_mut().zip(other.pixels()) {
+                for i in 0..4 {
+                    o[i] = blend_f32(o[i], p[i], t);
+                }
+            }
+            out
+        })
+    });
+    group.bench_function("fixed", |b| {
+        b.iter(|| {
+            let q8 = to_q8(black_box(0.3));
+            let mut out = a.clone();
+            for (o, p) in out.pixels_mut().zip(other.pixels()) {
+                for i in 0..4 {
+                    o[i] = blend_fixed(o[i], p[i], q8);
+                }
+            }
+            out
+        })
+    });
+    group.finish();
+}
+
+fn bench_blend_linear(c: &mut Criterion) {
+    let a = tile();
+    let other = RgbaImage::from_pixel(96, 96, Rgba([30, 60, 90, 255]));
+    let mut group = c.benchmark_group("blend_linear");
+    group.bench_function("f32", |b| {
+        b.iter(|| {
+            let t = black_box(0.3);
+            let mut out = a.clone();
+            for (o, p) in out.pixels_mut().zip(other.pixels()) {
+                for i in 0..3 {
+                    o[i] = blend_linear_f32(o[i], p[i], t);
+                }
+            }
+            out
+        })
+    });
+    group.bench_function("fixed", |b| {
+        b.iter(|| {
+            let q8 = to_q8(black_box(0.3));
+            let mut out = a.clone();
+            for (o, p) in out.pixels_mut().zip(other.pixels()) {
+                for i in 0..3 {
+                    o[i] = blend_linear_fixed(o[i], p[i], q8);
+                }
+            }
+            out
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_brightness,
+    bench_greyscale,
+    bench_blend,
+    bench_blend_linear
+);
+criterion_main!(benches);
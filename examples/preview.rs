@@ -0,0 +1,43 @@
+//! Render sample widgets to PNG files for quick visual iteration.
+//!
+//! ```sh
+//! cargo run --example preview -- /tmp/preview
+//! ```
+
+use std::path::PathBuf;
+
+use verandah_plugin_utils::prelude::*;
+use verandah_plugin_utils::preview::render_to_png;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = std::env::args_os()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."));
+    std::fs::create_dir_all(&dir)?;
+    let theme = Theme::default();
+
+    let spec = Spec::column()
+        .with(Element::text("CPU"))
+        .with_weighted(
+            Element::Stack(
+                Spec::row()
+                    .with_padding(0)
+                    .with(Element::ring(0.65))
+                    .with(Element::sparkline([1.0, 3.0, 2.0, 5.0, 4.0, 6.0])),
+            ),
+            2.0,
+        )
+        .with(Element::bar(0.4));
+    render_to_png(&spec, (96, 96), &theme, dir.join("spec.png"))?;
+
+    render_to_png(
+        |img: &mut RgbaImage, theme: &Theme| draw_centered_text(img, "42", theme.accent, 0.15),
+        (72, 72),
+        &theme,
+        dir.join("text.png"),
+    )?;
+
+    println!("wrote previews to {}", dir.display());
+    Ok(())
+}
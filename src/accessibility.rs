@@ -0,0 +1,77 @@
+//! Crate-wide accessibility settings.
+//!
+//! High-contrast mode is a process-wide flag, typically set once from the
+//! plugin's configuration at startup. While enabled:
+//!
+//! - [`Theme::effective`](crate::colors::Theme::effective) raises the
+//!   contrast of theme colors against the background to
+//!   [`HIGH_CONTRAST_RATIO`], and [`render`](crate::widget::render) uses it
+//! - text drawn with [`draw_centered_text`](crate::text::draw_centered_text)
+//!   and friends gets automatic legibility treatments targeting the same
+//!   ratio
+//! - strokes in widget specs are drawn thicker
+//! - decorations whose alpha is below [`MIN_DECORATION_ALPHA`] (faint
+//!   shadows, translucent backings) are dropped in favour of solid ones
+//!
+//! Reduced-motion mode is a separate flag for users who disable animations
+//! system-wide. While enabled, the animation helpers settle instantly:
+//!
+//! - tweens, odometers and flip digits jump straight to their new value,
+//!   and text reveals show the whole text at once
+//! - carousel transitions become cuts and shakes are suppressed
+//! - pulses hold at full brightness, skeleton shimmer and indeterminate
+//!   progress bars are drawn static, and particle bursts are not spawned
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static HIGH_CONTRAST: AtomicBool = AtomicBool::new(false);
+static REDUCED_MOTION: AtomicBool = AtomicBool::new(false);
+
+/// Contrast ratio enforced in high-contrast mode (WCAG AAA for normal text).
+pub const HIGH_CONTRAST_RATIO: f32 = 7.0;
+
+/// Contrast ratio enforced for non-text elements such as tracks and
+/// borders in high-contrast mode (WCAG 1.4.11).
+pub const HIGH_CONTRAST_UI_RATIO: f32 = 3.0;
+
+/// Decorations less opaque than this are dropped in high-contrast mode.
+pub const MIN_DECORATION_ALPHA: u8 = 128;
+
+/// Enable or disable high-contrast mode for the whole process.
+pub fn set_high_contrast(enabled: bool) {
+    HIGH_CONTRAST.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether high-contrast mode is enabled.
+pub fn high_contrast() -> bool {
+    HIGH_CONTRAST.load(Ordering::Relaxed)
+}
+
+/// Stroke width to draw for a nominal `width`: doubled in high-contrast mode.
+pub fn stroke_width(width: u32) -> u32 {
+    if high_contrast() { width * 2 } else { width }
+}
+
+/// Whether a decoration with the given alpha should be drawn.
+pub fn show_decoration(alpha: u8) -> bool {
+    !high_contrast() || alpha >= MIN_DECORATION_ALPHA
+}
+
+/// Enable or disable reduced-motion mode for the whole process.
+pub fn set_reduced_motion(enabled: bool) {
+    REDUCED_MOTION.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether reduced-motion mode is enabled.
+pub fn reduced_motion() -> bool {
+    REDUCED_MOTION.load(Ordering::Relaxed)
+}
+
+/// Transition progress to draw for a nominal `t` from 0.0 to 1.0.
+///
+/// Unchanged normally; in reduced-motion mode any transition that has
+/// started is shown complete, so changes happen in a single step. Use this
+/// in custom animations to honor the setting.
+pub fn motion_progress(t: f32) -> f32 {
+    if reduced_motion() && t > 0.0 { 1.0 } else { t }
+}
@@ -0,0 +1,962 @@
+//! Animation utilities.
+//!
+//! Provides a monotonic animation clock and time-driven helpers built on it.
+//! Helpers take an elapsed [`Duration`] so they can be driven by a [`Clock`]
+//! in plugins and by fixed values in tests.
+
+use std::time::{Duration, Instant};
+
+use image::{Rgba, RgbaImage};
+use imageproc::drawing::draw_filled_rect_mut;
+use imageproc::rect::Rect;
+
+use crate::accessibility::{motion_progress, reduced_motion};
+use crate::image::FramePool;
+use crate::image::pixel_math::Factor;
+use crate::locale::locale;
+use crate::text::{GlyphCache, draw_centered_text};
+
+mod particles;
+
+pub use particles::{Particle, Particles, Preset};
+
+/// Monotonic animation clock.
+///
+/// Measures time elapsed since the clock was created (or last reset).
+#[derive(Debug, Clone, Copy)]
+pub struct Clock {
+    start: Instant,
+}
+
+impl Clock {
+    /// Create a clock starting now.
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+
+    /// Restart the clock from zero.
+    pub fn reset(&mut self) {
+        self.start = Instant::now();
+    }
+
+    /// Time elapsed since the clock started.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Position within a repeating cycle of length `period`, from 0.0 to 1.0.
+    ///
+    /// Returns 0.0 for a zero period.
+    pub fn phase(&self, period: Duration) -> f32 {
+        phase(self.elapsed(), period)
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Position of `elapsed` within a repeating cycle of length `period`, from 0.0 to 1.0.
+///
+/// Returns 0.0 for a zero period.
+pub fn phase(elapsed: Duration, period: Duration) -> f32 {
+    if period.is_zero() {
+        return 0.0;
+    }
+    let period_ns = period.as_nanos();
+    (elapsed.as_nanos() % period_ns) as f32 / period_ns as f32
+}
+
+/// Easing curve mapping linear progress (0.0 to 1.0) to eased progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Start slow, finish fast (quadratic).
+    EaseIn,
+    /// Start fast, finish slow (quadratic).
+    EaseOut,
+    /// Slow at both ends (smoothstep).
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    /// Apply the curve to `t`, which is clamped to 0.0 to 1.0.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Value tween
+// ----------------------------------------------------------------------------
+
+/// Smoothly animates a displayed number towards a changing target.
+///
+/// Call [`set`](Self::set) when a new value arrives and
+/// [`value`](Self::value) each frame; the displayed value rolls from where
+/// it currently is to the new target over the tween duration. Times are
+/// elapsed durations from the plugin's [`Clock`]. In [reduced-motion
+/// mode](crate::accessibility::set_reduced_motion) the value jumps straight
+/// to the target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValueTween {
+    from: f64,
+    to: f64,
+    start: Duration,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl ValueTween {
+    /// Create a tween resting at `initial`, animating changes over `duration`.
+    pub fn new(initial: f64, duration: Duration) -> Self {
+        Self {
+            from: initial,
+            to: initial,
+            start: Duration::ZERO,
+            duration,
+            easing: Easing::default(),
+        }
+    }
+
+    /// Set the easing curve.
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Start animating towards `target` from the value displayed at `now`.
+    pub fn set(&mut self, target: f64, now: Duration) {
+        self.from = self.value(now);
+        self.to = target;
+        self.start = now;
+    }
+
+    /// Final value the tween is heading towards.
+    pub fn target(&self) -> f64 {
+        self.to
+    }
+
+    /// Value to display at `now`.
+    pub fn value(&self, now: Duration) -> f64 {
+        let t = self.progress(now);
+        self.from + (self.to - self.from) * self.easing.apply(t) as f64
+    }
+
+    /// Whether the tween has reached its target at `now`.
+    pub fn is_settled(&self, now: Duration) -> bool {
+        self.progress(now) >= 1.0
+    }
+
+    /// Draw the value at `now` centered on `rgba`, with `decimals` places
+    /// and the current [`locale`]'s separators.
+    pub fn render(
+        &self,
+        rgba: &mut RgbaImage,
+        now: Duration,
+        decimals: usize,
+        fg_color: Rgba<u8>,
+        padding: f32,
+    ) {
+        let text = locale().format_number(self.value(now), decimals);
+        draw_centered_text(rgba, &text, fg_color, padding);
+    }
+
+    fn progress(&self, now: Duration) -> f32 {
+        if self.duration.is_zero() || reduced_motion() {
+            return 1.0;
+        }
+        let since = now.saturating_sub(self.start);
+        (since.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Odometer
+// ----------------------------------------------------------------------------
+
+/// A single line of text whose changed characters roll vertically to their
+/// new value, like an odometer or split-flap clock.
+///
+/// Glyphs come from a [`GlyphCache`], so each frame only blits cached
+/// masks. Strings are right-aligned, so a counter growing from "99" to
+/// "100" rolls its digits in place and a new leading digit rolls in. In
+/// [reduced-motion mode](crate::accessibility::set_reduced_motion) changes
+/// are shown without rolling.
+#[derive(Debug, Clone)]
+pub struct Odometer {
+    x: i32,
+    y: i32,
+    fg: Rgba<u8>,
+    bg: Rgba<u8>,
+    glyphs: Option<GlyphCache>,
+    from: Vec<char>,
+    to: Vec<char>,
+    start: Duration,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl Odometer {
+    /// Create an odometer with its top-left corner at (`x`, `y`).
+    ///
+    /// Each change rolls over `duration`. Cells are cleared to `bg` before
+    /// glyphs are drawn in `fg`.
+    pub fn new(x: i32, y: i32, scale: f32, fg: Rgba<u8>, bg: Rgba<u8>, duration: Duration) -> Self {
+        Self {
+            x,
+            y,
+            fg,
+            bg,
+            glyphs: GlyphCache::new(scale),
+            from: Vec::new(),
+            to: Vec::new(),
+            start: Duration::ZERO,
+            duration,
+            easing: Easing::EaseOut,
+        }
+    }
+
+    /// Set the easing curve (defaults to [`Easing::EaseOut`]).
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Start rolling to `text` at `now`.
+    ///
+    /// A change arriving mid-roll snaps the previous roll to its end first.
+    pub fn set(&mut self, text: &str, now: Duration) {
+        let to: Vec<char> = text.chars().collect();
+        if to == self.to {
+            return;
+        }
+        self.from = std::mem::replace(&mut self.to, to);
+        self.start = now;
+    }
+
+    /// Whether a roll is still in progress at `now`.
+    pub fn is_rolling(&self, now: Duration) -> bool {
+        self.progress(now) < 1.0 && self.from != self.to
+    }
+
+    /// Draw the line as it appears at `now`.
+    ///
+    /// Returns the rectangle covered by the line, or None if nothing was
+    /// drawn (empty text or no system font).
+    pub fn render(&mut self, rgba: &mut RgbaImage, now: Duration) -> Option<Rect> {
+        let t = self.easing.apply(self.progress(now));
+        let glyphs = self.glyphs.as_mut()?;
+        let (cell_w, cell_h) = glyphs.cell_size();
+        let n = self.from.len().max(self.to.len());
+        if n == 0 {
+            return None;
+        }
+        // Right-align so place values line up
+        let at = |s: &[char], i: usize| (i + s.len()).checked_sub(n).map_or(' ', |j| s[j]);
+        let roll = (t * cell_h as f32).round() as i32;
+
+        let line = Rect::at(self.x, self.y).of_size(n as u32 * cell_w, cell_h);
+        draw_filled_rect_mut(rgba, line, self.bg);
+        for i in 0..n {
+            let cx = self.x + (i as u32 * cell_w) as i32;
+            let cell = Rect::at(cx, self.y).of_size(cell_w, cell_h);
+            let (old, new) = (at(&self.from, i), at(&self.to, i));
+            if old == new || roll >= cell_h as i32 {
+                glyphs.draw_clipped(rgba, new, cx, self.y, self.fg, cell);
+            } else {
+                // Old glyph scrolls up and out while the new one enters from below
+                glyphs.draw_clipped(rgba, old, cx, self.y - roll, self.fg, cell);
+                let y = self.y + cell_h as i32 - roll;
+                glyphs.draw_clipped(rgba, new, cx, y, self.fg, cell);
+            }
+        }
+        Some(line)
+    }
+
+    fn progress(&self, now: Duration) -> f32 {
+        if self.duration.is_zero() || reduced_motion() {
+            return 1.0;
+        }
+        let since = now.saturating_sub(self.start);
+        (since.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Flip digit
+// ----------------------------------------------------------------------------
+
+/// Colors and proportions for [`draw_flip_digit`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlipStyle {
+    /// Card face color.
+    pub card: Rgba<u8>,
+    /// Glyph color.
+    pub fg: Rgba<u8>,
+    /// Color of the split line across the middle of the card.
+    pub divider: Rgba<u8>,
+    /// Color of the highlight along the moving flap's edge.
+    pub highlight: Rgba<u8>,
+    /// How much the flap darkens as it turns edge-on, from 0.0 to 1.0.
+    pub shade: f32,
+    /// Glyph padding as a fraction of the card size.
+    pub padding: f32,
+}
+
+impl Default for FlipStyle {
+    fn default() -> Self {
+        Self {
+            card: Rgba([0x22, 0x22, 0x22, 0xFF]),
+            fg: Rgba([0xEE, 0xEE, 0xEE, 0xFF]),
+            divider: Rgba([0x00, 0x00, 0x00, 0xFF]),
+            highlight: Rgba([0x66, 0x66, 0x66, 0xFF]),
+            shade: 0.6,
+            padding: 0.15,
+        }
+    }
+}
+
+/// Draw one frame of a split-flap card flipping from `from` to `to`.
+///
+/// `t` runs from 0.0 (showing `from`) to 1.0 (showing `to`). During the
+/// first half the top flap of the old card folds down over the hinge; during
+/// the second half the bottom flap of the new card falls into place. The
+/// moving flap is shaded as it turns edge-on and its edge is highlighted.
+/// Drawing is clipped to the image bounds. In [reduced-motion
+/// mode](crate::accessibility::set_reduced_motion) the card switches to
+/// `to` as soon as `t` passes 0.0.
+pub fn draw_flip_digit(
+    rgba: &mut RgbaImage,
+    rect: Rect,
+    from: char,
+    to: char,
+    t: f32,
+    style: &FlipStyle,
+) {
+    draw_flip_digit_pooled(
+        rgba,
+        rect,
+        from,
+        to,
+        t,
+        style,
+        &mut FramePool::with_capacity(0),
+    );
+}
+
+/// Like [`draw_flip_digit`], but takes the card images it composes each
+/// frame from `pool`, so an animating flip does not allocate.
+pub fn draw_flip_digit_pooled(
+    rgba: &mut RgbaImage,
+    rect: Rect,
+    from: char,
+    to: char,
+    t: f32,
+    style: &FlipStyle,
+    pool: &mut FramePool,
+) {
+    let (w, h) = (rect.width(), rect.height());
+    if h < 2 {
+        return;
+    }
+    let t = motion_progress(t.clamp(0.0, 1.0));
+    let old = flip_card(from, w, h, style, pool);
+    let new = flip_card(to, w, h, style, pool);
+    let hinge = h / 2;
+
+    // Static halves: the new card's top is revealed behind the falling flap,
+    // the old card's bottom is covered by the rising one
+    let mut out = pool.take_uncleared(w, h);
+    out.copy_from_slice(if t > 0.0 { &new } else { &old });
+    if t > 0.0 && t < 1.0 {
+        for y in hinge..h {
+            for x in 0..w {
+                out.put_pixel(x, y, *old.get_pixel(x, y));
+            }
+        }
+
+        let squash = (t * std::f32::consts::PI).cos().abs();
+        let dim = Factor::new(1.0 - style.shade.clamp(0.0, 1.0) * (1.0 - squash));
+        let (card, rows, edge) = if t < 0.5 {
+            let top = hinge - (hinge as f32 * squash).round() as u32;
+            (&old, top..hinge, top)
+        } else {
+            let bottom = hinge + ((h - hinge) as f32 * squash).round() as u32;
+            (&new, hinge..bottom, bottom.saturating_sub(1))
+        };
+        for y in rows {
+            // Map the squashed row back to the unsquashed flap half
+            let sy = if y < hinge {
+                hinge - ((hinge - y) as f32 / squash).round().min(hinge as f32) as u32
+            } else {
+                hinge
+                    + ((y - hinge) as f32 / squash)
+                        .round()
+                        .min((h - 1 - hinge) as f32) as u32
+            };
+            for x in 0..w {
+                let p = card.get_pixel(x, sy);
+                out.put_pixel(
+                    x,
+                    y,
+                    Rgba([dim.scale(p[0]), dim.scale(p[1]), dim.scale(p[2]), p[3]]),
+                );
+            }
+        }
+        for x in 0..w {
+            out.put_pixel(x, edge, style.highlight);
+        }
+    }
+
+    for x in 0..w {
+        out.put_pixel(x, hinge, style.divider);
+    }
+
+    for (x, y, p) in out.enumerate_pixels() {
+        let px = rect.left() + x as i32;
+        let py = rect.top() + y as i32;
+        if px >= 0 && py >= 0 && (px as u32) < rgba.width() && (py as u32) < rgba.height() {
+            rgba.put_pixel(px as u32, py as u32, *p);
+        }
+    }
+
+    pool.give(old);
+    pool.give(new);
+    pool.give(out);
+}
+
+/// Render a full flip card showing `c` into a buffer from `pool`.
+fn flip_card(
+    c: char,
+    width: u32,
+    height: u32,
+    style: &FlipStyle,
+    pool: &mut FramePool,
+) -> RgbaImage {
+    let mut card = pool.take_uncleared(width, height);
+    for p in card.pixels_mut() {
+        *p = style.card;
+    }
+    let mut buf = [0u8; 4];
+    draw_centered_text(&mut card, c.encode_utf8(&mut buf), style.fg, style.padding);
+    card
+}
+
+// ----------------------------------------------------------------------------
+// Shake
+// ----------------------------------------------------------------------------
+
+/// Number of full oscillations over a shake.
+const SHAKE_CYCLES: f32 = 4.0;
+
+/// Horizontal offset in pixels for a decaying shake at progress `t`.
+///
+/// `t` runs from 0.0 to 1.0 over the shake; the offset oscillates with an
+/// amplitude of `intensity` pixels that decays to zero at the end. Returns
+/// 0.0 outside that range, and always in [reduced-motion
+/// mode](crate::accessibility::set_reduced_motion).
+pub fn shake(t: f32, intensity: f32) -> f32 {
+    if !(0.0..1.0).contains(&t) || reduced_motion() {
+        return 0.0;
+    }
+    let decay = (1.0 - t) * (1.0 - t);
+    intensity * decay * (t * SHAKE_CYCLES * std::f32::consts::TAU).sin()
+}
+
+/// Shift a layer horizontally by the [`shake`] offset at progress `t`.
+///
+/// Pixels uncovered by the shift are set to `fill`. Used to signal errors
+/// or rejected actions on button tiles.
+pub fn apply_shake(rgba: &mut RgbaImage, t: f32, intensity: f32, fill: Rgba<u8>) {
+    let offset = shake(t, intensity).round() as i64;
+    if offset == 0 {
+        return;
+    }
+    let width = rgba.width() as i64;
+    for y in 0..rgba.height() {
+        let row: Vec<Rgba<u8>> = (0..width).map(|x| *rgba.get_pixel(x as u32, y)).collect();
+        for x in 0..width {
+            let sx = x - offset;
+            let p = if (0..width).contains(&sx) {
+                row[sx as usize]
+            } else {
+                fill
+            };
+            rgba.put_pixel(x as u32, y, p);
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Carousel
+// ----------------------------------------------------------------------------
+
+/// A single carousel entry.
+pub enum Slide {
+    /// A pre-rendered image, resized to the output if dimensions differ.
+    Image(RgbaImage),
+    /// A closure that renders into the output image each frame.
+    Render(Box<dyn Fn(&mut RgbaImage)>),
+}
+
+impl Slide {
+    /// Create a slide from a render closure.
+    pub fn render<F>(f: F) -> Self
+    where
+        F: Fn(&mut RgbaImage) + 'static,
+    {
+        Slide::Render(Box::new(f))
+    }
+
+    /// Draw the slide into `out`, which must already have the target size.
+    fn draw_into(&self, out: &mut RgbaImage) {
+        let (width, height) = out.dimensions();
+        match self {
+            Slide::Image(img) if img.dimensions() == (width, height) => {
+                out.copy_from_slice(img.as_raw());
+            }
+            Slide::Image(img) => {
+                *out = image::imageops::resize(
+                    img,
+                    width,
+                    height,
+                    image::imageops::FilterType::Lanczos3,
+                );
+            }
+            Slide::Render(f) => {
+                out.fill(0);
+                f(out);
+            }
+        }
+    }
+}
+
+/// Transition effect between carousel slides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Transition {
+    /// Switch instantly.
+    Cut,
+    /// Blend from one slide to the next.
+    #[default]
+    Crossfade,
+    /// Push the current slide out to the left.
+    SlideLeft,
+    /// Push the current slide out to the top.
+    SlideUp,
+}
+
+/// Cycles through a list of slides with a dwell time and transition effect.
+///
+/// Each slide is shown for `dwell`, followed by a transition of
+/// `transition_duration` into the next slide. The cycle wraps around.
+pub struct Carousel {
+    slides: Vec<Slide>,
+    dwell: Duration,
+    transition: Transition,
+    transition_duration: Duration,
+}
+
+impl Carousel {
+    /// Create a carousel showing each slide for `dwell`, with a 500ms crossfade.
+    pub fn new(slides: Vec<Slide>, dwell: Duration) -> Self {
+        Self {
+            slides,
+            dwell,
+            transition: Transition::default(),
+            transition_duration: Duration::from_millis(500),
+        }
+    }
+
+    /// Set the transition effect and its duration.
+    pub fn with_transition(mut self, transition: Transition, duration: Duration) -> Self {
+        self.transition = transition;
+        self.transition_duration = duration;
+        self
+    }
+
+    /// Number of slides.
+    pub fn len(&self) -> usize {
+        self.slides.len()
+    }
+
+    /// Whether the carousel has no slides.
+    pub fn is_empty(&self) -> bool {
+        self.slides.is_empty()
+    }
+
+    /// Work out which slide is showing at `elapsed`.
+    ///
+    /// Returns the current slide index and, during a transition, the
+    /// transition progress from 0.0 to 1.0. In [reduced-motion
+    /// mode](crate::accessibility::set_reduced_motion) every transition is
+    /// treated as a [`Transition::Cut`].
+    pub fn position(&self, elapsed: Duration) -> (usize, Option<f32>) {
+        let n = self.slides.len();
+        if n <= 1 {
+            return (0, None);
+        }
+        let transition = match self.transition {
+            _ if reduced_motion() => Duration::ZERO,
+            Transition::Cut => Duration::ZERO,
+            _ => self.transition_duration,
+        };
+        let slot = self.dwell + transition;
+        if slot.is_zero() {
+            return (0, None);
+        }
+        let slot_ns = slot.as_nanos();
+        let index = ((elapsed.as_nanos() / slot_ns) % n as u128) as usize;
+        let within = Duration::from_nanos((elapsed.as_nanos() % slot_ns) as u64);
+        if within < self.dwell {
+            (index, None)
+        } else {
+            let t = (within - self.dwell).as_secs_f32() / transition.as_secs_f32();
+            (index, Some(t.clamp(0.0, 1.0)))
+        }
+    }
+
+    /// Render the carousel frame for `elapsed` into `rgba`.
+    pub fn render(&self, rgba: &mut RgbaImage, elapsed: Duration) {
+        self.render_pooled(rgba, elapsed, &mut FramePool::with_capacity(0));
+    }
+
+    /// Render the carousel frame for `elapsed`, taking temporary buffers from `pool`.
+    ///
+    /// Reusing one pool across frames avoids per-frame allocations during
+    /// transitions.
+    pub fn render_pooled(&self, rgba: &mut RgbaImage, elapsed: Duration, pool: &mut FramePool) {
+        if self.slides.is_empty() {
+            return;
+        }
+        let (width, height) = rgba.dimensions();
+        let (index, progress) = self.position(elapsed);
+        let cut = self.transition == Transition::Cut || reduced_motion();
+        let Some(t) = progress.filter(|_| !cut) else {
+            self.slides[index].draw_into(rgba);
+            return;
+        };
+
+        let mut current = pool.take_uncleared(width, height);
+        let mut next = pool.take_uncleared(width, height);
+        self.slides[index].draw_into(&mut current);
+        self.slides[(index + 1) % self.slides.len()].draw_into(&mut next);
+
+        match self.transition {
+            Transition::Cut => {}
+            Transition::Crossfade => {
+                let t = Factor::new(t);
+                for ((out, a), b) in rgba.pixels_mut().zip(current.pixels()).zip(next.pixels()) {
+                    *out = Rgba(std::array::from_fn(|c| t.blend(a[c], b[c])));
+                }
+            }
+            Transition::SlideLeft => {
+                let offset = (t * width as f32).round() as u32;
+                for (x, y, out) in rgba.enumerate_pixels_mut() {
+                    let sx = x + offset;
+                    *out = if sx < width {
+                        *current.get_pixel(sx, y)
+                    } else {
+                        *next.get_pixel(sx - width, y)
+                    };
+                }
+            }
+            Transition::SlideUp => {
+                let offset = (t * height as f32).round() as u32;
+                for (x, y, out) in rgba.enumerate_pixels_mut() {
+                    let sy = y + offset;
+                    *out = if sy < height {
+                        *current.get_pixel(x, sy)
+                    } else {
+                        *next.get_pixel(x, sy - height)
+                    };
+                }
+            }
+        }
+
+        pool.give(current);
+        pool.give(next);
+    }
+
+    /// Render the carousel frame for the current time on `clock`.
+    pub fn render_at(&self, rgba: &mut RgbaImage, clock: &Clock) {
+        self.render(rgba, clock.elapsed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(color: Rgba<u8>) -> Slide {
+        Slide::Image(RgbaImage::from_pixel(4, 4, color))
+    }
+
+    fn two_slides() -> Carousel {
+        Carousel::new(
+            vec![solid(Rgba([255, 0, 0, 255])), solid(Rgba([0, 0, 255, 255]))],
+            Duration::from_secs(2),
+        )
+        .with_transition(Transition::Crossfade, Duration::from_secs(1))
+    }
+
+    #[test]
+    fn test_phase_wraps() {
+        let period = Duration::from_secs(2);
+        assert_eq!(phase(Duration::from_secs(0), period), 0.0);
+        assert_eq!(phase(Duration::from_secs(1), period), 0.5);
+        assert_eq!(phase(Duration::from_secs(3), period), 0.5);
+        assert_eq!(phase(Duration::from_secs(3), Duration::ZERO), 0.0);
+    }
+
+    #[test]
+    fn test_easing_endpoints() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+            assert_eq!(easing.apply(2.0), 1.0);
+        }
+        assert!(Easing::EaseIn.apply(0.5) < 0.5);
+        assert!(Easing::EaseOut.apply(0.5) > 0.5);
+    }
+
+    #[test]
+    fn test_value_tween_rolls_to_target() {
+        let mut tween = ValueTween::new(10.0, Duration::from_secs(1)).with_easing(Easing::Linear);
+        assert_eq!(tween.value(Duration::ZERO), 10.0);
+        tween.set(20.0, Duration::from_secs(5));
+        assert_eq!(tween.value(Duration::from_secs(5)), 10.0);
+        assert!((tween.value(Duration::from_millis(5500)) - 15.0).abs() < 1e-3);
+        assert!(!tween.is_settled(Duration::from_millis(5500)));
+        assert_eq!(tween.value(Duration::from_secs(7)), 20.0);
+        assert!(tween.is_settled(Duration::from_secs(7)));
+    }
+
+    #[test]
+    fn test_value_tween_retarget_mid_flight() {
+        let mut tween = ValueTween::new(0.0, Duration::from_secs(1)).with_easing(Easing::Linear);
+        tween.set(100.0, Duration::ZERO);
+        tween.set(0.0, Duration::from_millis(500));
+        // Continues from the displayed value rather than jumping
+        assert!((tween.value(Duration::from_millis(500)) - 50.0).abs() < 1e-3);
+        assert_eq!(tween.target(), 0.0);
+    }
+
+    #[test]
+    fn test_odometer_rolls_changed_digits() {
+        let bg = Rgba([0, 0, 0, 255]);
+        let mut odo = Odometer::new(
+            0,
+            0,
+            16.0,
+            Rgba([255, 255, 255, 255]),
+            bg,
+            Duration::from_secs(1),
+        )
+        .with_easing(Easing::Linear);
+        if odo.glyphs.is_none() {
+            return;
+        }
+        odo.set("18", Duration::ZERO);
+        let rect = odo
+            .render(&mut RgbaImage::new(64, 24), Duration::ZERO)
+            .unwrap();
+        assert_eq!(rect.width(), 2 * odo.glyphs.as_ref().unwrap().cell_size().0);
+        odo.set("19", Duration::from_secs(10));
+        assert!(odo.is_rolling(Duration::from_millis(10_500)));
+        assert!(!odo.is_rolling(Duration::from_secs(12)));
+
+        let (cell_w, _) = odo.glyphs.as_ref().unwrap().cell_size();
+        let mut start = RgbaImage::from_pixel(64, 24, bg);
+        let mut mid = start.clone();
+        let mut end = start.clone();
+        odo.render(&mut start, Duration::from_secs(10));
+        odo.render(&mut mid, Duration::from_millis(10_500));
+        odo.render(&mut end, Duration::from_secs(11));
+        let column = |img: &RgbaImage, x0: u32, x1: u32| -> Vec<Rgba<u8>> {
+            (x0..x1)
+                .flat_map(|x| (0..24).map(move |y| (x, y)))
+                .map(|(x, y)| *img.get_pixel(x, y))
+                .collect()
+        };
+        // The unchanged tens digit stays put; the units digit is mid-roll
+        assert_eq!(column(&start, 0, cell_w), column(&mid, 0, cell_w));
+        assert_ne!(
+            column(&mid, cell_w, 2 * cell_w),
+            column(&start, cell_w, 2 * cell_w)
+        );
+        assert_ne!(
+            column(&mid, cell_w, 2 * cell_w),
+            column(&end, cell_w, 2 * cell_w)
+        );
+    }
+
+    #[test]
+    fn test_flip_digit_endpoints_match_cards() {
+        let style = FlipStyle::default();
+        let rect = Rect::at(0, 0).of_size(20, 30);
+        let mut start = RgbaImage::new(20, 30);
+        let mut end = RgbaImage::new(20, 30);
+        draw_flip_digit(&mut start, rect, '3', '4', 0.0, &style);
+        draw_flip_digit(&mut end, rect, '3', '4', 1.0, &style);
+
+        let mut expected = flip_card('3', 20, 30, &style, &mut FramePool::new());
+        for x in 0..20 {
+            expected.put_pixel(x, 15, style.divider);
+        }
+        assert_eq!(start, expected);
+        // Same digit at both ends renders identically
+        let mut same = RgbaImage::new(20, 30);
+        draw_flip_digit(&mut same, rect, '4', '4', 0.0, &style);
+        assert_eq!(same, end);
+    }
+
+    #[test]
+    fn test_flip_digit_mid_flip_draws_highlight() {
+        let style = FlipStyle::default();
+        let mut img = RgbaImage::new(20, 30);
+        draw_flip_digit(
+            &mut img,
+            Rect::at(0, 0).of_size(20, 30),
+            '1',
+            '2',
+            0.25,
+            &style,
+        );
+        let highlighted = (0..15).find(|&y| *img.get_pixel(0, y) == style.highlight);
+        // Flap has folded partway down towards the hinge
+        assert!(highlighted.is_some_and(|y| y > 0 && y < 15));
+    }
+
+    #[test]
+    fn test_flip_digit_pooled_reuses_buffers() {
+        let rect = Rect::at(0, 0).of_size(20, 30);
+        let style = FlipStyle::default();
+        let mut pool = FramePool::new();
+        let mut pooled = RgbaImage::new(20, 30);
+        for t in [0.25, 0.75] {
+            draw_flip_digit_pooled(&mut pooled, rect, '1', '2', t, &style, &mut pool);
+            assert_eq!(pool.idle(), 3);
+        }
+        let mut plain = RgbaImage::new(20, 30);
+        draw_flip_digit(&mut plain, rect, '1', '2', 0.75, &style);
+        assert_eq!(pooled, plain);
+    }
+
+    #[test]
+    fn test_flip_digit_clips_to_image() {
+        let mut img = RgbaImage::new(10, 10);
+        let rect = Rect::at(-5, 5).of_size(20, 30);
+        draw_flip_digit(&mut img, rect, '1', '2', 0.7, &FlipStyle::default());
+        assert_eq!(img.get_pixel(0, 0)[3], 0);
+    }
+
+    #[test]
+    fn test_shake_decays_to_rest() {
+        assert_eq!(shake(0.0, 5.0), 0.0);
+        assert_eq!(shake(1.0, 5.0), 0.0);
+        assert_eq!(shake(-0.5, 5.0), 0.0);
+        let peak = |range: std::ops::Range<u32>| {
+            range
+                .map(|i| shake(i as f32 / 100.0, 5.0).abs())
+                .fold(0.0, f32::max)
+        };
+        assert!(peak(0..25) <= 5.0);
+        assert!(peak(0..25) > peak(75..100));
+    }
+
+    #[test]
+    fn test_apply_shake_shifts_and_fills() {
+        let mut img = RgbaImage::from_fn(10, 1, |x, _| Rgba([x as u8, 0, 0, 255]));
+        let fill = Rgba([0, 0, 0, 0]);
+        // Quarter of the first cycle is the first peak
+        let t = 1.0 / (4.0 * SHAKE_CYCLES);
+        let offset = shake(t, 3.0).round() as u32;
+        assert!(offset > 0);
+        apply_shake(&mut img, t, 3.0, fill);
+        assert_eq!(*img.get_pixel(0, 0), fill);
+        assert_eq!(img.get_pixel(offset, 0)[0], 0);
+    }
+
+    #[test]
+    fn test_carousel_position_dwell_and_transition() {
+        let c = two_slides();
+        assert_eq!(c.position(Duration::from_millis(500)), (0, None));
+        assert_eq!(c.position(Duration::from_millis(2500)), (0, Some(0.5)));
+        assert_eq!(c.position(Duration::from_millis(3500)), (1, None));
+        // Wraps back to the first slide after two slots
+        assert_eq!(c.position(Duration::from_millis(6500)), (0, None));
+    }
+
+    #[test]
+    fn test_carousel_crossfade_midpoint() {
+        let c = two_slides();
+        let mut img = RgbaImage::new(4, 4);
+        c.render(&mut img, Duration::from_millis(2500));
+        assert_eq!(*img.get_pixel(0, 0), Rgba([128, 0, 128, 255]));
+    }
+
+    #[test]
+    fn test_carousel_slide_left() {
+        let c = two_slides().with_transition(Transition::SlideLeft, Duration::from_secs(1));
+        let mut img = RgbaImage::new(4, 4);
+        c.render(&mut img, Duration::from_millis(2500));
+        assert_eq!(*img.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+        assert_eq!(*img.get_pixel(3, 0), Rgba([0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn test_carousel_render_closure_and_resize() {
+        let c = Carousel::new(
+            vec![Slide::render(|img| {
+                for p in img.pixels_mut() {
+                    *p = Rgba([1, 2, 3, 4]);
+                }
+            })],
+            Duration::from_secs(1),
+        );
+        let mut img = RgbaImage::new(8, 8);
+        c.render(&mut img, Duration::from_secs(5));
+        assert_eq!(*img.get_pixel(7, 7), Rgba([1, 2, 3, 4]));
+
+        let c = Carousel::new(vec![solid(Rgba([9, 9, 9, 255]))], Duration::from_secs(1));
+        c.render(&mut img, Duration::ZERO);
+        assert_eq!(img.dimensions(), (8, 8));
+    }
+
+    #[test]
+    fn test_carousel_render_pooled_reuses_buffers() {
+        let c = two_slides();
+        let mut pool = FramePool::new();
+        let mut img = RgbaImage::new(4, 4);
+        c.render_pooled(&mut img, Duration::from_millis(2500), &mut pool);
+        assert_eq!(pool.idle(), 2);
+        c.render_pooled(&mut img, Duration::from_millis(2600), &mut pool);
+        assert_eq!(pool.idle(), 2);
+    }
+
+    #[test]
+    fn test_carousel_empty_is_noop() {
+        let c = Carousel::new(Vec::new(), Duration::from_secs(1));
+        let mut img = RgbaImage::from_pixel(2, 2, Rgba([5, 5, 5, 5]));
+        c.render(&mut img, Duration::from_secs(1));
+        assert_eq!(*img.get_pixel(0, 0), Rgba([5, 5, 5, 5]));
+    }
+}
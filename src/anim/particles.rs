@@ -0,0 +1,301 @@
+//! A tiny particle system for celebration and alert moments.
+//!
+//! [`Particles`] follows a spawn/update/render cycle: spawn bursts when
+//! something happens, advance the simulation by the frame delta each frame,
+//! then render into the tile. Particles outside the image are clipped and
+//! removed once their lifetime ends. Randomness is seeded, so a given
+//! sequence of calls always produces the same animation. Nothing is
+//! spawned in [reduced-motion mode](crate::accessibility::set_reduced_motion).
+
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+use image::{Rgba, RgbaImage};
+use imageproc::rect::Rect;
+
+use crate::accessibility::reduced_motion;
+use crate::colors::hex;
+use crate::image::noise::white;
+
+/// Built-in particle behaviours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Preset {
+    /// Colorful squares thrown upwards that tumble down under gravity.
+    #[default]
+    Confetti,
+    /// Short-lived bright points bursting outwards and slowing quickly.
+    Sparks,
+    /// Slow white flakes drifting downwards.
+    Snow,
+}
+
+const CONFETTI_COLORS: [Rgba<u8>; 6] = [
+    hex("#FF4D4D"),
+    hex("#FFC83D"),
+    hex("#4DD964"),
+    hex("#3DA5FF"),
+    hex("#B04DFF"),
+    hex("#FF7AC8"),
+];
+
+const SPARK_COLORS: [Rgba<u8>; 3] = [hex("#FFF3B0"), hex("#FFC83D"), hex("#FF8A3D")];
+
+const SNOW_COLORS: [Rgba<u8>; 2] = [hex("#FFFFFF"), hex("#DDE8FF")];
+
+/// Physical parameters for a preset.
+struct Params {
+    /// Downward acceleration in pixels per second squared.
+    gravity: f32,
+    /// Fraction of velocity lost per second.
+    drag: f32,
+    /// Launch speed range in pixels per second.
+    speed: (f32, f32),
+    /// Launch direction as (center, spread) in radians, 0 pointing right.
+    angle: (f32, f32),
+    /// Lifetime range in seconds.
+    life: (f32, f32),
+    /// Size range in pixels.
+    size: (f32, f32),
+    colors: &'static [Rgba<u8>],
+}
+
+impl Preset {
+    fn params(self) -> Params {
+        match self {
+            Preset::Confetti => Params {
+                gravity: 120.0,
+                drag: 0.8,
+                speed: (40.0, 110.0),
+                angle: (-TAU / 4.0, TAU / 6.0),
+                life: (1.5, 2.5),
+                size: (2.0, 3.0),
+                colors: &CONFETTI_COLORS,
+            },
+            Preset::Sparks => Params {
+                gravity: 40.0,
+                drag: 3.0,
+                speed: (60.0, 160.0),
+                angle: (0.0, TAU / 2.0),
+                life: (0.3, 0.8),
+                size: (1.0, 1.0),
+                colors: &SPARK_COLORS,
+            },
+            Preset::Snow => Params {
+                gravity: 0.0,
+                drag: 0.0,
+                speed: (8.0, 20.0),
+                angle: (TAU / 4.0, TAU / 16.0),
+                life: (4.0, 6.0),
+                size: (1.0, 2.0),
+                colors: &SNOW_COLORS,
+            },
+        }
+    }
+}
+
+/// A single simulated particle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Particle {
+    /// Horizontal position of the center in pixels.
+    pub x: f32,
+    /// Vertical position of the center in pixels, increasing downwards.
+    pub y: f32,
+    /// Horizontal velocity in pixels per second.
+    pub vx: f32,
+    /// Vertical velocity in pixels per second; gravity adds to it.
+    pub vy: f32,
+    /// Seconds since spawn.
+    pub age: f32,
+    /// Seconds until the particle disappears.
+    pub life: f32,
+    /// Edge length of the drawn square in pixels.
+    pub size: f32,
+    /// Fill color, picked from the preset's palette at spawn.
+    pub color: Rgba<u8>,
+}
+
+/// A seeded particle system following one [`Preset`].
+#[derive(Debug, Clone)]
+pub struct Particles {
+    preset: Preset,
+    seed: u64,
+    counter: i64,
+    particles: Vec<Particle>,
+}
+
+impl Particles {
+    /// Create an empty system for `preset`, seeding its random sequence with `seed`.
+    pub fn new(preset: Preset, seed: u64) -> Self {
+        Self {
+            preset,
+            seed,
+            counter: 0,
+            particles: Vec::new(),
+        }
+    }
+
+    /// Live particles.
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    /// Number of live particles.
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// Whether all particles have expired.
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    /// Remove all particles.
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    /// Spawn a burst of `count` particles at (x, y).
+    pub fn spawn(&mut self, x: f32, y: f32, count: usize) {
+        if reduced_motion() {
+            return;
+        }
+        for _ in 0..count {
+            let p = self.make(x, y);
+            self.particles.push(p);
+        }
+    }
+
+    /// Spawn `count` particles at random positions within `rect`.
+    ///
+    /// Useful for [`Preset::Snow`], spawned along a strip above the tile.
+    pub fn spawn_in(&mut self, rect: Rect, count: usize) {
+        if reduced_motion() {
+            return;
+        }
+        for _ in 0..count {
+            let x = rect.left() as f32 + self.random() * rect.width() as f32;
+            let y = rect.top() as f32 + self.random() * rect.height() as f32;
+            let p = self.make(x, y);
+            self.particles.push(p);
+        }
+    }
+
+    /// Advance the simulation by `dt`, removing expired particles.
+    pub fn update(&mut self, dt: Duration) {
+        let params = self.preset.params();
+        let dt = dt.as_secs_f32();
+        let damping = (1.0 - params.drag * dt).max(0.0);
+        for p in &mut self.particles {
+            p.vy += params.gravity * dt;
+            p.vx *= damping;
+            p.vy *= damping;
+            p.x += p.vx * dt;
+            p.y += p.vy * dt;
+            p.age += dt;
+        }
+        self.particles.retain(|p| p.age < p.life);
+    }
+
+    /// Draw live particles into `rgba`, clipped to its bounds.
+    ///
+    /// Particles fade out over the last third of their lifetime.
+    pub fn render(&self, rgba: &mut RgbaImage) {
+        let (width, height) = rgba.dimensions();
+        for p in &self.particles {
+            let remaining = 1.0 - p.age / p.life;
+            let fade = (remaining * 3.0).min(1.0);
+            let a = p.color[3] as f32 / 255.0 * fade;
+            let half = p.size / 2.0;
+            let x0 = (p.x - half).round().max(0.0) as u32;
+            let y0 = (p.y - half).round().max(0.0) as u32;
+            let x1 = ((p.x + half).round().max(0.0) as u32).min(width);
+            let y1 = ((p.y + half).round().max(0.0) as u32).min(height);
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let px = rgba.get_pixel_mut(x, y);
+                    for c in 0..3 {
+                        px[c] = (px[c] as f32 * (1.0 - a) + p.color[c] as f32 * a).round() as u8;
+                    }
+                    px[3] = px[3].max((a * 255.0).round() as u8);
+                }
+            }
+        }
+    }
+
+    fn make(&mut self, x: f32, y: f32) -> Particle {
+        let params = self.preset.params();
+        let angle = params.angle.0 + (self.random() * 2.0 - 1.0) * params.angle.1;
+        let speed = self.between(params.speed);
+        let life = self.between(params.life);
+        let size = self.between(params.size).round();
+        let color = params.colors[(self.random() * params.colors.len() as f32) as usize];
+        Particle {
+            x,
+            y,
+            vx: angle.cos() * speed,
+            vy: angle.sin() * speed,
+            age: 0.0,
+            life,
+            size,
+            color,
+        }
+    }
+
+    fn between(&mut self, (low, high): (f32, f32)) -> f32 {
+        low + self.random() * (high - low)
+    }
+
+    /// Next value in the seeded sequence, from 0.0 to just below 1.0.
+    fn random(&mut self) -> f32 {
+        self.counter += 1;
+        white(self.counter, 0, self.seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_particles_spawn_update_expire() {
+        let mut sys = Particles::new(Preset::Sparks, 7);
+        sys.spawn(10.0, 10.0, 20);
+        assert_eq!(sys.len(), 20);
+        sys.update(Duration::from_millis(100));
+        assert!(sys.particles().iter().any(|p| p.x != 10.0 || p.y != 10.0));
+        sys.update(Duration::from_secs(1));
+        assert!(sys.is_empty());
+    }
+
+    #[test]
+    fn test_particles_confetti_falls_under_gravity() {
+        let mut sys = Particles::new(Preset::Confetti, 1);
+        sys.spawn(36.0, 36.0, 10);
+        let vy: Vec<f32> = sys.particles().iter().map(|p| p.vy).collect();
+        // Launched upwards, then pulled back down
+        assert!(vy.iter().all(|v| *v < 0.0));
+        for _ in 0..20 {
+            sys.update(Duration::from_millis(50));
+        }
+        assert!(sys.particles().iter().all(|p| p.vy > 0.0));
+    }
+
+    #[test]
+    fn test_particles_seeded_is_deterministic() {
+        let mut a = Particles::new(Preset::Snow, 42);
+        let mut b = Particles::new(Preset::Snow, 42);
+        a.spawn_in(Rect::at(0, -4).of_size(72, 4), 5);
+        b.spawn_in(Rect::at(0, -4).of_size(72, 4), 5);
+        assert_eq!(a.particles(), b.particles());
+    }
+
+    #[test]
+    fn test_particles_render_clips() {
+        let mut sys = Particles::new(Preset::Confetti, 3);
+        sys.spawn(-50.0, -50.0, 5);
+        sys.spawn(4.0, 4.0, 1);
+        let mut img = RgbaImage::new(8, 8);
+        sys.render(&mut img);
+        assert!(img.pixels().any(|p| p[3] > 0));
+    }
+}
@@ -0,0 +1,72 @@
+//! Cancellation for renders superseded by newer data.
+//!
+//! A [`CancelToken`] is polled by the renders whose cost grows with their
+//! input:
+//!
+//! - [`render_cancelable`](crate::widget::render_cancelable), before each
+//!   spec element and sparkline segment
+//! - the `_cancelable` chart variants, such as
+//!   [`draw_timeline_cancelable`](crate::chart::draw_timeline_cancelable),
+//!   before each stacked-area column, candle or timeline span
+//!
+//! Fixed-size primitives (gauges, donuts, radar charts) always finish.
+//! Custom renders can poll a token themselves, and a
+//! [`RenderWorker`](crate::worker::RenderWorker) cancels the tokens of
+//! superseded jobs.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A shared flag asking a long-running render to stop early.
+///
+/// Clones share the flag, so the code that starts a render can keep one
+/// clone to cancel it while the render polls another with
+/// [`is_canceled`](Self::is_canceled) between units of work. Canceling is
+/// permanent; use a fresh token for the next render.
+///
+/// ```ignore
+/// fn render_heatmap(samples: &[f32], cancel: &CancelToken) -> Option<RgbaImage> {
+///     let mut img = RgbaImage::new(W, H);
+///     for (row, chunk) in samples.chunks(W as usize).enumerate() {
+///         if cancel.is_canceled() {
+///             return None;
+///         }
+///         draw_row(&mut img, row, chunk);
+///     }
+///     Some(img)
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// A token that has not been canceled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every holder of this token to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`cancel`](Self::cancel) has been called on any clone.
+    pub fn is_canceled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clones_share_cancellation() {
+        let cancel = CancelToken::new();
+        let clone = cancel.clone();
+        assert!(!cancel.is_canceled());
+        clone.cancel();
+        assert!(cancel.is_canceled());
+        assert!(!CancelToken::new().is_canceled());
+    }
+}
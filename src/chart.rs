@@ -0,0 +1,75 @@
+//! Chart primitives for data-driven tiles.
+//!
+//! Renderers draw into a [`Rect`](imageproc::rect::Rect) of an existing
+//! image and clip to its bounds. Axis ticks are chosen automatically at
+//! "nice" intervals (1, 2 or 5 times a power of ten); the same range and
+//! label helpers are public for plugins drawing custom charts.
+
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
+use image::{Rgba, RgbaImage};
+use imageproc::drawing::draw_text_mut;
+
+use crate::font::get_system_monospace_font;
+use crate::text::measure_text_width;
+
+mod area;
+mod axis;
+mod candlestick;
+mod donut;
+mod gauge;
+mod radar;
+mod timeline;
+
+pub use area::{AreaStyle, draw_stacked_area, draw_stacked_area_cancelable};
+pub use axis::{
+    DEFAULT_MAX_TICKS, MAX_TICK_COUNT, NiceRange, TickFormat, format_fixed, format_si, format_tick,
+    nice_range, nice_step,
+};
+pub use candlestick::{CandleStyle, draw_candlesticks, draw_candlesticks_cancelable};
+pub use donut::{DonutStyle, draw_donut};
+pub use gauge::{GaugeStyle, draw_gauge};
+pub use radar::{RadarStyle, draw_radar};
+pub use timeline::{TimelineStyle, draw_timeline, draw_timeline_cancelable};
+
+/// Blend `color` over `pixel` with opacity `a` (0.0 to 1.0), in linear light.
+pub(crate) use crate::colors::blend_linear as blend;
+
+/// Label drawing with the system monospace font at a fixed scale.
+pub(crate) struct Labeler<'a> {
+    font: FontRef<'a>,
+    scale: PxScale,
+}
+
+impl Labeler<'static> {
+    /// Returns None if the system font is unavailable.
+    pub(crate) fn new(scale: f32) -> Option<Self> {
+        let font = FontRef::try_from_slice(get_system_monospace_font()?).ok()?;
+        Some(Self {
+            font,
+            scale: PxScale::from(scale.max(1.0)),
+        })
+    }
+}
+
+impl Labeler<'_> {
+    /// Rendered size of `text` as `(width, height)`.
+    pub(crate) fn size(&self, text: &str) -> (f32, f32) {
+        let width = measure_text_width(&self.font, text) * self.scale.x;
+        (width, self.font.as_scaled(self.scale).height())
+    }
+
+    /// Draw `text` centered on (cx, cy).
+    pub(crate) fn draw_centered(
+        &self,
+        rgba: &mut RgbaImage,
+        text: &str,
+        cx: f32,
+        cy: f32,
+        color: Rgba<u8>,
+    ) {
+        let (w, h) = self.size(text);
+        let x = (cx - w / 2.0).round() as i32;
+        let y = (cy - h / 2.0).round() as i32;
+        draw_text_mut(rgba, color, x, y, self.scale, &self.font, text);
+    }
+}
@@ -0,0 +1,181 @@
+//! Stacked area chart.
+
+use image::{Rgba, RgbaImage};
+use imageproc::rect::Rect;
+
+use super::blend;
+use crate::cancel::CancelToken;
+
+/// Fill and scaling options for [`draw_stacked_area`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AreaStyle {
+    /// Opacity of each band's fill, 0.0 to 1.0, applied on top of the
+    /// series color's own alpha.
+    pub fill_alpha: f32,
+    /// Whether to draw each band's top edge at full opacity.
+    pub outline: bool,
+    /// Fixed top of the vertical scale (e.g. 100.0 for percentages).
+    /// None scales to the largest stacked total.
+    pub max: Option<f32>,
+}
+
+impl Default for AreaStyle {
+    fn default() -> Self {
+        Self {
+            fill_alpha: 0.6,
+            outline: true,
+            max: None,
+        }
+    }
+}
+
+/// Draw series as bands stacked on top of each other across `rect`.
+///
+/// Each series is a `(values, color)` pair; samples are spread evenly
+/// across the width and linearly interpolated between. The first series
+/// sits at the bottom. Negative values are treated as zero. Series of
+/// different lengths are each stretched across the full width. Drawing is
+/// clipped to the image bounds.
+pub fn draw_stacked_area(
+    rgba: &mut RgbaImage,
+    rect: Rect,
+    series: &[(&[f32], Rgba<u8>)],
+    style: &AreaStyle,
+) {
+    draw_stacked_area_cancelable(rgba, rect, series, style, &CancelToken::new());
+}
+
+/// Like [`draw_stacked_area`], but stops early once `cancel` is canceled,
+/// checking before each column. Returns whether the chart was drawn in
+/// full; a canceled chart is left partly drawn.
+pub fn draw_stacked_area_cancelable(
+    rgba: &mut RgbaImage,
+    rect: Rect,
+    series: &[(&[f32], Rgba<u8>)],
+    style: &AreaStyle,
+    cancel: &CancelToken,
+) -> bool {
+    let series: Vec<_> = series.iter().filter(|(v, _)| !v.is_empty()).collect();
+    if series.is_empty() || rgba.width() == 0 || rgba.height() == 0 {
+        return true;
+    }
+    let width = rect.width();
+    let height = rect.height() as f32;
+    let sample = |values: &[f32], x: u32| {
+        if values.len() == 1 || width == 1 {
+            return values[0].max(0.0);
+        }
+        let pos = x as f32 / (width - 1) as f32 * (values.len() - 1) as f32;
+        let i = (pos.floor() as usize).min(values.len() - 2);
+        let t = pos - i as f32;
+        (values[i] * (1.0 - t) + values[i + 1] * t).max(0.0)
+    };
+    let top = style.max.unwrap_or_else(|| {
+        (0..width)
+            .map(|x| series.iter().map(|(v, _)| sample(v, x)).sum::<f32>())
+            .fold(0.0, f32::max)
+    });
+    if top <= 0.0 {
+        return true;
+    }
+    let y_of = |v: f32| rect.top() as f32 + height * (1.0 - (v / top).min(1.0));
+
+    for x in 0..width {
+        if cancel.is_canceled() {
+            return false;
+        }
+        let px = rect.left() + x as i32;
+        if px < 0 || px as u32 >= rgba.width() {
+            continue;
+        }
+        let mut base = 0.0;
+        for (values, color) in &series {
+            let value = base + sample(values, x);
+            let (y_top, y_bottom) = (y_of(value).round() as i32, y_of(base).round() as i32);
+            let a = style.fill_alpha.clamp(0.0, 1.0) * color[3] as f32 / 255.0;
+            for py in y_top.max(0)..y_bottom.min(rgba.height() as i32) {
+                blend(rgba.get_pixel_mut(px as u32, py as u32), *color, a);
+            }
+            if style.outline && y_bottom > y_top && (0..rgba.height() as i32).contains(&y_top) {
+                rgba.put_pixel(px as u32, y_top as u32, *color);
+            }
+            base = value;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+
+    #[test]
+    fn test_stacked_area_bands_stack() {
+        let style = AreaStyle {
+            fill_alpha: 1.0,
+            outline: false,
+            max: Some(100.0),
+        };
+        let mut img = RgbaImage::new(10, 100);
+        let user = [25.0, 25.0];
+        let system = [25.0, 25.0];
+        draw_stacked_area(
+            &mut img,
+            Rect::at(0, 0).of_size(10, 100),
+            &[(&user, RED), (&system, BLUE)],
+            &style,
+        );
+        assert_eq!(*img.get_pixel(5, 90), RED);
+        assert_eq!(*img.get_pixel(5, 60), BLUE);
+        assert_eq!(img.get_pixel(5, 40)[3], 0);
+    }
+
+    #[test]
+    fn test_stacked_area_auto_scale_and_alpha() {
+        let style = AreaStyle::default();
+        let mut img = RgbaImage::new(4, 20);
+        let values = [1.0, 2.0, 4.0, 2.0];
+        draw_stacked_area(
+            &mut img,
+            Rect::at(0, 0).of_size(4, 20),
+            &[(&values, RED)],
+            &style,
+        );
+        // Peak reaches the top edge, drawn as the opaque outline
+        assert_eq!(*img.get_pixel(2, 0), RED);
+        // Fill below is translucent
+        let fill = img.get_pixel(2, 10);
+        assert!(fill[3] > 0 && fill[3] < 255);
+        // First column only reaches a quarter of the height
+        assert_eq!(img.get_pixel(0, 5)[3], 0);
+    }
+
+    #[test]
+    fn test_stacked_area_cancelable() {
+        let values = [1.0, 2.0];
+        let series = [(&values[..], RED)];
+        let rect = Rect::at(0, 0).of_size(4, 4);
+        let mut img = RgbaImage::new(4, 4);
+        let cancel = CancelToken::new();
+        assert!(draw_stacked_area_cancelable(
+            &mut img,
+            rect,
+            &series,
+            &AreaStyle::default(),
+            &cancel
+        ));
+        cancel.cancel();
+        let mut canceled = RgbaImage::new(4, 4);
+        assert!(!draw_stacked_area_cancelable(
+            &mut canceled,
+            rect,
+            &series,
+            &AreaStyle::default(),
+            &cancel
+        ));
+        assert!(canceled.pixels().all(|p| p[3] == 0));
+    }
+}
@@ -0,0 +1,315 @@
+//! Axis ranges and tick label formatting shared by the chart primitives.
+//!
+//! Plugins drawing their own charts can use the same helpers so their axes
+//! line up visually with the built-in ones.
+
+use crate::locale::locale;
+
+/// Tick count used by [`nice_range`].
+pub const DEFAULT_MAX_TICKS: usize = 5;
+
+/// Most ticks [`NiceRange::ticks`] will produce; more give none.
+pub const MAX_TICK_COUNT: usize = 1000;
+
+/// Largest magnitude [`NiceRange::with_max_ticks`] keeps, so the span and
+/// its nice boundaries stay finite.
+const MAX_BOUND: f64 = f64::MAX / 4.0;
+
+/// An axis range expanded outwards to "nice" tick boundaries.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NiceRange {
+    /// Lowest tick, at or below the data minimum.
+    pub min: f64,
+    /// Highest tick, at or above the data maximum.
+    pub max: f64,
+    /// Spacing between ticks (1, 2 or 5 times a power of ten).
+    pub step: f64,
+}
+
+impl NiceRange {
+    /// Compute a nice range covering `min..=max` with at most `max_ticks` ticks.
+    ///
+    /// The bounds are swapped if given in reverse. An empty range is widened
+    /// around its value so it still has ticks. Infinite and very large
+    /// bounds are clamped to a quarter of `f64::MAX` either way, and NaN is
+    /// treated as 0.
+    pub fn with_max_ticks(min: f64, max: f64, max_ticks: usize) -> Self {
+        let bound = |v: f64| {
+            if v.is_nan() {
+                0.0
+            } else {
+                v.clamp(-MAX_BOUND, MAX_BOUND)
+            }
+        };
+        let (min, max) = (bound(min), bound(max));
+        let (mut min, mut max) = if min <= max { (min, max) } else { (max, min) };
+        if max - min <= f64::EPSILON * max.abs().max(1.0) {
+            let pad = if min == 0.0 { 1.0 } else { min.abs() * 0.1 };
+            min -= pad;
+            max += pad;
+        }
+        // Expanding to the step can add an interval, so tighten if needed
+        let max_ticks = max_ticks.max(2);
+        let mut ticks = max_ticks;
+        loop {
+            let step = nice_step(max - min, ticks);
+            let lo = (min / step).floor() * step;
+            let hi = (max / step).ceil() * step;
+            let intervals = ((hi - lo) / step).round() as usize;
+            if intervals < max_ticks || ticks <= 2 {
+                return Self {
+                    min: lo,
+                    max: hi,
+                    step,
+                };
+            }
+            ticks -= 1;
+        }
+    }
+
+    /// Tick values from `min` to `max` inclusive, or none if there would be
+    /// more than [`MAX_TICK_COUNT`].
+    pub fn ticks(&self) -> Vec<f64> {
+        ticks_in(self.min, self.max, self.step)
+    }
+
+    /// Tick labels formatted with `format`.
+    pub fn labels(&self, format: TickFormat) -> Vec<String> {
+        self.ticks()
+            .into_iter()
+            .map(|v| format.format(v, self.step))
+            .collect()
+    }
+}
+
+/// Expand `min..=max` outwards to nice tick boundaries, with up to
+/// [`DEFAULT_MAX_TICKS`] ticks.
+pub fn nice_range(min: f64, max: f64) -> NiceRange {
+    NiceRange::with_max_ticks(min, max, DEFAULT_MAX_TICKS)
+}
+
+/// Tick spacing giving at most `max_ticks` ticks across `span`.
+///
+/// The step is 1, 2 or 5 times a power of ten.
+pub fn nice_step(span: f64, max_ticks: usize) -> f64 {
+    let intervals = max_ticks.saturating_sub(1).max(1) as f64;
+    let raw = span.abs() / intervals;
+    if raw <= 0.0 || !raw.is_finite() {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let normalized = raw / magnitude;
+    let nice = if normalized <= 1.0 {
+        1.0
+    } else if normalized <= 2.0 {
+        2.0
+    } else if normalized <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+/// Multiples of `step` within `min..=max`.
+///
+/// Returns none for non-finite inputs, a non-positive `step`, or more than
+/// [`MAX_TICK_COUNT`] ticks.
+pub(crate) fn ticks_in(min: f64, max: f64, step: f64) -> Vec<f64> {
+    if !(min.is_finite() && max.is_finite() && step.is_finite() && step > 0.0) {
+        return Vec::new();
+    }
+    let first = (min / step - 1e-9).ceil();
+    let last = (max / step + 1e-9).floor();
+    if last - first >= MAX_TICK_COUNT as f64 {
+        return Vec::new();
+    }
+    let first = first as i64;
+    (first..=last as i64).map(|i| i as f64 * step).collect()
+}
+
+/// How tick values are turned into labels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TickFormat {
+    /// Just enough decimals to distinguish ticks at the step size.
+    #[default]
+    Auto,
+    /// A fixed number of decimals.
+    Fixed(usize),
+    /// SI suffixes (k, M, G, ...) with just enough decimals for the step.
+    Si,
+}
+
+impl TickFormat {
+    /// Format `value` for an axis with ticks `step` apart, using the
+    /// current [`locale`]'s separators.
+    pub fn format(self, value: f64, step: f64) -> String {
+        let text = match self {
+            TickFormat::Auto => format_tick(value, step),
+            TickFormat::Fixed(decimals) => format_fixed(value, decimals),
+            TickFormat::Si => {
+                let (_, scale) = si_prefix(step.abs().max(value.abs()));
+                let decimals = step_decimals(step / scale);
+                format_si(value, decimals)
+            }
+        };
+        locale().localize_number(&text)
+    }
+}
+
+/// Format a tick value with just enough decimals for `step`.
+pub fn format_tick(value: f64, step: f64) -> String {
+    format_fixed(value, step_decimals(step))
+}
+
+/// Format `value` with exactly `decimals` decimal places, never as "-0".
+pub fn format_fixed(value: f64, decimals: usize) -> String {
+    let text = format!("{value:.decimals$}");
+    if text
+        .trim_start_matches('-')
+        .chars()
+        .all(|c| c == '0' || c == '.')
+    {
+        text.trim_start_matches('-').to_string()
+    } else {
+        text
+    }
+}
+
+/// Format `value` with an SI suffix and up to `decimals` decimal places.
+///
+/// Trailing zeros are trimmed, e.g. 1500.0 is "1.5k" and 2.0e6 is "2M".
+/// Values below 1 use m, µ and n.
+pub fn format_si(value: f64, decimals: usize) -> String {
+    let (suffix, scale) = si_prefix(value.abs());
+    let mut text = format_fixed(value / scale, decimals);
+    if text.contains('.') {
+        text = text.trim_end_matches('0').trim_end_matches('.').to_string();
+    }
+    if text == "0" {
+        return text;
+    }
+    text.push_str(suffix);
+    text
+}
+
+/// SI suffix and scale for a magnitude.
+fn si_prefix(magnitude: f64) -> (&'static str, f64) {
+    const PREFIXES: [(&str, f64); 9] = [
+        ("P", 1e15),
+        ("T", 1e12),
+        ("G", 1e9),
+        ("M", 1e6),
+        ("k", 1e3),
+        ("", 1.0),
+        ("m", 1e-3),
+        ("µ", 1e-6),
+        ("n", 1e-9),
+    ];
+    if magnitude == 0.0 || !magnitude.is_finite() {
+        return ("", 1.0);
+    }
+    PREFIXES
+        .iter()
+        .copied()
+        .find(|(_, scale)| magnitude >= *scale * (1.0 - 1e-9))
+        .unwrap_or(PREFIXES[PREFIXES.len() - 1])
+}
+
+/// Decimals needed to show multiples of `step` exactly.
+fn step_decimals(step: f64) -> usize {
+    if step <= 0.0 || !step.is_finite() {
+        return 0;
+    }
+    (-(step.log10() + 1e-9).floor()).max(0.0) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nice_step() {
+        assert_eq!(nice_step(100.0, 6), 20.0);
+        assert_eq!(nice_step(100.0, 11), 10.0);
+        assert_eq!(nice_step(1.0, 5), 0.5);
+        assert_eq!(nice_step(7.0, 8), 1.0);
+        assert_eq!(nice_step(0.0, 5), 1.0);
+    }
+
+    #[test]
+    fn test_ticks_in() {
+        assert_eq!(
+            ticks_in(0.0, 100.0, 25.0),
+            vec![0.0, 25.0, 50.0, 75.0, 100.0]
+        );
+        assert_eq!(ticks_in(-3.0, 7.0, 5.0), vec![0.0, 5.0]);
+        assert!(ticks_in(0.0, f64::INFINITY, 1.0).is_empty());
+        assert!(ticks_in(0.0, 1e9, 1.0).is_empty());
+        assert!(ticks_in(0.0, 1.0, 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_nice_range_expands_outwards() {
+        let r = nice_range(3.2, 87.0);
+        assert_eq!((r.min, r.max, r.step), (0.0, 100.0, 50.0));
+        assert!(r.ticks().len() <= DEFAULT_MAX_TICKS);
+        let r = nice_range(-0.37, 0.81);
+        assert_eq!((r.min, r.max, r.step), (-0.5, 1.0, 0.5));
+        assert_eq!(
+            r.labels(TickFormat::Auto),
+            vec!["-0.5", "0.0", "0.5", "1.0"]
+        );
+    }
+
+    #[test]
+    fn test_nice_range_degenerate() {
+        let r = nice_range(5.0, 5.0);
+        assert!(r.min < 5.0 && r.max > 5.0);
+        let r = nice_range(10.0, 0.0);
+        assert_eq!((r.min, r.max), (0.0, 10.0));
+    }
+
+    #[test]
+    fn test_nice_range_non_finite_bounds() {
+        for (min, max) in [
+            (0.0, f64::INFINITY),
+            (-1e308, 1e308),
+            (f64::NEG_INFINITY, f64::NAN),
+        ] {
+            let r = nice_range(min, max);
+            assert!(r.min.is_finite() && r.max.is_finite() && r.step.is_finite());
+            let ticks = r.ticks();
+            assert!(!ticks.is_empty() && ticks.len() <= DEFAULT_MAX_TICKS);
+            assert_eq!(r.labels(TickFormat::Si).len(), ticks.len());
+        }
+    }
+
+    #[test]
+    fn test_format_tick() {
+        assert_eq!(format_tick(20.0, 20.0), "20");
+        assert_eq!(format_tick(0.5, 0.5), "0.5");
+        assert_eq!(format_tick(-0.0001, 0.1), "0.0");
+        assert_eq!(format_tick(0.25, 0.05), "0.25");
+    }
+
+    #[test]
+    fn test_format_si() {
+        assert_eq!(format_si(1500.0, 1), "1.5k");
+        assert_eq!(format_si(2.0e6, 2), "2M");
+        assert_eq!(format_si(-3.25e9, 2), "-3.25G");
+        assert_eq!(format_si(0.004, 0), "4m");
+        assert_eq!(format_si(999.0, 0), "999");
+        assert_eq!(format_si(0.0, 2), "0");
+    }
+
+    #[test]
+    fn test_tick_format_si_uses_step() {
+        let r = nice_range(0.0, 2.0e6);
+        assert_eq!(
+            r.labels(TickFormat::Si),
+            vec!["0", "500k", "1M", "1.5M", "2M"]
+        );
+        assert_eq!(TickFormat::Fixed(2).format(1.0, 1.0), "1.00");
+    }
+}
@@ -0,0 +1,147 @@
+//! Candlestick (OHLC) mini-chart.
+
+use image::{Rgba, RgbaImage};
+use imageproc::drawing::draw_filled_rect_mut;
+use imageproc::rect::Rect;
+
+use crate::cancel::CancelToken;
+use crate::colors::hex;
+
+/// Colors and proportions for [`draw_candlesticks`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CandleStyle {
+    /// Color of candles that closed at or above their open.
+    pub up: Rgba<u8>,
+    /// Color of candles that closed below their open.
+    pub down: Rgba<u8>,
+    /// Body width as a fraction of each candle's slot.
+    pub body: f32,
+}
+
+impl Default for CandleStyle {
+    fn default() -> Self {
+        Self {
+            up: hex("#26A69A"),
+            down: hex("#EF5350"),
+            body: 0.6,
+        }
+    }
+}
+
+/// Draw candlesticks for `(open, high, low, close)` tuples across `rect`.
+///
+/// Candles are laid out left to right in equal slots. The vertical range is
+/// scaled automatically to the lowest low and highest high. Bodies are at
+/// least one pixel tall so flat candles remain visible. Drawing is clipped
+/// to the image bounds.
+pub fn draw_candlesticks(
+    rgba: &mut RgbaImage,
+    rect: Rect,
+    candles: &[(f32, f32, f32, f32)],
+    style: &CandleStyle,
+) {
+    draw_candlesticks_cancelable(rgba, rect, candles, style, &CancelToken::new());
+}
+
+/// Like [`draw_candlesticks`], but stops early once `cancel` is canceled,
+/// checking before each candle. Returns whether the chart was drawn in
+/// full; a canceled chart is left partly drawn.
+pub fn draw_candlesticks_cancelable(
+    rgba: &mut RgbaImage,
+    rect: Rect,
+    candles: &[(f32, f32, f32, f32)],
+    style: &CandleStyle,
+    cancel: &CancelToken,
+) -> bool {
+    if candles.is_empty() || rgba.width() == 0 || rgba.height() == 0 {
+        return true;
+    }
+    let bounds = Rect::at(0, 0).of_size(rgba.width(), rgba.height());
+    let low = candles
+        .iter()
+        .map(|c| c.2.min(c.0).min(c.3))
+        .fold(f32::MAX, f32::min);
+    let high = candles
+        .iter()
+        .map(|c| c.1.max(c.0).max(c.3))
+        .fold(f32::MIN, f32::max);
+    let span = (high - low).max(f32::EPSILON);
+    let height = rect.height() as f32 - 1.0;
+    let y_of = |v: f32| rect.top() + ((high - v) / span * height).round() as i32;
+
+    let slot = rect.width() as f32 / candles.len() as f32;
+    let body_width = (slot * style.body.clamp(0.0, 1.0)).round().max(1.0) as u32;
+    let fill = |rgba: &mut RgbaImage, x: i32, y0: i32, w: u32, y1: i32, color| {
+        let r = Rect::at(x, y0.min(y1)).of_size(w, (y1 - y0).unsigned_abs() + 1);
+        if let Some(r) = r.intersect(bounds) {
+            draw_filled_rect_mut(rgba, r, color);
+        }
+    };
+    for (i, &(open, high, low, close)) in candles.iter().enumerate() {
+        if cancel.is_canceled() {
+            return false;
+        }
+        let color = if close >= open { style.up } else { style.down };
+        let center = rect.left() + (slot * (i as f32 + 0.5)).floor() as i32;
+        fill(rgba, center, y_of(high), 1, y_of(low), color);
+        let left = center - (body_width as i32 - 1) / 2;
+        fill(rgba, left, y_of(open), body_width, y_of(close), color);
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candlesticks_scale_to_range() {
+        let style = CandleStyle::default();
+        let mut img = RgbaImage::new(20, 21);
+        let candles = [(10.0, 20.0, 0.0, 15.0), (15.0, 16.0, 5.0, 8.0)];
+        draw_candlesticks(&mut img, Rect::at(0, 0).of_size(20, 21), &candles, &style);
+        // First candle's wick spans the full height
+        assert_eq!(*img.get_pixel(5, 0), style.up);
+        assert_eq!(*img.get_pixel(5, 20), style.up);
+        // Second candle closed lower
+        assert_eq!(*img.get_pixel(15, 8), style.down);
+        assert_eq!(img.get_pixel(15, 2)[3], 0);
+    }
+
+    #[test]
+    fn test_candlesticks_flat_candle_visible() {
+        let style = CandleStyle::default();
+        let mut img = RgbaImage::new(10, 10);
+        draw_candlesticks(
+            &mut img,
+            Rect::at(0, 0).of_size(10, 10),
+            &[(1.0, 1.0, 1.0, 1.0)],
+            &style,
+        );
+        assert!(img.pixels().any(|p| *p == style.up));
+    }
+
+    #[test]
+    fn test_candlesticks_cancelable() {
+        let candles = [(1.0, 2.0, 0.0, 1.5); 8];
+        let rect = Rect::at(0, 0).of_size(16, 8);
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let mut img = RgbaImage::new(16, 8);
+        assert!(!draw_candlesticks_cancelable(
+            &mut img,
+            rect,
+            &candles,
+            &CandleStyle::default(),
+            &cancel
+        ));
+        assert!(img.pixels().all(|p| p[3] == 0));
+        assert!(draw_candlesticks_cancelable(
+            &mut img,
+            rect,
+            &candles,
+            &CandleStyle::default(),
+            &CancelToken::new()
+        ));
+    }
+}
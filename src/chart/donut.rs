@@ -0,0 +1,167 @@
+//! Pie and donut charts.
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use image::{Rgba, RgbaImage};
+use imageproc::rect::Rect;
+
+use super::blend;
+
+/// Subsamples per axis used for anti-aliasing.
+const SUPERSAMPLE: u32 = 4;
+
+/// Layout for [`draw_donut`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DonutStyle {
+    /// Radius of the center hole as a fraction of the outer radius.
+    /// 0.0 draws a pie chart.
+    pub hole: f32,
+    /// Width in pixels of the gap left between adjacent segments.
+    pub gap: f32,
+}
+
+impl Default for DonutStyle {
+    fn default() -> Self {
+        Self {
+            hole: 0.6,
+            gap: 1.0,
+        }
+    }
+}
+
+/// Draw a donut (or pie) chart fitted inside `rect`.
+///
+/// Each segment is a `(value, color)` pair sized in proportion to its share
+/// of the total; non-positive values are skipped. Segments start at the top
+/// and run clockwise. Edges are anti-aliased and blended over the existing
+/// image. Drawing is clipped to the image bounds.
+pub fn draw_donut(
+    rgba: &mut RgbaImage,
+    rect: Rect,
+    segments: &[(f32, Rgba<u8>)],
+    style: &DonutStyle,
+) {
+    let total: f32 = segments.iter().map(|(v, _)| v.max(0.0)).sum();
+    if total <= 0.0 || rgba.width() == 0 || rgba.height() == 0 {
+        return;
+    }
+    let cx = rect.left() as f32 + rect.width() as f32 / 2.0;
+    let cy = rect.top() as f32 + rect.height() as f32 / 2.0;
+    let outer = rect.width().min(rect.height()) as f32 / 2.0;
+    let inner = outer * style.hole.clamp(0.0, 0.95);
+    let half_gap = style.gap.max(0.0) / 2.0;
+
+    // Segment boundaries as cumulative fractions of a turn
+    let mut bounds = Vec::with_capacity(segments.len());
+    let mut acc = 0.0;
+    for &(value, color) in segments {
+        if value > 0.0 {
+            let end = acc + value / total;
+            bounds.push((acc, end, color));
+            acc = end;
+        }
+    }
+    let gaps = bounds.len() > 1 && half_gap > 0.0;
+
+    let x0 = (cx - outer).floor().max(0.0) as u32;
+    let y0 = (cy - outer).floor().max(0.0) as u32;
+    let x1 = ((cx + outer).ceil().max(0.0) as u32).min(rgba.width());
+    let y1 = ((cy + outer).ceil().max(0.0) as u32).min(rgba.height());
+    let step = 1.0 / SUPERSAMPLE as f32;
+    let samples = (SUPERSAMPLE * SUPERSAMPLE) as f32;
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let mut sum = [0.0f32; 4];
+            let mut hits = 0.0;
+            for sy in 0..SUPERSAMPLE {
+                for sx in 0..SUPERSAMPLE {
+                    let dx = x as f32 + (sx as f32 + 0.5) * step - cx;
+                    let dy = y as f32 + (sy as f32 + 0.5) * step - cy;
+                    let r = (dx * dx + dy * dy).sqrt();
+                    if r > outer || r < inner {
+                        continue;
+                    }
+                    // Fraction of a turn clockwise from the top
+                    let turn = ((dy.atan2(dx) + FRAC_PI_2) / TAU).rem_euclid(1.0);
+                    let Some(&(start, end, color)) =
+                        bounds.iter().find(|(s, e, _)| turn >= *s && turn < *e)
+                    else {
+                        continue;
+                    };
+                    // Distance along the arc to the nearest boundary, in pixels
+                    if gaps && ((turn - start).min(end - turn) * TAU * r) < half_gap {
+                        continue;
+                    }
+                    for c in 0..4 {
+                        sum[c] += color[c] as f32;
+                    }
+                    hits += 1.0;
+                }
+            }
+            if hits > 0.0 {
+                let color = Rgba(sum.map(|c| (c / hits).round() as u8));
+                let a = hits / samples * color[3] as f32 / 255.0;
+                blend(rgba.get_pixel_mut(x, y), color, a);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+
+    #[test]
+    fn test_donut_segments_in_proportion() {
+        let mut img = RgbaImage::new(64, 64);
+        let style = DonutStyle {
+            hole: 0.5,
+            gap: 0.0,
+        };
+        draw_donut(
+            &mut img,
+            Rect::at(0, 0).of_size(64, 64),
+            &[(1.0, RED), (3.0, BLUE)],
+            &style,
+        );
+        // Red covers the top-right quarter, blue the rest, hole stays empty
+        assert_eq!(*img.get_pixel(52, 12), RED);
+        assert_eq!(*img.get_pixel(12, 12), BLUE);
+        assert_eq!(*img.get_pixel(32, 56), BLUE);
+        assert_eq!(img.get_pixel(32, 32)[3], 0);
+    }
+
+    #[test]
+    fn test_donut_gap_and_antialiasing() {
+        let mut img = RgbaImage::new(64, 64);
+        let style = DonutStyle {
+            hole: 0.0,
+            gap: 4.0,
+        };
+        draw_donut(
+            &mut img,
+            Rect::at(0, 0).of_size(64, 64),
+            &[(1.0, RED), (1.0, BLUE)],
+            &style,
+        );
+        // Gap along the vertical boundary at the top
+        assert_eq!(img.get_pixel(32, 8)[3], 0);
+        // Outer edge has partial coverage
+        assert!(img.pixels().any(|p| p[3] > 0 && p[3] < 255));
+    }
+
+    #[test]
+    fn test_donut_empty_is_noop() {
+        let mut img = RgbaImage::new(16, 16);
+        draw_donut(
+            &mut img,
+            Rect::at(0, 0).of_size(16, 16),
+            &[(0.0, RED)],
+            &DonutStyle::default(),
+        );
+        assert!(img.pixels().all(|p| p[3] == 0));
+    }
+}
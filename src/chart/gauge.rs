@@ -0,0 +1,223 @@
+//! Arc gauge with automatic tick layout.
+
+use std::ops::RangeInclusive;
+
+use image::{Rgba, RgbaImage};
+use imageproc::drawing::draw_line_segment_mut;
+use imageproc::rect::Rect;
+
+use super::axis::ticks_in;
+use super::{Labeler, TickFormat, nice_step};
+use crate::colors::hex;
+
+/// Colors and proportions for [`draw_gauge`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GaugeStyle {
+    /// Unfilled arc color.
+    pub track: Rgba<u8>,
+    /// Filled arc color.
+    pub fill: Rgba<u8>,
+    /// Tick mark color.
+    pub tick: Rgba<u8>,
+    /// Tick label color.
+    pub label: Rgba<u8>,
+    /// Arc thickness as a fraction of the radius.
+    pub thickness: f32,
+    /// Total arc angle in degrees, centered on the bottom.
+    pub sweep: f32,
+    /// Upper bound on the number of ticks; fewer are used if labels would collide.
+    pub max_ticks: usize,
+    /// Whether to draw numeric tick labels.
+    pub labels: bool,
+    /// How tick labels are formatted.
+    pub format: TickFormat,
+}
+
+impl Default for GaugeStyle {
+    fn default() -> Self {
+        Self {
+            track: hex("#333333"),
+            fill: hex("#3DA5FF"),
+            tick: hex("#888888"),
+            label: hex("#CCCCCC"),
+            thickness: 0.18,
+            sweep: 270.0,
+            max_ticks: 6,
+            labels: true,
+            format: TickFormat::Auto,
+        }
+    }
+}
+
+/// Draw an arc gauge showing `value` within `range`, fitted inside `rect`.
+///
+/// Ticks are placed at a "nice" interval covering the range, and the tick
+/// count is reduced until the labels (measured with the system font) no
+/// longer overlap. Labels are skipped if the font is unavailable. Drawing
+/// is clipped to the image bounds. Nothing is drawn unless both ends of
+/// `range` are finite and `max` is above `min`.
+pub fn draw_gauge(
+    rgba: &mut RgbaImage,
+    rect: Rect,
+    value: f32,
+    range: RangeInclusive<f32>,
+    style: &GaugeStyle,
+) {
+    let (min, max) = (*range.start(), *range.end());
+    if !(min.is_finite() && max.is_finite())
+        || max <= min
+        || rgba.width() == 0
+        || rgba.height() == 0
+    {
+        return;
+    }
+    let cx = rect.left() as f32 + rect.width() as f32 / 2.0;
+    let cy = rect.top() as f32 + rect.height() as f32 / 2.0;
+    let outer = rect.width().min(rect.height()) as f32 / 2.0 - 0.5;
+    if outer < 2.0 {
+        return;
+    }
+    let inner = outer * (1.0 - style.thickness.clamp(0.05, 1.0));
+    let sweep = style.sweep.clamp(1.0, 360.0);
+    let start = 90.0 + (360.0 - sweep) / 2.0;
+    let filled = ((value - min) / (max - min)).clamp(0.0, 1.0);
+
+    // Arc band
+    let x0 = (cx - outer).floor().max(0.0) as u32;
+    let y0 = (cy - outer).floor().max(0.0) as u32;
+    let x1 = ((cx + outer).ceil() as u32).min(rgba.width());
+    let y1 = ((cy + outer).ceil() as u32).min(rgba.height());
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let dx = x as f32 + 0.5 - cx;
+            let dy = y as f32 + 0.5 - cy;
+            let r = (dx * dx + dy * dy).sqrt();
+            if r < inner || r > outer {
+                continue;
+            }
+            let rel = (dy.atan2(dx).to_degrees() - start).rem_euclid(360.0);
+            if rel <= sweep {
+                let color = if rel / sweep <= filled {
+                    style.fill
+                } else {
+                    style.track
+                };
+                rgba.put_pixel(x, y, color);
+            }
+        }
+    }
+
+    // Ticks and labels
+    let point = |fraction: f32, radius: f32| {
+        let angle = (start + fraction * sweep).to_radians();
+        (cx + angle.cos() * radius, cy + angle.sin() * radius)
+    };
+    let tick_len = (outer * 0.12).max(2.0);
+    let labeler = style
+        .labels
+        .then(|| Labeler::new((outer * 0.22).max(7.0)))
+        .flatten();
+    let label_radius = inner - tick_len - outer * 0.14;
+
+    let span = (max - min) as f64;
+    let mut ticks = Vec::new();
+    for n in (2..=style.max_ticks.max(2)).rev() {
+        let step = nice_step(span, n);
+        ticks = ticks_in(min as f64, max as f64, step)
+            .into_iter()
+            .map(|v| (v, style.format.format(v, step)))
+            .collect();
+        let Some(labeler) = &labeler else {
+            break;
+        };
+        // Chord between adjacent label centers must fit the widest label
+        let widest = ticks
+            .iter()
+            .map(|(_, t)| labeler.size(t).0)
+            .fold(0.0, f32::max);
+        let angle = (step / span) as f32 * sweep.to_radians();
+        let chord = 2.0 * label_radius * (angle / 2.0).sin();
+        if chord >= widest + 2.0 {
+            break;
+        }
+    }
+
+    for (v, text) in &ticks {
+        let fraction = ((*v - min as f64) / span) as f32;
+        let a = point(fraction, inner - 1.0);
+        let b = point(fraction, inner - tick_len);
+        draw_line_segment_mut(rgba, a, b, style.tick);
+        if let Some(labeler) = &labeler {
+            let (lx, ly) = point(fraction, label_radius);
+            labeler.draw_centered(rgba, text, lx, ly, style.label);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gauge_fills_proportionally() {
+        let style = GaugeStyle {
+            labels: false,
+            ..Default::default()
+        };
+        let mut img = RgbaImage::new(64, 64);
+        draw_gauge(
+            &mut img,
+            Rect::at(0, 0).of_size(64, 64),
+            50.0,
+            0.0..=100.0,
+            &style,
+        );
+        let radius = 31.5 * (1.0 - style.thickness / 2.0);
+        // Left of center is filled, right of center is still track
+        assert_eq!(*img.get_pixel((32.0 - radius) as u32, 32), style.fill);
+        assert_eq!(*img.get_pixel((32.0 + radius) as u32, 32), style.track);
+        // Gap at the bottom of the arc stays untouched
+        assert_eq!(img.get_pixel(32, 62)[3], 0);
+    }
+
+    #[test]
+    fn test_gauge_labels_drawn() {
+        if crate::font::get_system_monospace_font().is_none() {
+            return;
+        }
+        let style = GaugeStyle::default();
+        let mut img = RgbaImage::new(96, 96);
+        draw_gauge(
+            &mut img,
+            Rect::at(0, 0).of_size(96, 96),
+            0.0,
+            0.0..=100.0,
+            &style,
+        );
+        // Label glyphs are the only pixels lighter than the tick color
+        assert!(img.pixels().any(|p| p[0] > style.tick[0] && p[0] == p[2]));
+    }
+
+    #[test]
+    fn test_gauge_invalid_range_is_noop() {
+        let mut img = RgbaImage::new(16, 16);
+        draw_gauge(
+            &mut img,
+            Rect::at(0, 0).of_size(16, 16),
+            1.0,
+            5.0..=5.0,
+            &GaugeStyle::default(),
+        );
+        assert!(img.pixels().all(|p| p[3] == 0));
+        for range in [0.0..=f32::INFINITY, f32::NEG_INFINITY..=0.0, f32::NAN..=1.0] {
+            draw_gauge(
+                &mut img,
+                Rect::at(0, 0).of_size(16, 16),
+                1.0,
+                range,
+                &GaugeStyle::default(),
+            );
+        }
+        assert!(img.pixels().all(|p| p[3] == 0));
+    }
+}
@@ -0,0 +1,189 @@
+//! Polar (radar/spider) chart.
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use image::{GrayImage, Luma, Rgba, RgbaImage};
+use imageproc::drawing::{draw_hollow_polygon_mut, draw_line_segment_mut, draw_polygon_mut};
+use imageproc::point::Point;
+use imageproc::rect::Rect;
+
+use super::{Labeler, blend};
+use crate::colors::hex;
+
+/// Colors and layout for [`draw_radar`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RadarStyle {
+    /// Spoke and ring color.
+    pub grid: Rgba<u8>,
+    /// Value polygon fill; its alpha sets the fill opacity.
+    pub fill: Rgba<u8>,
+    /// Value polygon outline.
+    pub outline: Rgba<u8>,
+    /// Axis label color.
+    pub label: Rgba<u8>,
+    /// Number of concentric grid rings.
+    pub rings: usize,
+    /// Whether to draw axis labels around the chart.
+    pub labels: bool,
+}
+
+impl Default for RadarStyle {
+    fn default() -> Self {
+        Self {
+            grid: hex("#444444"),
+            fill: hex("#3DA5FF80"),
+            outline: hex("#3DA5FF"),
+            label: hex("#CCCCCC"),
+            rings: 3,
+            labels: true,
+        }
+    }
+}
+
+/// Draw an N-axis spider chart fitted inside `rect`.
+///
+/// Each axis is a `(label, value)` pair with `value` from 0.0 (center) to
+/// 1.0 (outer ring); values are clamped. The first axis points straight up
+/// and the rest follow clockwise. At least three axes are needed. Labels
+/// are skipped if the system font is unavailable. Drawing is clipped to
+/// the image bounds.
+pub fn draw_radar(rgba: &mut RgbaImage, rect: Rect, axes: &[(&str, f32)], style: &RadarStyle) {
+    let n = axes.len();
+    if n < 3 || rgba.width() == 0 || rgba.height() == 0 {
+        return;
+    }
+    let cx = rect.left() as f32 + rect.width() as f32 / 2.0;
+    let cy = rect.top() as f32 + rect.height() as f32 / 2.0;
+    let half = rect.width().min(rect.height()) as f32 / 2.0;
+    let labeler = style
+        .labels
+        .then(|| Labeler::new((half * 0.2).max(7.0)))
+        .flatten();
+    let angle = |i: usize| (i as f32 / n as f32) * TAU - FRAC_PI_2;
+    // Distance from the outer ring to each label's center along its spoke
+    let extents: Vec<(f32, f32, f32)> = match &labeler {
+        Some(labeler) => (0..n)
+            .map(|i| {
+                let (w, h) = labeler.size(axes[i].0);
+                let (cos, sin) = (angle(i).cos().abs(), angle(i).sin().abs());
+                (w, h, 1.0 + (cos * w + sin * h) / 2.0)
+            })
+            .collect(),
+        None => vec![(0.0, 0.0, 0.0); n],
+    };
+    // Shrink the chart until every label box fits inside the rect
+    let radius = (0..n)
+        .map(|i| {
+            let (w, h, extent) = extents[i];
+            let (cos, sin) = (angle(i).cos().abs(), angle(i).sin().abs());
+            let fit_x = if cos > 1e-3 {
+                (half - w / 2.0) / cos
+            } else {
+                f32::MAX
+            };
+            let fit_y = if sin > 1e-3 {
+                (half - h / 2.0) / sin
+            } else {
+                f32::MAX
+            };
+            fit_x.min(fit_y) - extent
+        })
+        .fold(half - 1.0, f32::min);
+    if radius < 2.0 {
+        return;
+    }
+
+    let point = |i: usize, r: f32| (cx + angle(i).cos() * r, cy + angle(i).sin() * r);
+
+    // Grid
+    for ring in 1..=style.rings {
+        let r = radius * ring as f32 / style.rings as f32;
+        let poly: Vec<Point<f32>> = (0..n)
+            .map(|i| point(i, r))
+            .map(|(x, y)| Point::new(x, y))
+            .collect();
+        draw_hollow_polygon_mut(rgba, &poly, style.grid);
+    }
+    for i in 0..n {
+        draw_line_segment_mut(rgba, (cx, cy), point(i, radius), style.grid);
+    }
+
+    // Value polygon, filled through a coverage mask so the fill alpha blends
+    let values: Vec<(f32, f32)> = axes
+        .iter()
+        .enumerate()
+        .map(|(i, (_, v))| point(i, radius * v.clamp(0.0, 1.0)))
+        .collect();
+    let mut mask = GrayImage::new(rgba.width(), rgba.height());
+    let mut corners: Vec<Point<i32>> = values
+        .iter()
+        .map(|(x, y)| Point::new(x.round() as i32, y.round() as i32))
+        .collect();
+    corners.dedup();
+    if corners.len() > 1 && corners.first() == corners.last() {
+        corners.pop();
+    }
+    if corners.len() >= 3 {
+        draw_polygon_mut(&mut mask, &corners, Luma([255]));
+    }
+    let a = style.fill[3] as f32 / 255.0;
+    for (x, y, m) in mask.enumerate_pixels() {
+        if m[0] > 0 {
+            blend(rgba.get_pixel_mut(x, y), style.fill, a);
+        }
+    }
+    let outline: Vec<Point<f32>> = values.iter().map(|&(x, y)| Point::new(x, y)).collect();
+    draw_hollow_polygon_mut(rgba, &outline, style.outline);
+
+    if let Some(labeler) = &labeler {
+        for (i, (label, _)) in axes.iter().enumerate() {
+            let (lx, ly) = point(i, radius + extents[i].2);
+            labeler.draw_centered(rgba, label, lx, ly, style.label);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style() -> RadarStyle {
+        RadarStyle {
+            labels: false,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_radar_fills_value_polygon() {
+        let mut img = RgbaImage::new(64, 64);
+        let axes = [("A", 1.0), ("B", 1.0), ("C", 1.0), ("D", 1.0)];
+        draw_radar(&mut img, Rect::at(0, 0).of_size(64, 64), &axes, &style());
+        // Center lies inside the polygon; the corner lies outside it
+        assert!(img.get_pixel(40, 40)[3] > 0);
+        assert_eq!(img.get_pixel(2, 2)[3], 0);
+    }
+
+    #[test]
+    fn test_radar_smaller_values_cover_less() {
+        let filled = |v: f32| {
+            let mut img = RgbaImage::new(64, 64);
+            let axes = [("A", v), ("B", v), ("C", v), ("D", v), ("E", v)];
+            draw_radar(&mut img, Rect::at(0, 0).of_size(64, 64), &axes, &style());
+            img.pixels().filter(|p| p[2] == style().fill[2]).count()
+        };
+        assert!(filled(0.3) < filled(0.9));
+    }
+
+    #[test]
+    fn test_radar_needs_three_axes() {
+        let mut img = RgbaImage::new(32, 32);
+        draw_radar(
+            &mut img,
+            Rect::at(0, 0).of_size(32, 32),
+            &[("A", 1.0), ("B", 1.0)],
+            &style(),
+        );
+        assert!(img.pixels().all(|p| p[3] == 0));
+    }
+}
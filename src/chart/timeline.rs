@@ -0,0 +1,235 @@
+//! Gantt-style timeline strip.
+
+use std::ops::Range;
+use std::time::SystemTime;
+
+use image::{Rgba, RgbaImage};
+use imageproc::drawing::draw_filled_rect_mut;
+use imageproc::rect::Rect;
+
+use super::Labeler;
+use crate::cancel::CancelToken;
+use crate::colors::{hex, readable_on};
+
+/// Colors and layout for [`draw_timeline`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimelineStyle {
+    /// Strip background behind the spans.
+    pub track: Rgba<u8>,
+    /// Pixels left between stacked lanes and adjacent spans.
+    pub gap: u32,
+    /// Whether to draw span labels where they fit.
+    pub labels: bool,
+}
+
+impl Default for TimelineStyle {
+    fn default() -> Self {
+        Self {
+            track: hex("#222222"),
+            gap: 1,
+            labels: true,
+        }
+    }
+}
+
+/// Draw `(start, end, color, label)` spans as horizontal bars within `window`.
+///
+/// The window maps onto the width of `rect`; spans are clipped to it and
+/// those entirely outside are skipped. Overlapping spans are stacked into
+/// lanes that share the strip height. Labels are drawn in black or white
+/// (whichever contrasts with the span) when they fit inside the bar.
+/// Drawing is clipped to the image bounds.
+pub fn draw_timeline(
+    rgba: &mut RgbaImage,
+    rect: Rect,
+    spans: &[(SystemTime, SystemTime, Rgba<u8>, &str)],
+    window: Range<SystemTime>,
+    style: &TimelineStyle,
+) {
+    draw_timeline_cancelable(rgba, rect, spans, window, style, &CancelToken::new());
+}
+
+/// Like [`draw_timeline`], but stops early once `cancel` is canceled,
+/// checking before each span. Returns whether the chart was drawn in
+/// full; a canceled chart is left partly drawn.
+pub fn draw_timeline_cancelable(
+    rgba: &mut RgbaImage,
+    rect: Rect,
+    spans: &[(SystemTime, SystemTime, Rgba<u8>, &str)],
+    window: Range<SystemTime>,
+    style: &TimelineStyle,
+    cancel: &CancelToken,
+) -> bool {
+    if rgba.width() == 0 || rgba.height() == 0 {
+        return true;
+    }
+    let bounds = Rect::at(0, 0).of_size(rgba.width(), rgba.height());
+    let Some(clipped) = rect.intersect(bounds) else {
+        return true;
+    };
+    draw_filled_rect_mut(rgba, clipped, style.track);
+    let Ok(length) = window.end.duration_since(window.start) else {
+        return true;
+    };
+    if length.is_zero() {
+        return true;
+    }
+    let offset = |t: SystemTime| match t.duration_since(window.start) {
+        Ok(d) => (d.as_secs_f64() / length.as_secs_f64()).min(1.0),
+        Err(_) => 0.0,
+    };
+
+    // Visible spans as (x0, x1) fractions, sorted by start
+    let mut visible: Vec<(f64, f64, Rgba<u8>, &str)> = spans
+        .iter()
+        .filter(|(start, end, _, _)| *end > window.start && *start < window.end && end > start)
+        .map(|&(start, end, color, label)| (offset(start), offset(end), color, label))
+        .collect();
+    visible.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    // Greedy lane assignment: first lane whose last span has ended
+    let mut lane_ends: Vec<f64> = Vec::new();
+    let lanes: Vec<usize> = visible
+        .iter()
+        .map(
+            |(x0, x1, _, _)| match lane_ends.iter().position(|end| end <= x0) {
+                Some(lane) => {
+                    lane_ends[lane] = *x1;
+                    lane
+                }
+                None => {
+                    lane_ends.push(*x1);
+                    lane_ends.len() - 1
+                }
+            },
+        )
+        .collect();
+    let lane_count = lane_ends.len().max(1) as u32;
+    let lane_height =
+        (rect.height().saturating_sub(style.gap * (lane_count - 1)) / lane_count).max(1);
+    let labeler = style
+        .labels
+        .then(|| Labeler::new(lane_height as f32 * 0.7))
+        .flatten();
+
+    let width = rect.width() as f64;
+    for ((x0, x1, color, label), lane) in visible.iter().zip(lanes) {
+        if cancel.is_canceled() {
+            return false;
+        }
+        let left = rect.left() + (x0 * width).round() as i32;
+        let right = rect.left() + (x1 * width).round() as i32 - style.gap as i32;
+        let top = rect.top() + (lane as u32 * (lane_height + style.gap)) as i32;
+        let bar = Rect::at(left, top).of_size((right - left).max(1) as u32, lane_height);
+        if let Some(visible_bar) = bar.intersect(bounds) {
+            draw_filled_rect_mut(rgba, visible_bar, *color);
+        }
+        if let Some(labeler) = &labeler
+            && !label.is_empty()
+        {
+            let (w, _) = labeler.size(label);
+            if w + 2.0 <= bar.width() as f32 {
+                let text_color = readable_on(*color);
+                let cx = bar.left() as f32 + bar.width() as f32 / 2.0;
+                let cy = bar.top() as f32 + bar.height() as f32 / 2.0;
+                labeler.draw_centered(rgba, label, cx, cy, text_color);
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    const RED: Rgba<u8> = Rgba([200, 0, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 200, 255]);
+
+    fn at(hours: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(hours * 3600)
+    }
+
+    #[test]
+    fn test_timeline_maps_window_to_width() {
+        let style = TimelineStyle {
+            gap: 0,
+            labels: false,
+            ..Default::default()
+        };
+        let mut img = RgbaImage::new(24, 4);
+        let spans = [(at(6), at(12), RED, ""), (at(20), at(30), BLUE, "")];
+        draw_timeline(
+            &mut img,
+            Rect::at(0, 0).of_size(24, 4),
+            &spans,
+            at(0)..at(24),
+            &style,
+        );
+        assert_eq!(*img.get_pixel(5, 1), style.track);
+        assert_eq!(*img.get_pixel(6, 1), RED);
+        assert_eq!(*img.get_pixel(11, 1), RED);
+        assert_eq!(*img.get_pixel(12, 1), style.track);
+        // Clipped at the end of the window
+        assert_eq!(*img.get_pixel(23, 1), BLUE);
+    }
+
+    #[test]
+    fn test_timeline_stacks_overlaps_into_lanes() {
+        let style = TimelineStyle {
+            gap: 0,
+            labels: false,
+            ..Default::default()
+        };
+        let mut img = RgbaImage::new(24, 8);
+        let spans = [(at(0), at(12), RED, ""), (at(6), at(18), BLUE, "")];
+        draw_timeline(
+            &mut img,
+            Rect::at(0, 0).of_size(24, 8),
+            &spans,
+            at(0)..at(24),
+            &style,
+        );
+        assert_eq!(*img.get_pixel(8, 1), RED);
+        assert_eq!(*img.get_pixel(8, 6), BLUE);
+    }
+
+    #[test]
+    fn test_timeline_skips_spans_outside_window() {
+        let style = TimelineStyle::default();
+        let mut img = RgbaImage::new(24, 4);
+        let spans = [(at(30), at(40), RED, "late")];
+        draw_timeline(
+            &mut img,
+            Rect::at(0, 0).of_size(24, 4),
+            &spans,
+            at(0)..at(24),
+            &style,
+        );
+        assert!(img.pixels().all(|p| *p == style.track));
+    }
+
+    #[test]
+    fn test_timeline_cancelable() {
+        let style = TimelineStyle {
+            labels: false,
+            ..TimelineStyle::default()
+        };
+        let spans = [(at(0), at(1), RED, "")];
+        let rect = Rect::at(0, 0).of_size(10, 4);
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let mut img = RgbaImage::new(10, 4);
+        assert!(!draw_timeline_cancelable(
+            &mut img,
+            rect,
+            &spans,
+            at(0)..at(2),
+            &style,
+            &cancel
+        ));
+        assert_eq!(*img.get_pixel(0, 0), style.track);
+    }
+}
@@ -1,41 +1,154 @@
 //! Color parsing module.
 //!
 //! Supports:
-//! - CSS named colors (based on CSS Color Module Level 4)
+//! - CSS named colors (based on CSS Color Module Level 4), also available as
+//!   [`css`] constants
 //! - Hex colors: #RGB, #RRGGBB, #RRGGBBAA
+//! - Functional notation: rgb(), rgba(), hsl(), hsla(), hwb(), lab(), lch(),
+//!   oklab(), oklch(), color-mix()
+//! - Gradients: linear-gradient(), radial-gradient() via [`parse_gradient`]
+//!
+//! Also provides conversions to and from perceptual color spaces (CIE Lab/LCH
+//! and OKLab/OKLCH), HSV and CMYK, CIEDE2000 [`delta_e`] color differences,
+//! perceptual adjustments such as [`lighten`] and [`desaturate`],
+//! color-vision-deficiency simulation, multi-stop [`Gradient`]s sampled in a
+//! chosen [`Interpolation`] space, threshold [`Scale`]s, scientific
+//! [`colormap`]s, [`palette`] harmonies and [`distinct_colors`], [`Color`]
+//! and [`ColorMap`] types that parse from strings (and implement serde traits
+//! with the `serde` feature), and a layered [`ColorResolver`].
+//!
+//! [`srgb_to_linear`] and [`linear_to_srgb`] convert channels to and from
+//! linear light, where [`mix_linear`] and [`blend_linear`] do their math;
+//! the crate's own brightness and blending effects work the same way.
 //!
 //! Reference: https://www.w3.org/TR/css-color-4/#named-colors
 
 use std::collections::HashMap;
+use std::sync::LazyLock;
 
 use image::Rgba;
 
+use crate::image::pixel_math::Factor;
+
+mod adjust;
+mod color;
+mod color_map;
+pub mod colormap;
+pub mod css;
+mod cvd;
+mod functional;
+mod gradient;
+pub mod palette;
+mod resolver;
+mod scale;
+mod spaces;
+mod theme;
+
+pub use adjust::{darken, desaturate, lighten, rotate_hue, saturate};
+pub use color::{Color, ColorParseErrors, ParseColorError};
+pub use color_map::ColorMap;
+pub use cvd::{CvdType, simulate_cvd};
+pub use gradient::{Gradient, parse_gradient};
+pub use palette::distinct_colors;
+pub use resolver::ColorResolver;
+pub use scale::{Scale, ScaleMode};
+pub use spaces::{
+    Cmyk, Hsv, Interpolation, Lab, Lch, Oklab, Oklch, delta_e, from_cmyk, from_hsv, from_lab,
+    from_lch, from_oklab, from_oklch, to_cmyk, to_hsv, to_lab, to_lch, to_oklab, to_oklch,
+};
+pub use theme::Theme;
+
 /// Parse a color from a string.
 ///
 /// Supports:
-/// - CSS named colors (e.g., "red", "steelblue", "rebeccapurple")
+/// - CSS named colors (e.g., "red", "steelblue", "rebeccapurple") and
+///   "transparent" (transparent black)
 /// - Hex with '#' prefix: #RGB, #RRGGBB, #RRGGBBAA
+/// - CSS rgb()/rgba() in comma or space-separated form, with number or
+///   percentage components (e.g., "rgb(255, 107, 53)", "rgba(255 107 53 / 0.5)")
+/// - CSS hsl()/hsla() with hue in degrees or angle units (e.g., "hsl(210, 50%, 40%)")
+/// - CSS hwb() with optional alpha (e.g., "hwb(210 20% 40% / 0.8)")
+/// - CSS lab() and lch() (e.g., "lab(54 81 70)", "lch(54% 107 41 / 0.5)")
+/// - CSS oklab() and oklch() (e.g., "oklch(0.63 0.26 29)")
+/// - CSS color-mix() in srgb, srgb-linear, hsl, lab, lch, oklab or oklch
+///   (e.g., "color-mix(in oklch, red 30%, blue)")
 ///
 /// Alpha defaults to 0xFF if not specified.
-/// Lookup is case-insensitive.
+/// Lookup is case-insensitive and ignores surrounding whitespace.
+/// "currentcolor" has no value here; use [`lookup_with_current`] to
+/// resolve it.
 pub fn lookup<S>(s: S) -> Option<Rgba<u8>>
 where
     S: AsRef<str>,
 {
-    let s = s.as_ref();
+    lookup_in(s.as_ref(), None)
+}
 
+/// Parse a color like [`lookup`], resolving the CSS `currentcolor` keyword
+/// to `current`, including inside `color-mix()`.
+///
+/// `current` is whatever the color is drawn alongside, usually the
+/// widget's foreground, so a config can say "the text color, but faded":
+///
+/// ```ignore
+/// let track = lookup_with_current("color-mix(in srgb, currentcolor 30%, transparent)", theme.fg);
+/// ```
+pub fn lookup_with_current<S>(s: S, current: Rgba<u8>) -> Option<Rgba<u8>>
+where
+    S: AsRef<str>,
+{
+    lookup_in(s.as_ref(), Some(current))
+}
+
+/// [`lookup`], with `currentcolor` resolving to `current` if given.
+pub(crate) fn lookup_in(s: &str, current: Option<Rgba<u8>>) -> Option<Rgba<u8>> {
+    let s = s.trim();
     // Try named color lookup first
     let lowercase = s.to_ascii_lowercase();
-    if let Some(rgba) = NAMED_COLORS
+    if let Some(rgba) = css::NAMED
         .iter()
         .find(|(n, _)| *n == lowercase)
         .map(|(_, rgba)| *rgba)
     {
         return Some(rgba);
     }
+    if lowercase == "currentcolor" {
+        return current;
+    }
 
-    // Fall back to hex parsing
-    parse_hex(s)
+    // Fall back to hex, then functional notation
+    parse_hex(s).or_else(|| functional::parse(&lowercase, current))
+}
+
+/// The CSS named color perceptually closest to `color`, with its value.
+///
+/// Distance is measured in OKLab, so the answer matches what a person
+/// would call the color rather than the nearest channel values. Alpha is
+/// ignored. Where names share a value ("gray"/"grey", "aqua"/"cyan") the
+/// alphabetically first is returned.
+///
+/// ```ignore
+/// let (name, _) = nearest_named(hex("#4A9EFF"));
+/// tracing::debug!("accent is roughly {name}"); // "cornflowerblue"
+/// ```
+pub fn nearest_named(color: Rgba<u8>) -> (&'static str, Rgba<u8>) {
+    let target = to_oklab(color);
+    let distance = |c: Rgba<u8>| {
+        let lab = to_oklab(c);
+        (lab.l - target.l).powi(2) + (lab.a - target.a).powi(2) + (lab.b - target.b).powi(2)
+    };
+    css::NAMED
+        .iter()
+        .map(|&(name, c)| (name, c, distance(c)))
+        .fold(
+            None,
+            |best: Option<(&str, Rgba<u8>, f32)>, entry| match best {
+                Some(b) if b.2 <= entry.2 => Some(b),
+                _ => Some(entry),
+            },
+        )
+        .map(|(name, c, _)| (name, c))
+        .expect("named color table is not empty")
 }
 
 /// Parse a hex color string.
@@ -92,55 +205,143 @@ const fn try_hex_digit(c: u8) -> Option<u8> {
     }
 }
 
-/// Parse a single hex digit to u8 at compile time. Panics on invalid input.
-const fn hex_digit(c: u8) -> u8 {
-    match try_hex_digit(c) {
-        Some(v) => v,
-        None => panic!("invalid hex digit"),
-    }
-}
-
 /// Parse a hex color string at compile time.
 ///
 /// Supports #RGB, #RRGGBB, and #RRGGBBAA formats.
 /// Alpha defaults to 0xFF if not specified.
-/// Panics on invalid input.
+/// Panics on invalid input; prefer [`rgba!`](crate::rgba) for literals,
+/// which reports the offending literal in the compile error.
 pub const fn hex(s: &str) -> Rgba<u8> {
-    let b = s.as_bytes();
-    assert!(b[0] == b'#', "expected hex color with '#' prefix");
-    match b.len() {
-        4 => {
-            // #RGB format - each digit is doubled, alpha = 0xFF
-            let r = hex_digit(b[1]);
-            let g = hex_digit(b[2]);
-            let b = hex_digit(b[3]);
-            Rgba([r * 17, g * 17, b * 17, 0xFF])
-        }
-        7 => {
-            // #RRGGBB format, alpha = 0xFF
-            Rgba([
-                hex_digit(b[1]) * 16 + hex_digit(b[2]),
-                hex_digit(b[3]) * 16 + hex_digit(b[4]),
-                hex_digit(b[5]) * 16 + hex_digit(b[6]),
-                0xFF,
-            ])
+    match parse_hex_const(s.as_bytes()) {
+        Ok(rgba) => rgba,
+        Err(e) => panic!("{}", e.message()),
+    }
+}
+
+/// Why a color literal failed to parse in a const context.
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstColorError {
+    /// Not a hex color and not a CSS color name.
+    Unknown,
+    /// A hex color with the wrong number of digits.
+    Length,
+    /// A hex color with a non-hex character.
+    Digit,
+}
+
+impl ConstColorError {
+    const fn message(self) -> &'static str {
+        match self {
+            ConstColorError::Unknown => "expected hex color with '#' prefix",
+            ConstColorError::Length => "expected #RGB, #RRGGBB, or #RRGGBBAA format",
+            ConstColorError::Digit => "invalid hex digit",
         }
-        9 => {
-            // #RRGGBBAA format
-            Rgba([
-                hex_digit(b[1]) * 16 + hex_digit(b[2]),
-                hex_digit(b[3]) * 16 + hex_digit(b[4]),
-                hex_digit(b[5]) * 16 + hex_digit(b[6]),
-                hex_digit(b[7]) * 16 + hex_digit(b[8]),
-            ])
+    }
+}
+
+/// Parse a hex color or CSS color name (case-insensitive) in a const
+/// context. Backs [`rgba!`](crate::rgba).
+#[doc(hidden)]
+pub const fn parse_const(s: &str) -> Result<Rgba<u8>, ConstColorError> {
+    let b = s.as_bytes();
+    if !b.is_empty() && b[0] == b'#' {
+        return parse_hex_const(b);
+    }
+    let mut i = 0;
+    while i < css::NAMED.len() {
+        let (name, rgba) = css::NAMED[i];
+        if name.as_bytes().eq_ignore_ascii_case(b) {
+            return Ok(rgba);
         }
-        _ => panic!("expected #RGB, #RRGGBB, or #RRGGBBAA format"),
+        i += 1;
+    }
+    Err(ConstColorError::Unknown)
+}
+
+const fn parse_hex_const(b: &[u8]) -> Result<Rgba<u8>, ConstColorError> {
+    if b.is_empty() || b[0] != b'#' {
+        return Err(ConstColorError::Unknown);
+    }
+    let digits = b.len() - 1;
+    if digits != 3 && digits != 6 && digits != 8 {
+        return Err(ConstColorError::Length);
+    }
+    let mut v = [0u8; 8];
+    let mut i = 0;
+    while i < digits {
+        v[i] = match try_hex_digit(b[i + 1]) {
+            Some(d) => d,
+            None => return Err(ConstColorError::Digit),
+        };
+        i += 1;
     }
+    Ok(match digits {
+        // #RGB format - each digit is doubled, alpha = 0xFF
+        3 => Rgba([v[0] * 17, v[1] * 17, v[2] * 17, 0xFF]),
+        // #RRGGBB format, alpha = 0xFF
+        6 => Rgba([v[0] * 16 + v[1], v[2] * 16 + v[3], v[4] * 16 + v[5], 0xFF]),
+        // #RRGGBBAA format
+        _ => Rgba([
+            v[0] * 16 + v[1],
+            v[2] * 16 + v[3],
+            v[4] * 16 + v[5],
+            v[6] * 16 + v[7],
+        ]),
+    })
+}
+
+/// A color literal checked at compile time.
+///
+/// Accepts the same hex forms as [`hex`](crate::colors::hex) plus CSS color
+/// names (case-insensitive). A malformed literal is a compile error that
+/// quotes the literal and says what is wrong with it, where `hex` would
+/// only report a const-eval panic. The result is a constant `Rgba<u8>`,
+/// usable in `const` items.
+///
+/// ```ignore
+/// use verandah_plugin_utils::rgba;
+///
+/// const ACCENT: Rgba<u8> = rgba!("#4A9EFF");
+/// const MUTED: Rgba<u8> = rgba!("SlateGray");
+/// const BAD: Rgba<u8> = rgba!("#4A9EFG");
+/// // error: evaluation panicked: invalid color "#4A9EFG": invalid hex digit
+/// ```
+#[macro_export]
+macro_rules! rgba {
+    ($color:literal) => {{
+        use $crate::colors::ConstColorError;
+        const COLOR: $crate::prelude::Rgba<u8> = match $crate::colors::parse_const($color) {
+            ::core::result::Result::Ok(rgba) => rgba,
+            ::core::result::Result::Err(ConstColorError::Unknown) => {
+                ::core::panic!(::core::concat!(
+                    "invalid color ",
+                    ::core::stringify!($color),
+                    ": expected #RGB, #RRGGBB, #RRGGBBAA or a CSS color name"
+                ))
+            }
+            ::core::result::Result::Err(ConstColorError::Length) => {
+                ::core::panic!(::core::concat!(
+                    "invalid color ",
+                    ::core::stringify!($color),
+                    ": expected 3, 6 or 8 hex digits"
+                ))
+            }
+            ::core::result::Result::Err(ConstColorError::Digit) => ::core::panic!(::core::concat!(
+                "invalid color ",
+                ::core::stringify!($color),
+                ": invalid hex digit"
+            )),
+        };
+        COLOR
+    }};
 }
 
 /// Parse a HashMap of color strings to RGBA values.
 ///
-/// Invalid colors are logged as warnings and skipped.
+/// Invalid colors are logged as warnings and skipped. [`ColorMap`] does the
+/// same and also records which keys failed; [`try_parse_colors`] reports
+/// them as an error instead.
 pub fn parse_colors(colors: &HashMap<String, String>) -> HashMap<String, Rgba<u8>> {
     let mut parsed = HashMap::new();
     for (key, value) in colors {
@@ -153,177 +354,267 @@ pub fn parse_colors(colors: &HashMap<String, String>) -> HashMap<String, Rgba<u8
     parsed
 }
 
+/// Parse a HashMap of color strings to RGBA values, failing on any invalid
+/// color.
+///
+/// Unlike [`parse_colors`], nothing is skipped: the error lists every
+/// invalid key with its value, so a plugin can show config mistakes to the
+/// user rather than only in the log.
+///
+/// ```ignore
+/// match try_parse_colors(&config.colors) {
+///     Ok(colors) => self.colors = colors,
+///     Err(errors) => {
+///         for (key, e) in errors.iter() {
+///             self.show_error(format!("colors.{key}: {e}"));
+///         }
+///     }
+/// }
+/// ```
+pub fn try_parse_colors(
+    colors: &HashMap<String, String>,
+) -> Result<HashMap<String, Rgba<u8>>, ColorParseErrors> {
+    let mut parsed = HashMap::new();
+    let mut errors = Vec::new();
+    for (key, value) in colors {
+        match value.parse::<Color>() {
+            Ok(Color(rgba)) => {
+                parsed.insert(key.clone(), rgba);
+            }
+            Err(e) => errors.push((key.clone(), e)),
+        }
+    }
+    if errors.is_empty() {
+        Ok(parsed)
+    } else {
+        Err(ColorParseErrors::new(errors))
+    }
+}
+
 /// Get a color from a parsed color map, returning a default if not found.
+///
+/// [`ColorResolver`] does the same across several maps, with fallbacks for
+/// dotted keys.
 pub fn get_color(colors: &HashMap<String, Rgba<u8>>, key: &str, default: Rgba<u8>) -> Rgba<u8> {
     colors.get(key).copied().unwrap_or(default)
 }
 
-const NUM_COLORS: usize = 148;
-
-/// CSS named colors as hex strings (sorted alphabetically).
-#[rustfmt::skip]
-const NAMED_COLOR_DATA: [(&str, &str); NUM_COLORS] = [
-    ("aliceblue",            "#F0F8FF"),
-    ("antiquewhite",         "#FAEBD7"),
-    ("aqua",                 "#00FFFF"),
-    ("aquamarine",           "#7FFFD4"),
-    ("azure",                "#F0FFFF"),
-    ("beige",                "#F5F5DC"),
-    ("bisque",               "#FFE4C4"),
-    ("black",                "#000000"),
-    ("blanchedalmond",       "#FFEBCD"),
-    ("blue",                 "#0000FF"),
-    ("blueviolet",           "#8A2BE2"),
-    ("brown",                "#A52A2A"),
-    ("burlywood",            "#DED887"),
-    ("cadetblue",            "#5F9EA0"),
-    ("chartreuse",           "#7FFF00"),
-    ("chocolate",            "#D2691E"),
-    ("coral",                "#FF7F50"),
-    ("cornflowerblue",       "#6495ED"),
-    ("cornsilk",             "#FFF8DC"),
-    ("crimson",              "#DC143C"),
-    ("cyan",                 "#00FFFF"),
-    ("darkblue",             "#00008B"),
-    ("darkcyan",             "#008B8B"),
-    ("darkgoldenrod",        "#B8860B"),
-    ("darkgray",             "#A9A9A9"),
-    ("darkgreen",            "#006400"),
-    ("darkgrey",             "#A9A9A9"),
-    ("darkkhaki",            "#BDB76B"),
-    ("darkmagenta",          "#8B008B"),
-    ("darkolivegreen",       "#556B2F"),
-    ("darkorange",           "#FF8C00"),
-    ("darkorchid",           "#9932CC"),
-    ("darkred",              "#8B0000"),
-    ("darksalmon",           "#E9967A"),
-    ("darkseagreen",         "#8FBC8F"),
-    ("darkslateblue",        "#483D8B"),
-    ("darkslategray",        "#2F4F4F"),
-    ("darkslategrey",        "#2F4F4F"),
-    ("darkturquoise",        "#00CED1"),
-    ("darkviolet",           "#9400D3"),
-    ("deeppink",             "#FF1493"),
-    ("deepskyblue",          "#00BFFF"),
-    ("dimgray",              "#696969"),
-    ("dimgrey",              "#696969"),
-    ("dodgerblue",           "#1E90FF"),
-    ("firebrick",            "#B22222"),
-    ("floralwhite",          "#FFFAF0"),
-    ("forestgreen",          "#228B22"),
-    ("fuchsia",              "#FF00FF"),
-    ("gainsboro",            "#DCDCDC"),
-    ("ghostwhite",           "#F8F8FF"),
-    ("gold",                 "#FFD700"),
-    ("goldenrod",            "#DAA520"),
-    ("gray",                 "#808080"),
-    ("green",                "#008000"),
-    ("greenyellow",          "#ADFF2F"),
-    ("grey",                 "#808080"),
-    ("honeydew",             "#F0FFF0"),
-    ("hotpink",              "#FF69B4"),
-    ("indianred",            "#CD5C5C"),
-    ("indigo",               "#4B0082"),
-    ("ivory",                "#FFFFF0"),
-    ("khaki",                "#F0E68C"),
-    ("lavender",             "#E6E6FA"),
-    ("lavenderblush",        "#FFF0F5"),
-    ("lawngreen",            "#7CFC00"),
-    ("lemonchiffon",         "#FFFACD"),
-    ("lightblue",            "#ADD8E6"),
-    ("lightcoral",           "#F08080"),
-    ("lightcyan",            "#E0FFFF"),
-    ("lightgoldenrodyellow", "#FAFAD2"),
-    ("lightgray",            "#D3D3D3"),
-    ("lightgreen",           "#90EE90"),
-    ("lightgrey",            "#D3D3D3"),
-    ("lightpink",            "#FFB6C1"),
-    ("lightsalmon",          "#FFA07A"),
-    ("lightseagreen",        "#20B2AA"),
-    ("lightskyblue",         "#87CEFA"),
-    ("lightslategray",       "#778899"),
-    ("lightslategrey",       "#778899"),
-    ("lightsteelblue",       "#B0C4DE"),
-    ("lightyellow",          "#FFFFE0"),
-    ("lime",                 "#00FF00"),
-    ("limegreen",            "#32CD32"),
-    ("linen",                "#FAF0E6"),
-    ("magenta",              "#FF00FF"),
-    ("maroon",               "#800000"),
-    ("mediumaquamarine",     "#66CDAA"),
-    ("mediumblue",           "#0000CD"),
-    ("mediumorchid",         "#BA55D3"),
-    ("mediumpurple",         "#9370DB"),
-    ("mediumseagreen",       "#3CB371"),
-    ("mediumslateblue",      "#7B68EE"),
-    ("mediumspringgreen",    "#00FA9A"),
-    ("mediumturquoise",      "#48D1CC"),
-    ("mediumvioletred",      "#C71585"),
-    ("midnightblue",         "#191970"),
-    ("mintcream",            "#F5FFFA"),
-    ("mistyrose",            "#FFE4E1"),
-    ("moccasin",             "#FFE4B5"),
-    ("navajowhite",          "#FFDEAD"),
-    ("navy",                 "#000080"),
-    ("oldlace",              "#FDF5E6"),
-    ("olive",                "#808000"),
-    ("olivedrab",            "#6B8E23"),
-    ("orange",               "#FFA500"),
-    ("orangered",            "#FF4500"),
-    ("orchid",               "#DA70D6"),
-    ("palegoldenrod",        "#EEE8AA"),
-    ("palegreen",            "#98FB98"),
-    ("paleturquoise",        "#AFEEEE"),
-    ("palevioletred",        "#DB7093"),
-    ("papayawhip",           "#FFEFD5"),
-    ("peachpuff",            "#FFDAB9"),
-    ("peru",                 "#CD853F"),
-    ("pink",                 "#FFC0CB"),
-    ("plum",                 "#DDA0DD"),
-    ("powderblue",           "#B0E0E6"),
-    ("purple",               "#800080"),
-    ("rebeccapurple",        "#663399"),
-    ("red",                  "#FF0000"),
-    ("rosybrown",            "#BC8F8F"),
-    ("royalblue",            "#4169E1"),
-    ("saddlebrown",          "#8B4513"),
-    ("salmon",               "#FA8072"),
-    ("sandybrown",           "#F4A460"),
-    ("seagreen",             "#2E8B57"),
-    ("seashell",             "#FFF5EE"),
-    ("sienna",               "#A0522D"),
-    ("silver",               "#C0C0C0"),
-    ("skyblue",              "#87CEEB"),
-    ("slateblue",            "#6A5ACD"),
-    ("slategray",            "#708090"),
-    ("slategrey",            "#708090"),
-    ("snow",                 "#FFFAFA"),
-    ("springgreen",          "#00FF7F"),
-    ("steelblue",            "#4682B4"),
-    ("tan",                  "#D2B48C"),
-    ("teal",                 "#008080"),
-    ("thistle",              "#D8BFD8"),
-    ("tomato",               "#FF6347"),
-    ("turquoise",            "#40E0D0"),
-    ("violet",               "#EE82EE"),
-    ("wheat",                "#F5DEB3"),
-    ("white",                "#FFFFFF"),
-    ("whitesmoke",           "#F5F5F5"),
-    ("yellow",               "#FFFF00"),
-    ("yellowgreen",          "#9ACD32"),
-];
-
-/// CSS named colors with their RGBA values (parsed at compile time).
-const NAMED_COLORS: [(&str, Rgba<u8>); NUM_COLORS] = {
-    let mut result: [(&str, Rgba<u8>); NUM_COLORS] = [("", Rgba([0, 0, 0, 0])); NUM_COLORS];
-    let mut i = 0;
-    while i < NUM_COLORS {
-        let (name, s) = NAMED_COLOR_DATA[i];
-        result[i] = (name, hex(s));
-        i += 1;
+/// Interpolate from `a` to `b` by `t` (0.0 to 1.0) in color space `space`.
+///
+/// Plain sRGB blending is what hand-rolled channel lerps do, and it dips
+/// towards grey between saturated colors; [`Interpolation::Oklch`] keeps
+/// the midpoint as vivid as the ends. Alpha is interpolated linearly, with
+/// colors premultiplied as CSS does.
+///
+/// ```ignore
+/// let mid = lerp(hex("#FF0000"), hex("#0000FF"), 0.5, Interpolation::Oklch);
+/// ```
+pub fn lerp(a: Rgba<u8>, b: Rgba<u8>, t: f32, space: Interpolation) -> Rgba<u8> {
+    spaces::interpolate(a, b, t, space)
+}
+
+/// The color of a black-body light source at `kelvin`, e.g. for showing a
+/// smart bulb's white temperature.
+///
+/// Uses Tanner Helland's curve fit, which is accurate to within a few
+/// levels per channel from 1000K (deep orange) through 6600K (white) to
+/// 40000K (pale blue). Temperatures outside that range are clamped.
+///
+/// ```ignore
+/// let warm = from_kelvin(2700.0);
+/// let daylight = from_kelvin(6500.0);
+/// ```
+pub fn from_kelvin(kelvin: f32) -> Rgba<u8> {
+    let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+    let channel = |v: f32| v.round().clamp(0.0, 255.0) as u8;
+    let (r, g) = if t <= 66.0 {
+        (255.0, 99.470_8 * t.ln() - 161.119_57)
+    } else {
+        (
+            329.698_73 * (t - 60.0).powf(-0.133_204_76),
+            288.122_17 * (t - 60.0).powf(-0.075_514_85),
+        )
+    };
+    let b = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.517_73 * (t - 10.0).ln() - 305.044_8
+    };
+    Rgba([channel(r), channel(g), channel(b), 255])
+}
+
+/// Composite `fg` over `bg` with the source-over operator.
+///
+/// Both colors use straight (non-premultiplied) alpha, as does the result.
+/// Compositing over a fully transparent `bg` returns `fg` unchanged.
+pub fn composite_over(fg: Rgba<u8>, bg: Rgba<u8>) -> Rgba<u8> {
+    let fa = fg[3] as f32 / 255.0;
+    let ba = bg[3] as f32 / 255.0;
+    let out_a = fa + ba * (1.0 - fa);
+    if out_a <= 0.0 {
+        return Rgba([0, 0, 0, 0]);
     }
-    result
-};
+    let mix = |f: u8, b: u8| {
+        let c = (f as f32 * fa + b as f32 * ba * (1.0 - fa)) / out_a;
+        c.round().clamp(0.0, 255.0) as u8
+    };
+    Rgba([
+        mix(fg[0], bg[0]),
+        mix(fg[1], bg[1]),
+        mix(fg[2], bg[2]),
+        (out_a * 255.0).round() as u8,
+    ])
+}
+
+/// Composite `fg` over an opaque `bg`, returning an opaque color.
+///
+/// The alpha of `bg` is ignored. Cheaper than [`composite_over`], using
+/// integer math only.
+pub fn composite_over_opaque(fg: Rgba<u8>, bg: Rgba<u8>) -> Rgba<u8> {
+    let a = fg[3] as u32;
+    let mix = |f: u8, b: u8| ((f as u32 * a + b as u32 * (255 - a) + 127) / 255) as u8;
+    Rgba([mix(fg[0], bg[0]), mix(fg[1], bg[1]), mix(fg[2], bg[2]), 255])
+}
+
+/// Convert a straight-alpha color to premultiplied alpha, scaling the color
+/// channels by the alpha.
+///
+/// Premultiplying loses precision at low alpha, so convert once when
+/// handing pixels to a backend rather than round-tripping repeatedly. See
+/// [`premultiply_image`](crate::image::premultiply_image) for whole images.
+pub fn premultiply(color: Rgba<u8>) -> Rgba<u8> {
+    let a = color[3] as u32;
+    let scale = |c: u8| ((c as u32 * a + 127) / 255) as u8;
+    Rgba([scale(color[0]), scale(color[1]), scale(color[2]), color[3]])
+}
+
+/// Convert a premultiplied-alpha color back to straight alpha.
+///
+/// Fully transparent colors become transparent black, and channels larger
+/// than the alpha (invalid when premultiplied) saturate at 255.
+pub fn unpremultiply(color: Rgba<u8>) -> Rgba<u8> {
+    let a = color[3] as u32;
+    if a == 0 {
+        return Rgba([0, 0, 0, 0]);
+    }
+    let scale = |c: u8| ((c as u32 * 255 + a / 2) / a).min(255) as u8;
+    Rgba([scale(color[0]), scale(color[1]), scale(color[2]), color[3]])
+}
+
+/// WCAG contrast ratio between two colors, from 1.0 (identical luminance)
+/// to 21.0 (black on white). Alpha is ignored.
+///
+/// WCAG AA asks for at least 4.5 for normal text and 3.0 for large text.
+pub fn contrast_ratio(a: Rgba<u8>, b: Rgba<u8>) -> f32 {
+    luminance_contrast(relative_luminance(a), relative_luminance(b))
+}
+
+/// Black or white, whichever is more readable on `bg`.
+///
+/// ```ignore
+/// let fg = readable_on(configured_bg);
+/// ```
+pub fn readable_on(bg: Rgba<u8>) -> Rgba<u8> {
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+    most_readable(bg, &[BLACK, WHITE]).unwrap_or(WHITE)
+}
+
+/// The candidate with the highest contrast ratio against `bg`, or `None`
+/// if `candidates` is empty. Ties go to the earlier candidate.
+pub fn most_readable(bg: Rgba<u8>, candidates: &[Rgba<u8>]) -> Option<Rgba<u8>> {
+    let bg_lum = relative_luminance(bg);
+    candidates
+        .iter()
+        .map(|&c| (c, luminance_contrast(relative_luminance(c), bg_lum)))
+        .fold(
+            None,
+            |best: Option<(Rgba<u8>, f32)>, (c, ratio)| match best {
+                Some((_, best_ratio)) if best_ratio >= ratio => best,
+                _ => Some((c, ratio)),
+            },
+        )
+        .map(|(c, _)| c)
+}
+
+/// Decoded linear-light value of every sRGB byte.
+static SRGB_TO_LINEAR: LazyLock<[f32; 256]> =
+    LazyLock::new(|| std::array::from_fn(|i| srgb_decode(i as f32 / 255.0)));
+
+/// Decode an sRGB channel byte to linear light, from 0.0 to 1.0.
+///
+/// Brightness and blending math should happen on these values: halving an
+/// sRGB byte darkens far more than halving the light it represents, which
+/// is what crushes midtones in naive effects. Table-backed, so cheap enough
+/// for per-pixel use.
+pub fn srgb_to_linear(c: u8) -> f32 {
+    SRGB_TO_LINEAR[c as usize]
+}
+
+/// Encode linear light (0.0 to 1.0, clamped) as an sRGB channel byte.
+///
+/// Inverse of [`srgb_to_linear`]; every byte survives a round trip.
+pub fn linear_to_srgb(v: f32) -> u8 {
+    (srgb_encode(v.clamp(0.0, 1.0)) * 255.0).round() as u8
+}
+
+/// Mix from `a` to `b` by `t` (0.0 to 1.0) in linear light.
+///
+/// The same as sampling a two-stop gradient in
+/// [`Interpolation::SrgbLinear`]: alpha is interpolated too, and color
+/// channels are premultiplied by it.
+pub fn mix_linear(a: Rgba<u8>, b: Rgba<u8>, t: f32) -> Rgba<u8> {
+    spaces::interpolate(a, b, t, Interpolation::SrgbLinear)
+}
+
+/// Blend `color` over `pixel` with opacity `alpha` (0.0 to 1.0) in linear
+/// light, leaving the pixel at least that opaque.
+///
+/// Blending a bright color over a dark one this way keeps the perceived
+/// brightness of the mix, where sRGB blending dips towards grey.
+pub fn blend_linear(pixel: &mut Rgba<u8>, color: Rgba<u8>, alpha: f32) {
+    let alpha = alpha.clamp(0.0, 1.0);
+    let weight = Factor::new(alpha);
+    for c in 0..3 {
+        pixel[c] = weight.blend_linear(pixel[c], color[c]);
+    }
+    pixel[3] = pixel[3].max((alpha * 255.0).round() as u8);
+}
+
+/// WCAG relative luminance of a color, from 0.0 (black) to 1.0 (white).
+///
+/// Alpha is ignored.
+pub(crate) fn relative_luminance(color: Rgba<u8>) -> f32 {
+    0.2126 * srgb_to_linear(color[0])
+        + 0.7152 * srgb_to_linear(color[1])
+        + 0.0722 * srgb_to_linear(color[2])
+}
+
+/// Decode an sRGB-encoded channel (0.0 to 1.0) to linear light.
+pub(crate) fn srgb_decode(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encode a linear-light channel (0.0 to 1.0) with the sRGB transfer curve.
+pub(crate) fn srgb_encode(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// WCAG contrast ratio between two relative luminances, from 1.0 to 21.0.
+pub(crate) fn luminance_contrast(a: f32, b: f32) -> f32 {
+    let (hi, lo) = if a > b { (a, b) } else { (b, a) };
+    (hi + 0.05) / (lo + 0.05)
+}
 
 #[cfg(test)]
 mod tests {
@@ -339,6 +630,32 @@ mod tests {
         assert_eq!(lookup("black"), Some(Rgba([0, 0, 0, 255])));
     }
 
+    #[test]
+    fn test_lookup_trims_whitespace() {
+        let red = Some(Rgba([255, 0, 0, 255]));
+        assert_eq!(lookup(" red"), red);
+        assert_eq!(lookup("\t#F00\n"), red);
+        assert_eq!(lookup(" rgb(255 0 0) "), red);
+    }
+
+    #[test]
+    fn test_lookup_transparent_and_currentcolor() {
+        assert_eq!(lookup("transparent"), Some(Rgba([0, 0, 0, 0])));
+        assert_eq!(lookup("Transparent"), Some(css::TRANSPARENT));
+        assert_eq!(lookup("currentcolor"), None);
+
+        let fg = Rgba([10, 20, 30, 255]);
+        assert_eq!(lookup_with_current("currentColor", fg), Some(fg));
+        assert_eq!(lookup_with_current(" currentcolor ", fg), Some(fg));
+        assert_eq!(lookup_with_current("red", fg), Some(css::RED));
+        assert_eq!(
+            lookup_with_current("color-mix(in srgb, currentcolor, white)", fg),
+            Some(Rgba([133, 138, 143, 255]))
+        );
+        // Black stays the nearest name for opaque black
+        assert_eq!(nearest_named(Rgba([0, 0, 0, 255])).0, "black");
+    }
+
     #[test]
     fn test_lookup_case_insensitive() {
         assert_eq!(lookup("RED"), Some(Rgba([255, 0, 0, 255])));
@@ -365,6 +682,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_nearest_named() {
+        assert_eq!(nearest_named(Rgba([255, 0, 0, 255])), ("red", css::RED));
+        assert_eq!(nearest_named(Rgba([128, 128, 128, 0])).0, "gray");
+        assert_eq!(nearest_named(hex("#4A9EFF")).0, "cornflowerblue");
+        assert_eq!(nearest_named(hex("#FE4602")).0, "orangered");
+    }
+
     // Hex color tests
     #[test]
     fn test_lookup_hex_rrggbb() {
@@ -417,6 +742,46 @@ mod tests {
         assert!(lookup("#zzzzzz").is_none());
     }
 
+    #[test]
+    fn test_try_parse_colors() {
+        let mut colors = HashMap::new();
+        colors.insert("fg".to_string(), "white".to_string());
+        colors.insert("bg".to_string(), "#12".to_string());
+        colors.insert("accent".to_string(), "blurple".to_string());
+        let errors = try_parse_colors(&colors).unwrap_err();
+        let keys: Vec<_> = errors.iter().map(|(k, e)| (k, e.input())).collect();
+        assert_eq!(keys, [("accent", "blurple"), ("bg", "#12")]);
+        assert_eq!(
+            errors.to_string(),
+            r##"accent: invalid color: "blurple"; bg: invalid color: "#12""##
+        );
+
+        colors.retain(|k, _| k == "fg");
+        let parsed = try_parse_colors(&colors).unwrap();
+        assert_eq!(parsed["fg"], css::WHITE);
+    }
+
+    #[test]
+    fn test_rgba_macro() {
+        const ACCENT: Rgba<u8> = crate::rgba!("#4A9EFF80");
+        assert_eq!(ACCENT, Rgba([0x4A, 0x9E, 0xFF, 0x80]));
+        assert_eq!(crate::rgba!("#F0A"), Rgba([255, 0, 170, 255]));
+        assert_eq!(crate::rgba!("SlateGray"), css::SLATEGRAY);
+        assert_eq!(parse_const("#12345"), Err(ConstColorError::Length));
+        assert_eq!(parse_const("#12345G"), Err(ConstColorError::Digit));
+        assert_eq!(parse_const("notacolor"), Err(ConstColorError::Unknown));
+    }
+
+    // Functional notation tests
+    #[test]
+    fn test_lookup_rgb_function() {
+        assert_eq!(lookup("rgb(255, 107, 53)"), Some(Rgba([255, 107, 53, 255])));
+        assert_eq!(
+            lookup(" RGBA(255 107 53 / 0.5) "),
+            Some(Rgba([255, 107, 53, 128]))
+        );
+    }
+
     #[test]
     fn test_lookup_unknown() {
         assert!(lookup("notacolor").is_none());
@@ -460,4 +825,165 @@ mod tests {
         const COLOR: Rgba<u8> = hex("#ff6b3580");
         assert_eq!(COLOR, Rgba([255, 107, 53, 128]));
     }
+
+    // Interpolation tests
+
+    #[test]
+    fn test_lerp_endpoints_in_every_space() {
+        let (a, b) = (hex("#FF0000"), hex("#0000FF"));
+        for space in [
+            Interpolation::Srgb,
+            Interpolation::SrgbLinear,
+            Interpolation::Hsl,
+            Interpolation::Lab,
+            Interpolation::Lch,
+            Interpolation::Oklab,
+            Interpolation::Oklch,
+        ] {
+            assert_eq!(lerp(a, b, 0.0, space), a, "{space:?}");
+            assert_eq!(lerp(a, b, 1.0, space), b, "{space:?}");
+        }
+    }
+
+    #[test]
+    fn test_lerp_polar_spaces_keep_saturation() {
+        let (red, blue) = (hex("#FF0000"), hex("#0000FF"));
+        let chroma = |c| to_oklch(c).c;
+        let srgb = lerp(red, blue, 0.5, Interpolation::Srgb);
+        let oklch = lerp(red, blue, 0.5, Interpolation::Oklch);
+        assert!(chroma(oklch) > chroma(srgb));
+        // HSL goes the short way round through magenta at full saturation
+        assert_eq!(lerp(red, blue, 0.5, Interpolation::Hsl), hex("#FF00FF"));
+        assert_eq!(
+            lerp(
+                Rgba([255, 0, 0, 255]),
+                Rgba([0, 0, 255, 0]),
+                0.5,
+                Interpolation::Srgb
+            ),
+            Rgba([255, 0, 0, 128])
+        );
+    }
+
+    // Color temperature tests
+
+    #[test]
+    fn test_from_kelvin() {
+        assert_eq!(from_kelvin(1000.0), Rgba([255, 68, 0, 255]));
+        assert_eq!(from_kelvin(6600.0), Rgba([255, 255, 255, 255]));
+        let cool = from_kelvin(12000.0);
+        assert!(cool[0] < cool[1] && cool[1] < cool[2]);
+        // Warmer temperatures have less blue, continuously across the fit's seam
+        let blues: Vec<u8> = (10..=120)
+            .map(|k| from_kelvin(k as f32 * 100.0)[2])
+            .collect();
+        assert!(blues.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(from_kelvin(500.0), from_kelvin(1000.0));
+        assert_eq!(from_kelvin(f32::INFINITY), from_kelvin(40000.0));
+    }
+
+    // Compositing tests
+
+    #[test]
+    fn test_composite_over() {
+        let half_red = Rgba([255, 0, 0, 128]);
+        let blue = Rgba([0, 0, 255, 255]);
+        assert_eq!(composite_over(half_red, blue), Rgba([128, 0, 127, 255]));
+        assert_eq!(composite_over(half_red, Rgba([0, 0, 0, 0])), half_red);
+        assert_eq!(composite_over(blue, half_red), blue);
+        assert_eq!(composite_over(Rgba([0, 0, 0, 0]), Rgba([0, 0, 0, 0]))[3], 0);
+
+        // Two half-transparent layers give 75% coverage
+        let both = composite_over(half_red, Rgba([0, 0, 255, 128]));
+        assert_eq!(both[3], 192);
+        assert!(both[0] > both[2]);
+    }
+
+    #[test]
+    fn test_composite_over_opaque() {
+        let half_white = Rgba([255, 255, 255, 128]);
+        let black = Rgba([0, 0, 0, 0]);
+        assert_eq!(
+            composite_over_opaque(half_white, black),
+            Rgba([128, 128, 128, 255])
+        );
+        assert_eq!(
+            composite_over_opaque(Rgba([10, 20, 30, 255]), black),
+            Rgba([10, 20, 30, 255])
+        );
+    }
+
+    #[test]
+    fn test_premultiply() {
+        assert_eq!(
+            premultiply(Rgba([255, 128, 0, 128])),
+            Rgba([128, 64, 0, 128])
+        );
+        assert_eq!(
+            premultiply(Rgba([10, 20, 30, 255])),
+            Rgba([10, 20, 30, 255])
+        );
+        assert_eq!(premultiply(Rgba([10, 20, 30, 0])), Rgba([0, 0, 0, 0]));
+
+        assert_eq!(
+            unpremultiply(Rgba([128, 64, 0, 128])),
+            Rgba([255, 128, 0, 128])
+        );
+        assert_eq!(
+            unpremultiply(Rgba([200, 20, 30, 100])),
+            Rgba([255, 51, 77, 100])
+        );
+        assert_eq!(unpremultiply(Rgba([9, 9, 9, 0])), Rgba([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_linear_conversions_and_blending() {
+        for c in 0..=255 {
+            assert_eq!(linear_to_srgb(srgb_to_linear(c)), c);
+        }
+        assert_eq!(srgb_to_linear(0), 0.0);
+        assert_eq!(srgb_to_linear(255), 1.0);
+        assert!((srgb_to_linear(128) - 0.2158).abs() < 1e-3);
+        assert_eq!(linear_to_srgb(0.5), 188);
+        assert_eq!(linear_to_srgb(-1.0), 0);
+
+        let black = Rgba([0, 0, 0, 255]);
+        let white = Rgba([255, 255, 255, 255]);
+        assert_eq!(mix_linear(black, white, 0.5), Rgba([188, 188, 188, 255]));
+
+        let mut pixel = Rgba([0, 0, 0, 0]);
+        blend_linear(&mut pixel, white, 0.5);
+        assert_eq!(pixel, Rgba([188, 188, 188, 128]));
+        blend_linear(&mut pixel, black, 0.0);
+        assert_eq!(pixel, Rgba([188, 188, 188, 128]));
+    }
+
+    // Contrast tests
+
+    #[test]
+    fn test_contrast_ratio() {
+        let black = Rgba([0, 0, 0, 255]);
+        let white = Rgba([255, 255, 255, 255]);
+        assert!((contrast_ratio(black, white) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio(white, black) - 21.0).abs() < 0.01);
+        assert_eq!(contrast_ratio(white, white), 1.0);
+        // #767676 is the lightest grey passing AA on white
+        let ratio = contrast_ratio(hex("#767676"), white);
+        assert!((4.5..4.6).contains(&ratio), "{ratio}");
+    }
+
+    #[test]
+    fn test_readable_on() {
+        assert_eq!(readable_on(hex("#FFD700")), Rgba([0, 0, 0, 255]));
+        assert_eq!(readable_on(hex("#1E1E1E")), Rgba([255, 255, 255, 255]));
+        assert_eq!(readable_on(hex("#0000FF")), Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn test_most_readable() {
+        let bg = hex("#202020");
+        let candidates = [hex("#404040"), hex("#FFCC00"), hex("#0050A0")];
+        assert_eq!(most_readable(bg, &candidates), Some(hex("#FFCC00")));
+        assert_eq!(most_readable(bg, &[]), None);
+    }
 }
@@ -0,0 +1,174 @@
+//! Perceptual color adjustments.
+//!
+//! Adjustments work in OKLCH so equal amounts look like equal changes
+//! across hues, and results are brought back into the sRGB gamut by
+//! reducing chroma rather than clipping channels.
+
+use image::Rgba;
+
+use super::spaces::{from_oklch_mapped, to_oklch};
+use super::{luminance_contrast, relative_luminance};
+
+/// Raise a color's perceptual lightness by `amount` (0.0 to 1.0, where 1.0
+/// always reaches white). Alpha is unchanged.
+///
+/// ```ignore
+/// let hover = lighten(accent, 0.1);
+/// ```
+pub fn lighten(color: Rgba<u8>, amount: f32) -> Rgba<u8> {
+    let mut lch = to_oklch(color);
+    lch.l = (lch.l + amount).clamp(0.0, 1.0);
+    from_oklch_mapped(lch)
+}
+
+/// Lower a color's perceptual lightness by `amount` (0.0 to 1.0, where 1.0
+/// always reaches black). Alpha is unchanged.
+pub fn darken(color: Rgba<u8>, amount: f32) -> Rgba<u8> {
+    lighten(color, -amount)
+}
+
+/// Scale a color's perceptual chroma up by `amount` (0.5 is 50% more
+/// colorful). The result is brought back into the sRGB gamut by reducing
+/// chroma, so very large amounts saturate as far as the gamut allows.
+/// Greys stay grey. Alpha is unchanged.
+pub fn saturate(color: Rgba<u8>, amount: f32) -> Rgba<u8> {
+    let mut lch = to_oklch(color);
+    lch.c = (lch.c * (1.0 + amount)).max(0.0);
+    from_oklch_mapped(lch)
+}
+
+/// Scale a color's perceptual chroma down by `amount` (0.0 to 1.0, where
+/// 1.0 gives a grey of the same lightness). Alpha is unchanged.
+///
+/// ```ignore
+/// let inactive = desaturate(accent, 0.7);
+/// ```
+pub fn desaturate(color: Rgba<u8>, amount: f32) -> Rgba<u8> {
+    saturate(color, -amount.clamp(0.0, 1.0))
+}
+
+/// Rotate a color's hue by `degrees` around the OKLCH hue circle, keeping
+/// perceptual lightness and chroma. Greys are unchanged. Alpha is unchanged.
+///
+/// ```ignore
+/// // Cycle through related colors over an animation
+/// let color = rotate_hue(base, phase(elapsed, period) * 360.0);
+/// ```
+pub fn rotate_hue(color: Rgba<u8>, degrees: f32) -> Rgba<u8> {
+    let mut lch = to_oklch(color);
+    lch.h = (lch.h + degrees).rem_euclid(360.0);
+    from_oklch_mapped(lch)
+}
+
+/// Adjust `color`'s lightness away from `bg` until their contrast ratio
+/// reaches `ratio`, keeping hue. Falls back to black or white if the
+/// ratio cannot be reached otherwise. Alpha is unchanged.
+pub(crate) fn ensure_contrast(color: Rgba<u8>, bg: Rgba<u8>, ratio: f32) -> Rgba<u8> {
+    let bg_lum = relative_luminance(bg);
+    let contrast = |c: Rgba<u8>| luminance_contrast(relative_luminance(c), bg_lum);
+    if contrast(color) >= ratio {
+        return color;
+    }
+    // Move toward whichever extreme offers more contrast with the background
+    let lighter = luminance_contrast(1.0, bg_lum) >= luminance_contrast(0.0, bg_lum);
+    for step in 1..=20 {
+        let amount = step as f32 * 0.05;
+        let candidate = if lighter {
+            lighten(color, amount)
+        } else {
+            darken(color, amount)
+        };
+        if contrast(candidate) >= ratio {
+            return candidate;
+        }
+    }
+    let extreme = if lighter { 255 } else { 0 };
+    Rgba([extreme, extreme, extreme, color[3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::relative_luminance;
+
+    const ACCENT: Rgba<u8> = Rgba([74, 158, 255, 255]);
+
+    #[test]
+    fn test_lighten_darken_direction() {
+        let base = relative_luminance(ACCENT);
+        assert!(relative_luminance(lighten(ACCENT, 0.1)) > base);
+        assert!(relative_luminance(darken(ACCENT, 0.1)) < base);
+        assert_eq!(lighten(ACCENT, 0.0), ACCENT);
+    }
+
+    #[test]
+    fn test_lighten_darken_extremes() {
+        assert_eq!(lighten(ACCENT, 1.0), Rgba([255, 255, 255, 255]));
+        assert_eq!(darken(ACCENT, 1.0), Rgba([0, 0, 0, 255]));
+        assert_eq!(darken(Rgba([255, 0, 0, 128]), 0.2)[3], 128);
+    }
+
+    #[test]
+    fn test_lighten_keeps_hue() {
+        let hue = to_oklch(ACCENT).h;
+        let lighter = to_oklch(lighten(ACCENT, 0.15)).h;
+        assert!((lighter - hue).abs() < 3.0, "{hue} -> {lighter}");
+    }
+
+    #[test]
+    fn test_saturate_desaturate() {
+        let chroma = |c| to_oklch(c).c;
+        let muted = Rgba([100, 140, 200, 255]);
+        assert!(chroma(saturate(muted, 0.2)) > chroma(muted));
+        let base = chroma(ACCENT);
+        assert!(chroma(desaturate(ACCENT, 0.5)) < base);
+        let grey = desaturate(ACCENT, 1.0);
+        assert!(grey[0].abs_diff(grey[1]) <= 1 && grey[1].abs_diff(grey[2]) <= 1);
+        assert!((to_oklch(grey).l - to_oklch(ACCENT).l).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_saturate_stays_in_gamut() {
+        // Huge amounts clamp to the gamut edge without shifting hue
+        let vivid = saturate(ACCENT, 10.0);
+        assert!((to_oklch(vivid).h - to_oklch(ACCENT).h).abs() < 3.0);
+        assert_eq!(
+            saturate(Rgba([128, 128, 128, 200]), 1.0),
+            Rgba([128, 128, 128, 200])
+        );
+    }
+
+    #[test]
+    fn test_rotate_hue() {
+        let base = to_oklch(ACCENT);
+        let rotated = to_oklch(rotate_hue(ACCENT, 120.0));
+        let delta = (rotated.h - base.h).rem_euclid(360.0);
+        assert!((delta - 120.0).abs() < 3.0, "rotated by {delta}");
+        assert!((rotated.l - base.l).abs() < 0.01);
+        // A full turn comes back to the start
+        let full = rotate_hue(ACCENT, 360.0);
+        assert!((0..3).all(|c| full[c].abs_diff(ACCENT[c]) <= 1));
+        assert_eq!(
+            rotate_hue(Rgba([90, 90, 90, 255]), 45.0),
+            Rgba([90, 90, 90, 255])
+        );
+    }
+
+    #[test]
+    fn test_ensure_contrast() {
+        let bg = Rgba([30, 30, 30, 255]);
+        let dim = Rgba([70, 90, 120, 255]);
+        let fixed = ensure_contrast(dim, bg, 7.0);
+        let ratio = |c| luminance_contrast(relative_luminance(c), relative_luminance(bg));
+        assert!(ratio(fixed) >= 7.0);
+        assert!(relative_luminance(fixed) > relative_luminance(dim));
+        // Already readable colors are untouched
+        assert_eq!(
+            ensure_contrast(Rgba([255, 255, 255, 255]), bg, 7.0),
+            Rgba([255, 255, 255, 255])
+        );
+        // Light backgrounds push darker
+        let on_white = ensure_contrast(dim, Rgba([250, 250, 250, 255]), 7.0);
+        assert!(relative_luminance(on_white) < relative_luminance(dim));
+    }
+}
@@ -0,0 +1,174 @@
+//! A parseable color value for use in plugin config structs.
+
+use std::fmt;
+use std::str::FromStr;
+
+use image::Rgba;
+
+use super::lookup;
+
+/// An `Rgba<u8>` that parses from and displays as a CSS color string.
+///
+/// Accepts any syntax [`lookup`] understands. With the `serde` feature it
+/// implements `Deserialize` and `Serialize`, so colors can sit directly in
+/// config structs:
+///
+/// ```ignore
+/// #[derive(Deserialize)]
+/// struct Config {
+///     fg: Color,
+///     bg: Option<Color>,
+/// }
+/// ```
+///
+/// Colors serialize as `#rrggbb`, or `#rrggbbaa` when not fully opaque.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color(pub Rgba<u8>);
+
+/// Error returned when a string is not a recognized color.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColorError(String);
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid color: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+impl ParseColorError {
+    /// The string that failed to parse.
+    pub fn input(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Errors from [`try_parse_colors`](super::try_parse_colors), one per
+/// invalid key, sorted by key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorParseErrors(Vec<(String, ParseColorError)>);
+
+impl ColorParseErrors {
+    pub(super) fn new(mut errors: Vec<(String, ParseColorError)>) -> Self {
+        errors.sort_by(|a, b| a.0.cmp(&b.0));
+        Self(errors)
+    }
+
+    /// Each invalid key with its error, sorted by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ParseColorError)> {
+        self.0.iter().map(|(key, e)| (key.as_str(), e))
+    }
+
+    /// Number of invalid keys.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Always false; an error has at least one invalid key.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Display for ColorParseErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (key, e)) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{key}: {e}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ColorParseErrors {}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        lookup(s)
+            .map(Color)
+            .ok_or_else(|| ParseColorError(s.to_string()))
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Rgba([r, g, b, a]) = self.0;
+        if a == 255 {
+            write!(f, "#{r:02x}{g:02x}{b:02x}")
+        } else {
+            write!(f, "#{r:02x}{g:02x}{b:02x}{a:02x}")
+        }
+    }
+}
+
+impl From<Rgba<u8>> for Color {
+    fn from(rgba: Rgba<u8>) -> Self {
+        Color(rgba)
+    }
+}
+
+impl From<Color> for Rgba<u8> {
+    fn from(color: Color) -> Self {
+        color.0
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Color {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Color {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_from_str() {
+        assert_eq!("red".parse(), Ok(Color(Rgba([255, 0, 0, 255]))));
+        assert_eq!(
+            "rgb(0 128 255 / 50%)".parse(),
+            Ok(Color(Rgba([0, 128, 255, 128])))
+        );
+        assert!("nope".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn test_color_display_round_trip() {
+        let opaque = Color(Rgba([255, 107, 53, 255]));
+        assert_eq!(opaque.to_string(), "#ff6b35");
+        let translucent = Color(Rgba([255, 107, 53, 128]));
+        assert_eq!(translucent.to_string(), "#ff6b3580");
+        assert_eq!(translucent.to_string().parse(), Ok(translucent));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_color_serde() {
+        let color: Color = serde_json::from_str("\"steelblue\"").unwrap();
+        assert_eq!(color, Color(Rgba([70, 130, 180, 255])));
+        assert_eq!(serde_json::to_string(&color).unwrap(), "\"#4682b4\"");
+        assert!(serde_json::from_str::<Color>("\"not-a-color\"").is_err());
+        assert!(serde_json::from_str::<Color>("42").is_err());
+    }
+}
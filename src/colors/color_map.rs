@@ -0,0 +1,162 @@
+//! A parsed map of named colors for plugin config.
+
+use std::collections::HashMap;
+
+use image::Rgba;
+
+use super::lookup;
+
+/// Named colors parsed from config, with a record of entries that failed.
+///
+/// A typed replacement for passing `HashMap<String, String>` through
+/// [`parse_colors`](super::parse_colors). With the `serde` feature it
+/// implements `Deserialize`, parsing each value as it is read, so it can
+/// sit directly in a config struct:
+///
+/// ```ignore
+/// #[derive(Deserialize)]
+/// struct Config {
+///     colors: ColorMap,
+/// }
+///
+/// let fg = config.colors.get_or("fg", hex("#FFFFFF"));
+/// ```
+///
+/// Values that are not valid colors do not fail deserialization; they are
+/// logged as warnings and listed by [`ColorMap::failed`], so a plugin can
+/// report them while still rendering with defaults.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColorMap {
+    colors: HashMap<String, Rgba<u8>>,
+    failed: Vec<String>,
+}
+
+impl ColorMap {
+    /// Parse `(key, color string)` pairs.
+    pub fn parse<K, V>(entries: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: AsRef<str>,
+    {
+        let mut map = Self::default();
+        for (key, value) in entries {
+            let key = key.into();
+            let value = value.as_ref();
+            match lookup(value) {
+                Some(rgba) => {
+                    map.colors.insert(key, rgba);
+                }
+                None => {
+                    tracing::warn!(key, value, "Invalid color format");
+                    map.failed.push(key);
+                }
+            }
+        }
+        map.failed.sort();
+        map
+    }
+
+    /// The color for `key`, if present and valid.
+    pub fn get(&self, key: &str) -> Option<Rgba<u8>> {
+        self.colors.get(key).copied()
+    }
+
+    /// The color for `key`, or `default` if missing or invalid.
+    pub fn get_or(&self, key: &str, default: Rgba<u8>) -> Rgba<u8> {
+        self.get(key).unwrap_or(default)
+    }
+
+    /// Keys whose values could not be parsed, sorted.
+    pub fn failed(&self) -> &[String] {
+        &self.failed
+    }
+
+    /// Number of valid colors.
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// Whether there are no valid colors.
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// Valid colors, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Rgba<u8>)> {
+        self.colors.iter().map(|(k, v)| (k.as_str(), *v))
+    }
+
+    /// The valid colors as a plain map, e.g. for
+    /// [`Theme::from_map`](super::Theme::from_map).
+    pub fn as_map(&self) -> &HashMap<String, Rgba<u8>> {
+        &self.colors
+    }
+}
+
+impl From<ColorMap> for HashMap<String, Rgba<u8>> {
+    fn from(map: ColorMap) -> Self {
+        map.colors
+    }
+}
+
+impl From<HashMap<String, Rgba<u8>>> for ColorMap {
+    fn from(colors: HashMap<String, Rgba<u8>>) -> Self {
+        Self {
+            colors,
+            failed: Vec::new(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ColorMap {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let raw = HashMap::<String, String>::deserialize(deserializer)?;
+        Ok(Self::parse(raw))
+    }
+}
+
+/// Serializes the valid colors only, as `#rrggbb` or `#rrggbbaa` strings.
+#[cfg(feature = "serde")]
+impl serde::Serialize for ColorMap {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_map(self.colors.iter().map(|(k, v)| (k, super::Color(*v))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_map_records_failures() {
+        let map = ColorMap::parse([("fg", "white"), ("bg", "#nope"), ("accent", "rgb(0 0 255)")]);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get("fg"), Some(Rgba([255, 255, 255, 255])));
+        assert_eq!(map.get("bg"), None);
+        assert_eq!(map.failed(), ["bg"]);
+        assert_eq!(map.get_or("bg", Rgba([1, 2, 3, 255])), Rgba([1, 2, 3, 255]));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_color_map_serde() {
+        let map: ColorMap =
+            serde_json::from_str(r#"{ "fg": "steelblue", "bg": "bogus" }"#).unwrap();
+        assert_eq!(map.get("fg"), Some(Rgba([70, 130, 180, 255])));
+        assert_eq!(map.failed(), ["bg"]);
+        assert_eq!(
+            serde_json::to_string(&map).unwrap(),
+            r##"{"fg":"#4682b4"}"##
+        );
+
+        // Values must still be strings
+        assert!(serde_json::from_str::<ColorMap>(r#"{ "fg": 42 }"#).is_err());
+    }
+}
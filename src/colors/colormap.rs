@@ -0,0 +1,247 @@
+//! Scientific colormaps for mapping values onto colors.
+//!
+//! The perceptually uniform maps from matplotlib (viridis, plasma, magma,
+//! inferno) change lightness evenly along their length, so equal steps in
+//! value look like equal steps in color and the ordering survives greyscale
+//! and color-vision deficiencies. Turbo trades that uniformity for more
+//! distinguishable hues, like a rainbow map without the false bands.
+//!
+//! Each map is evaluated from a polynomial fit to its reference table
+//! (matplotlib's for the first four, Google's published fit for turbo),
+//! within a few levels per channel of the originals.
+//!
+//! ```ignore
+//! // CPU temperature heat strip, 30 to 90 °C
+//! for (x, temp) in history.iter().enumerate() {
+//!     let t = (temp - 30.0) / 60.0;
+//!     draw_column(&mut img, x, Colormap::Inferno.sample(t));
+//! }
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use image::Rgba;
+
+use super::Gradient;
+
+/// A standard scientific colormap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Colormap {
+    /// Dark purple through teal to yellow. Matplotlib's default.
+    Viridis,
+    /// Dark blue through magenta to yellow.
+    Plasma,
+    /// Black through purple and pink to pale yellow.
+    Magma,
+    /// Black through purple and orange to pale yellow; higher contrast at
+    /// the top end than magma.
+    Inferno,
+    /// Dark blue through cyan, green and yellow to dark red.
+    Turbo,
+}
+
+/// Polynomial coefficients per channel, lowest degree first.
+type Coefficients = [[f64; 3]; 7];
+
+const VIRIDIS: Coefficients = [
+    [0.277_727_327, 0.005_407_345, 0.334_099_805],
+    [0.105_093_043, 1.404_613_530, 1.384_590_163],
+    [-0.330_861_829, 0.214_847_559, 0.095_095_163],
+    [-4.634_230_499, -5.799_100_973, -19.332_440_956],
+    [6.228_269_936, 14.179_933_367, 56.690_552_601],
+    [4.776_384_998, -13.745_145_378, -65.353_032_633],
+    [-5.435_455_856, 4.645_852_612, 26.312_435_250],
+];
+
+const PLASMA: Coefficients = [
+    [0.058_732_344, 0.023_336_709, 0.543_340_183],
+    [2.176_514_634, 0.238_383_417, 0.753_960_460],
+    [-2.689_460_476, -7.455_851_136, 3.110_799_940],
+    [6.130_348_346, 42.346_188_148, -28.518_854_653],
+    [-11.107_436_191, -82.666_311_094, 60.139_847_674],
+    [10.023_065_576, 71.413_617_701, -54.072_186_556],
+    [-3.658_713_843, -22.931_534_655, 18.191_907_785],
+];
+
+const MAGMA: Coefficients = [
+    [-0.002_136_485, -0.000_749_655, -0.005_386_128],
+    [0.251_660_541, 0.677_523_244, 2.494_026_599],
+    [8.353_717_279, -3.577_719_515, 0.314_467_903],
+    [-27.668_733_086, 14.264_730_781, -13.649_213_188],
+    [52.176_139_812, -27.943_606_072, 12.944_169_442],
+    [-50.768_525_365, 29.046_582_821, 4.234_152_994],
+    [18.655_705_066, -11.489_773_520, -5.601_961_509],
+];
+
+const INFERNO: Coefficients = [
+    [0.000_218_940, 0.001_651_005, -0.019_480_898],
+    [0.106_513_419, 0.563_956_437, 3.932_712_389],
+    [11.602_493_082, -3.972_853_966, -15.942_394_106],
+    [-41.703_996_131, 17.436_398_882, 44.354_145_199],
+    [77.162_935_699, -33.402_358_942, -81.807_309_257],
+    [-71.319_428_245, 32.626_064_264, 73.209_519_858],
+    [25.131_126_225, -12.242_668_952, -23.070_325_003],
+];
+
+const TURBO: Coefficients = [
+    [0.135_721_38, 0.091_402_61, 0.106_673_30],
+    [4.615_392_60, 2.194_188_39, 12.641_946_08],
+    [-42.660_322_58, 4.842_966_58, -60.582_048_36],
+    [132.131_082_34, -14.185_033_33, 110.362_767_71],
+    [-152.942_393_96, 4.277_298_57, -89.903_109_12],
+    [59.286_379_43, 2.829_566_04, 27.348_249_73],
+    [0.0, 0.0, 0.0],
+];
+
+impl Colormap {
+    /// Every colormap, in declaration order.
+    pub const ALL: [Colormap; 5] = [
+        Colormap::Viridis,
+        Colormap::Plasma,
+        Colormap::Magma,
+        Colormap::Inferno,
+        Colormap::Turbo,
+    ];
+
+    /// Lowercase name, as accepted by [`FromStr`].
+    pub const fn name(self) -> &'static str {
+        match self {
+            Colormap::Viridis => "viridis",
+            Colormap::Plasma => "plasma",
+            Colormap::Magma => "magma",
+            Colormap::Inferno => "inferno",
+            Colormap::Turbo => "turbo",
+        }
+    }
+
+    const fn coefficients(self) -> &'static Coefficients {
+        match self {
+            Colormap::Viridis => &VIRIDIS,
+            Colormap::Plasma => &PLASMA,
+            Colormap::Magma => &MAGMA,
+            Colormap::Inferno => &INFERNO,
+            Colormap::Turbo => &TURBO,
+        }
+    }
+
+    /// Color at position `t`, from 0.0 (low) to 1.0 (high).
+    ///
+    /// Positions outside that range are clamped; NaN maps to 0.0.
+    pub fn sample(self, t: f32) -> Rgba<u8> {
+        let t = if t.is_nan() {
+            0.0
+        } else {
+            t.clamp(0.0, 1.0) as f64
+        };
+        let coefficients = self.coefficients();
+        let channel = |c: usize| {
+            let v = coefficients.iter().rev().fold(0.0, |acc, k| acc * t + k[c]);
+            (v.clamp(0.0, 1.0) * 255.0).round() as u8
+        };
+        Rgba([channel(0), channel(1), channel(2), 255])
+    }
+
+    /// The colormap as a [`Gradient`] of `stops` evenly spaced samples
+    /// (at least 2), for APIs that take a gradient.
+    ///
+    /// 16 stops are indistinguishable from [`sample`](Self::sample).
+    pub fn gradient(self, stops: usize) -> Gradient {
+        let last = stops.max(2) - 1;
+        Gradient::new((0..=last).map(|i| {
+            let t = i as f32 / last as f32;
+            (t, self.sample(t))
+        }))
+    }
+}
+
+impl fmt::Display for Colormap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Error returned when parsing an unknown colormap name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColormapError(String);
+
+impl fmt::Display for ParseColormapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown colormap: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseColormapError {}
+
+impl FromStr for Colormap {
+    type Err = ParseColormapError;
+
+    /// Parse a colormap name, ignoring case and surrounding whitespace.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        Colormap::ALL
+            .into_iter()
+            .find(|map| map.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| ParseColormapError(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::{hex, relative_luminance};
+
+    fn assert_close(actual: Rgba<u8>, expected: Rgba<u8>) {
+        let diff = (0..3).map(|c| actual[c].abs_diff(expected[c])).max();
+        assert!(diff <= Some(6), "{actual:?} vs {expected:?}");
+    }
+
+    #[test]
+    fn test_colormaps_match_reference() {
+        // Reference colors at 0.0, 0.5 and 1.0
+        let cases = [
+            (Colormap::Viridis, ["#440154", "#21918C", "#FDE725"]),
+            (Colormap::Plasma, ["#0D0887", "#CC4778", "#F0F921"]),
+            (Colormap::Magma, ["#000004", "#B73779", "#FCFDBF"]),
+            (Colormap::Inferno, ["#000004", "#BC3754", "#FCFFA4"]),
+        ];
+        for (map, colors) in cases {
+            for (t, color) in [0.0, 0.5, 1.0].into_iter().zip(colors) {
+                assert_close(map.sample(t), hex(color));
+            }
+        }
+        assert_eq!(
+            Colormap::Viridis.sample(-1.0),
+            Colormap::Viridis.sample(0.0)
+        );
+        assert_eq!(
+            Colormap::Viridis.sample(f32::NAN),
+            Colormap::Viridis.sample(0.0)
+        );
+    }
+
+    #[test]
+    fn test_uniform_colormaps_increase_in_lightness() {
+        for map in &Colormap::ALL[..4] {
+            let lum: Vec<f32> = (0..=20)
+                .map(|i| relative_luminance(map.sample(i as f32 / 20.0)))
+                .collect();
+            assert!(lum.windows(2).all(|w| w[1] > w[0]), "{map}");
+        }
+    }
+
+    #[test]
+    fn test_colormap_gradient_and_names() {
+        let gradient = Colormap::Turbo.gradient(16);
+        assert_eq!(gradient.stops().len(), 16);
+        assert_eq!(gradient.sample(1.0), Colormap::Turbo.sample(1.0));
+        assert_close(gradient.sample(0.5), Colormap::Turbo.sample(0.5));
+        assert_eq!(Colormap::Magma.gradient(0).stops().len(), 2);
+
+        assert_eq!(" Inferno".parse(), Ok(Colormap::Inferno));
+        assert!("jet".parse::<Colormap>().is_err());
+        for map in Colormap::ALL {
+            assert_eq!(map.to_string().parse(), Ok(map));
+        }
+    }
+}
@@ -0,0 +1,194 @@
+//! CSS named colors as constants.
+//!
+//! Every color [`lookup`](super::lookup) accepts by name, as a
+//! compile-time `Rgba<u8>`:
+//!
+//! ```ignore
+//! use verandah_plugin_utils::colors::css;
+//!
+//! const OK: Rgba<u8> = css::SEAGREEN;
+//! ```
+
+use image::Rgba;
+
+use super::hex;
+
+macro_rules! named_colors {
+    ($($name:ident $css:literal $hex:literal,)*) => {
+        $(
+            #[doc = concat!("CSS `", $css, "` (", $hex, ").")]
+            pub const $name: Rgba<u8> = hex($hex);
+        )*
+
+        /// Every named color with its CSS name (sorted alphabetically).
+        pub(super) const NAMED: &[(&str, Rgba<u8>)] = &[$(($css, $name)),*];
+    };
+}
+
+#[rustfmt::skip]
+named_colors! {
+    ALICEBLUE            "aliceblue"            "#F0F8FF",
+    ANTIQUEWHITE         "antiquewhite"         "#FAEBD7",
+    AQUA                 "aqua"                 "#00FFFF",
+    AQUAMARINE           "aquamarine"           "#7FFFD4",
+    AZURE                "azure"                "#F0FFFF",
+    BEIGE                "beige"                "#F5F5DC",
+    BISQUE               "bisque"               "#FFE4C4",
+    BLACK                "black"                "#000000",
+    BLANCHEDALMOND       "blanchedalmond"       "#FFEBCD",
+    BLUE                 "blue"                 "#0000FF",
+    BLUEVIOLET           "blueviolet"           "#8A2BE2",
+    BROWN                "brown"                "#A52A2A",
+    BURLYWOOD            "burlywood"            "#DEB887",
+    CADETBLUE            "cadetblue"            "#5F9EA0",
+    CHARTREUSE           "chartreuse"           "#7FFF00",
+    CHOCOLATE            "chocolate"            "#D2691E",
+    CORAL                "coral"                "#FF7F50",
+    CORNFLOWERBLUE       "cornflowerblue"       "#6495ED",
+    CORNSILK             "cornsilk"             "#FFF8DC",
+    CRIMSON              "crimson"              "#DC143C",
+    CYAN                 "cyan"                 "#00FFFF",
+    DARKBLUE             "darkblue"             "#00008B",
+    DARKCYAN             "darkcyan"             "#008B8B",
+    DARKGOLDENROD        "darkgoldenrod"        "#B8860B",
+    DARKGRAY             "darkgray"             "#A9A9A9",
+    DARKGREEN            "darkgreen"            "#006400",
+    DARKGREY             "darkgrey"             "#A9A9A9",
+    DARKKHAKI            "darkkhaki"            "#BDB76B",
+    DARKMAGENTA          "darkmagenta"          "#8B008B",
+    DARKOLIVEGREEN       "darkolivegreen"       "#556B2F",
+    DARKORANGE           "darkorange"           "#FF8C00",
+    DARKORCHID           "darkorchid"           "#9932CC",
+    DARKRED              "darkred"              "#8B0000",
+    DARKSALMON           "darksalmon"           "#E9967A",
+    DARKSEAGREEN         "darkseagreen"         "#8FBC8F",
+    DARKSLATEBLUE        "darkslateblue"        "#483D8B",
+    DARKSLATEGRAY        "darkslategray"        "#2F4F4F",
+    DARKSLATEGREY        "darkslategrey"        "#2F4F4F",
+    DARKTURQUOISE        "darkturquoise"        "#00CED1",
+    DARKVIOLET           "darkviolet"           "#9400D3",
+    DEEPPINK             "deeppink"             "#FF1493",
+    DEEPSKYBLUE          "deepskyblue"          "#00BFFF",
+    DIMGRAY              "dimgray"              "#696969",
+    DIMGREY              "dimgrey"              "#696969",
+    DODGERBLUE           "dodgerblue"           "#1E90FF",
+    FIREBRICK            "firebrick"            "#B22222",
+    FLORALWHITE          "floralwhite"          "#FFFAF0",
+    FORESTGREEN          "forestgreen"          "#228B22",
+    FUCHSIA              "fuchsia"              "#FF00FF",
+    GAINSBORO            "gainsboro"            "#DCDCDC",
+    GHOSTWHITE           "ghostwhite"           "#F8F8FF",
+    GOLD                 "gold"                 "#FFD700",
+    GOLDENROD            "goldenrod"            "#DAA520",
+    GRAY                 "gray"                 "#808080",
+    GREEN                "green"                "#008000",
+    GREENYELLOW          "greenyellow"          "#ADFF2F",
+    GREY                 "grey"                 "#808080",
+    HONEYDEW             "honeydew"             "#F0FFF0",
+    HOTPINK              "hotpink"              "#FF69B4",
+    INDIANRED            "indianred"            "#CD5C5C",
+    INDIGO               "indigo"               "#4B0082",
+    IVORY                "ivory"                "#FFFFF0",
+    KHAKI                "khaki"                "#F0E68C",
+    LAVENDER             "lavender"             "#E6E6FA",
+    LAVENDERBLUSH        "lavenderblush"        "#FFF0F5",
+    LAWNGREEN            "lawngreen"            "#7CFC00",
+    LEMONCHIFFON         "lemonchiffon"         "#FFFACD",
+    LIGHTBLUE            "lightblue"            "#ADD8E6",
+    LIGHTCORAL           "lightcoral"           "#F08080",
+    LIGHTCYAN            "lightcyan"            "#E0FFFF",
+    LIGHTGOLDENRODYELLOW "lightgoldenrodyellow" "#FAFAD2",
+    LIGHTGRAY            "lightgray"            "#D3D3D3",
+    LIGHTGREEN           "lightgreen"           "#90EE90",
+    LIGHTGREY            "lightgrey"            "#D3D3D3",
+    LIGHTPINK            "lightpink"            "#FFB6C1",
+    LIGHTSALMON          "lightsalmon"          "#FFA07A",
+    LIGHTSEAGREEN        "lightseagreen"        "#20B2AA",
+    LIGHTSKYBLUE         "lightskyblue"         "#87CEFA",
+    LIGHTSLATEGRAY       "lightslategray"       "#778899",
+    LIGHTSLATEGREY       "lightslategrey"       "#778899",
+    LIGHTSTEELBLUE       "lightsteelblue"       "#B0C4DE",
+    LIGHTYELLOW          "lightyellow"          "#FFFFE0",
+    LIME                 "lime"                 "#00FF00",
+    LIMEGREEN            "limegreen"            "#32CD32",
+    LINEN                "linen"                "#FAF0E6",
+    MAGENTA              "magenta"              "#FF00FF",
+    MAROON               "maroon"               "#800000",
+    MEDIUMAQUAMARINE     "mediumaquamarine"     "#66CDAA",
+    MEDIUMBLUE           "mediumblue"           "#0000CD",
+    MEDIUMORCHID         "mediumorchid"         "#BA55D3",
+    MEDIUMPURPLE         "mediumpurple"         "#9370DB",
+    MEDIUMSEAGREEN       "mediumseagreen"       "#3CB371",
+    MEDIUMSLATEBLUE      "mediumslateblue"      "#7B68EE",
+    MEDIUMSPRINGGREEN    "mediumspringgreen"    "#00FA9A",
+    MEDIUMTURQUOISE      "mediumturquoise"      "#48D1CC",
+    MEDIUMVIOLETRED      "mediumvioletred"      "#C71585",
+    MIDNIGHTBLUE         "midnightblue"         "#191970",
+    MINTCREAM            "mintcream"            "#F5FFFA",
+    MISTYROSE            "mistyrose"            "#FFE4E1",
+    MOCCASIN             "moccasin"             "#FFE4B5",
+    NAVAJOWHITE          "navajowhite"          "#FFDEAD",
+    NAVY                 "navy"                 "#000080",
+    OLDLACE              "oldlace"              "#FDF5E6",
+    OLIVE                "olive"                "#808000",
+    OLIVEDRAB            "olivedrab"            "#6B8E23",
+    ORANGE               "orange"               "#FFA500",
+    ORANGERED            "orangered"            "#FF4500",
+    ORCHID               "orchid"               "#DA70D6",
+    PALEGOLDENROD        "palegoldenrod"        "#EEE8AA",
+    PALEGREEN            "palegreen"            "#98FB98",
+    PALETURQUOISE        "paleturquoise"        "#AFEEEE",
+    PALEVIOLETRED        "palevioletred"        "#DB7093",
+    PAPAYAWHIP           "papayawhip"           "#FFEFD5",
+    PEACHPUFF            "peachpuff"            "#FFDAB9",
+    PERU                 "peru"                 "#CD853F",
+    PINK                 "pink"                 "#FFC0CB",
+    PLUM                 "plum"                 "#DDA0DD",
+    POWDERBLUE           "powderblue"           "#B0E0E6",
+    PURPLE               "purple"               "#800080",
+    REBECCAPURPLE        "rebeccapurple"        "#663399",
+    RED                  "red"                  "#FF0000",
+    ROSYBROWN            "rosybrown"            "#BC8F8F",
+    ROYALBLUE            "royalblue"            "#4169E1",
+    SADDLEBROWN          "saddlebrown"          "#8B4513",
+    SALMON               "salmon"               "#FA8072",
+    SANDYBROWN           "sandybrown"           "#F4A460",
+    SEAGREEN             "seagreen"             "#2E8B57",
+    SEASHELL             "seashell"             "#FFF5EE",
+    SIENNA               "sienna"               "#A0522D",
+    SILVER               "silver"               "#C0C0C0",
+    SKYBLUE              "skyblue"              "#87CEEB",
+    SLATEBLUE            "slateblue"            "#6A5ACD",
+    SLATEGRAY            "slategray"            "#708090",
+    SLATEGREY            "slategrey"            "#708090",
+    SNOW                 "snow"                 "#FFFAFA",
+    SPRINGGREEN          "springgreen"          "#00FF7F",
+    STEELBLUE            "steelblue"            "#4682B4",
+    TAN                  "tan"                  "#D2B48C",
+    TEAL                 "teal"                 "#008080",
+    THISTLE              "thistle"              "#D8BFD8",
+    TOMATO               "tomato"               "#FF6347",
+    TRANSPARENT          "transparent"          "#00000000",
+    TURQUOISE            "turquoise"            "#40E0D0",
+    VIOLET               "violet"               "#EE82EE",
+    WHEAT                "wheat"                "#F5DEB3",
+    WHITE                "white"                "#FFFFFF",
+    WHITESMOKE           "whitesmoke"           "#F5F5F5",
+    YELLOW               "yellow"               "#FFFF00",
+    YELLOWGREEN          "yellowgreen"          "#9ACD32",
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_css_constants() {
+        assert_eq!(RED, Rgba([255, 0, 0, 255]));
+        assert_eq!(STEELBLUE, hex("#4682B4"));
+        assert_eq!(REBECCAPURPLE, hex("#663399"));
+        assert_eq!(BURLYWOOD, hex("#DEB887"));
+        assert_eq!(NAMED.len(), 149);
+        assert!(NAMED.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+}
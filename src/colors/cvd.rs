@@ -0,0 +1,105 @@
+//! Color-vision-deficiency simulation.
+//!
+//! Uses the Machado, Oliveira and Fernandes (2009) model at full severity,
+//! applied in linear RGB. Running a palette through [`simulate_cvd`] (or a
+//! whole rendered frame through
+//! [`apply_cvd_filter`](crate::image::apply_cvd_filter)) shows whether
+//! colors that carry meaning stay distinguishable for colorblind users.
+
+use image::Rgba;
+
+use super::{srgb_decode, srgb_encode};
+
+/// A type of dichromatic color vision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CvdType {
+    /// No functioning long-wavelength (red) cones.
+    Protanopia,
+    /// No functioning medium-wavelength (green) cones; the most common form.
+    Deuteranopia,
+    /// No functioning short-wavelength (blue) cones.
+    Tritanopia,
+}
+
+impl CvdType {
+    /// Every deficiency type, for checking a palette against all of them.
+    pub const ALL: [CvdType; 3] = [
+        CvdType::Protanopia,
+        CvdType::Deuteranopia,
+        CvdType::Tritanopia,
+    ];
+
+    /// Linear RGB transform for this deficiency.
+    #[rustfmt::skip]
+    const fn matrix(self) -> [[f32; 3]; 3] {
+        match self {
+            CvdType::Protanopia => [
+                [0.152_286, 1.052_583, -0.204_868],
+                [0.114_503, 0.786_281, 0.099_216],
+                [-0.003_882, -0.048_116, 1.051_998],
+            ],
+            CvdType::Deuteranopia => [
+                [0.367_322, 0.860_646, -0.227_968],
+                [0.280_085, 0.672_501, 0.047_413],
+                [-0.011_820, 0.042_940, 0.968_881],
+            ],
+            CvdType::Tritanopia => [
+                [1.255_528, -0.076_749, -0.178_779],
+                [-0.078_411, 0.930_809, 0.147_602],
+                [0.004_733, 0.691_367, 0.303_900],
+            ],
+        }
+    }
+}
+
+/// How `color` appears to someone with color vision deficiency `kind`.
+///
+/// Greys are unchanged and alpha is preserved.
+///
+/// ```ignore
+/// let seen: Vec<_> = series_colors
+///     .iter()
+///     .map(|&c| simulate_cvd(c, CvdType::Deuteranopia))
+///     .collect();
+/// ```
+pub fn simulate_cvd(color: Rgba<u8>, kind: CvdType) -> Rgba<u8> {
+    let linear = [0, 1, 2].map(|c| srgb_decode(color[c] as f32 / 255.0));
+    let m = kind.matrix();
+    let channel = |row: [f32; 3]| {
+        let v = row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2];
+        (srgb_encode(v.clamp(0.0, 1.0)) * 255.0).round() as u8
+    };
+    Rgba([channel(m[0]), channel(m[1]), channel(m[2]), color[3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulate_cvd_keeps_greys() {
+        for kind in CvdType::ALL {
+            for v in [0, 128, 255] {
+                let out = simulate_cvd(Rgba([v, v, v, 77]), kind);
+                assert!(
+                    out.0[..3].iter().all(|c| c.abs_diff(v) <= 1),
+                    "{kind:?} {out:?}"
+                );
+                assert_eq!(out[3], 77);
+            }
+        }
+    }
+
+    #[test]
+    fn test_simulate_cvd_red_green_confusion() {
+        // Red and green both collapse towards yellows without green cones
+        for color in [Rgba([255, 0, 0, 255]), Rgba([0, 255, 0, 255])] {
+            let Rgba([r, g, b, _]) = simulate_cvd(color, CvdType::Deuteranopia);
+            assert!(r.abs_diff(g) < 40 && b < g, "{color:?}");
+        }
+        // ...while tritanopes still see red as red
+        let red = simulate_cvd(Rgba([255, 0, 0, 255]), CvdType::Tritanopia);
+        assert_eq!(red[0], 255);
+        assert!(red[1] < 10);
+    }
+}
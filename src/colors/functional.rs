@@ -0,0 +1,467 @@
+//! CSS functional color notation, e.g. `rgb(255 107 53 / 50%)` or
+//! `hsl(210, 50%, 40%)`.
+//!
+//! Both the modern space-separated syntax (with an optional `/ alpha`) and
+//! the legacy comma-separated syntax are accepted, except for `hwb()`,
+//! which CSS only defines in the modern form. `color-mix()` takes any
+//! color the crate can parse as its inputs.
+
+use image::Rgba;
+
+use super::lookup_in;
+use super::spaces::{
+    Interpolation, Lab, Lch, Oklab, Oklch, from_lab, from_lch, from_oklab, from_oklch, hsl_to_rgb,
+    interpolate,
+};
+
+/// Parse a functional color such as `rgb(...)` or `rgba(...)`.
+///
+/// `s` must already be trimmed and lower-cased. `currentcolor` inputs to
+/// `color-mix()` resolve to `current`. Returns None for unknown functions
+/// and malformed arguments.
+pub(super) fn parse(s: &str, current: Option<Rgba<u8>>) -> Option<Rgba<u8>> {
+    let (name, args) = s.strip_suffix(')')?.split_once('(')?;
+    if name.trim_end() == "color-mix" {
+        return parse_color_mix(args, current);
+    }
+    let (components, alpha) = split_args(args)?;
+    match name.trim_end() {
+        "rgb" | "rgba" => parse_rgb(&components, alpha),
+        "hsl" | "hsla" => parse_hsl(&components, alpha),
+        // CSS hwb() has no legacy comma form
+        "hwb" if !args.contains(',') => parse_hwb(&components, alpha),
+        "lab" => parse_lab(&components, alpha),
+        "lch" => parse_lch(&components, alpha),
+        "oklab" => parse_oklab(&components, alpha),
+        "oklch" => parse_oklch(&components, alpha),
+        _ => None,
+    }
+}
+
+/// Split function arguments into components and an optional alpha.
+///
+/// Legacy comma syntax takes alpha as a fourth component; modern syntax
+/// separates it with `/`.
+fn split_args(args: &str) -> Option<(Vec<&str>, Option<&str>)> {
+    if args.contains(',') {
+        let mut parts: Vec<&str> = args.split(',').map(str::trim).collect();
+        if parts.iter().any(|p| p.is_empty()) {
+            return None;
+        }
+        let alpha = (parts.len() == 4).then(|| parts.pop()).flatten();
+        Some((parts, alpha))
+    } else {
+        let (components, alpha) = match args.split_once('/') {
+            Some((c, a)) => (c, Some(a.trim())),
+            None => (args, None),
+        };
+        if alpha.is_some_and(|a| a.is_empty() || a.contains(char::is_whitespace)) {
+            return None;
+        }
+        Some((components.split_whitespace().collect(), alpha))
+    }
+}
+
+/// Parse a number, or a percentage of `full`.
+fn number_or_percent(s: &str, full: f32) -> Option<f32> {
+    let value = match s.strip_suffix('%') {
+        Some(pct) => pct.parse::<f32>().ok()? / 100.0 * full,
+        None => s.parse::<f32>().ok()?,
+    };
+    value.is_finite().then_some(value)
+}
+
+/// Parse an alpha value (0.0 to 1.0 or a percentage) to a channel byte.
+fn parse_alpha(alpha: Option<&str>) -> Option<u8> {
+    match alpha {
+        Some(a) => Some(to_channel(number_or_percent(a, 1.0)? * 255.0)),
+        None => Some(255),
+    }
+}
+
+/// Parse a hue angle to degrees in 0.0..360.0.
+///
+/// Bare numbers are degrees; `deg`, `rad`, `grad` and `turn` units are accepted.
+pub(super) fn parse_hue(s: &str) -> Option<f32> {
+    let (number, per_unit) = if let Some(n) = s.strip_suffix("deg") {
+        (n, 1.0)
+    } else if let Some(n) = s.strip_suffix("grad") {
+        (n, 0.9)
+    } else if let Some(n) = s.strip_suffix("rad") {
+        (n, 180.0 / std::f32::consts::PI)
+    } else if let Some(n) = s.strip_suffix("turn") {
+        (n, 360.0)
+    } else {
+        (s, 1.0)
+    };
+    let degrees = number.parse::<f32>().ok()? * per_unit;
+    degrees.is_finite().then(|| degrees.rem_euclid(360.0))
+}
+
+/// Parse a percentage (or bare number on the same 0-100 scale) to 0.0..=1.0.
+fn parse_fraction(s: &str) -> Option<f32> {
+    Some((number_or_percent(s, 100.0)? / 100.0).clamp(0.0, 1.0))
+}
+
+fn to_channel(v: f32) -> u8 {
+    v.clamp(0.0, 255.0).round() as u8
+}
+
+fn parse_rgb(components: &[&str], alpha: Option<&str>) -> Option<Rgba<u8>> {
+    let [r, g, b] = components else {
+        return None;
+    };
+    Some(Rgba([
+        to_channel(number_or_percent(r, 255.0)?),
+        to_channel(number_or_percent(g, 255.0)?),
+        to_channel(number_or_percent(b, 255.0)?),
+        parse_alpha(alpha)?,
+    ]))
+}
+
+fn parse_hsl(components: &[&str], alpha: Option<&str>) -> Option<Rgba<u8>> {
+    let [h, s, l] = components else {
+        return None;
+    };
+    let [r, g, b] = hsl_to_rgb(parse_hue(h)?, parse_fraction(s)?, parse_fraction(l)?);
+    Some(Rgba([
+        to_channel(r * 255.0),
+        to_channel(g * 255.0),
+        to_channel(b * 255.0),
+        parse_alpha(alpha)?,
+    ]))
+}
+
+fn parse_hwb(components: &[&str], alpha: Option<&str>) -> Option<Rgba<u8>> {
+    let [h, w, b] = components else {
+        return None;
+    };
+    let hue = parse_hue(h)?;
+    let (white, black) = (parse_fraction(w)?, parse_fraction(b)?);
+    let rgb = if white + black >= 1.0 {
+        // Fully desaturated: a grey in proportion to whiteness
+        let grey = white / (white + black);
+        [grey; 3]
+    } else {
+        hsl_to_rgb(hue, 1.0, 0.5).map(|c| c * (1.0 - white - black) + white)
+    };
+    Some(Rgba([
+        to_channel(rgb[0] * 255.0),
+        to_channel(rgb[1] * 255.0),
+        to_channel(rgb[2] * 255.0),
+        parse_alpha(alpha)?,
+    ]))
+}
+
+/// Parse an alpha value to 0.0..=1.0.
+fn parse_alpha_fraction(alpha: Option<&str>) -> Option<f32> {
+    Some(parse_alpha(alpha)? as f32 / 255.0)
+}
+
+fn parse_lab(components: &[&str], alpha: Option<&str>) -> Option<Rgba<u8>> {
+    let [l, a, b] = components else {
+        return None;
+    };
+    Some(from_lab(Lab {
+        l: number_or_percent(l, 100.0)?.clamp(0.0, 100.0),
+        a: number_or_percent(a, 125.0)?,
+        b: number_or_percent(b, 125.0)?,
+        alpha: parse_alpha_fraction(alpha)?,
+    }))
+}
+
+fn parse_lch(components: &[&str], alpha: Option<&str>) -> Option<Rgba<u8>> {
+    let [l, c, h] = components else {
+        return None;
+    };
+    Some(from_lch(Lch {
+        l: number_or_percent(l, 100.0)?.clamp(0.0, 100.0),
+        c: number_or_percent(c, 150.0)?.max(0.0),
+        h: parse_hue(h)?,
+        alpha: parse_alpha_fraction(alpha)?,
+    }))
+}
+
+fn parse_oklab(components: &[&str], alpha: Option<&str>) -> Option<Rgba<u8>> {
+    let [l, a, b] = components else {
+        return None;
+    };
+    Some(from_oklab(Oklab {
+        l: number_or_percent(l, 1.0)?.clamp(0.0, 1.0),
+        a: number_or_percent(a, 0.4)?,
+        b: number_or_percent(b, 0.4)?,
+        alpha: parse_alpha_fraction(alpha)?,
+    }))
+}
+
+fn parse_oklch(components: &[&str], alpha: Option<&str>) -> Option<Rgba<u8>> {
+    let [l, c, h] = components else {
+        return None;
+    };
+    Some(from_oklch(Oklch {
+        l: number_or_percent(l, 1.0)?.clamp(0.0, 1.0),
+        c: number_or_percent(c, 0.4)?.max(0.0),
+        h: parse_hue(h)?,
+        alpha: parse_alpha_fraction(alpha)?,
+    }))
+}
+
+/// Split `s` on top-level commas, ignoring commas inside parentheses.
+pub(super) fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(s[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(s[start..].trim());
+    parts
+}
+
+/// Parse one `color-mix()` input: a color with an optional trailing percentage.
+fn parse_mix_input(s: &str, current: Option<Rgba<u8>>) -> Option<(Rgba<u8>, Option<f32>)> {
+    if let Some((color, pct)) = s.rsplit_once(char::is_whitespace)
+        && let Some(pct) = pct.strip_suffix('%')
+    {
+        let pct = pct.parse::<f32>().ok()?;
+        if !(0.0..=100.0).contains(&pct) {
+            return None;
+        }
+        return Some((lookup_in(color.trim(), current)?, Some(pct / 100.0)));
+    }
+    Some((lookup_in(s, current)?, None))
+}
+
+/// Parse `color-mix(in <space>, <color> [<pct>], <color> [<pct>])`.
+fn parse_color_mix(args: &str, current: Option<Rgba<u8>>) -> Option<Rgba<u8>> {
+    let [space, first, second] = split_top_level(args)[..] else {
+        return None;
+    };
+    let space = match space.strip_prefix("in")?.trim() {
+        "srgb" => Interpolation::Srgb,
+        "srgb-linear" => Interpolation::SrgbLinear,
+        "hsl" => Interpolation::Hsl,
+        "lab" => Interpolation::Lab,
+        "lch" => Interpolation::Lch,
+        "oklab" => Interpolation::Oklab,
+        "oklch" => Interpolation::Oklch,
+        _ => return None,
+    };
+    let (a, pa) = parse_mix_input(first, current)?;
+    let (b, pb) = parse_mix_input(second, current)?;
+    // Missing percentages complement each other; the pair is normalized to 100%
+    let (pa, pb) = match (pa, pb) {
+        (None, None) => (0.5, 0.5),
+        (Some(p), None) => (p, 1.0 - p),
+        (None, Some(p)) => (1.0 - p, p),
+        (Some(p), Some(q)) => (p, q),
+    };
+    let sum = pa + pb;
+    if sum <= 0.0 {
+        return None;
+    }
+    let mut mixed = interpolate(a, b, pb / sum, space);
+    // Percentages summing below 100% scale down the result's alpha
+    if sum < 1.0 {
+        mixed[3] = (mixed[3] as f32 * sum).round() as u8;
+    }
+    Some(mixed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Option<Rgba<u8>> {
+        super::parse(s, None)
+    }
+
+    #[test]
+    fn test_color_mix_resolves_currentcolor() {
+        let fg = Rgba([200, 100, 0, 255]);
+        assert_eq!(
+            super::parse(
+                "color-mix(in srgb, currentcolor 50%, transparent)",
+                Some(fg)
+            ),
+            Some(Rgba([200, 100, 0, 128]))
+        );
+        assert_eq!(parse("color-mix(in srgb, currentcolor, red)"), None);
+    }
+
+    #[test]
+    fn test_parse_rgb_comma_syntax() {
+        assert_eq!(parse("rgb(255, 107, 53)"), Some(Rgba([255, 107, 53, 255])));
+        assert_eq!(
+            parse("rgba(255,107,53,0.5)"),
+            Some(Rgba([255, 107, 53, 128]))
+        );
+    }
+
+    #[test]
+    fn test_parse_rgb_space_syntax() {
+        assert_eq!(parse("rgb(255 107 53)"), Some(Rgba([255, 107, 53, 255])));
+        assert_eq!(
+            parse("rgba(255 107 53 / 0.5)"),
+            Some(Rgba([255, 107, 53, 128]))
+        );
+        assert_eq!(
+            parse("rgb(255 107 53/50%)"),
+            Some(Rgba([255, 107, 53, 128]))
+        );
+    }
+
+    #[test]
+    fn test_parse_rgb_percentages_and_clamping() {
+        assert_eq!(parse("rgb(100%, 50%, 0%)"), Some(Rgba([255, 128, 0, 255])));
+        assert_eq!(parse("rgb(300 -5 12.6)"), Some(Rgba([255, 0, 13, 255])));
+        assert_eq!(parse("rgb(0 0 0 / 150%)"), Some(Rgba([0, 0, 0, 255])));
+    }
+
+    #[test]
+    fn test_parse_rgb_invalid() {
+        assert_eq!(parse("rgb(1, 2)"), None);
+        assert_eq!(parse("rgb(1, 2, 3, 4, 5)"), None);
+        assert_eq!(parse("rgb(1, , 3)"), None);
+        assert_eq!(parse("rgb(a b c)"), None);
+        assert_eq!(parse("rgb(1 2 3 / )"), None);
+        assert_eq!(parse("rgb(1 2 3"), None);
+        assert_eq!(parse("foo(1 2 3)"), None);
+    }
+
+    #[test]
+    fn test_parse_hsl() {
+        assert_eq!(parse("hsl(0, 100%, 50%)"), Some(Rgba([255, 0, 0, 255])));
+        assert_eq!(parse("hsl(120 100% 25%)"), Some(Rgba([0, 128, 0, 255])));
+        assert_eq!(parse("hsl(210, 50%, 40%)"), Some(Rgba([51, 102, 153, 255])));
+        assert_eq!(
+            parse("hsla(0, 0%, 100%, 0.5)"),
+            Some(Rgba([255, 255, 255, 128]))
+        );
+        assert_eq!(
+            parse("hsl(240 100% 50% / 25%)"),
+            Some(Rgba([0, 0, 255, 64]))
+        );
+    }
+
+    #[test]
+    fn test_parse_hsl_hue_wrapping_and_units() {
+        let red = Some(Rgba([255, 0, 0, 255]));
+        assert_eq!(parse("hsl(360, 100%, 50%)"), red);
+        assert_eq!(parse("hsl(-360 100% 50%)"), red);
+        assert_eq!(parse("hsl(0.5turn 100% 50%)"), parse("hsl(180 100% 50%)"));
+        assert_eq!(
+            parse("hsl(200grad 100% 50%)"),
+            parse("hsl(180deg 100% 50%)")
+        );
+        assert_eq!(
+            parse("hsl(3.14159rad 100% 50%)"),
+            parse("hsl(180 100% 50%)")
+        );
+    }
+
+    #[test]
+    fn test_parse_hsl_invalid() {
+        assert_eq!(parse("hsl(0, 100%)"), None);
+        assert_eq!(parse("hsl(red, 100%, 50%)"), None);
+    }
+
+    #[test]
+    fn test_parse_hwb() {
+        assert_eq!(parse("hwb(0 0% 0%)"), Some(Rgba([255, 0, 0, 255])));
+        assert_eq!(parse("hwb(120 0% 50%)"), Some(Rgba([0, 128, 0, 255])));
+        assert_eq!(
+            parse("hwb(240 20% 20% / 0.5)"),
+            Some(Rgba([51, 51, 204, 128]))
+        );
+        // Whiteness and blackness summing past 100% normalize to grey
+        assert_eq!(parse("hwb(90 60% 60%)"), Some(Rgba([128, 128, 128, 255])));
+        assert_eq!(parse("hwb(0 0%)"), None);
+        assert_eq!(parse("hwb(garbage 60% 60%)"), None);
+        assert_eq!(parse("hwb(0, 0%, 0%)"), None);
+    }
+
+    #[test]
+    fn test_parse_lab_and_lch() {
+        assert_eq!(parse("lab(54.29 80.8 69.89)"), Some(Rgba([255, 0, 0, 255])));
+        assert_eq!(
+            parse("lab(100% 0 0 / 0.5)"),
+            Some(Rgba([255, 255, 255, 128]))
+        );
+        assert_eq!(
+            parse("lch(54.29 106.84 40.85)"),
+            Some(Rgba([255, 0, 0, 255]))
+        );
+        assert_eq!(parse("lch(0% 0 0)"), Some(Rgba([0, 0, 0, 255])));
+        assert_eq!(parse("lab(50 10)"), None);
+    }
+
+    #[test]
+    fn test_parse_oklab_and_oklch() {
+        assert_eq!(
+            parse("oklab(0.628 0.2249 0.1258)"),
+            Some(Rgba([255, 0, 0, 255]))
+        );
+        assert_eq!(
+            parse("oklab(100% 0 0 / 50%)"),
+            Some(Rgba([255, 255, 255, 128]))
+        );
+        assert_eq!(
+            parse("oklch(0.628 0.2577 29.23)"),
+            Some(Rgba([255, 0, 0, 255]))
+        );
+        assert_eq!(
+            parse("oklch(62.8% 64.4% 29.23deg)"),
+            Some(Rgba([255, 0, 0, 255]))
+        );
+        assert_eq!(parse("oklch(0.5 0.1)"), None);
+    }
+
+    #[test]
+    fn test_parse_color_mix_srgb() {
+        assert_eq!(
+            parse("color-mix(in srgb, red, blue)"),
+            Some(Rgba([128, 0, 128, 255]))
+        );
+        assert_eq!(
+            parse("color-mix(in srgb, red 30%, blue)"),
+            Some(Rgba([77, 0, 179, 255]))
+        );
+        assert_eq!(
+            parse("color-mix(in srgb, red, blue 70%)"),
+            Some(Rgba([77, 0, 179, 255]))
+        );
+        assert_eq!(
+            parse("color-mix(in srgb, rgb(255, 0, 0) 20%, #0000ff 20%)"),
+            Some(Rgba([128, 0, 128, 102]))
+        );
+    }
+
+    #[test]
+    fn test_parse_color_mix_oklch() {
+        let mixed = parse("color-mix(in oklch, red, blue)").unwrap();
+        // Hue travels the short way through purple rather than through grey
+        assert!(mixed[0] > 100 && mixed[2] > 100 && mixed[1] < 80);
+        assert_eq!(
+            parse("color-mix(in hsl, red, blue)"),
+            Some(Rgba([255, 0, 255, 255]))
+        );
+    }
+
+    #[test]
+    fn test_parse_color_mix_invalid() {
+        assert_eq!(parse("color-mix(in srgb, red)"), None);
+        assert_eq!(parse("color-mix(srgb, red, blue)"), None);
+        assert_eq!(parse("color-mix(in xyz-d99, red, blue)"), None);
+        assert_eq!(parse("color-mix(in srgb, red 0%, blue 0%)"), None);
+        assert_eq!(parse("color-mix(in srgb, red 150%, blue)"), None);
+    }
+}
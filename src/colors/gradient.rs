@@ -0,0 +1,402 @@
+//! Multi-stop gradients and CSS gradient parsing.
+
+use image::Rgba;
+
+use super::functional::{parse_hue, split_top_level};
+use super::lookup;
+use super::spaces::{Interpolation, interpolate};
+use crate::image::Background;
+
+/// An ordered list of color stops that can be sampled at any position.
+///
+/// Used to map values onto colors for gauges, progress bars and heatmaps.
+/// Stops are kept sorted by position; outside the first and last stop the
+/// end colors extend. Colors between stops are interpolated in the
+/// gradient's [`Interpolation`] space (sRGB by default).
+///
+/// ```ignore
+/// let heat = Gradient::even(&[hex("#2B83BA"), hex("#FFFFBF"), hex("#D7191C")])
+///     .with_space(Interpolation::Oklab);
+/// let color = heat.sample(0.8);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    stops: Vec<(f32, Rgba<u8>)>,
+    space: Interpolation,
+}
+
+impl Gradient {
+    /// Create a gradient from `(position, color)` stops.
+    ///
+    /// Stops are sorted by position, keeping the given order for equal
+    /// positions so they form a hard edge. Stops with non-finite positions
+    /// are dropped.
+    pub fn new(stops: impl IntoIterator<Item = (f32, Rgba<u8>)>) -> Self {
+        let mut stops: Vec<_> = stops.into_iter().filter(|(p, _)| p.is_finite()).collect();
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self {
+            stops,
+            space: Interpolation::default(),
+        }
+    }
+
+    /// Create a gradient with `colors` spread evenly from 0.0 to 1.0.
+    pub fn even(colors: &[Rgba<u8>]) -> Self {
+        let last = colors.len().saturating_sub(1).max(1) as f32;
+        Self::new(
+            colors
+                .iter()
+                .enumerate()
+                .map(|(i, &c)| (i as f32 / last, c)),
+        )
+    }
+
+    /// Set the color space used between stops.
+    pub fn with_space(mut self, space: Interpolation) -> Self {
+        self.space = space;
+        self
+    }
+
+    /// The stops, sorted by position.
+    pub fn stops(&self) -> &[(f32, Rgba<u8>)] {
+        &self.stops
+    }
+
+    /// The color space used between stops.
+    pub fn space(&self) -> Interpolation {
+        self.space
+    }
+
+    /// Color at position `t`.
+    ///
+    /// An empty gradient is transparent.
+    pub fn sample(&self, t: f32) -> Rgba<u8> {
+        let (Some(first), Some(last)) = (self.stops.first(), self.stops.last()) else {
+            return Rgba([0, 0, 0, 0]);
+        };
+        if t.is_nan() || t <= first.0 {
+            return first.1;
+        }
+        if t >= last.0 {
+            return last.1;
+        }
+        for pair in self.stops.windows(2) {
+            let ((p0, c0), (p1, c1)) = (pair[0], pair[1]);
+            if t < p1 {
+                let f = if p1 > p0 { (t - p0) / (p1 - p0) } else { 1.0 };
+                return interpolate(c0, c1, f, self.space);
+            }
+        }
+        last.1
+    }
+}
+
+/// Parse a CSS `linear-gradient()` or `radial-gradient()` into a
+/// [`Background`] the image module can paint.
+///
+/// Supports:
+/// - Angles in deg, grad, rad or turn, or `to <side>` / `to <corner>`
+///   directions (corners are treated as 45° diagonals). The default
+///   direction is `to bottom`.
+/// - Radial gradients with an optional `circle` shape and `at <x> <y>`
+///   center, as percentages or keywords (`left`, `center`, `top`, ...).
+///   The gradient always extends to the farthest corner.
+/// - Color stops with any color syntax [`lookup`] accepts, each with
+///   zero, one or two percentage positions. Missing positions are spread
+///   evenly, as in CSS.
+///
+/// ```ignore
+/// let bg = parse_gradient("linear-gradient(45deg, #222, steelblue)").unwrap();
+/// bg.paint(&mut img);
+/// ```
+///
+/// Returns `None` for other syntax, lengths in pixels, or fewer than two stops.
+pub fn parse_gradient(s: &str) -> Option<Background> {
+    let lowercase = s.trim().to_ascii_lowercase();
+    let (name, args) = lowercase.strip_suffix(')')?.split_once('(')?;
+    let mut args = split_top_level(args);
+    match name.trim_end() {
+        "linear-gradient" => {
+            let angle = match args.first().and_then(|a| parse_direction(a)) {
+                Some(angle) => {
+                    args.remove(0);
+                    angle
+                }
+                None => 180.0,
+            };
+            Some(Background::LinearGradient {
+                angle,
+                stops: parse_stops(&args)?,
+            })
+        }
+        "radial-gradient" => {
+            let center = match args.first().and_then(|a| parse_shape(a)) {
+                Some(center) => {
+                    args.remove(0);
+                    center
+                }
+                None => (0.5, 0.5),
+            };
+            Some(Background::RadialGradient {
+                center,
+                stops: parse_stops(&args)?,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Parse a linear gradient direction into CSS degrees.
+fn parse_direction(s: &str) -> Option<f32> {
+    if let Some(sides) = s.strip_prefix("to ") {
+        let (mut x, mut y) = (0.0f32, 0.0f32);
+        for side in sides.split_whitespace() {
+            match side {
+                "top" => y = -1.0,
+                "bottom" => y = 1.0,
+                "left" => x = -1.0,
+                "right" => x = 1.0,
+                _ => return None,
+            }
+        }
+        if x == 0.0 && y == 0.0 {
+            return None;
+        }
+        // CSS angles run clockwise from up
+        return Some(x.atan2(-y).to_degrees().rem_euclid(360.0));
+    }
+    let has_unit = ["deg", "grad", "rad", "turn"]
+        .iter()
+        .any(|unit| s.ends_with(unit));
+    if has_unit || s == "0" {
+        parse_hue(s)
+    } else {
+        None
+    }
+}
+
+/// Parse a radial gradient shape argument, returning its center.
+fn parse_shape(s: &str) -> Option<(f32, f32)> {
+    let mut tokens = s.split_whitespace().peekable();
+    let mut recognized = false;
+    while let Some(&token) = tokens.peek() {
+        match token {
+            "circle" | "ellipse" | "farthest-corner" => {
+                tokens.next();
+                recognized = true;
+            }
+            _ => break,
+        }
+    }
+    let center = match tokens.next() {
+        None if recognized => (0.5, 0.5),
+        Some("at") => {
+            let first = tokens.next()?;
+            let second = tokens.next().unwrap_or("center");
+            // Keywords may come in either order ("at top left")
+            let (x, y) = if matches!(first, "top" | "bottom") || matches!(second, "left" | "right")
+            {
+                (second, first)
+            } else {
+                (first, second)
+            };
+            (position(x, "left", "right")?, position(y, "top", "bottom")?)
+        }
+        _ => return None,
+    };
+    tokens.next().is_none().then_some(center)
+}
+
+/// A percentage or keyword position along one axis, as a fraction.
+fn position(s: &str, start: &str, end: &str) -> Option<f32> {
+    match s {
+        "center" => Some(0.5),
+        _ if s == start => Some(0.0),
+        _ if s == end => Some(1.0),
+        _ => percent(s),
+    }
+}
+
+fn percent(s: &str) -> Option<f32> {
+    let v = s.strip_suffix('%')?.parse::<f32>().ok()?;
+    v.is_finite().then_some(v / 100.0)
+}
+
+/// Parse color stops, resolving missing positions as CSS does.
+fn parse_stops(args: &[&str]) -> Option<Vec<(f32, Rgba<u8>)>> {
+    let mut stops: Vec<(Option<f32>, Rgba<u8>)> = Vec::new();
+    for arg in args {
+        // Up to two trailing percentages; the rest is the color
+        let mut color = *arg;
+        let mut positions = Vec::new();
+        while positions.len() < 2
+            && let Some((rest, last)) = color.rsplit_once(char::is_whitespace)
+            && let Some(p) = percent(last)
+        {
+            positions.insert(0, p);
+            color = rest.trim_end();
+        }
+        let color = lookup(color)?;
+        if positions.is_empty() {
+            stops.push((None, color));
+        }
+        for p in positions {
+            stops.push((Some(p), color));
+        }
+    }
+    if stops.len() < 2 {
+        return None;
+    }
+
+    let last = stops.len() - 1;
+    stops[0].0.get_or_insert(0.0);
+    stops[last].0.get_or_insert(1.0);
+    // Positions never go backwards
+    let mut max = f32::NEG_INFINITY;
+    for (p, _) in stops.iter_mut() {
+        if let Some(p) = p {
+            *p = p.max(max);
+            max = *p;
+        }
+    }
+    // Spread runs of missing positions evenly between their neighbours
+    let mut i = 1;
+    while i < last {
+        if stops[i].0.is_some() {
+            i += 1;
+            continue;
+        }
+        let start = i - 1;
+        let end = (i..=last).find(|&j| stops[j].0.is_some())?;
+        let (from, to) = (stops[start].0?, stops[end].0?);
+        for (j, stop) in stops[i..end].iter_mut().enumerate() {
+            let f = (i + j - start) as f32 / (end - start) as f32;
+            stop.0 = Some(from + (to - from) * f);
+        }
+        i = end;
+    }
+    stops.into_iter().map(|(p, c)| Some((p?, c))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::hex;
+
+    fn linear(s: &str) -> (f32, Vec<(f32, Rgba<u8>)>) {
+        match parse_gradient(s) {
+            Some(Background::LinearGradient { angle, stops }) => (angle, stops),
+            other => panic!("expected linear gradient, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_gradient_sample() {
+        let g = Gradient::new([(1.0, hex("#FFFFFF")), (0.0, hex("#000000"))]);
+        assert_eq!(g.stops()[0].0, 0.0);
+        assert_eq!(g.sample(-1.0), hex("#000000"));
+        assert_eq!(g.sample(0.5), Rgba([128, 128, 128, 255]));
+        assert_eq!(g.sample(2.0), hex("#FFFFFF"));
+        assert_eq!(Gradient::new([]).sample(0.5), Rgba([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_gradient_even_stops_and_hard_edge() {
+        let g = Gradient::even(&[hex("#FF0000"), hex("#00FF00"), hex("#0000FF")]);
+        let positions: Vec<f32> = g.stops().iter().map(|s| s.0).collect();
+        assert_eq!(positions, vec![0.0, 0.5, 1.0]);
+        assert_eq!(g.sample(0.5), hex("#00FF00"));
+        assert_eq!(
+            Gradient::even(&[hex("#FF0000")]).sample(0.7),
+            hex("#FF0000")
+        );
+
+        // Equal positions keep their order and switch sharply
+        let band = Gradient::new([
+            (0.0, hex("#FF0000")),
+            (0.5, hex("#FF0000")),
+            (0.5, hex("#0000FF")),
+            (1.0, hex("#0000FF")),
+        ]);
+        assert_eq!(band.sample(0.49), hex("#FF0000"));
+        assert_eq!(band.sample(0.5), hex("#0000FF"));
+    }
+
+    #[test]
+    fn test_gradient_interpolation_space() {
+        let stops = [(0.0, hex("#FF0000")), (1.0, hex("#00FF00"))];
+        let srgb = Gradient::new(stops).sample(0.5);
+        let linear = Gradient::new(stops)
+            .with_space(Interpolation::SrgbLinear)
+            .sample(0.5);
+        assert_eq!(srgb, Rgba([128, 128, 0, 255]));
+        // Linear-light mixing is brighter at the midpoint
+        assert!(linear[0] > srgb[0] && linear[1] > srgb[1]);
+    }
+
+    #[test]
+    fn test_parse_linear_gradient() {
+        let (angle, stops) = linear("linear-gradient(45deg, #222, steelblue)");
+        assert_eq!(angle, 45.0);
+        assert_eq!(stops, vec![(0.0, hex("#222222")), (1.0, hex("#4682B4"))]);
+
+        let (angle, stops) = linear("linear-gradient(red, rgb(0 255 0) 30%, blue)");
+        assert_eq!(angle, 180.0);
+        assert_eq!(stops[1], (0.3, hex("#00FF00")));
+        assert_eq!(stops[2].0, 1.0);
+    }
+
+    #[test]
+    fn test_parse_linear_gradient_directions() {
+        assert_eq!(linear("linear-gradient(to right, red, blue)").0, 90.0);
+        assert_eq!(linear("linear-gradient(to top, red, blue)").0, 0.0);
+        assert_eq!(
+            linear("linear-gradient(to bottom left, red, blue)").0,
+            225.0
+        );
+        assert_eq!(linear("linear-gradient(0.25turn, red, blue)").0, 90.0);
+        assert!(parse_gradient("linear-gradient(to middle, red, blue)").is_none());
+    }
+
+    #[test]
+    fn test_parse_gradient_stop_positions() {
+        // Missing positions spread evenly; backwards positions clamp
+        let (_, stops) = linear("linear-gradient(red, lime, aqua, blue 90%, black 50%)");
+        let expected = [0.0, 0.3, 0.6, 0.9, 0.9];
+        for ((p, _), e) in stops.iter().zip(expected) {
+            assert!((p - e).abs() < 1e-5, "{p} != {e}");
+        }
+
+        // Two positions give a hard band
+        let (_, stops) = linear("linear-gradient(red 0% 50%, blue 50% 100%)");
+        assert_eq!(stops.len(), 4);
+        assert_eq!(stops[1], (0.5, hex("#FF0000")));
+        assert_eq!(stops[2], (0.5, hex("#0000FF")));
+    }
+
+    #[test]
+    fn test_parse_radial_gradient() {
+        let Some(Background::RadialGradient { center, stops }) =
+            parse_gradient("radial-gradient(circle at 25% top, white, black)")
+        else {
+            panic!("expected radial gradient");
+        };
+        assert_eq!(center, (0.25, 0.0));
+        assert_eq!(stops.len(), 2);
+
+        let Some(Background::RadialGradient { center, .. }) =
+            parse_gradient("radial-gradient(at top left, white, black)")
+        else {
+            panic!("expected radial gradient");
+        };
+        assert_eq!(center, (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_parse_gradient_invalid() {
+        assert!(parse_gradient("linear-gradient(red)").is_none());
+        assert!(parse_gradient("linear-gradient(red 10px, blue)").is_none());
+        assert!(parse_gradient("conic-gradient(red, blue)").is_none());
+        assert!(parse_gradient("steelblue").is_none());
+    }
+}
@@ -0,0 +1,162 @@
+//! Harmonious palettes generated from a single color.
+//!
+//! Each scheme rotates the base color's hue around the OKLCH hue circle,
+//! so the generated colors keep its perceived lightness and chroma (colors
+//! that fall outside sRGB are reduced in chroma to fit). The base color is
+//! always the first entry, so a palette built from a configured accent can
+//! be indexed without searching for it. Greys have no hue and produce
+//! copies of themselves.
+//!
+//! ```ignore
+//! let accent = hex("#4A9EFF");
+//! let series = triadic(accent); // [accent, +120°, +240°]
+//! let segments: Vec<_> = values.iter().copied().zip(series).collect();
+//! draw_donut(&mut img, rect, &segments, &style);
+//! ```
+//!
+//! For more series than a harmony covers, [`distinct_colors`] generates as
+//! many well-separated colors as needed without a base color.
+
+use image::Rgba;
+
+use super::rotate_hue;
+use super::spaces::{Oklch, from_oklch_mapped};
+use crate::image::noise;
+
+/// Hue offset between neighbours in [`analogous`].
+pub const ANALOGOUS_ANGLE: f32 = 30.0;
+
+/// The base color and the color opposite it on the hue circle.
+pub fn complementary(color: Rgba<u8>) -> Vec<Rgba<u8>> {
+    rotations(color, &[180.0])
+}
+
+/// The base color and two more spaced evenly around the hue circle.
+pub fn triadic(color: Rgba<u8>) -> Vec<Rgba<u8>> {
+    rotations(color, &[120.0, 240.0])
+}
+
+/// The base color and its neighbours [`ANALOGOUS_ANGLE`] either side.
+///
+/// Returned as base, counter-clockwise neighbour, clockwise neighbour.
+pub fn analogous(color: Rgba<u8>) -> Vec<Rgba<u8>> {
+    rotations(color, &[-ANALOGOUS_ANGLE, ANALOGOUS_ANGLE])
+}
+
+/// The base color and the two colors either side of its complement.
+pub fn split_complementary(color: Rgba<u8>) -> Vec<Rgba<u8>> {
+    rotations(color, &[180.0 - ANALOGOUS_ANGLE, 180.0 + ANALOGOUS_ANGLE])
+}
+
+/// Hue step between consecutive [`distinct_colors`]: the golden angle, which
+/// never repeats and keeps each new hue far from all earlier ones.
+pub const GOLDEN_ANGLE: f32 = 137.507_77;
+
+/// OKLCH lightness levels [`distinct_colors`] cycles through, so colors with
+/// nearby hues still differ in brightness.
+const DISTINCT_LIGHTNESS: [f32; 3] = [0.72, 0.58, 0.84];
+
+/// OKLCH chroma of [`distinct_colors`], reduced where it leaves the sRGB
+/// gamut.
+const DISTINCT_CHROMA: f32 = 0.14;
+
+/// `n` visually distinct colors, e.g. for the series of a multi-line chart.
+///
+/// Hues step around the OKLCH circle by [`GOLDEN_ANGLE`] from a starting
+/// hue picked by `seed`, while lightness cycles through three levels. The
+/// same seed always gives the same colors, and each color depends only on
+/// its index, so adding a series leaves the earlier colors unchanged.
+///
+/// ```ignore
+/// let colors = distinct_colors(series.len(), 7);
+/// for (values, color) in series.iter().zip(colors) {
+///     draw_line(&mut img, values, color);
+/// }
+/// ```
+pub fn distinct_colors(n: usize, seed: u64) -> Vec<Rgba<u8>> {
+    let start = noise::white(0, 0, seed) * 360.0;
+    (0..n)
+        .map(|i| {
+            from_oklch_mapped(Oklch {
+                l: DISTINCT_LIGHTNESS[i % DISTINCT_LIGHTNESS.len()],
+                c: DISTINCT_CHROMA,
+                h: (start + i as f32 * GOLDEN_ANGLE).rem_euclid(360.0),
+                alpha: 1.0,
+            })
+        })
+        .collect()
+}
+
+fn rotations(color: Rgba<u8>, degrees: &[f32]) -> Vec<Rgba<u8>> {
+    std::iter::once(color)
+        .chain(degrees.iter().map(|&d| rotate_hue(color, d)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::{delta_e, hex, to_oklch};
+
+    fn hue(c: Rgba<u8>) -> f32 {
+        to_oklch(c).h
+    }
+
+    fn hue_distance(a: f32, b: f32) -> f32 {
+        let d = (a - b).rem_euclid(360.0);
+        d.min(360.0 - d)
+    }
+
+    #[test]
+    fn test_palette_sizes_and_base_first() {
+        let base = hex("#4A9EFF");
+        for (palette, len) in [
+            (complementary(base), 2),
+            (triadic(base), 3),
+            (analogous(base), 3),
+            (split_complementary(base), 3),
+        ] {
+            assert_eq!(palette.len(), len);
+            assert_eq!(palette[0], base);
+        }
+    }
+
+    #[test]
+    fn test_palette_hue_offsets() {
+        // A moderate color so rotations stay in gamut and keep their hue
+        let base = hex("#7A8FB0");
+        let h = hue(base);
+        let triad = triadic(base);
+        assert!((hue_distance(hue(triad[1]), h) - 120.0).abs() < 3.0);
+        assert!((hue_distance(hue(triad[2]), h) - 120.0).abs() < 3.0);
+        let complement = complementary(base);
+        assert!((hue_distance(hue(complement[1]), h) - 180.0).abs() < 3.0);
+        let split = split_complementary(base);
+        assert!((hue_distance(hue(split[1]), h) - 150.0).abs() < 3.0);
+        let near = analogous(base);
+        assert!((hue_distance(hue(near[1]), h) - 30.0).abs() < 3.0);
+    }
+
+    #[test]
+    fn test_distinct_colors() {
+        let colors = distinct_colors(12, 42);
+        assert_eq!(colors.len(), 12);
+        assert_eq!(distinct_colors(12, 42), colors);
+        assert_eq!(distinct_colors(5, 42), colors[..5]);
+        assert_ne!(distinct_colors(5, 43), colors[..5]);
+        assert!(distinct_colors(0, 1).is_empty());
+
+        for (i, &a) in colors.iter().enumerate() {
+            assert_eq!(a[3], 255);
+            for &b in &colors[i + 1..] {
+                assert!(delta_e(a, b) > 8.0, "{a:?} and {b:?} are too close");
+            }
+        }
+    }
+
+    #[test]
+    fn test_palette_grey_is_unchanged() {
+        let grey = hex("#808080");
+        assert!(triadic(grey).iter().all(|&c| c == grey));
+    }
+}
@@ -0,0 +1,95 @@
+//! Layered color lookup with dotted-key fallbacks.
+
+use std::collections::HashMap;
+
+use image::Rgba;
+
+/// Resolves colors through several maps, cascading from specific keys to
+/// general ones.
+///
+/// Layers are checked in the order they were added, so add the most
+/// specific first (widget config, then plugin config, then the global
+/// theme). Within each layer a dotted key falls back by dropping its
+/// leading segment: `"button.primary.fg"`, then `"primary.fg"`, then
+/// `"fg"`. A layer is searched fully before the next, so a widget-level
+/// `"fg"` overrides a theme-level `"button.fg"`.
+///
+/// ```ignore
+/// let colors = ColorResolver::new()
+///     .layer(&widget_colors)
+///     .layer(plugin_colors.as_map())
+///     .layer(&theme_colors);
+/// let fg = colors.get_or("button.fg", theme.fg);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ColorResolver<'a> {
+    layers: Vec<&'a HashMap<String, Rgba<u8>>>,
+}
+
+impl<'a> ColorResolver<'a> {
+    /// A resolver with no layers, which resolves nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a layer checked after all existing ones.
+    pub fn layer(mut self, colors: &'a HashMap<String, Rgba<u8>>) -> Self {
+        self.layers.push(colors);
+        self
+    }
+
+    /// The color for `key`, trying each layer with progressively shorter
+    /// keys.
+    pub fn get(&self, key: &str) -> Option<Rgba<u8>> {
+        self.layers
+            .iter()
+            .find_map(|colors| fallbacks(key).find_map(|candidate| colors.get(candidate).copied()))
+    }
+
+    /// The color for `key`, or `default` if no layer has it.
+    pub fn get_or(&self, key: &str, default: Rgba<u8>) -> Rgba<u8> {
+        self.get(key).unwrap_or(default)
+    }
+}
+
+/// `key`, then `key` with each leading dotted segment removed in turn.
+fn fallbacks(key: &str) -> impl Iterator<Item = &str> {
+    std::iter::successors(Some(key), |k| k.split_once('.').map(|(_, rest)| rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(entries: &[(&str, Rgba<u8>)]) -> HashMap<String, Rgba<u8>> {
+        entries.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+    }
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const GREEN: Rgba<u8> = Rgba([0, 255, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+
+    #[test]
+    fn test_fallbacks() {
+        let keys: Vec<_> = fallbacks("a.b.c").collect();
+        assert_eq!(keys, ["a.b.c", "b.c", "c"]);
+        assert_eq!(fallbacks("fg").collect::<Vec<_>>(), ["fg"]);
+    }
+
+    #[test]
+    fn test_resolver_layers_and_fallbacks() {
+        let widget = map(&[("fg", RED)]);
+        let theme = map(&[("button.fg", GREEN), ("bg", BLUE), ("fg", BLUE)]);
+        let colors = ColorResolver::new().layer(&widget).layer(&theme);
+
+        // Earlier layers win, even over a more specific key in a later one
+        assert_eq!(colors.get("button.fg"), Some(RED));
+        assert_eq!(colors.get("button.bg"), Some(BLUE));
+        assert_eq!(colors.get("accent"), None);
+        assert_eq!(colors.get_or("accent", GREEN), GREEN);
+
+        let theme_only = ColorResolver::new().layer(&theme);
+        assert_eq!(theme_only.get("button.fg"), Some(GREEN));
+        assert_eq!(theme_only.get("label.fg"), Some(BLUE));
+    }
+}
@@ -0,0 +1,131 @@
+//! Threshold-based mapping of values to colors.
+
+use image::Rgba;
+
+use super::spaces::{Interpolation, interpolate};
+
+/// How a [`Scale`] colors values between thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScaleMode {
+    /// Each threshold's color applies from that threshold up to the next.
+    #[default]
+    Step,
+    /// Colors blend between thresholds in the given space.
+    Interpolate(Interpolation),
+}
+
+/// Maps values onto colors through `(threshold, color)` pairs, as metric
+/// widgets color a reading by how high it is.
+///
+/// In [`ScaleMode::Step`] a value takes the color of the highest threshold
+/// it reaches; values below the first threshold take the first color. In
+/// [`ScaleMode::Interpolate`] values blend between neighbouring thresholds
+/// and the end colors extend beyond them. Thresholds are in whatever unit
+/// the values are, not limited to 0.0 to 1.0.
+///
+/// ```ignore
+/// // 0-60% green, 60-85% yellow, 85% and above red
+/// let load = Scale::new([(0.0, css::GREEN), (60.0, css::GOLD), (85.0, css::RED)]);
+/// let color = load.color(cpu_percent);
+///
+/// let temp = Scale::new([(30.0, css::STEELBLUE), (90.0, css::ORANGERED)])
+///     .with_mode(ScaleMode::Interpolate(Interpolation::Oklch));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scale {
+    thresholds: Vec<(f32, Rgba<u8>)>,
+    mode: ScaleMode,
+}
+
+impl Scale {
+    /// Create a stepped scale from `(threshold, color)` pairs.
+    ///
+    /// Pairs are sorted by threshold, keeping the given order for equal
+    /// thresholds (the last one wins). Pairs with non-finite thresholds are
+    /// dropped.
+    pub fn new(thresholds: impl IntoIterator<Item = (f32, Rgba<u8>)>) -> Self {
+        let mut thresholds: Vec<_> = thresholds
+            .into_iter()
+            .filter(|(t, _)| t.is_finite())
+            .collect();
+        thresholds.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self {
+            thresholds,
+            mode: ScaleMode::default(),
+        }
+    }
+
+    /// Set how values between thresholds are colored.
+    pub fn with_mode(mut self, mode: ScaleMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// The thresholds, sorted.
+    pub fn thresholds(&self) -> &[(f32, Rgba<u8>)] {
+        &self.thresholds
+    }
+
+    /// How values between thresholds are colored.
+    pub fn mode(&self) -> ScaleMode {
+        self.mode
+    }
+
+    /// Color for `value`.
+    ///
+    /// NaN takes the first color. An empty scale is transparent.
+    pub fn color(&self, value: f32) -> Rgba<u8> {
+        let Some(&(_, first)) = self.thresholds.first() else {
+            return Rgba([0, 0, 0, 0]);
+        };
+        // Index of the first threshold above the value
+        let above = self.thresholds.partition_point(|(t, _)| *t <= value);
+        if value.is_nan() || above == 0 {
+            return first;
+        }
+        let (t0, c0) = self.thresholds[above - 1];
+        match (self.mode, self.thresholds.get(above)) {
+            (ScaleMode::Interpolate(space), Some(&(t1, c1))) => {
+                interpolate(c0, c1, (value - t0) / (t1 - t0), space)
+            }
+            _ => c0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GREEN: Rgba<u8> = Rgba([0, 255, 0, 255]);
+    const YELLOW: Rgba<u8> = Rgba([255, 255, 0, 255]);
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+    #[test]
+    fn test_step_scale() {
+        let scale = Scale::new([(85.0, RED), (0.0, GREEN), (60.0, YELLOW)]);
+        assert_eq!(scale.color(-5.0), GREEN);
+        assert_eq!(scale.color(59.9), GREEN);
+        assert_eq!(scale.color(60.0), YELLOW);
+        assert_eq!(scale.color(85.0), RED);
+        assert_eq!(scale.color(1000.0), RED);
+        assert_eq!(scale.color(f32::NAN), GREEN);
+        assert_eq!(Scale::new([]).color(1.0), Rgba([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_interpolated_scale() {
+        let scale = Scale::new([
+            (0.0, GREEN),
+            (50.0, YELLOW),
+            (100.0, RED),
+            (f32::NAN, GREEN),
+        ])
+        .with_mode(ScaleMode::Interpolate(Interpolation::Srgb));
+        assert_eq!(scale.thresholds().len(), 3);
+        assert_eq!(scale.color(25.0), Rgba([128, 255, 0, 255]));
+        assert_eq!(scale.color(50.0), YELLOW);
+        assert_eq!(scale.color(75.0), Rgba([255, 128, 0, 255]));
+        assert_eq!(scale.color(150.0), RED);
+    }
+}
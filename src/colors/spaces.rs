@@ -0,0 +1,795 @@
+//! Conversions between sRGB and perceptual color spaces.
+//!
+//! CIE Lab and LCH use the D50 white point, matching CSS `lab()` and `lch()`.
+//! OKLab and OKLCH follow Björn Ottosson's definition, matching CSS `oklab()`
+//! and `oklch()`. Interpolating in these spaces gives perceptually even
+//! gradients without the muddy midpoints of sRGB blending. Colors outside the
+//! sRGB gamut are clamped when converted back to [`Rgba<u8>`].
+//!
+//! [`Hsv`] and [`Cmyk`] are plain reshapings of sRGB for interop with
+//! lighting and print APIs.
+
+use image::Rgba;
+
+use super::{srgb_decode, srgb_encode};
+
+/// A color in CIE Lab (D50).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Lab {
+    /// Lightness, 0.0 (black) to 100.0 (white).
+    pub l: f32,
+    /// Green (negative) to red (positive) axis, roughly -125.0 to 125.0.
+    pub a: f32,
+    /// Blue (negative) to yellow (positive) axis, roughly -125.0 to 125.0.
+    pub b: f32,
+    /// Opacity, 0.0 to 1.0.
+    pub alpha: f32,
+}
+
+/// A color in CIE LCH (D50), the polar form of [`Lab`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Lch {
+    /// Lightness, 0.0 (black) to 100.0 (white).
+    pub l: f32,
+    /// Chroma, 0.0 (grey) to roughly 150.0.
+    pub c: f32,
+    /// Hue angle in degrees, 0.0 to 360.0.
+    pub h: f32,
+    /// Opacity, 0.0 to 1.0.
+    pub alpha: f32,
+}
+
+/// A color in OKLab.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Oklab {
+    /// Perceived lightness, 0.0 (black) to 1.0 (white).
+    pub l: f32,
+    /// Green (negative) to red (positive) axis, roughly -0.4 to 0.4.
+    pub a: f32,
+    /// Blue (negative) to yellow (positive) axis, roughly -0.4 to 0.4.
+    pub b: f32,
+    /// Opacity, 0.0 to 1.0.
+    pub alpha: f32,
+}
+
+/// A color in OKLCH, the polar form of [`Oklab`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Oklch {
+    /// Perceived lightness, 0.0 (black) to 1.0 (white).
+    pub l: f32,
+    /// Chroma, 0.0 (grey) to roughly 0.4.
+    pub c: f32,
+    /// Hue angle in degrees, 0.0 to 360.0.
+    pub h: f32,
+    /// Opacity, 0.0 to 1.0.
+    pub alpha: f32,
+}
+
+/// A color in HSV (hue, saturation, value), the cylindrical model used by
+/// color pickers and most LED and smart-lighting APIs.
+///
+/// Unlike the spaces above it is not perceptual: it is a direct reshaping
+/// of (gamma-encoded) sRGB, so every HSV color is inside the sRGB gamut.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Hsv {
+    /// Hue angle in degrees, 0.0 (red) to 360.0.
+    pub h: f32,
+    /// Saturation, 0.0 (grey) to 1.0 (fully saturated).
+    pub s: f32,
+    /// Value, 0.0 (black) to 1.0 (brightest).
+    pub v: f32,
+    /// Opacity, 0.0 to 1.0.
+    pub alpha: f32,
+}
+
+/// A color as CMYK ink coverage.
+///
+/// Uses the simple device-independent formula (no ICC profile), which is
+/// what most APIs mean by CMYK values and is good enough to preview a
+/// print color on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Cmyk {
+    /// Cyan, 0.0 to 1.0.
+    pub c: f32,
+    /// Magenta, 0.0 to 1.0.
+    pub m: f32,
+    /// Yellow, 0.0 to 1.0.
+    pub y: f32,
+    /// Key (black), 0.0 to 1.0.
+    pub k: f32,
+    /// Opacity, 0.0 to 1.0.
+    pub alpha: f32,
+}
+
+/// Linear sRGB to CIE XYZ (D50, Bradford-adapted from D65).
+const SRGB_TO_XYZ_D50: [[f32; 3]; 3] = [
+    [0.436_074_7, 0.385_064_9, 0.143_080_4],
+    [0.222_504_5, 0.716_878_6, 0.060_616_9],
+    [0.013_932_2, 0.097_104_5, 0.714_173_2],
+];
+
+/// CIE XYZ (D50) to linear sRGB.
+const XYZ_D50_TO_SRGB: [[f32; 3]; 3] = [
+    [3.134_136, -1.617_386, -0.490_662_1],
+    [-0.978_795_4, 1.916_254, 0.033_442_5],
+    [0.071_945_3, -0.228_976_8, 1.405_386_5],
+];
+
+/// D50 reference white.
+const WHITE_D50: [f32; 3] = [0.964_22, 1.0, 0.825_21];
+
+const EPSILON: f32 = 216.0 / 24389.0;
+const KAPPA: f32 = 24389.0 / 27.0;
+
+fn mul(m: &[[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+    m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+/// Decode an sRGB color to linear-light channels.
+pub(crate) fn to_linear(color: Rgba<u8>) -> [f32; 3] {
+    [0, 1, 2].map(|c| srgb_decode(color[c] as f32 / 255.0))
+}
+
+/// Encode linear-light channels to sRGB, clamping out-of-gamut values.
+pub(crate) fn from_linear(rgb: [f32; 3], alpha: f32) -> Rgba<u8> {
+    let [r, g, b] = rgb.map(|c| (srgb_encode(c.clamp(0.0, 1.0)) * 255.0).round() as u8);
+    Rgba([r, g, b, (alpha.clamp(0.0, 1.0) * 255.0).round() as u8])
+}
+
+/// Convert an sRGB color to CIE Lab.
+pub fn to_lab(color: Rgba<u8>) -> Lab {
+    let xyz = mul(&SRGB_TO_XYZ_D50, to_linear(color));
+    let f = |i: usize| {
+        let t = xyz[i] / WHITE_D50[i];
+        if t > EPSILON {
+            t.cbrt()
+        } else {
+            (KAPPA * t + 16.0) / 116.0
+        }
+    };
+    let (fx, fy, fz) = (f(0), f(1), f(2));
+    Lab {
+        l: 116.0 * fy - 16.0,
+        a: 500.0 * (fx - fy),
+        b: 200.0 * (fy - fz),
+        alpha: color[3] as f32 / 255.0,
+    }
+}
+
+/// Convert a CIE Lab color to sRGB, clamping to the sRGB gamut.
+pub fn from_lab(lab: Lab) -> Rgba<u8> {
+    let fy = (lab.l + 16.0) / 116.0;
+    let fx = lab.a / 500.0 + fy;
+    let fz = fy - lab.b / 200.0;
+    let inverse = |f: f32| {
+        let cubed = f * f * f;
+        if cubed > EPSILON {
+            cubed
+        } else {
+            (116.0 * f - 16.0) / KAPPA
+        }
+    };
+    let y = if lab.l > KAPPA * EPSILON {
+        fy * fy * fy
+    } else {
+        lab.l / KAPPA
+    };
+    let xyz = [
+        inverse(fx) * WHITE_D50[0],
+        y * WHITE_D50[1],
+        inverse(fz) * WHITE_D50[2],
+    ];
+    from_linear(mul(&XYZ_D50_TO_SRGB, xyz), lab.alpha)
+}
+
+/// Convert an sRGB color to CIE LCH.
+pub fn to_lch(color: Rgba<u8>) -> Lch {
+    let lab = to_lab(color);
+    Lch {
+        l: lab.l,
+        c: lab.a.hypot(lab.b),
+        h: lab.b.atan2(lab.a).to_degrees().rem_euclid(360.0),
+        alpha: lab.alpha,
+    }
+}
+
+/// Convert a CIE LCH color to sRGB, clamping to the sRGB gamut.
+pub fn from_lch(lch: Lch) -> Rgba<u8> {
+    let h = lch.h.to_radians();
+    from_lab(Lab {
+        l: lch.l,
+        a: lch.c * h.cos(),
+        b: lch.c * h.sin(),
+        alpha: lch.alpha,
+    })
+}
+
+/// Perceptual difference between two colors by the CIEDE2000 formula.
+///
+/// About 1.0 is the smallest difference most people notice side by side;
+/// below 2.0 colors read as the same in small UI elements, and series
+/// colors in a chart want 10.0 or more. Alpha is ignored.
+///
+/// ```ignore
+/// palette.dedup_by(|a, b| delta_e(*a, *b) < 2.0);
+/// ```
+pub fn delta_e(a: Rgba<u8>, b: Rgba<u8>) -> f32 {
+    ciede2000(to_lab(a), to_lab(b))
+}
+
+/// CIEDE2000 color difference with unit weighting factors.
+fn ciede2000(x: Lab, y: Lab) -> f32 {
+    let pow7 = |v: f32| v.powi(7);
+    let c_bar = (x.a.hypot(x.b) + y.a.hypot(y.b)) / 2.0;
+    let g = 0.5 * (1.0 - (pow7(c_bar) / (pow7(c_bar) + pow7(25.0))).sqrt());
+    let (a1, a2) = (x.a * (1.0 + g), y.a * (1.0 + g));
+    let (c1, c2) = (a1.hypot(x.b), a2.hypot(y.b));
+    let hue = |b: f32, a: f32| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        }
+    };
+    let (h1, h2) = (hue(x.b, a1), hue(y.b, a2));
+
+    let dl = y.l - x.l;
+    let dc = c2 - c1;
+    let dh = if c1 * c2 == 0.0 {
+        0.0
+    } else if (h2 - h1).abs() <= 180.0 {
+        h2 - h1
+    } else if h2 <= h1 {
+        h2 - h1 + 360.0
+    } else {
+        h2 - h1 - 360.0
+    };
+    let dh_big = 2.0 * (c1 * c2).sqrt() * (dh / 2.0).to_radians().sin();
+
+    let l_bar = (x.l + y.l) / 2.0;
+    let c_bar = (c1 + c2) / 2.0;
+    let h_bar = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) / 2.0
+    } else {
+        (h1 + h2 - 360.0) / 2.0
+    };
+    let t = 1.0 - 0.17 * (h_bar - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_bar).to_radians().cos()
+        + 0.32 * (3.0 * h_bar + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_bar - 63.0).to_radians().cos();
+    let d_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+    let r_c = 2.0 * (pow7(c_bar) / (pow7(c_bar) + pow7(25.0))).sqrt();
+    let s_l = 1.0 + 0.015 * (l_bar - 50.0).powi(2) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+    let s_c = 1.0 + 0.045 * c_bar;
+    let s_h = 1.0 + 0.015 * c_bar * t;
+    let r_t = -(2.0 * d_theta).to_radians().sin() * r_c;
+
+    let (l, c, h) = (dl / s_l, dc / s_c, dh_big / s_h);
+    (l * l + c * c + h * h + r_t * c * h).sqrt()
+}
+
+/// Linear sRGB to LMS cone response, for OKLab.
+const SRGB_TO_LMS: [[f32; 3]; 3] = [
+    [0.412_221_47, 0.536_332_54, 0.051_445_99],
+    [0.211_903_5, 0.680_699_5, 0.107_396_96],
+    [0.088_302_46, 0.281_718_85, 0.629_978_7],
+];
+
+/// Non-linear LMS to OKLab.
+const LMS_TO_OKLAB: [[f32; 3]; 3] = [
+    [0.210_454_26, 0.793_617_8, -0.004_072_047],
+    [1.977_998_5, -2.428_592_2, 0.450_593_7],
+    [0.025_904_037, 0.782_771_77, -0.808_675_77],
+];
+
+/// OKLab to non-linear LMS.
+const OKLAB_TO_LMS: [[f32; 3]; 3] = [
+    [1.0, 0.396_337_78, 0.215_803_76],
+    [1.0, -0.105_561_346, -0.063_854_17],
+    [1.0, -0.089_484_18, -1.291_485_5],
+];
+
+/// LMS cone response to linear sRGB.
+const LMS_TO_SRGB: [[f32; 3]; 3] = [
+    [4.076_741_7, -3.307_711_6, 0.230_969_94],
+    [-1.268_438, 2.609_757_4, -0.341_319_38],
+    [-0.004_196_086_3, -0.703_418_6, 1.707_614_7],
+];
+
+/// Convert an sRGB color to OKLab.
+pub fn to_oklab(color: Rgba<u8>) -> Oklab {
+    let lms = mul(&SRGB_TO_LMS, to_linear(color)).map(f32::cbrt);
+    let [l, a, b] = mul(&LMS_TO_OKLAB, lms);
+    Oklab {
+        l,
+        a,
+        b,
+        alpha: color[3] as f32 / 255.0,
+    }
+}
+
+/// Convert an OKLab color to sRGB, clamping to the sRGB gamut.
+pub fn from_oklab(lab: Oklab) -> Rgba<u8> {
+    let lms = mul(&OKLAB_TO_LMS, [lab.l, lab.a, lab.b]).map(|c| c * c * c);
+    from_linear(mul(&LMS_TO_SRGB, lms), lab.alpha)
+}
+
+/// Convert an sRGB color to OKLCH.
+pub fn to_oklch(color: Rgba<u8>) -> Oklch {
+    let lab = to_oklab(color);
+    Oklch {
+        l: lab.l,
+        c: lab.a.hypot(lab.b),
+        h: lab.b.atan2(lab.a).to_degrees().rem_euclid(360.0),
+        alpha: lab.alpha,
+    }
+}
+
+/// Convert an OKLCH color to sRGB, clamping to the sRGB gamut.
+pub fn from_oklch(lch: Oklch) -> Rgba<u8> {
+    let h = lch.h.to_radians();
+    from_oklab(Oklab {
+        l: lch.l,
+        a: lch.c * h.cos(),
+        b: lch.c * h.sin(),
+        alpha: lch.alpha,
+    })
+}
+
+/// Convert an OKLCH color to sRGB, reducing chroma until it fits the sRGB
+/// gamut so lightness and hue are preserved.
+pub(crate) fn from_oklch_mapped(lch: Oklch) -> Rgba<u8> {
+    let l = lch.l.clamp(0.0, 1.0);
+    let (sin, cos) = lch.h.to_radians().sin_cos();
+    let linear = |c: f32| {
+        let lms = mul(&OKLAB_TO_LMS, [l, c * cos, c * sin]).map(|v| v * v * v);
+        mul(&LMS_TO_SRGB, lms)
+    };
+    let in_gamut = |rgb: [f32; 3]| rgb.iter().all(|v| (-1e-4..=1.0 + 1e-4).contains(v));
+    // Pure black and white have no room for chroma
+    let mut c = if l <= 0.0 || l >= 1.0 {
+        0.0
+    } else {
+        lch.c.max(0.0)
+    };
+    if !in_gamut(linear(c)) {
+        // Bisect for the largest chroma still inside the gamut
+        let (mut lo, mut hi) = (0.0, c);
+        for _ in 0..16 {
+            let mid = (lo + hi) / 2.0;
+            if in_gamut(linear(mid)) {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        c = lo;
+    }
+    from_linear(linear(c), lch.alpha)
+}
+
+/// Convert HSL (hue in degrees, saturation and lightness 0.0 to 1.0) to RGB.
+pub(super) fn hsl_to_rgb(h: f32, s: f32, l: f32) -> [f32; 3] {
+    let a = s * l.min(1.0 - l);
+    let f = |n: f32| {
+        let k = (n + h / 30.0).rem_euclid(12.0);
+        l - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+    };
+    [f(0.0), f(8.0), f(4.0)]
+}
+
+/// Convert RGB (0.0 to 1.0) to HSL (hue in degrees, saturation and
+/// lightness 0.0 to 1.0). Greys have hue 0.0 and saturation 0.0.
+pub(super) fn rgb_to_hsl([r, g, b]: [f32; 3]) -> [f32; 3] {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d <= 0.0 {
+        return [0.0, 0.0, l];
+    }
+    let s = d / (1.0 - (max + min - 1.0).abs());
+    let h = if max == r {
+        (g - b) / d
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    [(h * 60.0).rem_euclid(360.0), s, l]
+}
+
+/// Convert an sRGB color to HSV. Greys have hue 0.0 and saturation 0.0.
+pub fn to_hsv(color: Rgba<u8>) -> Hsv {
+    let [r, g, b] = [0, 1, 2].map(|c| color[c] as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let d = max - r.min(g).min(b);
+    let h = if d <= 0.0 {
+        0.0
+    } else if max == r {
+        (g - b) / d
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    Hsv {
+        h: (h * 60.0).rem_euclid(360.0),
+        s: if max > 0.0 { d / max } else { 0.0 },
+        v: max,
+        alpha: color[3] as f32 / 255.0,
+    }
+}
+
+/// Convert an HSV color to sRGB. Hue wraps around; saturation, value and
+/// alpha are clamped to 0.0 to 1.0.
+pub fn from_hsv(hsv: Hsv) -> Rgba<u8> {
+    let h = hsv.h.rem_euclid(360.0) / 60.0;
+    let s = hsv.s.clamp(0.0, 1.0);
+    let v = hsv.v.clamp(0.0, 1.0);
+    let f = |n: f32| {
+        let k = (n + h).rem_euclid(6.0);
+        v - v * s * k.min(4.0 - k).clamp(0.0, 1.0)
+    };
+    let channel = |c: f32| (c * 255.0).round() as u8;
+    Rgba([
+        channel(f(5.0)),
+        channel(f(3.0)),
+        channel(f(1.0)),
+        channel(hsv.alpha.clamp(0.0, 1.0)),
+    ])
+}
+
+/// Convert an sRGB color to CMYK, using as much black as possible.
+pub fn to_cmyk(color: Rgba<u8>) -> Cmyk {
+    let [r, g, b] = [0, 1, 2].map(|c| color[c] as f32 / 255.0);
+    let k = 1.0 - r.max(g).max(b);
+    let ink = |v: f32| {
+        if k < 1.0 {
+            (1.0 - v - k) / (1.0 - k)
+        } else {
+            0.0
+        }
+    };
+    Cmyk {
+        c: ink(r),
+        m: ink(g),
+        y: ink(b),
+        k,
+        alpha: color[3] as f32 / 255.0,
+    }
+}
+
+/// Convert a CMYK color to sRGB. Components are clamped to 0.0 to 1.0.
+pub fn from_cmyk(cmyk: Cmyk) -> Rgba<u8> {
+    let k = cmyk.k.clamp(0.0, 1.0);
+    let channel = |v: f32| ((1.0 - v.clamp(0.0, 1.0)) * (1.0 - k) * 255.0).round() as u8;
+    Rgba([
+        channel(cmyk.c),
+        channel(cmyk.m),
+        channel(cmyk.y),
+        (cmyk.alpha.clamp(0.0, 1.0) * 255.0).round() as u8,
+    ])
+}
+
+/// Color space used to interpolate between two colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// Gamma-encoded sRGB channels, as plain CSS gradients blend.
+    #[default]
+    Srgb,
+    /// Linear-light sRGB; physically correct mixing of light.
+    #[doc(alias = "LinearRgb")]
+    SrgbLinear,
+    /// HSL, taking the shorter way round the hue circle.
+    Hsl,
+    /// CIELAB.
+    Lab,
+    /// CIE LCh, taking the shorter way round the hue circle.
+    Lch,
+    /// OKLab; perceptually even without hue shifts through grey.
+    Oklab,
+    /// OKLCH, taking the shorter way round the hue circle.
+    Oklch,
+}
+
+/// Interpolate from `a` to `b` by `t` (0.0 to 1.0) in `space`.
+///
+/// Rectangular coordinates are interpolated with premultiplied alpha, as
+/// CSS does. Polar spaces take the shorter way round the hue circle; the
+/// hue of an achromatic endpoint is ignored.
+pub(crate) fn interpolate(a: Rgba<u8>, b: Rgba<u8>, t: f32, space: Interpolation) -> Rgba<u8> {
+    let t = t.clamp(0.0, 1.0);
+    let alpha = |c: Rgba<u8>| c[3] as f32 / 255.0;
+    let (alpha_a, alpha_b) = (alpha(a), alpha(b));
+    let out_alpha = alpha_a + (alpha_b - alpha_a) * t;
+    // Premultiplied interpolation of one coordinate
+    let premul = |x: f32, y: f32| {
+        if out_alpha <= 0.0 {
+            x + (y - x) * t
+        } else {
+            (x * alpha_a + (y * alpha_b - x * alpha_a) * t) / out_alpha
+        }
+    };
+    let hue = |h0: f32, c0: f32, h1: f32, c1: f32| {
+        let (h0, h1) = match (c0 < 1e-4, c1 < 1e-4) {
+            (true, false) => (h1, h1),
+            (false, true) => (h0, h0),
+            _ => (h0, h1),
+        };
+        let delta = (h1 - h0 + 540.0).rem_euclid(360.0) - 180.0;
+        (h0 + delta * t).rem_euclid(360.0)
+    };
+    match space {
+        Interpolation::Srgb => {
+            let [r, g, bl] = [0, 1, 2].map(|c| premul(a[c] as f32, b[c] as f32));
+            Rgba([
+                r.round().clamp(0.0, 255.0) as u8,
+                g.round().clamp(0.0, 255.0) as u8,
+                bl.round().clamp(0.0, 255.0) as u8,
+                (out_alpha * 255.0).round() as u8,
+            ])
+        }
+        Interpolation::SrgbLinear => {
+            let (la, lb) = (to_linear(a), to_linear(b));
+            from_linear([0, 1, 2].map(|c| premul(la[c], lb[c])), out_alpha)
+        }
+        Interpolation::Hsl => {
+            let hsl = |c: Rgba<u8>| rgb_to_hsl([0, 1, 2].map(|i| c[i] as f32 / 255.0));
+            let ([h0, s0, l0], [h1, s1, l1]) = (hsl(a), hsl(b));
+            let rgb = hsl_to_rgb(hue(h0, s0, h1, s1), premul(s0, s1), premul(l0, l1));
+            let [r, g, bl] = rgb.map(|c| (c * 255.0).round().clamp(0.0, 255.0) as u8);
+            Rgba([r, g, bl, (out_alpha * 255.0).round() as u8])
+        }
+        Interpolation::Lab => {
+            let (x, y) = (to_lab(a), to_lab(b));
+            from_lab(Lab {
+                l: premul(x.l, y.l),
+                a: premul(x.a, y.a),
+                b: premul(x.b, y.b),
+                alpha: out_alpha,
+            })
+        }
+        Interpolation::Lch => {
+            let (x, y) = (to_lch(a), to_lch(b));
+            from_lch(Lch {
+                l: premul(x.l, y.l),
+                c: premul(x.c, y.c),
+                h: hue(x.h, x.c, y.h, y.c),
+                alpha: out_alpha,
+            })
+        }
+        Interpolation::Oklab => {
+            let (x, y) = (to_oklab(a), to_oklab(b));
+            from_oklab(Oklab {
+                l: premul(x.l, y.l),
+                a: premul(x.a, y.a),
+                b: premul(x.b, y.b),
+                alpha: out_alpha,
+            })
+        }
+        Interpolation::Oklch => {
+            let (x, y) = (to_oklch(a), to_oklch(b));
+            from_oklch(Oklch {
+                l: premul(x.l, y.l),
+                c: premul(x.c, y.c),
+                h: hue(x.h, x.c, y.h, y.c),
+                alpha: out_alpha,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hsv_reference_values() {
+        let orange = to_hsv(Rgba([255, 128, 0, 255]));
+        assert!((orange.h - 30.1).abs() < 0.1);
+        assert_eq!((orange.s, orange.v, orange.alpha), (1.0, 1.0, 1.0));
+        let grey = to_hsv(Rgba([64, 64, 64, 0]));
+        assert_eq!((grey.h, grey.s, grey.alpha), (0.0, 0.0, 0.0));
+        let blue = Hsv {
+            h: 240.0 - 360.0,
+            s: 1.0,
+            v: 0.5,
+            alpha: 1.0,
+        };
+        assert_eq!(from_hsv(blue), Rgba([0, 0, 128, 255]));
+    }
+
+    #[test]
+    fn test_hsv_round_trip() {
+        for color in [
+            Rgba([255, 107, 53, 255]),
+            Rgba([0, 0, 0, 128]),
+            Rgba([12, 200, 90, 255]),
+            Rgba([90, 12, 200, 7]),
+            Rgba([128, 128, 128, 0]),
+        ] {
+            assert_eq!(from_hsv(to_hsv(color)), color);
+        }
+    }
+
+    #[test]
+    fn test_cmyk_conversions() {
+        let cmyk = to_cmyk(Rgba([255, 128, 0, 255]));
+        assert_eq!((cmyk.c, cmyk.y, cmyk.k), (0.0, 1.0, 0.0));
+        assert!((cmyk.m - 0.498).abs() < 0.001);
+        assert_eq!(to_cmyk(Rgba([0, 0, 0, 255])).k, 1.0);
+        let red = Cmyk {
+            c: 0.0,
+            m: 1.0,
+            y: 1.0,
+            k: 0.5,
+            alpha: 1.0,
+        };
+        assert_eq!(from_cmyk(red), Rgba([128, 0, 0, 255]));
+        for color in [
+            Rgba([255, 107, 53, 255]),
+            Rgba([0, 0, 0, 128]),
+            Rgba([12, 200, 90, 9]),
+        ] {
+            assert_eq!(from_cmyk(to_cmyk(color)), color);
+        }
+    }
+
+    #[test]
+    fn test_ciede2000_reference_pairs() {
+        // Sharma, Wu and Dalal (2005) test data
+        let lab = |l, a, b| Lab {
+            l,
+            a,
+            b,
+            alpha: 1.0,
+        };
+        let pairs = [
+            (
+                lab(50.0, 2.6772, -79.7751),
+                lab(50.0, 0.0, -82.7485),
+                2.0425,
+            ),
+            (lab(50.0, 0.0, 0.0), lab(50.0, -1.0, 2.0), 2.3669),
+            (lab(50.0, 2.5, 0.0), lab(73.0, 25.0, -18.0), 27.1492),
+            (lab(50.0, 2.5, 0.0), lab(50.0, 0.0, -2.5), 4.3065),
+            (
+                lab(60.2574, -34.0099, 36.2677),
+                lab(60.4626, -34.1751, 39.4387),
+                1.2644,
+            ),
+            (
+                lab(2.0776, 0.0795, -1.135),
+                lab(0.9033, -0.0636, -0.5514),
+                0.9082,
+            ),
+        ];
+        for (x, y, expected) in pairs {
+            assert!((ciede2000(x, y) - expected).abs() < 1e-3, "{x:?} {y:?}");
+            assert!((ciede2000(y, x) - expected).abs() < 1e-3);
+        }
+        assert_eq!(delta_e(Rgba([9, 99, 199, 255]), Rgba([9, 99, 199, 0])), 0.0);
+    }
+
+    #[test]
+    fn test_lab_reference_values() {
+        let white = to_lab(Rgba([255, 255, 255, 255]));
+        assert!((white.l - 100.0).abs() < 0.05);
+        assert!(white.a.abs() < 0.05 && white.b.abs() < 0.05);
+
+        // CSS Color 4 reference: sRGB red is lab(54.29 80.80 69.89)
+        let red = to_lab(Rgba([255, 0, 0, 255]));
+        assert!((red.l - 54.29).abs() < 0.1);
+        assert!((red.a - 80.80).abs() < 0.2);
+        assert!((red.b - 69.89).abs() < 0.2);
+    }
+
+    #[test]
+    fn test_lab_round_trip() {
+        for color in [
+            Rgba([255, 107, 53, 255]),
+            Rgba([0, 0, 0, 128]),
+            Rgba([12, 200, 90, 255]),
+            Rgba([128, 128, 128, 0]),
+        ] {
+            assert_eq!(from_lab(to_lab(color)), color);
+            assert_eq!(from_lch(to_lch(color)), color);
+        }
+    }
+
+    #[test]
+    fn test_lch_hue_and_gamut_clamp() {
+        let blue = to_lch(Rgba([0, 0, 255, 255]));
+        assert!(blue.h > 290.0 && blue.h < 310.0);
+        let out_of_gamut = from_lch(Lch {
+            l: 50.0,
+            c: 300.0,
+            h: 40.0,
+            alpha: 1.0,
+        });
+        assert_eq!(out_of_gamut[3], 255);
+    }
+
+    #[test]
+    fn test_oklab_reference_values() {
+        let white = to_oklab(Rgba([255, 255, 255, 255]));
+        assert!((white.l - 1.0).abs() < 1e-3);
+        assert!(white.a.abs() < 1e-3 && white.b.abs() < 1e-3);
+
+        // CSS Color 4 reference: sRGB red is oklch(0.628 0.2577 29.23)
+        let red = to_oklch(Rgba([255, 0, 0, 255]));
+        assert!((red.l - 0.628).abs() < 1e-3);
+        assert!((red.c - 0.2577).abs() < 1e-3);
+        assert!((red.h - 29.23).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_oklab_round_trip() {
+        for color in [
+            Rgba([255, 107, 53, 255]),
+            Rgba([0, 0, 0, 128]),
+            Rgba([12, 200, 90, 255]),
+            Rgba([0, 0, 255, 255]),
+        ] {
+            assert_eq!(from_oklab(to_oklab(color)), color);
+            assert_eq!(from_oklch(to_oklch(color)), color);
+        }
+    }
+
+    #[test]
+    fn test_interpolate_endpoints_and_midpoint() {
+        let red = Rgba([255, 0, 0, 255]);
+        let blue = Rgba([0, 0, 255, 255]);
+        for space in [
+            Interpolation::Srgb,
+            Interpolation::SrgbLinear,
+            Interpolation::Lab,
+            Interpolation::Lch,
+            Interpolation::Oklab,
+            Interpolation::Oklch,
+        ] {
+            assert_eq!(interpolate(red, blue, 0.0, space), red);
+            assert_eq!(interpolate(red, blue, 1.0, space), blue);
+        }
+        assert_eq!(
+            interpolate(red, blue, 0.5, Interpolation::Srgb),
+            Rgba([128, 0, 128, 255])
+        );
+        // OKLCH keeps the midpoint saturated instead of muddy
+        let mid = interpolate(red, blue, 0.5, Interpolation::Oklch);
+        assert!(to_oklch(mid).c > to_oklch(Rgba([128, 0, 128, 255])).c);
+    }
+
+    #[test]
+    fn test_interpolate_premultiplied_alpha() {
+        let red = Rgba([255, 0, 0, 255]);
+        let clear = Rgba([0, 0, 255, 0]);
+        // A fully transparent endpoint contributes no color
+        assert_eq!(
+            interpolate(red, clear, 0.5, Interpolation::Srgb),
+            Rgba([255, 0, 0, 128])
+        );
+    }
+
+    #[test]
+    fn test_from_oklch_mapped_keeps_hue() {
+        // Far outside the gamut: plain clamping shifts hue, mapping does not
+        let vivid = Oklch {
+            l: 0.7,
+            c: 0.4,
+            h: 150.0,
+            alpha: 1.0,
+        };
+        let mapped = to_oklch(from_oklch_mapped(vivid));
+        assert!((mapped.h - 150.0).abs() < 3.0, "hue {}", mapped.h);
+        assert!((mapped.l - 0.7).abs() < 0.01, "lightness {}", mapped.l);
+        // In-gamut colors are unchanged
+        let red = Rgba([255, 0, 0, 255]);
+        assert_eq!(from_oklch_mapped(to_oklch(red)), red);
+    }
+}
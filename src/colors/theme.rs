@@ -0,0 +1,160 @@
+//! Shared color roles for widgets.
+
+use std::collections::HashMap;
+
+use image::Rgba;
+
+use super::adjust::ensure_contrast;
+use super::{hex, relative_luminance};
+use crate::accessibility::{HIGH_CONTRAST_RATIO, HIGH_CONTRAST_UI_RATIO, high_contrast};
+
+/// Named color roles shared by a plugin's widgets.
+///
+/// Start from [`Theme::dark`] or [`Theme::light`], or build one from a
+/// plugin's parsed color config with [`Theme::from_map`], then pass it to
+/// every widget instead of looking colors up one by one. The default is
+/// the dark theme.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// Primary text and icon color.
+    pub fg: Rgba<u8>,
+    /// Tile background.
+    pub bg: Rgba<u8>,
+    /// Highlight color for values, bars and rings.
+    pub accent: Rgba<u8>,
+    /// Color for values that need attention soon.
+    pub warning: Rgba<u8>,
+    /// Color for failures and critical values.
+    pub error: Rgba<u8>,
+    /// Secondary color for tracks, grids and inactive elements.
+    pub muted: Rgba<u8>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// Light text on a near-black background.
+    pub const fn dark() -> Self {
+        Self {
+            fg: hex("#E6E6E6"),
+            bg: hex("#1E1E1E"),
+            accent: hex("#4A9EFF"),
+            warning: hex("#FFB020"),
+            error: hex("#FF5C5C"),
+            muted: hex("#3C3C3C"),
+        }
+    }
+
+    /// Dark text on a near-white background.
+    pub const fn light() -> Self {
+        Self {
+            fg: hex("#1E1E1E"),
+            bg: hex("#F5F5F5"),
+            accent: hex("#0B62D6"),
+            warning: hex("#A15C00"),
+            error: hex("#C62828"),
+            muted: hex("#C8C8C8"),
+        }
+    }
+
+    /// Build a theme from a map of role names to colors, as returned by
+    /// [`parse_colors`](super::parse_colors).
+    ///
+    /// Keys are the field names (`"fg"`, `"bg"`, `"accent"`, `"warning"`,
+    /// `"error"`, `"muted"`). Missing roles come from [`Theme::light`] if
+    /// the map's `bg` is light, and from [`Theme::dark`] otherwise; other
+    /// keys are ignored.
+    pub fn from_map(colors: &HashMap<String, Rgba<u8>>) -> Self {
+        let base = match colors.get("bg") {
+            Some(&bg) if relative_luminance(bg) > 0.5 => Self::light(),
+            _ => Self::dark(),
+        };
+        let get = |key: &str, default: Rgba<u8>| colors.get(key).copied().unwrap_or(default);
+        Self {
+            fg: get("fg", base.fg),
+            bg: get("bg", base.bg),
+            accent: get("accent", base.accent),
+            warning: get("warning", base.warning),
+            error: get("error", base.error),
+            muted: get("muted", base.muted),
+        }
+    }
+
+    /// Copy of this theme with `fg`, `accent`, `warning` and `error`
+    /// adjusted to reach `ratio` against `bg`, and `muted` adjusted to reach
+    /// the lower [`HIGH_CONTRAST_UI_RATIO`] used for non-text elements.
+    pub fn with_min_contrast(self, ratio: f32) -> Self {
+        Self {
+            fg: ensure_contrast(self.fg, self.bg, ratio),
+            accent: ensure_contrast(self.accent, self.bg, ratio),
+            warning: ensure_contrast(self.warning, self.bg, ratio),
+            error: ensure_contrast(self.error, self.bg, ratio),
+            muted: ensure_contrast(self.muted, self.bg, HIGH_CONTRAST_UI_RATIO.min(ratio)),
+            ..self
+        }
+    }
+
+    /// The theme to draw with: unchanged normally, or raised to
+    /// [`HIGH_CONTRAST_RATIO`] when [high-contrast
+    /// mode](crate::accessibility::set_high_contrast) is enabled.
+    pub fn effective(self) -> Self {
+        if high_contrast() {
+            self.with_min_contrast(HIGH_CONTRAST_RATIO)
+        } else {
+            self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::{luminance_contrast, relative_luminance};
+
+    fn contrast(a: Rgba<u8>, b: Rgba<u8>) -> f32 {
+        luminance_contrast(relative_luminance(a), relative_luminance(b))
+    }
+
+    #[test]
+    fn test_theme_presets_are_readable() {
+        for theme in [Theme::dark(), Theme::light()] {
+            assert!(contrast(theme.fg, theme.bg) >= 7.0);
+            assert!(contrast(theme.accent, theme.bg) >= 4.5);
+            assert!(contrast(theme.warning, theme.bg) >= 4.5);
+            assert!(contrast(theme.error, theme.bg) >= 4.5);
+        }
+        assert_eq!(Theme::default(), Theme::dark());
+    }
+
+    #[test]
+    fn test_theme_from_map() {
+        let map = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), hex(v)))
+                .collect::<HashMap<_, _>>()
+        };
+        let theme = Theme::from_map(&map(&[("accent", "#00FF00"), ("other", "#123456")]));
+        assert_eq!(theme.accent, hex("#00FF00"));
+        assert_eq!(theme.fg, Theme::dark().fg);
+
+        // A light background pulls the remaining roles from the light preset
+        let theme = Theme::from_map(&map(&[("bg", "#FFFFFF")]));
+        assert_eq!(theme.bg, hex("#FFFFFF"));
+        assert_eq!(theme.fg, Theme::light().fg);
+    }
+
+    #[test]
+    fn test_theme_with_min_contrast() {
+        let theme = Theme::default().with_min_contrast(7.0);
+        assert!(contrast(theme.fg, theme.bg) >= 7.0);
+        assert!(contrast(theme.accent, theme.bg) >= 7.0);
+        assert!(contrast(theme.error, theme.bg) >= 7.0);
+        assert!(contrast(theme.muted, theme.bg) >= 3.0);
+        assert_eq!(theme.bg, Theme::default().bg);
+    }
+}
@@ -0,0 +1,195 @@
+//! Visual debug overlay for layout problems.
+//!
+//! While the overlay is enabled, layout code records rectangles as it draws
+//! and [`draw_overlay`] paints them over the finished frame in translucent
+//! colors:
+//!
+//! - [`DebugMark::Layout`]: slots assigned to widget spec elements
+//! - [`DebugMark::TextBounds`]: boxes around centered text
+//! - [`DebugMark::Baseline`]: the baseline of each text line
+//! - [`DebugMark::SafeArea`]: the device safe area in a
+//!   [`Pipeline`](crate::pipeline::Pipeline)
+//!
+//! [`render`](crate::widget::render) and
+//! [`Pipeline::render`](crate::pipeline::Pipeline::render) draw the overlay
+//! themselves; code that composes frames by hand can [`mark`] its own
+//! rectangles and call [`draw_overlay`] at the end. The flag is
+//! process-wide and can be toggled at runtime, e.g. from a key binding:
+//!
+//! ```ignore
+//! if key == Key::F12 {
+//!     set_debug_overlay(!debug_overlay());
+//! }
+//! ```
+//!
+//! Marks are collected per thread, so frames rendered on a
+//! [`RenderWorker`](crate::worker::RenderWorker) get their own. Only the
+//! latest [`MAX_MARKS`] are kept, so a thread that records marks without
+//! ever drawing the overlay holds a bounded amount.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use image::{Rgba, RgbaImage};
+use imageproc::rect::Rect;
+
+use crate::chart::blend;
+
+static DEBUG_OVERLAY: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static MARKS: RefCell<VecDeque<(DebugMark, Rect)>> = const { RefCell::new(VecDeque::new()) };
+    static ORIGIN: Cell<(i32, i32)> = const { Cell::new((0, 0)) };
+}
+
+/// Marks kept per thread between [`draw_overlay`] calls; older ones are
+/// dropped first.
+pub const MAX_MARKS: usize = 4096;
+
+/// Opacity of overlay strokes.
+const OVERLAY_ALPHA: f32 = 0.6;
+
+/// Kind of rectangle recorded for the overlay, which sets its color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugMark {
+    /// A layout slot (magenta outline).
+    Layout,
+    /// The box around a block of text (cyan outline).
+    TextBounds,
+    /// A text baseline (yellow line along the rectangle's top edge).
+    Baseline,
+    /// The device safe area (green outline).
+    SafeArea,
+}
+
+impl DebugMark {
+    /// Color the mark is drawn in, before [`OVERLAY_ALPHA`] is applied.
+    pub const fn color(self) -> Rgba<u8> {
+        match self {
+            DebugMark::Layout => Rgba([255, 0, 255, 255]),
+            DebugMark::TextBounds => Rgba([0, 220, 255, 255]),
+            DebugMark::Baseline => Rgba([255, 220, 0, 255]),
+            DebugMark::SafeArea => Rgba([0, 255, 96, 255]),
+        }
+    }
+}
+
+/// Enable or disable the debug overlay for the whole process.
+pub fn set_debug_overlay(enabled: bool) {
+    DEBUG_OVERLAY.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether the debug overlay is enabled.
+pub fn debug_overlay() -> bool {
+    DEBUG_OVERLAY.load(Ordering::Relaxed)
+}
+
+/// Record `rect` for the next [`draw_overlay`] on this thread. Does nothing
+/// while the overlay is disabled.
+pub fn mark(kind: DebugMark, rect: Rect) {
+    if debug_overlay() {
+        record(kind, rect);
+    }
+}
+
+/// Record `rect` offset by the current origin, dropping the oldest mark
+/// once [`MAX_MARKS`] are held.
+fn record(kind: DebugMark, rect: Rect) {
+    let (dx, dy) = ORIGIN.get();
+    let rect = Rect::at(rect.left() + dx, rect.top() + dy).of_size(rect.width(), rect.height());
+    MARKS.with_borrow_mut(|marks| {
+        if marks.len() == MAX_MARKS {
+            marks.pop_front();
+        }
+        marks.push_back((kind, rect));
+    });
+}
+
+/// Run `draw` with marks offset by `(dx, dy)`, for drawing onto a layer
+/// that will be pasted into the frame at that position.
+pub(crate) fn with_origin<R>(dx: i32, dy: i32, draw: impl FnOnce() -> R) -> R {
+    let previous = ORIGIN.get();
+    ORIGIN.set((previous.0 + dx, previous.1 + dy));
+    let result = draw();
+    ORIGIN.set(previous);
+    result
+}
+
+/// Paint and clear the marks recorded on this thread.
+///
+/// Marks are cleared even while the overlay is disabled, so turning it on
+/// never shows stale rectangles. Drawing is clipped to the image bounds.
+pub fn draw_overlay(rgba: &mut RgbaImage) {
+    let marks = MARKS.take();
+    if debug_overlay() {
+        paint(rgba, marks);
+    }
+}
+
+/// Blend the outlines of `marks` onto `rgba`, clipped to its bounds.
+fn paint(rgba: &mut RgbaImage, marks: impl IntoIterator<Item = (DebugMark, Rect)>) {
+    let (width, height) = (rgba.width() as i32, rgba.height() as i32);
+    let mut plot = |x: i32, y: i32, color: Rgba<u8>| {
+        if (0..width).contains(&x) && (0..height).contains(&y) {
+            blend(rgba.get_pixel_mut(x as u32, y as u32), color, OVERLAY_ALPHA);
+        }
+    };
+    for (kind, rect) in marks {
+        let color = kind.color();
+        let (left, top, right, bottom) = (rect.left(), rect.top(), rect.right(), rect.bottom());
+        for x in left..=right {
+            plot(x, top, color);
+            if kind != DebugMark::Baseline && bottom != top {
+                plot(x, bottom, color);
+            }
+        }
+        if kind != DebugMark::Baseline {
+            for y in top + 1..bottom {
+                plot(left, y, color);
+                if right != left {
+                    plot(right, y, color);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marks_ignored_while_disabled() {
+        mark(DebugMark::Layout, Rect::at(0, 0).of_size(4, 4));
+        assert!(MARKS.with_borrow(VecDeque::is_empty));
+    }
+
+    #[test]
+    fn test_paint_outlines_and_baselines() {
+        record(DebugMark::Layout, Rect::at(1, 1).of_size(4, 3));
+        with_origin(2, 3, || {
+            record(DebugMark::Baseline, Rect::at(0, 2).of_size(3, 1))
+        });
+        let mut img = RgbaImage::new(8, 8);
+        paint(&mut img, MARKS.take());
+
+        // Blended in linear light (Q8 rounding may shift a level)
+        let magenta = *img.get_pixel(1, 1);
+        assert!(magenta[0].abs_diff(203) <= 1 && magenta[1] == 0);
+        assert_eq!(*img.get_pixel(4, 3), magenta);
+        assert_eq!(img.get_pixel(2, 2)[3], 0, "outline interior is untouched");
+        assert!((2..5).all(|x| img.get_pixel(x, 5)[0] == magenta[0] && img.get_pixel(x, 5)[1] > 0));
+        assert_eq!(img.get_pixel(2, 6)[3], 0);
+    }
+
+    #[test]
+    fn test_marks_are_bounded() {
+        for x in 0..MAX_MARKS as i32 + 10 {
+            record(DebugMark::Layout, Rect::at(x, 0).of_size(1, 1));
+        }
+        let marks = MARKS.take();
+        assert_eq!(marks.len(), MAX_MARKS);
+        assert_eq!(marks.front().map(|(_, r)| r.left()), Some(10));
+    }
+}
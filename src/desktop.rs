@@ -0,0 +1,323 @@
+//! Colors from the user's desktop (`desktop-theme` feature).
+//!
+//! [`desktop_theme`] builds a [`Theme`] from whichever of these it finds,
+//! so widgets can match the desktop instead of a fixed default:
+//!
+//! - The XDG settings portal's light/dark preference and accent color,
+//!   read with `gdbus` (no D-Bus library needed)
+//! - GTK `@define-color` rules in `gtk-4.0/gtk.css` and `gtk-3.0/gtk.css`
+//! - The KDE color scheme in `kdeglobals`
+//!
+//! Config files are looked up in `$XDG_CONFIG_HOME`, falling back to
+//! `~/.config`. Roles no source provides come from [`Theme::dark`] or
+//! [`Theme::light`], as in [`Theme::from_map`].
+//!
+//! ```ignore
+//! let theme = desktop_theme().unwrap_or_default();
+//! ```
+//!
+//! The parsers are public too, returning role maps that can be layered
+//! under a plugin's own colors with a
+//! [`ColorResolver`](crate::colors::ColorResolver).
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Command;
+
+use image::Rgba;
+
+use crate::colors::{Theme, lookup};
+
+/// Roles and the GTK color names that supply them, most specific first.
+/// libadwaita (GTK 4) names come before their GTK 3 equivalents.
+const GTK_ROLES: [(&str, &[&str]); 6] = [
+    ("bg", &["window_bg_color", "theme_bg_color"]),
+    ("fg", &["window_fg_color", "theme_fg_color"]),
+    (
+        "accent",
+        &["accent_bg_color", "accent_color", "theme_selected_bg_color"],
+    ),
+    ("warning", &["warning_color", "warning_bg_color"]),
+    ("error", &["error_color", "destructive_color"]),
+    ("muted", &["borders", "unfocused_borders"]),
+];
+
+/// Roles and the KDE `(group, key)` entries that supply them, most specific
+/// first.
+const KDE_ROLES: [(&str, &[(&str, &str)]); 6] = [
+    ("bg", &[("Colors:Window", "BackgroundNormal")]),
+    ("fg", &[("Colors:Window", "ForegroundNormal")]),
+    (
+        "accent",
+        &[
+            ("General", "AccentColor"),
+            ("Colors:Selection", "BackgroundNormal"),
+        ],
+    ),
+    ("warning", &[("Colors:Window", "ForegroundNeutral")]),
+    ("error", &[("Colors:Window", "ForegroundNegative")]),
+    ("muted", &[("Colors:Button", "BackgroundNormal")]),
+];
+
+/// Build a theme from the desktop's colors, or `None` if no source had
+/// any.
+///
+/// The portal's preference is applied first, then the GTK and KDE files;
+/// whichever matches `$XDG_CURRENT_DESKTOP` is applied last, so it wins
+/// where both define a role. Missing or unreadable sources are skipped.
+pub fn desktop_theme() -> Option<Theme> {
+    let mut colors = HashMap::new();
+    match portal_read("color-scheme")
+        .as_deref()
+        .and_then(parse_portal_color_scheme)
+    {
+        Some(true) => colors.extend(theme_roles(Theme::dark())),
+        Some(false) => colors.extend(theme_roles(Theme::light())),
+        None => {}
+    }
+    if let Some(accent) = portal_read("accent-color")
+        .as_deref()
+        .and_then(parse_portal_accent)
+    {
+        colors.insert("accent".to_string(), accent);
+    }
+
+    let gtk = ["gtk-3.0/gtk.css", "gtk-4.0/gtk.css"]
+        .into_iter()
+        .filter_map(read_config)
+        .flat_map(|css| parse_gtk_css(&css))
+        .collect::<HashMap<_, _>>();
+    let kde = read_config("kdeglobals")
+        .map(|ini| parse_kdeglobals(&ini))
+        .unwrap_or_default();
+    let on_kde = std::env::var("XDG_CURRENT_DESKTOP")
+        .is_ok_and(|desktop| desktop.split(':').any(|d| d.eq_ignore_ascii_case("KDE")));
+    if on_kde {
+        colors.extend(gtk);
+        colors.extend(kde);
+    } else {
+        colors.extend(kde);
+        colors.extend(gtk);
+    }
+
+    tracing::debug!(roles = colors.len(), "desktop_theme");
+    (!colors.is_empty()).then(|| Theme::from_map(&colors))
+}
+
+/// Theme roles from GTK CSS `@define-color name value;` rules.
+///
+/// Values may be any color [`lookup`] accepts or a `@name` reference to an
+/// earlier definition; GTK expressions such as `mix()` and `shade()` are
+/// skipped. Only roles the CSS defines are returned.
+pub fn parse_gtk_css(css: &str) -> HashMap<String, Rgba<u8>> {
+    let mut defined: HashMap<&str, Rgba<u8>> = HashMap::new();
+    for rule in css.split(';') {
+        let Some(rest) = rule.trim().split("@define-color").nth(1) else {
+            continue;
+        };
+        let Some((name, value)) = rest.trim().split_once(char::is_whitespace) else {
+            continue;
+        };
+        let value = value.trim();
+        let color = match value.strip_prefix('@') {
+            Some(reference) => defined.get(reference).copied(),
+            None => lookup(value),
+        };
+        if let Some(color) = color {
+            defined.insert(name, color);
+        }
+    }
+    GTK_ROLES
+        .iter()
+        .filter_map(|(role, names)| {
+            let color = names.iter().find_map(|name| defined.get(name))?;
+            Some((role.to_string(), *color))
+        })
+        .collect()
+}
+
+/// Theme roles from a KDE `kdeglobals` file.
+///
+/// Colors are `r,g,b` entries in the `[Colors:*]` groups; the accent comes
+/// from `[General] AccentColor` when set, otherwise the selection color.
+/// Only roles the file defines are returned.
+pub fn parse_kdeglobals(ini: &str) -> HashMap<String, Rgba<u8>> {
+    let mut entries: HashMap<(&str, &str), Rgba<u8>> = HashMap::new();
+    let mut group = "";
+    for line in ini.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            group = name;
+        } else if let Some((key, value)) = line.split_once('=')
+            && let Some(color) = parse_kde_color(value)
+        {
+            entries.insert((group, key.trim()), color);
+        }
+    }
+    KDE_ROLES
+        .iter()
+        .filter_map(|(role, keys)| {
+            let color = keys.iter().find_map(|key| entries.get(key))?;
+            Some((role.to_string(), *color))
+        })
+        .collect()
+}
+
+/// Parse a KDE `r,g,b` or `r,g,b,a` color.
+fn parse_kde_color(value: &str) -> Option<Rgba<u8>> {
+    let channels: Vec<u8> = value
+        .split(',')
+        .map(|c| c.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    match channels[..] {
+        [r, g, b] => Some(Rgba([r, g, b, 255])),
+        [r, g, b, a] => Some(Rgba([r, g, b, a])),
+        _ => None,
+    }
+}
+
+/// Whether `gdbus` output for the portal's `color-scheme` prefers dark
+/// (`Some(true)`) or light (`Some(false)`); `None` for no preference.
+fn parse_portal_color_scheme(output: &str) -> Option<bool> {
+    let value = output.split("uint32").nth(1)?;
+    let digits: String = value
+        .trim_start()
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    match digits.parse::<u32>().ok()? {
+        1 => Some(true),
+        2 => Some(false),
+        _ => None,
+    }
+}
+
+/// The accent color in `gdbus` output for the portal's `accent-color`, a
+/// tuple of three 0.0 to 1.0 doubles. Out-of-range values mean unset.
+fn parse_portal_accent(output: &str) -> Option<Rgba<u8>> {
+    let start = output.rfind('(')?;
+    let end = start + output[start..].find(')')?;
+    let channels: Vec<f64> = output[start + 1..end]
+        .split(',')
+        .map(|c| c.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    let [r, g, b] = channels[..] else {
+        return None;
+    };
+    let channel = |c: f64| (0.0..=1.0).contains(&c).then(|| (c * 255.0).round() as u8);
+    Some(Rgba([channel(r)?, channel(g)?, channel(b)?, 255]))
+}
+
+/// Read an `org.freedesktop.appearance` setting from the settings portal.
+fn portal_read(key: &str) -> Option<String> {
+    let output = Command::new("gdbus")
+        .args([
+            "call",
+            "--session",
+            "--timeout",
+            "1",
+            "--dest",
+            "org.freedesktop.portal.Desktop",
+            "--object-path",
+            "/org/freedesktop/portal/desktop",
+            "--method",
+            "org.freedesktop.portal.Settings.ReadOne",
+            "org.freedesktop.appearance",
+            key,
+        ])
+        .output()
+        .inspect_err(|e| tracing::debug!(error = %e, "gdbus unavailable"))
+        .ok()?;
+    if !output.status.success() {
+        tracing::debug!(key, "Settings portal has no value");
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+/// Contents of `file` in the user's config directory.
+fn read_config(file: &str) -> Option<String> {
+    let dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    std::fs::read_to_string(dir.join(file)).ok()
+}
+
+/// Every role of `theme`, as [`Theme::from_map`] keys.
+fn theme_roles(theme: Theme) -> [(String, Rgba<u8>); 6] {
+    [
+        ("fg", theme.fg),
+        ("bg", theme.bg),
+        ("accent", theme.accent),
+        ("warning", theme.warning),
+        ("error", theme.error),
+        ("muted", theme.muted),
+    ]
+    .map(|(role, color)| (role.to_string(), color))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::hex;
+
+    #[test]
+    fn test_parse_gtk_css() {
+        let css = "
+            @define-color accent_bg_color #3584e4;
+            @define-color window_bg_color rgb(36, 36, 36);
+            @define-color theme_fg_color white;
+            @define-color borders @window_bg_color;
+            @define-color error_color mix(red, black, 0.2);
+            window { background: @window_bg_color; }
+        ";
+        let colors = parse_gtk_css(css);
+        assert_eq!(colors["accent"], hex("#3584E4"));
+        assert_eq!(colors["bg"], Rgba([36, 36, 36, 255]));
+        assert_eq!(colors["fg"], Rgba([255, 255, 255, 255]));
+        assert_eq!(colors["muted"], colors["bg"]);
+        assert!(!colors.contains_key("error"));
+        assert_eq!(colors.len(), 4);
+    }
+
+    #[test]
+    fn test_parse_kdeglobals() {
+        let ini = "
+            [General]
+            AccentColor=61,174,233
+
+            [Colors:Selection]
+            BackgroundNormal=1,2,3
+
+            [Colors:Window]
+            BackgroundNormal=32,35,38
+            ForegroundNormal=252,252,252
+            ForegroundNegative=218,68,83,128
+            ForegroundNeutral=not,a,color
+        ";
+        let colors = parse_kdeglobals(ini);
+        assert_eq!(colors["accent"], Rgba([61, 174, 233, 255]));
+        assert_eq!(colors["bg"], Rgba([32, 35, 38, 255]));
+        assert_eq!(colors["fg"], Rgba([252, 252, 252, 255]));
+        assert_eq!(colors["error"], Rgba([218, 68, 83, 128]));
+        assert!(!colors.contains_key("warning"));
+
+        let theme = Theme::from_map(&colors);
+        assert_eq!(theme.muted, Theme::dark().muted);
+    }
+
+    #[test]
+    fn test_parse_portal_output() {
+        assert_eq!(parse_portal_color_scheme("(<uint32 1>,)\n"), Some(true));
+        assert_eq!(parse_portal_color_scheme("(<<uint32 2>>,)"), Some(false));
+        assert_eq!(parse_portal_color_scheme("(<uint32 0>,)"), None);
+        assert_eq!(parse_portal_color_scheme(""), None);
+
+        assert_eq!(
+            parse_portal_accent("(<(0.2, 0.4, 1.0)>,)\n"),
+            Some(Rgba([51, 102, 255, 255]))
+        );
+        assert_eq!(parse_portal_accent("(<(-1.0, -1.0, -1.0)>,)"), None);
+        assert_eq!(parse_portal_accent("(<(0.5, 0.5)>,)"), None);
+    }
+}
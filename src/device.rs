@@ -0,0 +1,435 @@
+//! Target device profiles.
+//!
+//! Describes the display surfaces verandah renders to (key tiles and
+//! touchscreen strips) so plugins can size, mask and encode frames correctly
+//! without hard-coding dimensions.
+
+use image::{Rgba, RgbaImage};
+use imageproc::rect::Rect;
+
+use crate::colors::{composite_over_opaque, srgb_decode};
+use crate::image::{Letterbox, scale_to_cover, scale_with_letterbox};
+
+/// Pixel layout expected by a device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// 3 bytes per pixel, R G B.
+    Rgb888,
+    /// 3 bytes per pixel, B G R.
+    Bgr888,
+    /// 4 bytes per pixel, R G B A.
+    Rgba8888,
+}
+
+impl PixelFormat {
+    /// Number of bytes used per pixel.
+    pub const fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelFormat::Rgb888 | PixelFormat::Bgr888 => 3,
+            PixelFormat::Rgba8888 => 4,
+        }
+    }
+}
+
+/// Color correction applied when a frame is finalized for a device.
+///
+/// Some panels (notably OLED deck hardware) render configured colors
+/// washed out or tinted. The transform compensates so the same configured
+/// color looks alike across devices.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OutputTransform {
+    /// Send pixels unchanged.
+    #[default]
+    Identity,
+    /// Apply a power curve to each channel: `out = in^gamma`.
+    ///
+    /// Values above 1.0 darken midtones (countering a washed-out panel);
+    /// values below 1.0 brighten them.
+    Gamma(f32),
+    /// Linearize sRGB input, apply a 3×3 primaries correction matrix, then
+    /// encode for a display with the given gamma (tone response curve).
+    MatrixTrc {
+        /// Row-major matrix applied to linear RGB.
+        matrix: [[f32; 3]; 3],
+        /// Display gamma used to re-encode, typically 2.2.
+        gamma: f32,
+    },
+}
+
+impl OutputTransform {
+    /// Apply the transform in place. Alpha is unchanged.
+    pub fn apply(&self, rgba: &mut RgbaImage) {
+        match *self {
+            OutputTransform::Identity => {}
+            OutputTransform::Gamma(gamma) => {
+                let lut: [u8; 256] =
+                    std::array::from_fn(|i| ((i as f32 / 255.0).powf(gamma) * 255.0).round() as u8);
+                for pixel in rgba.pixels_mut() {
+                    for c in 0..3 {
+                        pixel[c] = lut[pixel[c] as usize];
+                    }
+                }
+            }
+            OutputTransform::MatrixTrc { matrix, gamma } => {
+                let linear: [f32; 256] = std::array::from_fn(|i| srgb_decode(i as f32 / 255.0));
+                let inv_gamma = 1.0 / gamma.max(f32::EPSILON);
+                for pixel in rgba.pixels_mut() {
+                    let rgb = [
+                        linear[pixel[0] as usize],
+                        linear[pixel[1] as usize],
+                        linear[pixel[2] as usize],
+                    ];
+                    for (c, row) in matrix.iter().enumerate() {
+                        let v = row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2];
+                        pixel[c] = (v.clamp(0.0, 1.0).powf(inv_gamma) * 255.0).round() as u8;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Insets from each edge, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Insets {
+    /// Inset from the top edge.
+    pub top: u32,
+    /// Inset from the right edge.
+    pub right: u32,
+    /// Inset from the bottom edge.
+    pub bottom: u32,
+    /// Inset from the left edge.
+    pub left: u32,
+}
+
+impl Insets {
+    /// Equal insets on all four edges.
+    pub const fn uniform(v: u32) -> Self {
+        Self {
+            top: v,
+            right: v,
+            bottom: v,
+            left: v,
+        }
+    }
+}
+
+/// Physical characteristics of a render target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeviceProfile {
+    /// Short identifier, e.g. "tile-72".
+    pub name: &'static str,
+    /// Surface width in pixels.
+    pub width: u32,
+    /// Surface height in pixels.
+    pub height: u32,
+    /// Radius of the rounded corners visible on the hardware, in pixels.
+    pub corner_radius: u32,
+    /// Area at the edges that may be obscured by the bezel.
+    pub safe_area: Insets,
+    /// Pixel layout the device expects.
+    pub pixel_format: PixelFormat,
+    /// Color correction applied by [`DeviceProfile::finalize`] and [`DeviceProfile::encode`].
+    pub output: OutputTransform,
+    /// Color transparent pixels show as on formats without alpha. Its own
+    /// alpha is ignored.
+    pub background: Rgba<u8>,
+}
+
+/// 72×72 key tile.
+pub const TILE_72: DeviceProfile = DeviceProfile {
+    name: "tile-72",
+    width: 72,
+    height: 72,
+    corner_radius: 8,
+    safe_area: Insets::uniform(4),
+    pixel_format: PixelFormat::Bgr888,
+    output: OutputTransform::Identity,
+    background: Rgba([0, 0, 0, 255]),
+};
+
+/// 80×80 key tile.
+pub const TILE_80: DeviceProfile = DeviceProfile {
+    name: "tile-80",
+    width: 80,
+    height: 80,
+    corner_radius: 9,
+    safe_area: Insets::uniform(4),
+    pixel_format: PixelFormat::Bgr888,
+    output: OutputTransform::Identity,
+    background: Rgba([0, 0, 0, 255]),
+};
+
+/// 96×96 key tile.
+pub const TILE_96: DeviceProfile = DeviceProfile {
+    name: "tile-96",
+    width: 96,
+    height: 96,
+    corner_radius: 10,
+    safe_area: Insets::uniform(5),
+    pixel_format: PixelFormat::Bgr888,
+    output: OutputTransform::Identity,
+    background: Rgba([0, 0, 0, 255]),
+};
+
+/// 800×100 touchscreen strip.
+pub const TOUCH_STRIP: DeviceProfile = DeviceProfile {
+    name: "touch-strip",
+    width: 800,
+    height: 100,
+    corner_radius: 6,
+    safe_area: Insets {
+        top: 4,
+        right: 8,
+        bottom: 4,
+        left: 8,
+    },
+    pixel_format: PixelFormat::Rgb888,
+    output: OutputTransform::Identity,
+    background: Rgba([0, 0, 0, 255]),
+};
+
+/// All built-in profiles.
+pub const PROFILES: [DeviceProfile; 4] = [TILE_72, TILE_80, TILE_96, TOUCH_STRIP];
+
+impl DeviceProfile {
+    /// Look up a built-in profile by name.
+    pub fn by_name(name: &str) -> Option<&'static DeviceProfile> {
+        PROFILES.iter().find(|p| p.name == name)
+    }
+
+    /// Look up a built-in profile by surface dimensions.
+    pub fn for_size(width: u32, height: u32) -> Option<&'static DeviceProfile> {
+        PROFILES
+            .iter()
+            .find(|p| p.width == width && p.height == height)
+    }
+
+    /// Copy of this profile with a different background for formats without alpha.
+    pub const fn with_background(mut self, background: Rgba<u8>) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Copy of this profile with a different output transform.
+    ///
+    /// ```ignore
+    /// const OLED_72: DeviceProfile = TILE_72.with_output(OutputTransform::Gamma(1.2));
+    /// ```
+    pub const fn with_output(mut self, output: OutputTransform) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// Surface dimensions as `(width, height)`.
+    pub const fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Create a blank (transparent) frame of the device's size.
+    pub fn new_frame(&self) -> RgbaImage {
+        RgbaImage::new(self.width, self.height)
+    }
+
+    /// The area guaranteed to be visible, inside the safe-area insets.
+    pub fn safe_rect(&self) -> Rect {
+        let w = self
+            .width
+            .saturating_sub(self.safe_area.left + self.safe_area.right)
+            .max(1);
+        let h = self
+            .height
+            .saturating_sub(self.safe_area.top + self.safe_area.bottom)
+            .max(1);
+        Rect::at(self.safe_area.left as i32, self.safe_area.top as i32).of_size(w, h)
+    }
+
+    /// Scale `img` to fit within the device, preserving aspect ratio.
+    ///
+    /// The scaled image is centered on a transparent frame of the device size.
+    pub fn fit(&self, img: &RgbaImage) -> RgbaImage {
+        self.fit_with(img, Letterbox::Transparent)
+    }
+
+    /// Like [`fit`](Self::fit), with the letterbox bars filled according
+    /// to `bars`.
+    pub fn fit_with(&self, img: &RgbaImage, bars: Letterbox) -> RgbaImage {
+        scale_with_letterbox(img, self.width, self.height, bars)
+    }
+
+    /// Scale `img` to cover the device, preserving aspect ratio and cropping overflow.
+    pub fn fill(&self, img: &RgbaImage) -> RgbaImage {
+        scale_to_cover(img, self.width, self.height)
+    }
+
+    /// Make pixels outside the device's rounded corners transparent.
+    ///
+    /// Useful for previews, so content hidden by the bezel is visible.
+    pub fn apply_corner_mask(&self, rgba: &mut RgbaImage) {
+        let r = self
+            .corner_radius
+            .min(rgba.width() / 2)
+            .min(rgba.height() / 2) as f32;
+        if r <= 0.0 {
+            return;
+        }
+        let (w, h) = (rgba.width() as f32, rgba.height() as f32);
+        for (x, y, pixel) in rgba.enumerate_pixels_mut() {
+            // Distance from the nearest corner circle center, sampled at the pixel center
+            let px = x as f32 + 0.5;
+            let py = y as f32 + 0.5;
+            let cx = px.clamp(r, w - r);
+            let cy = py.clamp(r, h - r);
+            let dist = ((px - cx).powi(2) + (py - cy).powi(2)).sqrt();
+            let coverage = (r - dist + 0.5).clamp(0.0, 1.0);
+            pixel[3] = (pixel[3] as f32 * coverage).round() as u8;
+        }
+    }
+
+    /// Finalize a frame for display: apply the device's output transform in place.
+    ///
+    /// Use this when handing an `RgbaImage` to the host directly. Do not call
+    /// it before [`encode`](Self::encode), which applies the transform itself.
+    pub fn finalize(&self, rgba: &mut RgbaImage) {
+        self.output.apply(rgba);
+    }
+
+    /// Encode a frame as raw bytes in the device's pixel format.
+    ///
+    /// The frame is fitted to the device size first if dimensions differ,
+    /// and the output transform is applied. Formats without alpha composite
+    /// each pixel over [`background`](Self::background).
+    pub fn encode(&self, rgba: &RgbaImage) -> Vec<u8> {
+        let mut frame = if rgba.dimensions() == self.size() {
+            rgba.clone()
+        } else {
+            self.fit(rgba)
+        };
+        self.finalize(&mut frame);
+
+        let bpp = self.pixel_format.bytes_per_pixel();
+        let mut out = Vec::with_capacity(frame.len() / 4 * bpp);
+        for &pixel in frame.pixels() {
+            let flat = || composite_over_opaque(pixel, self.background);
+            match self.pixel_format {
+                PixelFormat::Rgb888 => {
+                    let Rgba([r, g, b, _]) = flat();
+                    out.extend_from_slice(&[r, g, b]);
+                }
+                PixelFormat::Bgr888 => {
+                    let Rgba([r, g, b, _]) = flat();
+                    out.extend_from_slice(&[b, g, r]);
+                }
+                PixelFormat::Rgba8888 => out.extend_from_slice(&pixel.0),
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_by_name_and_size() {
+        assert_eq!(DeviceProfile::by_name("tile-96"), Some(&TILE_96));
+        assert_eq!(DeviceProfile::for_size(800, 100), Some(&TOUCH_STRIP));
+        assert!(DeviceProfile::by_name("nope").is_none());
+    }
+
+    #[test]
+    fn test_safe_rect() {
+        let r = TILE_72.safe_rect();
+        assert_eq!((r.left(), r.top(), r.width(), r.height()), (4, 4, 64, 64));
+    }
+
+    #[test]
+    fn test_fit_letterboxes_wide_image() {
+        let img = RgbaImage::from_pixel(200, 100, Rgba([255, 0, 0, 255]));
+        let out = TILE_72.fit(&img);
+        assert_eq!(out.dimensions(), (72, 72));
+        // Top rows are transparent letterbox, center is content
+        assert_eq!(out.get_pixel(36, 0)[3], 0);
+        assert_eq!(*out.get_pixel(36, 36), Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_fill_covers_frame() {
+        let img = RgbaImage::from_pixel(200, 100, Rgba([0, 255, 0, 255]));
+        let out = TILE_96.fill(&img);
+        assert_eq!(out.dimensions(), (96, 96));
+        assert_eq!(out.get_pixel(0, 0)[3], 255);
+    }
+
+    #[test]
+    fn test_apply_corner_mask() {
+        let mut img = RgbaImage::from_pixel(72, 72, Rgba([255, 255, 255, 255]));
+        TILE_72.apply_corner_mask(&mut img);
+        assert_eq!(img.get_pixel(0, 0)[3], 0);
+        assert_eq!(img.get_pixel(36, 36)[3], 255);
+        assert_eq!(img.get_pixel(36, 0)[3], 255);
+    }
+
+    #[test]
+    fn test_output_gamma() {
+        let mut img = RgbaImage::from_pixel(1, 1, Rgba([128, 0, 255, 77]));
+        OutputTransform::Gamma(2.0).apply(&mut img);
+        assert_eq!(*img.get_pixel(0, 0), Rgba([64, 0, 255, 77]));
+    }
+
+    #[test]
+    fn test_output_matrix_trc_identity_round_trips() {
+        let identity = OutputTransform::MatrixTrc {
+            matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            gamma: 2.4,
+        };
+        let mut img = RgbaImage::from_pixel(1, 1, Rgba([0, 255, 200, 255]));
+        identity.apply(&mut img);
+        let p = img.get_pixel(0, 0);
+        assert_eq!((p[0], p[1]), (0, 255));
+        assert!((p[2] as i32 - 200).abs() <= 8);
+    }
+
+    #[test]
+    fn test_output_matrix_swaps_channels() {
+        let swap = OutputTransform::MatrixTrc {
+            matrix: [[0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]],
+            gamma: 2.2,
+        };
+        let mut img = RgbaImage::from_pixel(1, 1, Rgba([255, 0, 0, 255]));
+        swap.apply(&mut img);
+        assert_eq!(*img.get_pixel(0, 0), Rgba([0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn test_encode_applies_output_transform() {
+        let profile = TILE_72.with_output(OutputTransform::Gamma(2.0));
+        let img = RgbaImage::from_pixel(72, 72, Rgba([128, 128, 128, 255]));
+        assert_eq!(&profile.encode(&img)[..3], &[64, 64, 64]);
+    }
+
+    #[test]
+    fn test_encode_bgr() {
+        let img = RgbaImage::from_pixel(72, 72, Rgba([1, 2, 3, 255]));
+        let bytes = TILE_72.encode(&img);
+        assert_eq!(bytes.len(), 72 * 72 * 3);
+        assert_eq!(&bytes[..3], &[3, 2, 1]);
+    }
+
+    #[test]
+    fn test_encode_flattens_alpha_onto_background() {
+        let img = RgbaImage::from_pixel(72, 72, Rgba([255, 255, 255, 0]));
+        assert_eq!(&TILE_72.encode(&img)[..3], &[0, 0, 0]);
+        let profile = TILE_72.with_background(Rgba([0, 0, 200, 255]));
+        assert_eq!(&profile.encode(&img)[..3], &[200, 0, 0]);
+        let half = RgbaImage::from_pixel(72, 72, Rgba([200, 100, 0, 128]));
+        assert_eq!(&TILE_72.encode(&half)[..3], &[0, 50, 100]);
+        // Rgba8888 keeps alpha untouched
+        let rgba = DeviceProfile {
+            pixel_format: PixelFormat::Rgba8888,
+            ..TILE_72
+        };
+        assert_eq!(&rgba.encode(&img)[..4], &[255, 255, 255, 0]);
+    }
+}
@@ -0,0 +1,325 @@
+//! Country flag rendering.
+//!
+//! Draws simplified national flags from ISO 3166-1 alpha-2 codes, for
+//! timezone and server-location widgets. Flags are built from a small table
+//! of stripe, cross, saltire and disc patterns rather than bitmap assets, so
+//! they scale cleanly to any tile size. Unknown codes fall back to a
+//! labelled box.
+
+use image::{GenericImage, GenericImageView, Rgba, RgbaImage};
+use imageproc::drawing::{draw_filled_circle_mut, draw_filled_rect_mut};
+use imageproc::rect::Rect;
+
+use crate::colors::hex;
+use crate::text::draw_centered_text;
+
+/// Simplified flag layouts.
+enum Pattern {
+    /// Equal horizontal stripes, top to bottom.
+    Horizontal(&'static [Rgba<u8>]),
+    /// Equal vertical stripes, left to right.
+    Vertical(&'static [Rgba<u8>]),
+    /// Off-center Scandinavian cross, with an optional border around the cross.
+    NordicCross {
+        field: Rgba<u8>,
+        cross: Rgba<u8>,
+        border: Option<Rgba<u8>>,
+    },
+    /// Centered symmetric cross.
+    Cross { field: Rgba<u8>, cross: Rgba<u8> },
+    /// Centered disc on a plain field.
+    Disc { field: Rgba<u8>, disc: Rgba<u8> },
+    /// Diagonal saltire under a centered cross, each a broad `outer` band
+    /// with a narrower `inner` one, as on the Union Flag.
+    Union {
+        field: Rgba<u8>,
+        outer: Rgba<u8>,
+        inner: Rgba<u8>,
+    },
+}
+
+const WHITE: Rgba<u8> = hex("#FFFFFF");
+const BLACK: Rgba<u8> = hex("#000000");
+const RED: Rgba<u8> = hex("#D52B1E");
+const BLUE: Rgba<u8> = hex("#0055A4");
+const GREEN: Rgba<u8> = hex("#009246");
+const YELLOW: Rgba<u8> = hex("#FFCD00");
+
+/// Known flags keyed by upper-case ISO 3166-1 alpha-2 code (sorted alphabetically).
+#[rustfmt::skip]
+const FLAGS: &[(&str, Pattern)] = &[
+    ("AT", Pattern::Horizontal(&[hex("#ED2939"), WHITE, hex("#ED2939")])),
+    ("BD", Pattern::Disc { field: hex("#006A4E"), disc: hex("#F42A41") }),
+    ("BE", Pattern::Vertical(&[BLACK, hex("#FDDA24"), hex("#EF3340")])),
+    ("BG", Pattern::Horizontal(&[WHITE, hex("#00966E"), hex("#D62612")])),
+    ("CH", Pattern::Cross { field: RED, cross: WHITE }),
+    ("DE", Pattern::Horizontal(&[BLACK, hex("#DD0000"), hex("#FFCE00")])),
+    ("DK", Pattern::NordicCross { field: hex("#C8102E"), cross: WHITE, border: None }),
+    ("EE", Pattern::Horizontal(&[hex("#0072CE"), BLACK, WHITE])),
+    ("FI", Pattern::NordicCross { field: WHITE, cross: hex("#002F6C"), border: None }),
+    ("FR", Pattern::Vertical(&[BLUE, WHITE, hex("#EF4135")])),
+    ("GB", Pattern::Union { field: hex("#012169"), outer: WHITE, inner: hex("#C8102E") }),
+    ("HU", Pattern::Horizontal(&[hex("#CD2A3E"), WHITE, hex("#436F4D")])),
+    ("ID", Pattern::Horizontal(&[hex("#FF0000"), WHITE])),
+    ("IE", Pattern::Vertical(&[hex("#169B62"), WHITE, hex("#FF883E")])),
+    ("IS", Pattern::NordicCross { field: hex("#02529C"), cross: hex("#DC1E35"), border: Some(WHITE) }),
+    ("IT", Pattern::Vertical(&[GREEN, WHITE, hex("#CE2B37")])),
+    ("JP", Pattern::Disc { field: WHITE, disc: hex("#BC002D") }),
+    ("LT", Pattern::Horizontal(&[hex("#FDB913"), hex("#006A44"), hex("#C1272D")])),
+    ("LU", Pattern::Horizontal(&[hex("#EF3340"), WHITE, hex("#00A3E0")])),
+    ("MC", Pattern::Horizontal(&[hex("#CE1126"), WHITE])),
+    ("NG", Pattern::Vertical(&[hex("#008751"), WHITE, hex("#008751")])),
+    ("NL", Pattern::Horizontal(&[hex("#AE1C28"), WHITE, hex("#21468B")])),
+    ("NO", Pattern::NordicCross { field: hex("#BA0C2F"), cross: hex("#00205B"), border: Some(WHITE) }),
+    ("PL", Pattern::Horizontal(&[WHITE, hex("#DC143C")])),
+    ("RO", Pattern::Vertical(&[hex("#002B7F"), YELLOW, hex("#CE1126")])),
+    ("RU", Pattern::Horizontal(&[WHITE, hex("#0039A6"), hex("#D52B1E")])),
+    ("SE", Pattern::NordicCross { field: hex("#006AA7"), cross: hex("#FECC00"), border: None }),
+    ("UA", Pattern::Horizontal(&[hex("#0057B7"), hex("#FFD700")])),
+];
+
+/// Background used for codes without a known flag.
+const FALLBACK_BG: Rgba<u8> = hex("#3A3A3A");
+
+/// Draw a country flag into `rect`.
+///
+/// `iso_code` is an ISO 3166-1 alpha-2 code (case-insensitive, e.g. "fr",
+/// "DE") or the equivalent pair of regional indicator symbols as used by
+/// flag emoji (e.g. "🇫🇷"). "UK" is accepted as an alias for "GB".
+///
+/// Codes without a built-in flag are drawn as a grey box labelled with the
+/// code, so the widget still shows something meaningful. Drawing is clipped
+/// to the image bounds.
+pub fn draw_region_flag(rgba: &mut RgbaImage, rect: Rect, iso_code: &str) {
+    if rgba.width() == 0 || rgba.height() == 0 {
+        return;
+    }
+    let bounds = Rect::at(0, 0).of_size(rgba.width(), rgba.height());
+    let Some(clipped) = rect.intersect(bounds) else {
+        return;
+    };
+
+    let code = normalize_code(iso_code);
+    match FLAGS.iter().find(|(c, _)| *c == code) {
+        Some((_, pattern)) => draw_pattern(rgba, rect, pattern),
+        None => {
+            tracing::debug!(code, "No built-in flag, drawing label");
+            draw_fallback(rgba, clipped, &code);
+        }
+    }
+}
+
+/// Normalize a region code to upper-case ASCII.
+///
+/// Regional indicator symbols (U+1F1E6..U+1F1FF) are mapped to 'A'..'Z'.
+fn normalize_code(s: &str) -> String {
+    let code: String = s
+        .trim()
+        .chars()
+        .filter_map(|c| match c as u32 {
+            0x1F1E6..=0x1F1FF => char::from_u32('A' as u32 + (c as u32 - 0x1F1E6)),
+            _ if c.is_ascii_alphabetic() => Some(c.to_ascii_uppercase()),
+            _ => None,
+        })
+        .collect();
+    if code == "UK" { "GB".to_string() } else { code }
+}
+
+/// Fill a sub-rectangle given in fractions of `rect`.
+fn fill_fraction(
+    rgba: &mut RgbaImage,
+    rect: Rect,
+    x0: f32,
+    y0: f32,
+    x1: f32,
+    y1: f32,
+    color: Rgba<u8>,
+) {
+    let w = rect.width() as f32;
+    let h = rect.height() as f32;
+    let left = rect.left() + (x0 * w).round() as i32;
+    let top = rect.top() + (y0 * h).round() as i32;
+    let right = rect.left() + (x1 * w).round() as i32;
+    let bottom = rect.top() + (y1 * h).round() as i32;
+    if right > left && bottom > top {
+        let r = Rect::at(left, top).of_size((right - left) as u32, (bottom - top) as u32);
+        draw_filled_rect_mut(rgba, r, color);
+    }
+}
+
+fn draw_pattern(rgba: &mut RgbaImage, rect: Rect, pattern: &Pattern) {
+    match pattern {
+        Pattern::Horizontal(stripes) => {
+            let n = stripes.len() as f32;
+            for (i, color) in stripes.iter().enumerate() {
+                let i = i as f32;
+                fill_fraction(rgba, rect, 0.0, i / n, 1.0, (i + 1.0) / n, *color);
+            }
+        }
+        Pattern::Vertical(stripes) => {
+            let n = stripes.len() as f32;
+            for (i, color) in stripes.iter().enumerate() {
+                let i = i as f32;
+                fill_fraction(rgba, rect, i / n, 0.0, (i + 1.0) / n, 1.0, *color);
+            }
+        }
+        Pattern::NordicCross {
+            field,
+            cross,
+            border,
+        } => {
+            // Proportions loosely follow the Danish/Swedish 5:2:9 x 5:2:5 layout
+            fill_fraction(rgba, rect, 0.0, 0.0, 1.0, 1.0, *field);
+            if let Some(border) = border {
+                fill_fraction(rgba, rect, 0.0, 0.35, 1.0, 0.65, *border);
+                fill_fraction(rgba, rect, 0.26, 0.0, 0.44, 1.0, *border);
+            }
+            fill_fraction(rgba, rect, 0.0, 0.42, 1.0, 0.58, *cross);
+            fill_fraction(rgba, rect, 0.31, 0.0, 0.39, 1.0, *cross);
+        }
+        Pattern::Cross { field, cross } => {
+            fill_fraction(rgba, rect, 0.0, 0.0, 1.0, 1.0, *field);
+            fill_fraction(rgba, rect, 0.2, 0.4, 0.8, 0.6, *cross);
+            fill_fraction(rgba, rect, 0.4, 0.2, 0.6, 0.8, *cross);
+        }
+        Pattern::Disc { field, disc } => {
+            fill_fraction(rgba, rect, 0.0, 0.0, 1.0, 1.0, *field);
+            let cx = rect.left() + rect.width() as i32 / 2;
+            let cy = rect.top() + rect.height() as i32 / 2;
+            let radius = (rect.width().min(rect.height()) as f32 * 0.3).round() as i32;
+            draw_filled_circle_mut(rgba, (cx, cy), radius, *disc);
+        }
+        Pattern::Union {
+            field,
+            outer,
+            inner,
+        } => {
+            fill_fraction(rgba, rect, 0.0, 0.0, 1.0, 1.0, *field);
+            draw_saltire(rgba, rect, 0.1, *outer);
+            draw_saltire(rgba, rect, 0.035, *inner);
+            // Cross arms are measured against the height, so the vertical
+            // arm keeps its width on wide flags
+            let arm = |half: f32| half * rect.height() as f32 / rect.width().max(1) as f32;
+            for (half, color) in [(1.0 / 6.0, *outer), (0.1, *inner)] {
+                fill_fraction(rgba, rect, 0.0, 0.5 - half, 1.0, 0.5 + half, color);
+                fill_fraction(
+                    rgba,
+                    rect,
+                    0.5 - arm(half),
+                    0.0,
+                    0.5 + arm(half),
+                    1.0,
+                    color,
+                );
+            }
+        }
+    }
+}
+
+/// Fill both diagonals of `rect` with bands `half_width` of its height
+/// either side of the corner-to-corner line, clipped to the image bounds.
+fn draw_saltire(rgba: &mut RgbaImage, rect: Rect, half_width: f32, color: Rgba<u8>) {
+    let bounds = Rect::at(0, 0).of_size(rgba.width(), rgba.height());
+    let Some(clipped) = rect.intersect(bounds) else {
+        return;
+    };
+    let (w, h) = (rect.width() as f32, rect.height() as f32);
+    let limit = half_width * h * w.hypot(h);
+    for py in clipped.top()..=clipped.bottom() {
+        for px in clipped.left()..=clipped.right() {
+            // Sample pixel centers relative to the flag's top-left corner
+            let x = (px - rect.left()) as f32 + 0.5;
+            let y = (py - rect.top()) as f32 + 0.5;
+            let down = (x * h - y * w).abs();
+            let up = (x * h + y * w - w * h).abs();
+            if down <= limit || up <= limit {
+                rgba.put_pixel(px as u32, py as u32, color);
+            }
+        }
+    }
+}
+
+/// Draw a grey box with the region code as a label.
+fn draw_fallback(rgba: &mut RgbaImage, clipped: Rect, code: &str) {
+    let x = clipped.left() as u32;
+    let y = clipped.top() as u32;
+    let mut sub = rgba
+        .view(x, y, clipped.width(), clipped.height())
+        .to_image();
+    for pixel in sub.pixels_mut() {
+        *pixel = FALLBACK_BG;
+    }
+    if !code.is_empty() {
+        draw_centered_text(&mut sub, code, WHITE, 0.15);
+    }
+    // Sub-image was taken from within bounds, so this cannot fail
+    let _ = rgba.copy_from(&sub, x, y);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_code_ascii() {
+        assert_eq!(normalize_code("fr"), "FR");
+        assert_eq!(normalize_code(" De "), "DE");
+    }
+
+    #[test]
+    fn test_normalize_code_regional_indicators() {
+        assert_eq!(normalize_code("\u{1F1EB}\u{1F1F7}"), "FR");
+    }
+
+    #[test]
+    fn test_normalize_code_uk_alias() {
+        assert_eq!(normalize_code("uk"), "GB");
+    }
+
+    #[test]
+    fn test_draw_region_flag_vertical_stripes() {
+        let mut img = RgbaImage::new(30, 20);
+        draw_region_flag(&mut img, Rect::at(0, 0).of_size(30, 20), "fr");
+        assert_eq!(*img.get_pixel(2, 10), BLUE);
+        assert_eq!(*img.get_pixel(15, 10), WHITE);
+        assert_eq!(*img.get_pixel(27, 10), hex("#EF4135"));
+    }
+
+    #[test]
+    fn test_draw_region_flag_horizontal_stripes() {
+        let mut img = RgbaImage::new(30, 30);
+        draw_region_flag(&mut img, Rect::at(0, 0).of_size(30, 30), "DE");
+        assert_eq!(*img.get_pixel(15, 2), BLACK);
+        assert_eq!(*img.get_pixel(15, 27), hex("#FFCE00"));
+    }
+
+    #[test]
+    fn test_draw_region_flag_uk_renders_union_flag() {
+        let rect = Rect::at(0, 0).of_size(60, 30);
+        let mut uk = RgbaImage::new(60, 30);
+        draw_region_flag(&mut uk, rect, "UK");
+        assert_eq!(*uk.get_pixel(30, 15), hex("#C8102E"));
+        assert_eq!(*uk.get_pixel(20, 3), hex("#012169"));
+        assert_eq!(*uk.get_pixel(1, 1), hex("#C8102E"));
+        assert_eq!(*uk.get_pixel(6, 0), WHITE);
+
+        let mut gb = RgbaImage::new(60, 30);
+        draw_region_flag(&mut gb, rect, "gb");
+        assert_eq!(uk, gb);
+    }
+
+    #[test]
+    fn test_draw_region_flag_unknown_code_fills_rect() {
+        let mut img = RgbaImage::new(20, 20);
+        draw_region_flag(&mut img, Rect::at(5, 5).of_size(10, 10), "zz");
+        assert_eq!(*img.get_pixel(0, 0), Rgba([0, 0, 0, 0]));
+        assert_ne!(*img.get_pixel(5, 5), Rgba([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_draw_region_flag_clips_to_image() {
+        let mut img = RgbaImage::new(10, 10);
+        draw_region_flag(&mut img, Rect::at(-20, -20).of_size(100, 100), "zz");
+        draw_region_flag(&mut img, Rect::at(50, 50).of_size(10, 10), "fr");
+    }
+}
@@ -0,0 +1,251 @@
+//! Sample renders of every built-in primitive for theme design.
+//!
+//! [`render_all`] draws each chart, widget and text primitive with
+//! representative data in a given [`Theme`], and [`render_sheet`] tiles
+//! them into one image, so a palette can be judged across everything at
+//! once instead of widget by widget:
+//!
+//! ```ignore
+//! let theme = Theme::from_map(&parse_colors(&config.colors));
+//! render_sheet(&theme, (72, 72)).save("theme-sheet.png")?;
+//! ```
+//!
+//! Samples are fixed (no clock, no randomness), so sheets rendered from
+//! the same theme are identical and can be diffed.
+
+use std::time::{Duration, SystemTime};
+
+use image::RgbaImage;
+use imageproc::rect::Rect;
+
+use crate::anim::{FlipStyle, draw_flip_digit};
+use crate::chart::{
+    AreaStyle, CandleStyle, DonutStyle, GaugeStyle, RadarStyle, TimelineStyle, draw_candlesticks,
+    draw_donut, draw_gauge, draw_radar, draw_stacked_area, draw_timeline,
+};
+use crate::colors::Theme;
+use crate::text::draw_centered_text;
+use crate::widget::{
+    Element, Progress, ProgressStyle, Spec, draw_progress_bar, draw_skeleton, render,
+};
+
+/// Pixels between tiles in [`render_sheet`].
+const SHEET_GAP: u32 = 2;
+
+/// Render every built-in primitive at `size` with `theme`, returning each
+/// sample with its name.
+///
+/// Each image starts from the theme background. Icons are included with
+/// the `icons` feature, named `icon-<name>`.
+pub fn render_all(theme: &Theme, size: (u32, u32)) -> Vec<(String, RgbaImage)> {
+    let (width, height) = size;
+    let inset = inset_rect(size);
+    let draw = |name: &str, paint: &dyn Fn(&mut RgbaImage)| {
+        let mut rgba = RgbaImage::from_pixel(width, height, theme.bg);
+        paint(&mut rgba);
+        (name.to_string(), rgba)
+    };
+
+    let samples = vec![
+        draw("text", &|img| draw_centered_text(img, "42°", theme.fg, 0.1)),
+        draw("progress", &|img| {
+            let style = ProgressStyle {
+                track: theme.muted,
+                fill: theme.accent,
+                ..ProgressStyle::default()
+            };
+            let bar = Rect::at(inset.left(), (height / 2) as i32 - 3).of_size(inset.width(), 6);
+            draw_progress_bar(img, bar, Progress::Determinate(0.6), &style, Duration::ZERO);
+        }),
+        draw("skeleton", &|img| {
+            let line = |y: f32, w: f32| {
+                Rect::at(inset.left(), (height as f32 * y) as i32).of_size(
+                    ((inset.width() as f32) * w).max(1.0) as u32,
+                    (height / 8).max(1),
+                )
+            };
+            draw_skeleton(img, &[line(0.2, 1.0), line(0.45, 0.8), line(0.7, 0.6)], 0.4);
+        }),
+        draw("spec", &|img| {
+            let spec = Spec::column()
+                .with_padding(width / 12)
+                .with_gap(height / 24)
+                .with(Element::text("CPU"))
+                .with(Element::bar(0.7))
+                .with(Element::sparkline([0.2, 0.5, 0.3, 0.8, 0.6, 0.9]));
+            *img = render(&spec, size, theme);
+        }),
+        draw("ring", &|img| {
+            let spec = Spec::column()
+                .with_padding(width / 12)
+                .with(Element::ring(0.65));
+            *img = render(&spec, size, theme);
+        }),
+        draw("gauge", &|img| {
+            let style = GaugeStyle {
+                track: theme.muted,
+                fill: theme.accent,
+                tick: theme.fg,
+                label: theme.fg,
+                ..GaugeStyle::default()
+            };
+            draw_gauge(img, inset, 62.0, 0.0..=100.0, &style);
+        }),
+        draw("donut", &|img| {
+            let segments = [
+                (5.0, theme.accent),
+                (3.0, theme.warning),
+                (2.0, theme.error),
+            ];
+            draw_donut(img, inset, &segments, &DonutStyle::default());
+        }),
+        draw("radar", &|img| {
+            let mut fill = theme.accent;
+            fill[3] = 96;
+            let style = RadarStyle {
+                grid: theme.muted,
+                fill,
+                outline: theme.accent,
+                label: theme.fg,
+                ..RadarStyle::default()
+            };
+            let axes = [("A", 0.8), ("B", 0.5), ("C", 0.9), ("D", 0.4), ("E", 0.7)];
+            draw_radar(img, inset, &axes, &style);
+        }),
+        draw("candlesticks", &|img| {
+            let style = CandleStyle {
+                up: theme.accent,
+                down: theme.error,
+                ..CandleStyle::default()
+            };
+            let candles = [
+                (10.0, 14.0, 9.0, 13.0),
+                (13.0, 15.0, 11.0, 12.0),
+                (12.0, 16.0, 12.0, 15.0),
+                (15.0, 15.5, 10.0, 11.0),
+                (11.0, 13.0, 10.5, 12.5),
+            ];
+            draw_candlesticks(img, inset, &candles, &style);
+        }),
+        draw("area", &|img| {
+            let cpu = [0.2, 0.4, 0.3, 0.5, 0.4, 0.6];
+            let io = [0.1, 0.2, 0.3, 0.2, 0.3, 0.2];
+            draw_stacked_area(
+                img,
+                inset,
+                &[(&cpu, theme.accent), (&io, theme.warning)],
+                &AreaStyle::default(),
+            );
+        }),
+        draw("timeline", &|img| {
+            let at = |secs: u64| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+            let spans = [
+                (at(0), at(40), theme.accent, "Build"),
+                (at(30), at(70), theme.warning, "Test"),
+                (at(75), at(100), theme.error, "Fail"),
+            ];
+            let style = TimelineStyle {
+                track: theme.muted,
+                ..TimelineStyle::default()
+            };
+            let strip = Rect::at(inset.left(), (height / 3) as i32)
+                .of_size(inset.width(), (height / 3).max(1));
+            draw_timeline(img, strip, &spans, at(0)..at(100), &style);
+        }),
+        draw("flip-digit", &|img| {
+            let style = FlipStyle {
+                card: theme.muted,
+                fg: theme.fg,
+                divider: theme.bg,
+                ..FlipStyle::default()
+            };
+            draw_flip_digit(img, inset, '3', '4', 0.3, &style);
+        }),
+    ];
+
+    #[cfg(feature = "icons")]
+    let samples = {
+        let mut samples = samples;
+        for icon in crate::icons::BuiltinIcon::ALL {
+            samples.push(draw(&format!("icon-{}", icon.name()), &|img| {
+                crate::icons::draw_icon(img, icon, inset, theme.fg)
+            }));
+        }
+        samples
+    };
+    samples
+}
+
+/// Render every primitive as in [`render_all`] and tile the samples into
+/// one sheet, in a roughly square grid on the theme's muted color.
+pub fn render_sheet(theme: &Theme, size: (u32, u32)) -> RgbaImage {
+    let samples = render_all(theme, size);
+    let columns = (samples.len() as f32).sqrt().ceil().max(1.0) as u32;
+    let rows = (samples.len() as u32).div_ceil(columns);
+    let (width, height) = size;
+    let mut sheet = RgbaImage::from_pixel(
+        columns * (width + SHEET_GAP) + SHEET_GAP,
+        rows * (height + SHEET_GAP) + SHEET_GAP,
+        theme.muted,
+    );
+    for (i, (_, sample)) in samples.iter().enumerate() {
+        let (column, row) = (i as u32 % columns, i as u32 / columns);
+        let x = SHEET_GAP + column * (width + SHEET_GAP);
+        let y = SHEET_GAP + row * (height + SHEET_GAP);
+        image::imageops::replace(&mut sheet, sample, x as i64, y as i64);
+    }
+    sheet
+}
+
+/// The sample area inside a tile: a centered square with a margin, so round
+/// primitives stay round on non-square tiles.
+fn inset_rect((width, height): (u32, u32)) -> Rect {
+    let side = width.min(height);
+    let margin = side / 10;
+    let inner = (side - 2 * margin).max(1);
+    Rect::at(
+        (width.saturating_sub(inner) / 2) as i32,
+        (height.saturating_sub(inner) / 2) as i32,
+    )
+    .of_size(inner, inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_all_samples() {
+        let theme = Theme::dark();
+        let samples = render_all(&theme, (48, 32));
+        let mut names: Vec<_> = samples.iter().map(|(name, _)| name.as_str()).collect();
+        assert!(names.contains(&"gauge") && names.contains(&"timeline"));
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), samples.len(), "names are unique");
+
+        for (name, img) in &samples {
+            assert_eq!(img.dimensions(), (48, 32), "{name}");
+        }
+        // Chart samples draw in the theme's colors
+        let (_, donut) = samples.iter().find(|(name, _)| name == "donut").unwrap();
+        assert!(donut.pixels().any(|p| *p == theme.accent));
+        assert_eq!(render_all(&theme, (48, 32)), samples);
+    }
+
+    #[test]
+    fn test_render_sheet_tiles_samples() {
+        let theme = Theme::light();
+        let count = render_all(&theme, (20, 10)).len() as u32;
+        let sheet = render_sheet(&theme, (20, 10));
+        let columns = (sheet.width() - SHEET_GAP) / (20 + SHEET_GAP);
+        let rows = (sheet.height() - SHEET_GAP) / (10 + SHEET_GAP);
+        assert!(columns * rows >= count && (rows - 1) * columns < count);
+        assert_eq!(*sheet.get_pixel(0, 0), theme.muted);
+        assert!(
+            render_all(&theme, (0, 0))
+                .iter()
+                .all(|(_, img)| img.is_empty())
+        );
+    }
+}
@@ -0,0 +1,360 @@
+//! Built-in vector icons (`icons` feature).
+//!
+//! A small set of common glyphs (media controls, status marks, settings)
+//! drawn from geometry rather than bitmaps, so simple plugins need no icon
+//! assets at all. Icons are rendered with 4x4 supersampled anti-aliasing at
+//! whatever size the target rectangle has, in a single color, typically a
+//! [`Theme`](crate::colors::Theme) role:
+//!
+//! ```ignore
+//! draw_icon(&mut img, BuiltinIcon::Play, Rect::at(16, 16).of_size(40, 40), theme.fg);
+//! ```
+//!
+//! For anything beyond this set, ship a sprite sheet and use
+//! [`IconAtlas`](crate::image::IconAtlas).
+
+use std::f32::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+use image::{Rgba, RgbaImage};
+use imageproc::rect::Rect;
+
+use crate::chart::blend;
+
+/// Samples per pixel along each axis.
+const SUPERSAMPLE: u32 = 4;
+
+/// A built-in icon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuiltinIcon {
+    /// Right-pointing triangle.
+    Play,
+    /// Two vertical bars.
+    Pause,
+    /// Triangle followed by a bar (skip to next).
+    Next,
+    /// Speaker with a cross.
+    Mute,
+    /// Gear.
+    Settings,
+    /// Triangle with an exclamation mark.
+    Warning,
+    /// Check mark.
+    Check,
+    /// Diagonal cross.
+    Cross,
+    /// Clockwise circular arrow.
+    Refresh,
+}
+
+impl BuiltinIcon {
+    /// Every built-in icon.
+    pub const ALL: [BuiltinIcon; 9] = [
+        BuiltinIcon::Play,
+        BuiltinIcon::Pause,
+        BuiltinIcon::Next,
+        BuiltinIcon::Mute,
+        BuiltinIcon::Settings,
+        BuiltinIcon::Warning,
+        BuiltinIcon::Check,
+        BuiltinIcon::Cross,
+        BuiltinIcon::Refresh,
+    ];
+
+    /// Lower-case name, as accepted by [`str::parse`].
+    pub const fn name(self) -> &'static str {
+        match self {
+            BuiltinIcon::Play => "play",
+            BuiltinIcon::Pause => "pause",
+            BuiltinIcon::Next => "next",
+            BuiltinIcon::Mute => "mute",
+            BuiltinIcon::Settings => "settings",
+            BuiltinIcon::Warning => "warning",
+            BuiltinIcon::Check => "check",
+            BuiltinIcon::Cross => "cross",
+            BuiltinIcon::Refresh => "refresh",
+        }
+    }
+
+    /// Whether the point `(x, y)`, in unit coordinates with y pointing
+    /// down, lies inside the icon.
+    fn covers(self, x: f32, y: f32) -> bool {
+        let p = (x, y);
+        match self {
+            BuiltinIcon::Play => polygon(p, &[(0.25, 0.15), (0.85, 0.5), (0.25, 0.85)]),
+            BuiltinIcon::Pause => {
+                rect(p, 0.22, 0.15, 0.42, 0.85) || rect(p, 0.58, 0.15, 0.78, 0.85)
+            }
+            BuiltinIcon::Next => {
+                polygon(p, &[(0.18, 0.18), (0.68, 0.5), (0.18, 0.82)])
+                    || rect(p, 0.7, 0.18, 0.82, 0.82)
+            }
+            BuiltinIcon::Mute => {
+                let speaker = [
+                    (0.1, 0.37),
+                    (0.27, 0.37),
+                    (0.5, 0.15),
+                    (0.5, 0.85),
+                    (0.27, 0.63),
+                    (0.1, 0.63),
+                ];
+                polygon(p, &speaker)
+                    || segment(p, (0.62, 0.37), (0.88, 0.63), 0.08)
+                    || segment(p, (0.88, 0.37), (0.62, 0.63), 0.08)
+            }
+            BuiltinIcon::Settings => {
+                let teeth = (0..8).any(|i| {
+                    let angle = i as f32 * PI / 4.0;
+                    let at = |r: f32| (0.5 + r * angle.cos(), 0.5 + r * angle.sin());
+                    segment(p, at(0.26), at(0.4), 0.13)
+                });
+                ring(p, 0.14, 0.31) || teeth
+            }
+            BuiltinIcon::Warning => {
+                let triangle = polygon(p, &[(0.5, 0.1), (0.92, 0.86), (0.08, 0.86)]);
+                let mark = segment(p, (0.5, 0.38), (0.5, 0.6), 0.09)
+                    || segment(p, (0.5, 0.73), (0.5, 0.73), 0.1);
+                triangle && !mark
+            }
+            BuiltinIcon::Check => {
+                segment(p, (0.18, 0.52), (0.4, 0.74), 0.12)
+                    || segment(p, (0.4, 0.74), (0.84, 0.28), 0.12)
+            }
+            BuiltinIcon::Cross => {
+                segment(p, (0.22, 0.22), (0.78, 0.78), 0.12)
+                    || segment(p, (0.78, 0.22), (0.22, 0.78), 0.12)
+            }
+            BuiltinIcon::Refresh => {
+                // Arc runs clockwise from 20 degrees above 3 o'clock round to
+                // just right of 12 o'clock, where the arrow head points right
+                let (start, end) = (-20f32.to_radians(), -80f32.to_radians());
+                let angle = (y - 0.5).atan2(x - 0.5);
+                let in_gap = angle > end && angle < start;
+                let arc = ring(p, 0.24, 0.34) && !in_gap;
+                let on_circle = |r: f32| (0.5 + r * end.cos(), 0.5 + r * end.sin());
+                let (tx, ty) = (-end.sin(), end.cos());
+                let (bx, by) = on_circle(0.29);
+                let tip = (bx + tx * 0.17, by + ty * 0.17);
+                arc || polygon(p, &[on_circle(0.15), on_circle(0.43), tip])
+            }
+        }
+    }
+}
+
+impl fmt::Display for BuiltinIcon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Error returned when parsing an unknown icon name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseIconError(String);
+
+impl fmt::Display for ParseIconError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown icon: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseIconError {}
+
+impl FromStr for BuiltinIcon {
+    type Err = ParseIconError;
+
+    /// Parse an icon name, ignoring case and surrounding whitespace.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        BuiltinIcon::ALL
+            .into_iter()
+            .find(|icon| icon.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| ParseIconError(s.to_string()))
+    }
+}
+
+/// Draw `icon` in `color`, scaled to the largest square that fits inside
+/// `rect` and centered in it.
+///
+/// Edges are anti-aliased and the icon is blended over the existing
+/// pixels. Drawing is clipped to the image bounds.
+pub fn draw_icon(rgba: &mut RgbaImage, icon: BuiltinIcon, rect: Rect, color: Rgba<u8>) {
+    let size = rect.width().min(rect.height());
+    if size == 0 || color[3] == 0 {
+        return;
+    }
+    let left = rect.left() + ((rect.width() - size) / 2) as i32;
+    let top = rect.top() + ((rect.height() - size) / 2) as i32;
+    let opacity = color[3] as f32 / 255.0;
+    let samples = (SUPERSAMPLE * SUPERSAMPLE) as f32;
+    let scale = 1.0 / (size * SUPERSAMPLE) as f32;
+
+    for py in 0..size {
+        let y = top + py as i32;
+        if y < 0 || y >= rgba.height() as i32 {
+            continue;
+        }
+        for px in 0..size {
+            let x = left + px as i32;
+            if x < 0 || x >= rgba.width() as i32 {
+                continue;
+            }
+            let mut hits = 0;
+            for sy in 0..SUPERSAMPLE {
+                let v = ((py * SUPERSAMPLE + sy) as f32 + 0.5) * scale;
+                for sx in 0..SUPERSAMPLE {
+                    let u = ((px * SUPERSAMPLE + sx) as f32 + 0.5) * scale;
+                    hits += icon.covers(u, v) as u32;
+                }
+            }
+            if hits > 0 {
+                let pixel = rgba.get_pixel_mut(x as u32, y as u32);
+                blend(pixel, color, opacity * hits as f32 / samples);
+            }
+        }
+    }
+}
+
+/// Render `icon` alone onto a transparent `size` x `size` image.
+pub fn render_icon(icon: BuiltinIcon, size: u32, color: Rgba<u8>) -> RgbaImage {
+    let mut img = RgbaImage::new(size, size);
+    draw_icon(
+        &mut img,
+        icon,
+        Rect::at(0, 0).of_size(size.max(1), size.max(1)),
+        color,
+    );
+    img
+}
+
+fn rect(p: (f32, f32), x0: f32, y0: f32, x1: f32, y1: f32) -> bool {
+    p.0 >= x0 && p.0 <= x1 && p.1 >= y0 && p.1 <= y1
+}
+
+/// Even-odd point-in-polygon test.
+fn polygon(p: (f32, f32), points: &[(f32, f32)]) -> bool {
+    let mut inside = false;
+    let mut j = points.len() - 1;
+    for i in 0..points.len() {
+        let (xi, yi) = points[i];
+        let (xj, yj) = points[j];
+        if (yi > p.1) != (yj > p.1) && p.0 < (xj - xi) * (p.1 - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Whether `p` is within `width / 2` of the segment `a`-`b` (round caps).
+fn segment(p: (f32, f32), a: (f32, f32), b: (f32, f32), width: f32) -> bool {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 > 0.0 {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len2).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (ex, ey) = (p.0 - (a.0 + t * dx), p.1 - (a.1 + t * dy));
+    ex * ex + ey * ey <= width * width / 4.0
+}
+
+/// Whether `p` lies between radii `inner` and `outer` around the center.
+fn ring(p: (f32, f32), inner: f32, outer: f32) -> bool {
+    let d2 = (p.0 - 0.5).powi(2) + (p.1 - 0.5).powi(2);
+    d2 >= inner * inner && d2 <= outer * outer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+    #[test]
+    fn test_icon_names_round_trip() {
+        for icon in BuiltinIcon::ALL {
+            assert_eq!(icon.to_string().parse::<BuiltinIcon>(), Ok(icon));
+        }
+        assert_eq!(" Play ".parse::<BuiltinIcon>(), Ok(BuiltinIcon::Play));
+        assert!("volume".parse::<BuiltinIcon>().is_err());
+    }
+
+    #[test]
+    fn test_every_icon_draws_something_at_small_and_large_sizes() {
+        for icon in BuiltinIcon::ALL {
+            for size in [12, 72] {
+                let img = render_icon(icon, size, WHITE);
+                let covered = img.pixels().filter(|p| p[3] > 0).count();
+                assert!(covered > 0, "{icon} drew nothing at {size}px");
+                assert!(covered < (size * size) as usize, "{icon} filled {size}px");
+            }
+        }
+    }
+
+    #[test]
+    fn test_draw_icon_tints_and_centers() {
+        let color = Rgba([255, 0, 0, 255]);
+        let mut img = RgbaImage::new(40, 20);
+        draw_icon(
+            &mut img,
+            BuiltinIcon::Pause,
+            Rect::at(0, 0).of_size(40, 20),
+            color,
+        );
+        // Fitted to a 20x20 square at x = 10..30; left bar spans x 14..18
+        assert_eq!(*img.get_pixel(16, 10), color);
+        assert_eq!(img.get_pixel(20, 10)[3], 0);
+        assert_eq!(img.get_pixel(5, 10)[3], 0);
+    }
+
+    #[test]
+    fn test_warning_mark_is_cut_out() {
+        let img = render_icon(BuiltinIcon::Warning, 100, WHITE);
+        assert_eq!(img.get_pixel(50, 50)[3], 0);
+        assert_eq!(img.get_pixel(40, 75)[3], 255);
+    }
+
+    #[test]
+    fn test_draw_icon_clips_to_image() {
+        // Pause bars span x 4..8 and 12..16 of a 20px square
+        let drawn_columns = |img: &RgbaImage| {
+            (0..img.width())
+                .filter(|&x| (0..img.height()).any(|y| img.get_pixel(x, y)[3] > 0))
+                .collect::<Vec<_>>()
+        };
+        let pause = |x| {
+            let mut img = RgbaImage::new(20, 20);
+            draw_icon(
+                &mut img,
+                BuiltinIcon::Pause,
+                Rect::at(x, 0).of_size(20, 20),
+                WHITE,
+            );
+            img
+        };
+        // Only the bar inside the image is drawn, with nothing wrapping
+        // round to the opposite edge
+        let right = drawn_columns(&pause(10));
+        assert!(right.contains(&16) && right.iter().all(|x| (13..=18).contains(x)));
+        let left = drawn_columns(&pause(-10));
+        assert!(left.contains(&4) && left.iter().all(|x| (1..=6).contains(x)));
+
+        let mut img = RgbaImage::new(10, 10);
+        draw_icon(
+            &mut img,
+            BuiltinIcon::Settings,
+            Rect::at(-20, -20).of_size(40, 40),
+            WHITE,
+        );
+        assert!(img.pixels().any(|p| p[3] > 0));
+        let before = img.clone();
+        draw_icon(
+            &mut img,
+            BuiltinIcon::Check,
+            Rect::at(50, 50).of_size(10, 10),
+            WHITE,
+        );
+        assert_eq!(img, before);
+    }
+}
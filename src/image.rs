@@ -1,42 +1,314 @@
 //! Image manipulation utilities.
 //!
-//! Provides functions for image effects and format conversions.
+//! Provides functions for image effects, geometric warps and format conversions
+//! (including padded screen-capture buffers, premultiplied alpha and, with the
+//! `yuv` feature, NV12/I420 camera frames), pixel-art upscaling, plus
+//! [`IconAtlas`] sprite sheets for named icons.
 
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
-use image::{Rgb, RgbImage, Rgba, RgbaImage};
+use image::imageops::FilterType;
+use image::{GrayImage, Rgb, RgbImage, Rgba, RgbaImage};
+use imageproc::rect::Rect;
+
+use crate::accessibility::reduced_motion;
+use crate::anim::phase;
+use crate::colors::{CvdType, composite_over_opaque, simulate_cvd};
+
+use self::pixel_math::Factor;
+
+mod atlas;
+mod background;
+mod blur;
+mod cache;
+mod capture;
+mod convolve;
+mod curves;
+mod duotone;
+mod edges;
+mod halftone;
+mod hdr;
+mod letterbox;
+mod lut;
+pub mod noise;
+mod pixel_art;
+pub mod pixel_math;
+mod pool;
+mod premultiply;
+mod recolor;
+mod stats;
+mod warp;
+#[cfg(feature = "yuv")]
+mod yuv;
+
+pub use atlas::{AtlasError, IconAtlas};
+pub(crate) use background::paint_image;
+pub use background::{Background, Fit};
+pub use blur::{BlurQuality, blur};
+pub use cache::ScaledImageCache;
+pub use capture::{CaptureFormat, capture_to_rgba};
+pub use convolve::{EdgeMode, KernelError, convolve, convolve_into};
+pub use curves::{Channel, apply_curve};
+pub use duotone::{duotone, tritone};
+pub use edges::{emboss, outline, sobel_edges};
+pub use halftone::halftone;
+pub use hdr::{HdrSurface, ToneMap};
+pub use letterbox::{Letterbox, average_color, scale_to_cover, scale_with_letterbox};
+pub use lut::{Lut3d, LutError, apply_lut};
+pub use pixel_art::{scale_nearest, scale2x, scale3x};
+pub use pool::FramePool;
+pub use premultiply::{premultiply_image, unpremultiply_image};
+pub use recolor::replace_color;
+pub use stats::{ImageStats, stats};
+pub use warp::{barrel, warp_perspective};
+#[cfg(feature = "yuv")]
+pub use yuv::{
+    YuvMatrix, YuvRange, i420_to_rgba, i420_to_rgba_with_stride, nv12_to_rgba,
+    nv12_to_rgba_with_stride,
+};
+
+/// Brightness pulse configuration.
+///
+/// The pulse follows a sine wave between `min` and `max` brightness with one
+/// full cycle every `period`. The wave is aligned to wall-clock time so
+/// separate tiles pulse in sync.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pulse {
+    /// Brightness at the darkest point (0.0 to 1.0).
+    pub min: f32,
+    /// Brightness at the brightest point (0.0 to 1.0).
+    pub max: f32,
+    /// Duration of one full cycle.
+    pub period: Duration,
+}
+
+impl Default for Pulse {
+    /// Slow pulse between 10% and 100% brightness with a 1.5 second cycle.
+    fn default() -> Self {
+        Self {
+            min: 0.1,
+            max: 1.0,
+            period: Duration::from_millis(1500),
+        }
+    }
+}
+
+impl Pulse {
+    /// Brightness factor for the current system time.
+    pub fn level(&self) -> f32 {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        self.level_at(now)
+    }
+
+    /// Brightness factor at `time` (measured from any fixed origin).
+    ///
+    /// Holds at `max` in [reduced-motion
+    /// mode](crate::accessibility::set_reduced_motion).
+    pub fn level_at(&self, time: Duration) -> f32 {
+        if reduced_motion() {
+            return self.max;
+        }
+        let wave = (phase(time, self.period) * std::f32::consts::TAU).sin();
+        let mid = (self.max + self.min) / 2.0;
+        let amplitude = (self.max - self.min) / 2.0;
+        wave * amplitude + mid
+    }
+}
+
+/// Lookup table scaling sRGB channel bytes by a brightness factor in linear
+/// light, so dimming keeps midtones instead of crushing them. Built once per
+/// factor with [`Factor::scale_linear`], so it follows the active pixel
+/// math mode.
+#[derive(Clone, Copy)]
+struct BrightnessLut([u8; 256]);
+
+impl BrightnessLut {
+    fn new(factor: f32) -> Self {
+        let factor = Factor::new(factor.max(0.0));
+        Self(std::array::from_fn(|c| factor.scale_linear(c as u8)))
+    }
+
+    /// Scale the color channels of a pixel, leaving alpha untouched.
+    #[inline]
+    fn apply(&self, pixel: &mut Rgba<u8>) {
+        for c in 0..3 {
+            pixel[c] = self.0[pixel[c] as usize];
+        }
+    }
+}
 
 /// Apply a slow brightness pulse to the image based on system time.
 ///
 /// Creates a sine wave oscillation between 10% and 100% brightness
 /// with a 1.5 second cycle. Useful for attention-grabbing animations.
 pub fn apply_brightness_pulse(rgba: &mut RgbaImage) {
-    // Use subsec portion for precision (f32 can't handle billions of seconds)
-    let now = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .unwrap_or_default();
-    // Use seconds mod 3 + subsec (3 is a multiple of 1.5, so sine wave aligns at wrap)
-    let secs = (now.as_secs() % 3) as f32 + now.subsec_nanos() as f32 / 1_000_000_000.0;
+    apply_brightness_pulse_with(rgba, &Pulse::default());
+}
+
+/// Apply a brightness pulse to the whole image using `pulse`.
+pub fn apply_brightness_pulse_with(rgba: &mut RgbaImage, pulse: &Pulse) {
+    let level = pulse.level();
+    tracing::debug!(pulse = level, "apply_brightness_pulse");
+
+    let lut = BrightnessLut::new(level);
+    for pixel in rgba.pixels_mut() {
+        lut.apply(pixel);
+    }
+}
+
+/// Apply a brightness pulse only within `rect`.
+///
+/// The rectangle is clipped to the image bounds.
+pub fn apply_brightness_pulse_in_rect(rgba: &mut RgbaImage, rect: Rect, pulse: &Pulse) {
+    if rgba.width() == 0 || rgba.height() == 0 {
+        return;
+    }
+    let bounds = Rect::at(0, 0).of_size(rgba.width(), rgba.height());
+    let Some(area) = rect.intersect(bounds) else {
+        return;
+    };
+    let level = pulse.level();
+    tracing::debug!(pulse = level, "apply_brightness_pulse_in_rect");
+
+    let lut = BrightnessLut::new(level);
+    for y in area.top()..=area.bottom() {
+        for x in area.left()..=area.right() {
+            lut.apply(rgba.get_pixel_mut(x as u32, y as u32));
+        }
+    }
+}
 
-    // Sine wave oscillating between 0.1 and 1.0 (dims to 10% at darkest)
-    // Divide by 1.5 for one cycle per 1.5 seconds
-    let pulse = (secs * std::f32::consts::TAU / 1.5).sin() * 0.45 + 0.55;
+/// Apply a brightness pulse weighted by a greyscale mask.
+///
+/// Mask value 255 applies the full pulse, 0 leaves the pixel unchanged, and
+/// values in between apply it proportionally. Pixels outside the mask are
+/// unchanged.
+pub fn apply_brightness_pulse_masked(rgba: &mut RgbaImage, mask: &GrayImage, pulse: &Pulse) {
+    let level = pulse.level();
+    tracing::debug!(pulse = level, "apply_brightness_pulse_masked");
 
-    tracing::debug!(pulse, "apply_brightness_pulse");
+    // One table per mask value, built on first use
+    let mut luts: Vec<Option<BrightnessLut>> = vec![None; 256];
+    for (x, y, pixel) in rgba.enumerate_pixels_mut() {
+        if x >= mask.width() || y >= mask.height() {
+            continue;
+        }
+        let weight = mask.get_pixel(x, y)[0];
+        let lut = luts[weight as usize].get_or_insert_with(|| {
+            BrightnessLut::new(1.0 - (1.0 - level) * (weight as f32 / 255.0))
+        });
+        lut.apply(pixel);
+    }
+}
 
+/// Interaction state of a widget, used to pick a standard visual treatment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WidgetState {
+    /// Normal rendering.
+    #[default]
+    Active,
+    /// Present but not currently relevant; dimmed.
+    Inactive,
+    /// Unavailable; desaturated and dimmed.
+    Disabled,
+}
+
+/// Brightness applied to inactive and disabled widgets.
+const STATE_DIM: f32 = 0.6;
+
+/// Apply the standard visual treatment for a widget state.
+///
+/// - `Active`: unchanged
+/// - `Inactive`: dimmed to 60% brightness
+/// - `Disabled`: converted to greyscale and dimmed to 60% brightness
+///
+/// Alpha is preserved.
+pub fn apply_state_filter(rgba: &mut RgbaImage, state: WidgetState) {
+    match state {
+        WidgetState::Active => {}
+        WidgetState::Inactive => {
+            let lut = BrightnessLut::new(STATE_DIM);
+            for pixel in rgba.pixels_mut() {
+                lut.apply(pixel);
+            }
+        }
+        WidgetState::Disabled => {
+            let lut = BrightnessLut::new(STATE_DIM);
+            for pixel in rgba.pixels_mut() {
+                let grey = to_greyscale(pixel[0], pixel[1], pixel[2]);
+                *pixel = Rgba([grey, grey, grey, pixel[3]]);
+                lut.apply(pixel);
+            }
+        }
+    }
+}
+
+/// Recolor the image as seen with color vision deficiency `kind`, for
+/// previewing a rendered widget as colorblind users would see it.
+///
+/// See [`simulate_cvd`]; alpha is preserved.
+pub fn apply_cvd_filter(rgba: &mut RgbaImage, kind: CvdType) {
+    // Widgets use few distinct colors, so remember the last conversion
+    let mut last: Option<(Rgba<u8>, Rgba<u8>)> = None;
     for pixel in rgba.pixels_mut() {
-        pixel[0] = (pixel[0] as f32 * pulse) as u8;
-        pixel[1] = (pixel[1] as f32 * pulse) as u8;
-        pixel[2] = (pixel[2] as f32 * pulse) as u8;
+        let seen = match last {
+            Some((from, to)) if from == *pixel => to,
+            _ => {
+                let to = simulate_cvd(*pixel, kind);
+                last = Some((*pixel, to));
+                to
+            }
+        };
+        *pixel = seen;
+    }
+}
+
+/// Fade the alpha channel to transparent near the image edges.
+///
+/// Alpha ramps smoothly from zero at the border to its original value
+/// `radius` pixels in, so photos blend into tile backgrounds without hard
+/// seams. A radius of zero leaves the image unchanged.
+pub fn feather_edges(rgba: &mut RgbaImage, radius: u32) {
+    if radius == 0 {
+        return;
+    }
+    let (width, height) = rgba.dimensions();
+    let radius = radius as f32;
+    let ramp = |pos: u32, len: u32| {
+        let d = pos.min(len - 1 - pos) as f32 + 0.5;
+        let t = (d / radius).min(1.0);
+        // Smoothstep avoids a visible crease where the ramp meets full opacity
+        t * t * (3.0 - 2.0 * t)
+    };
+    let columns: Vec<f32> = (0..width).map(|x| ramp(x, width)).collect();
+    for y in 0..height {
+        let row = ramp(y, height);
+        for (x, column) in columns.iter().enumerate() {
+            let pixel = rgba.get_pixel_mut(x as u32, y);
+            let factor = Factor::new(row.min(*column));
+            pixel[3] = factor.scale(pixel[3]);
+        }
+    }
+}
+
+/// Recolor every pixel to `color`, keeping each pixel's alpha as a mask
+/// (scaled by the alpha of `color`). Used to tint monochrome icons.
+pub(crate) fn apply_tint(rgba: &mut RgbaImage, color: Rgba<u8>) {
+    for p in rgba.pixels_mut() {
+        let alpha = (p[3] as u32 * color[3] as u32 + 127) / 255;
+        *p = Rgba([color[0], color[1], color[2], alpha as u8]);
     }
 }
 
 /// Convert RGB to greyscale using the luminosity method.
 ///
 /// Uses standard luminosity coefficients: 0.299*R + 0.587*G + 0.114*B
+/// (approximated in integer arithmetic with the `fixed-point` feature).
 #[inline]
 pub fn to_greyscale(r: u8, g: u8, b: u8) -> u8 {
-    ((0.299 * r as f32) + (0.587 * g as f32) + (0.114 * b as f32)) as u8
+    pixel_math::greyscale(r, g, b)
 }
 
 /// Convert an RgbImage to an RgbaImage with full opacity.
@@ -48,6 +320,9 @@ pub fn rgb_to_rgba(rgb: &RgbImage) -> RgbaImage {
 }
 
 /// Convert an RgbaImage to an RgbImage, discarding alpha.
+///
+/// Transparent pixels keep whatever color they carry; use [`flatten_onto`]
+/// when the result is going to be displayed.
 pub fn rgba_to_rgb(rgba: &RgbaImage) -> RgbImage {
     RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
         let pixel = rgba.get_pixel(x, y);
@@ -55,6 +330,18 @@ pub fn rgba_to_rgb(rgba: &RgbaImage) -> RgbImage {
     })
 }
 
+/// Composite an RgbaImage over a solid background, producing an RgbImage.
+///
+/// Each pixel is alpha-weighted against `background`, so transparent areas
+/// show the background and antialiased edges blend smoothly. The alpha of
+/// `background` is ignored.
+pub fn flatten_onto(rgba: &RgbaImage, background: Rgba<u8>) -> RgbImage {
+    RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let Rgba([r, g, b, _]) = composite_over_opaque(*rgba.get_pixel(x, y), background);
+        Rgb([r, g, b])
+    })
+}
+
 /// Convert raw RGB bytes to an RgbImage.
 ///
 /// # Arguments
@@ -91,21 +378,112 @@ pub fn bytes_to_rgba(width: u32, height: u32, data: &[u8]) -> RgbaImage {
 
 /// Scale an image to fit within target dimensions using high-quality Lanczos3 filter.
 pub fn scale_image(src: &RgbImage, target_width: u32, target_height: u32) -> RgbImage {
+    scale_image_with(src, target_width, target_height, FilterType::Lanczos3)
+}
+
+/// Scale an image to the target dimensions with a chosen resampling filter.
+///
+/// [`FilterType::Nearest`] keeps pixel art crisp at any size; for integer
+/// factors see also [`scale_nearest`], [`scale2x`] and [`scale3x`].
+pub fn scale_image_with(
+    src: &RgbImage,
+    target_width: u32,
+    target_height: u32,
+    filter: FilterType,
+) -> RgbImage {
     if src.width() == target_width && src.height() == target_height {
         return src.clone();
     }
 
-    image::imageops::resize(
-        src,
-        target_width,
-        target_height,
-        image::imageops::FilterType::Lanczos3,
-    )
+    image::imageops::resize(src, target_width, target_height, filter)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use image::Luma;
+
+    fn constant_pulse(level: f32) -> Pulse {
+        Pulse {
+            min: level,
+            max: level,
+            period: Duration::from_secs(1),
+        }
+    }
+
+    #[test]
+    fn test_pulse_default_range() {
+        let pulse = Pulse::default();
+        // Quarter and three-quarter cycle are the peak and trough
+        assert!((pulse.level_at(Duration::from_millis(375)) - 1.0).abs() < 1e-4);
+        assert!((pulse.level_at(Duration::from_millis(1125)) - 0.1).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_brightness_pulse_in_rect_limits_area() {
+        let mut img = RgbaImage::from_pixel(4, 4, Rgba([200, 200, 200, 255]));
+        apply_brightness_pulse_in_rect(
+            &mut img,
+            Rect::at(2, 2).of_size(10, 10),
+            &constant_pulse(0.5),
+        );
+        assert_eq!(*img.get_pixel(0, 0), Rgba([200, 200, 200, 255]));
+        // Half the light, not half the sRGB value
+        assert_eq!(*img.get_pixel(3, 3), Rgba([146, 146, 146, 255]));
+    }
+
+    #[test]
+    fn test_brightness_pulse_masked_weights_effect() {
+        let mut img = RgbaImage::from_pixel(2, 1, Rgba([200, 200, 200, 255]));
+        let mut mask = GrayImage::new(2, 1);
+        mask.put_pixel(1, 0, Luma([255]));
+        apply_brightness_pulse_masked(&mut img, &mask, &constant_pulse(0.5));
+        assert_eq!(*img.get_pixel(0, 0), Rgba([200, 200, 200, 255]));
+        assert_eq!(*img.get_pixel(1, 0), Rgba([146, 146, 146, 255]));
+    }
+
+    #[test]
+    fn test_apply_state_filter_active_unchanged() {
+        let mut img = RgbaImage::from_pixel(1, 1, Rgba([200, 100, 50, 128]));
+        apply_state_filter(&mut img, WidgetState::Active);
+        assert_eq!(*img.get_pixel(0, 0), Rgba([200, 100, 50, 128]));
+    }
+
+    #[test]
+    fn test_apply_state_filter_inactive_dims() {
+        let mut img = RgbaImage::from_pixel(1, 1, Rgba([200, 100, 50, 128]));
+        apply_state_filter(&mut img, WidgetState::Inactive);
+        assert_eq!(*img.get_pixel(0, 0), Rgba([159, 78, 38, 128]));
+    }
+
+    #[test]
+    fn test_apply_state_filter_disabled_greys() {
+        let mut img = RgbaImage::from_pixel(1, 1, Rgba([255, 0, 0, 255]));
+        apply_state_filter(&mut img, WidgetState::Disabled);
+        let p = img.get_pixel(0, 0);
+        assert_eq!(p[0], p[1]);
+        assert_eq!(p[1], p[2]);
+        assert!(p[0] < 76);
+        assert_eq!(p[3], 255);
+    }
+
+    #[test]
+    fn test_feather_edges_ramps_alpha() {
+        let mut img = RgbaImage::from_pixel(20, 20, Rgba([10, 20, 30, 255]));
+        feather_edges(&mut img, 5);
+        assert!(img.get_pixel(0, 10)[3] < 10);
+        assert!(img.get_pixel(0, 10)[3] < img.get_pixel(2, 10)[3]);
+        assert!(img.get_pixel(0, 0)[3] <= img.get_pixel(0, 10)[3]);
+        assert_eq!(*img.get_pixel(10, 10), Rgba([10, 20, 30, 255]));
+    }
+
+    #[test]
+    fn test_feather_edges_zero_radius_is_noop() {
+        let mut img = RgbaImage::from_pixel(3, 3, Rgba([1, 2, 3, 200]));
+        let before = img.clone();
+        feather_edges(&mut img, 0);
+        assert_eq!(img, before);
+    }
 
     #[test]
     fn test_to_greyscale_black() {
@@ -151,6 +529,19 @@ mod tests {
         assert_eq!(*rgb.get_pixel(0, 0), Rgb([100, 150, 200]));
     }
 
+    #[test]
+    fn test_flatten_onto_blends_alpha() {
+        let mut rgba = RgbaImage::new(3, 1);
+        rgba.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+        rgba.put_pixel(1, 0, Rgba([255, 0, 0, 0]));
+        rgba.put_pixel(2, 0, Rgba([255, 0, 0, 128]));
+        let rgb = flatten_onto(&rgba, Rgba([0, 0, 255, 255]));
+
+        assert_eq!(*rgb.get_pixel(0, 0), Rgb([255, 0, 0]));
+        assert_eq!(*rgb.get_pixel(1, 0), Rgb([0, 0, 255]));
+        assert_eq!(*rgb.get_pixel(2, 0), Rgb([128, 0, 127]));
+    }
+
     #[test]
     fn test_bytes_to_rgb() {
         let data = vec![255, 0, 0, 0, 255, 0, 0, 0, 255, 128, 128, 128];
@@ -169,10 +560,30 @@ mod tests {
         assert_eq!(scaled.dimensions(), (10, 10));
     }
 
+    #[test]
+    fn test_apply_cvd_filter_matches_color_simulation() {
+        let red = Rgba([220, 40, 40, 200]);
+        let mut img = RgbaImage::from_fn(3, 2, |x, _| if x == 1 { red } else { Rgba([9; 4]) });
+        apply_cvd_filter(&mut img, CvdType::Protanopia);
+        assert_eq!(*img.get_pixel(1, 1), simulate_cvd(red, CvdType::Protanopia));
+        assert_eq!(
+            *img.get_pixel(2, 0),
+            simulate_cvd(Rgba([9; 4]), CvdType::Protanopia)
+        );
+    }
+
     #[test]
     fn test_scale_image_resizes() {
         let img = RgbImage::from_pixel(10, 10, Rgb([100, 100, 100]));
         let scaled = scale_image(&img, 20, 20);
         assert_eq!(scaled.dimensions(), (20, 20));
     }
+
+    #[test]
+    fn test_scale_image_with_nearest_keeps_hard_edges() {
+        let img = RgbImage::from_fn(2, 1, |x, _| Rgb([x as u8 * 255; 3]));
+        let scaled = scale_image_with(&img, 8, 4, FilterType::Nearest);
+        assert_eq!(*scaled.get_pixel(3, 2), Rgb([0, 0, 0]));
+        assert_eq!(*scaled.get_pixel(4, 2), Rgb([255, 255, 255]));
+    }
 }
@@ -0,0 +1,306 @@
+//! Named icons packed into a single sprite sheet.
+
+use std::collections::HashMap;
+use std::fmt;
+#[cfg(feature = "icon-atlas")]
+use std::path::Path;
+
+use image::imageops;
+use image::{Rgba, RgbaImage};
+use imageproc::rect::Rect;
+
+use super::{Fit, apply_tint, paint_image};
+
+/// Error building or loading an [`IconAtlas`].
+#[derive(Debug)]
+pub enum AtlasError {
+    /// The sheet or manifest could not be read.
+    Io(std::io::Error),
+    /// The sheet could not be decoded.
+    Image(image::ImageError),
+    /// The manifest is not valid.
+    Parse(String),
+    /// An icon's rectangle extends outside the sheet.
+    OutOfBounds(String),
+}
+
+impl fmt::Display for AtlasError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AtlasError::Io(e) => write!(f, "atlas I/O error: {e}"),
+            AtlasError::Image(e) => write!(f, "invalid atlas sheet: {e}"),
+            AtlasError::Parse(e) => write!(f, "invalid atlas manifest: {e}"),
+            AtlasError::OutOfBounds(name) => write!(f, "icon {name:?} lies outside the sheet"),
+        }
+    }
+}
+
+impl std::error::Error for AtlasError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AtlasError::Io(e) => Some(e),
+            AtlasError::Image(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for AtlasError {
+    fn from(e: std::io::Error) -> Self {
+        AtlasError::Io(e)
+    }
+}
+
+impl From<image::ImageError> for AtlasError {
+    fn from(e: image::ImageError) -> Self {
+        AtlasError::Image(e)
+    }
+}
+
+/// A sprite sheet of icons looked up by name.
+///
+/// Icon packs ship as one image plus a manifest mapping names to
+/// rectangles, so several plugins can share a pack and each icon costs no
+/// more than its pixels in the sheet. Icons are scaled to fit the target
+/// rectangle, keeping their aspect ratio; scaled copies are cached per
+/// size, so drawing the same icon every frame only resamples once.
+///
+/// With the `icon-atlas` feature, atlases load from a JSON manifest:
+///
+/// ```json
+/// {
+///   "wifi-3": { "x": 0, "y": 0, "w": 16, "h": 16 },
+///   "battery-low": { "x": 16, "y": 0, "w": 16, "h": 16 }
+/// }
+/// ```
+///
+/// TexturePacker's "JSON (Hash)" export, with rectangles under
+/// `frames.<name>.frame`, is accepted too.
+///
+/// ```ignore
+/// let mut icons = IconAtlas::load("icons.png", "icons.json")?;
+/// icons.draw(&mut img, "wifi-3", Rect::at(4, 4).of_size(24, 24), Some(theme.fg));
+/// ```
+#[derive(Debug, Clone)]
+pub struct IconAtlas {
+    sheet: RgbaImage,
+    icons: HashMap<String, Rect>,
+    scaled: HashMap<(String, u32, u32), RgbaImage>,
+}
+
+impl IconAtlas {
+    /// Create an atlas from a sheet and `(name, rect)` entries.
+    ///
+    /// Fails if any rectangle extends outside the sheet.
+    pub fn new<S: Into<String>>(
+        sheet: RgbaImage,
+        icons: impl IntoIterator<Item = (S, Rect)>,
+    ) -> Result<Self, AtlasError> {
+        let icons: HashMap<String, Rect> = icons
+            .into_iter()
+            .map(|(name, rect)| (name.into(), rect))
+            .collect();
+        for (name, rect) in &icons {
+            let inside = rect.left() >= 0
+                && rect.top() >= 0
+                && rect.right() < sheet.width() as i32
+                && rect.bottom() < sheet.height() as i32;
+            if !inside {
+                return Err(AtlasError::OutOfBounds(name.clone()));
+            }
+        }
+        Ok(Self {
+            sheet,
+            icons,
+            scaled: HashMap::new(),
+        })
+    }
+
+    /// Create an atlas from a sheet and a JSON manifest.
+    #[cfg(feature = "icon-atlas")]
+    pub fn from_json(sheet: RgbaImage, manifest: &str) -> Result<Self, AtlasError> {
+        let json: serde_json::Value =
+            serde_json::from_str(manifest).map_err(|e| AtlasError::Parse(e.to_string()))?;
+        let entries = json.get("frames").unwrap_or(&json);
+        let entries = entries
+            .as_object()
+            .ok_or_else(|| AtlasError::Parse("expected an object of icons".into()))?;
+        let mut icons = Vec::with_capacity(entries.len());
+        for (name, entry) in entries {
+            let entry = entry.get("frame").unwrap_or(entry);
+            let field = |key: &str| {
+                entry
+                    .get(key)
+                    .and_then(serde_json::Value::as_u64)
+                    .and_then(|v| u32::try_from(v).ok())
+                    .ok_or_else(|| AtlasError::Parse(format!("icon {name:?} has no valid {key:?}")))
+            };
+            let (x, y, w, h) = (field("x")?, field("y")?, field("w")?, field("h")?);
+            if w == 0 || h == 0 {
+                return Err(AtlasError::Parse(format!("icon {name:?} is empty")));
+            }
+            icons.push((name.clone(), Rect::at(x as i32, y as i32).of_size(w, h)));
+        }
+        Self::new(sheet, icons)
+    }
+
+    /// Load an atlas from a sheet image and a JSON manifest file.
+    #[cfg(feature = "icon-atlas")]
+    pub fn load(sheet: impl AsRef<Path>, manifest: impl AsRef<Path>) -> Result<Self, AtlasError> {
+        let image = image::open(sheet)?.to_rgba8();
+        Self::from_json(image, &std::fs::read_to_string(manifest)?)
+    }
+
+    /// Number of icons.
+    pub fn len(&self) -> usize {
+        self.icons.len()
+    }
+
+    /// Whether the atlas has no icons.
+    pub fn is_empty(&self) -> bool {
+        self.icons.is_empty()
+    }
+
+    /// Whether an icon called `name` exists.
+    pub fn contains(&self, name: &str) -> bool {
+        self.icons.contains_key(name)
+    }
+
+    /// Icon names, in no particular order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.icons.keys().map(String::as_str)
+    }
+
+    /// A copy of the icon `name` at its natural size.
+    pub fn icon(&self, name: &str) -> Option<RgbaImage> {
+        let rect = self.icons.get(name)?;
+        Some(
+            imageops::crop_imm(
+                &self.sheet,
+                rect.left() as u32,
+                rect.top() as u32,
+                rect.width(),
+                rect.height(),
+            )
+            .to_image(),
+        )
+    }
+
+    /// Draw icon `name` scaled to fit inside `rect`, centered, optionally
+    /// recolored to `tint` (keeping the icon's alpha as a mask).
+    ///
+    /// Returns false, drawing nothing, if there is no such icon. Drawing is
+    /// clipped to the image bounds.
+    pub fn draw(
+        &mut self,
+        rgba: &mut RgbaImage,
+        name: &str,
+        rect: Rect,
+        tint: Option<Rgba<u8>>,
+    ) -> bool {
+        let size = (rect.width(), rect.height());
+        let key = (name.to_string(), size.0, size.1);
+        if !self.scaled.contains_key(&key) {
+            let Some(icon) = self.icon(name) else {
+                return false;
+            };
+            let mut layer = RgbaImage::new(size.0, size.1);
+            paint_image(&mut layer, &icon, Fit::Contain);
+            self.scaled.insert(key.clone(), layer);
+        }
+        let layer = &self.scaled[&key];
+        let (x, y) = (rect.left() as i64, rect.top() as i64);
+        match tint {
+            Some(color) => {
+                let mut tinted = layer.clone();
+                apply_tint(&mut tinted, color);
+                imageops::overlay(rgba, &tinted, x, y);
+            }
+            None => imageops::overlay(rgba, layer, x, y),
+        }
+        true
+    }
+
+    /// Drop cached scaled icons, e.g. after the tile size changes.
+    pub fn clear_cache(&mut self) {
+        self.scaled.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+
+    /// An 8x4 sheet: a red 4x4 icon on the left, a blue one on the right.
+    fn atlas() -> IconAtlas {
+        let sheet = RgbaImage::from_fn(8, 4, |x, _| if x < 4 { RED } else { BLUE });
+        IconAtlas::new(
+            sheet,
+            [
+                ("red", Rect::at(0, 0).of_size(4, 4)),
+                ("blue", Rect::at(4, 0).of_size(4, 4)),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_atlas_lookup() {
+        let atlas = atlas();
+        assert_eq!(atlas.len(), 2);
+        assert!(atlas.contains("blue"));
+        let blue = atlas.icon("blue").unwrap();
+        assert_eq!(blue.dimensions(), (4, 4));
+        assert!(blue.pixels().all(|p| *p == BLUE));
+        assert!(atlas.icon("green").is_none());
+    }
+
+    #[test]
+    fn test_atlas_draw_scales_and_tints() {
+        let mut atlas = atlas();
+        let mut img = RgbaImage::new(12, 12);
+        assert!(atlas.draw(&mut img, "red", Rect::at(2, 2).of_size(8, 8), None));
+        assert_eq!(*img.get_pixel(5, 5), RED);
+        assert_eq!(img.get_pixel(1, 1)[3], 0);
+
+        let tint = Rgba([0, 255, 0, 255]);
+        assert!(atlas.draw(&mut img, "blue", Rect::at(2, 2).of_size(8, 8), Some(tint)));
+        assert_eq!(*img.get_pixel(5, 5), tint);
+        assert!(!atlas.draw(&mut img, "green", Rect::at(0, 0).of_size(4, 4), None));
+    }
+
+    #[test]
+    fn test_atlas_rejects_out_of_bounds() {
+        let err = IconAtlas::new(
+            RgbaImage::new(4, 4),
+            [("big", Rect::at(2, 0).of_size(4, 4))],
+        );
+        assert!(matches!(err, Err(AtlasError::OutOfBounds(name)) if name == "big"));
+    }
+
+    #[cfg(feature = "icon-atlas")]
+    #[test]
+    fn test_atlas_from_json() {
+        let sheet = RgbaImage::from_fn(8, 4, |x, _| if x < 4 { RED } else { BLUE });
+        let atlas = IconAtlas::from_json(
+            sheet.clone(),
+            r#"{ "wifi-3": { "x": 4, "y": 0, "w": 4, "h": 4 } }"#,
+        )
+        .unwrap();
+        assert_eq!(*atlas.icon("wifi-3").unwrap().get_pixel(0, 0), BLUE);
+
+        // TexturePacker hash export
+        let packed = IconAtlas::from_json(
+            sheet.clone(),
+            r#"{ "frames": { "a.png": { "frame": { "x": 0, "y": 0, "w": 4, "h": 4 } } } }"#,
+        )
+        .unwrap();
+        assert!(packed.contains("a.png"));
+
+        let missing = IconAtlas::from_json(sheet, r#"{ "a": { "x": 0, "y": 0, "w": 4 } }"#);
+        assert!(matches!(missing, Err(AtlasError::Parse(_))));
+    }
+}
@@ -0,0 +1,231 @@
+//! Declarative backgrounds painted behind widget content.
+
+use image::imageops::{self, FilterType};
+use image::{Rgba, RgbaImage};
+
+use super::{BlurQuality, blur};
+
+/// How an image background is sized to the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Fit {
+    /// Scale to cover the target, preserving aspect ratio and cropping overflow.
+    #[default]
+    Cover,
+    /// Scale to fit inside the target, preserving aspect ratio; uncovered
+    /// areas are left as they were.
+    Contain,
+    /// Scale to exactly the target size, ignoring aspect ratio.
+    Stretch,
+    /// Draw at natural size, centered, cropping any overflow.
+    Center,
+}
+
+/// What fills behind a widget's content.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Background {
+    /// Leave the image untouched.
+    #[default]
+    None,
+    /// A single color.
+    Solid(Rgba<u8>),
+    /// A gradient along a line at `angle` degrees, following CSS
+    /// conventions (0 points up, 90 points right). Stops are
+    /// `(position, color)` with positions from 0.0 to 1.0.
+    LinearGradient {
+        angle: f32,
+        stops: Vec<(f32, Rgba<u8>)>,
+    },
+    /// A circular gradient from `center` (fractions of width and height)
+    /// out to the farthest corner.
+    RadialGradient {
+        center: (f32, f32),
+        stops: Vec<(f32, Rgba<u8>)>,
+    },
+    /// An image scaled according to `fit` and composited over the target.
+    Image { image: RgbaImage, fit: Fit },
+    /// Blur whatever is already drawn, e.g. a wallpaper behind a panel,
+    /// with standard deviation `sigma` pixels. `quality` trades accuracy
+    /// for speed; [`BlurQuality::Fast`] keeps large blurs cheap enough to
+    /// redraw every frame.
+    BlurBehind { sigma: f32, quality: BlurQuality },
+}
+
+impl Background {
+    /// Paint the background into `rgba`, covering the whole image.
+    pub fn paint(&self, rgba: &mut RgbaImage) {
+        let (width, height) = rgba.dimensions();
+        if width == 0 || height == 0 {
+            return;
+        }
+        match self {
+            Background::None => {}
+            Background::Solid(color) => {
+                for p in rgba.pixels_mut() {
+                    *p = *color;
+                }
+            }
+            Background::LinearGradient { angle, stops } => {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                let (w, h) = (width as f32, height as f32);
+                // Gradient line long enough that the corners hit 0.0 and 1.0
+                let length = (w * sin).abs() + (h * cos).abs();
+                for (x, y, p) in rgba.enumerate_pixels_mut() {
+                    let dx = x as f32 + 0.5 - w / 2.0;
+                    let dy = y as f32 + 0.5 - h / 2.0;
+                    *p = sample_stops(stops, (dx * sin - dy * cos) / length + 0.5);
+                }
+            }
+            Background::RadialGradient { center, stops } => {
+                let (w, h) = (width as f32, height as f32);
+                let (cx, cy) = (center.0 * w, center.1 * h);
+                let radius = cx.max(w - cx).hypot(cy.max(h - cy)).max(f32::EPSILON);
+                for (x, y, p) in rgba.enumerate_pixels_mut() {
+                    let d = (x as f32 + 0.5 - cx).hypot(y as f32 + 0.5 - cy);
+                    *p = sample_stops(stops, d / radius);
+                }
+            }
+            Background::Image { image, fit } => paint_image(rgba, image, *fit),
+            Background::BlurBehind { sigma, quality } => {
+                if *sigma > 0.0 {
+                    *rgba = blur(rgba, *sigma, *quality);
+                }
+            }
+        }
+    }
+}
+
+/// Color at `t` along a list of `(position, color)` stops.
+///
+/// Stops are assumed sorted by position. Outside the first and last stop
+/// the end colors extend. An empty list is transparent.
+pub(crate) fn sample_stops(stops: &[(f32, Rgba<u8>)], t: f32) -> Rgba<u8> {
+    let (Some(first), Some(last)) = (stops.first(), stops.last()) else {
+        return Rgba([0, 0, 0, 0]);
+    };
+    if t <= first.0 {
+        return first.1;
+    }
+    if t >= last.0 {
+        return last.1;
+    }
+    for pair in stops.windows(2) {
+        let ((p0, c0), (p1, c1)) = (pair[0], pair[1]);
+        if t <= p1 {
+            let f = if p1 > p0 { (t - p0) / (p1 - p0) } else { 1.0 };
+            return Rgba(std::array::from_fn(|c| {
+                (c0[c] as f32 + (c1[c] as f32 - c0[c] as f32) * f).round() as u8
+            }));
+        }
+    }
+    last.1
+}
+
+/// Composite `image` over `rgba`, sized according to `fit`.
+pub(crate) fn paint_image(rgba: &mut RgbaImage, image: &RgbaImage, fit: Fit) {
+    let (width, height) = rgba.dimensions();
+    if image.width() == 0 || image.height() == 0 {
+        return;
+    }
+    let sx = width as f32 / image.width() as f32;
+    let sy = height as f32 / image.height() as f32;
+    let scale = match fit {
+        Fit::Cover => sx.max(sy),
+        Fit::Contain => sx.min(sy),
+        Fit::Center => 1.0,
+        Fit::Stretch => {
+            let scaled = imageops::resize(image, width, height, FilterType::Lanczos3);
+            imageops::overlay(rgba, &scaled, 0, 0);
+            return;
+        }
+    };
+    let w = ((image.width() as f32 * scale).round() as u32).max(1);
+    let h = ((image.height() as f32 * scale).round() as u32).max(1);
+    let scaled = if (w, h) == image.dimensions() {
+        image.clone()
+    } else {
+        imageops::resize(image, w, h, FilterType::Lanczos3)
+    };
+    let x = (width as i64 - w as i64) / 2;
+    let y = (height as i64 - h as i64) / 2;
+    imageops::overlay(rgba, &scaled, x, y);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+    #[test]
+    fn test_background_solid_and_none() {
+        let mut img = RgbaImage::new(4, 4);
+        Background::None.paint(&mut img);
+        assert_eq!(img.get_pixel(0, 0)[3], 0);
+        Background::Solid(WHITE).paint(&mut img);
+        assert!(img.pixels().all(|p| *p == WHITE));
+    }
+
+    #[test]
+    fn test_background_linear_gradient_direction() {
+        let mut img = RgbaImage::new(10, 10);
+        let bg = Background::LinearGradient {
+            angle: 90.0,
+            stops: vec![(0.0, BLACK), (1.0, WHITE)],
+        };
+        bg.paint(&mut img);
+        assert!(img.get_pixel(0, 5)[0] < 20);
+        assert!(img.get_pixel(9, 5)[0] > 235);
+        assert_eq!(img.get_pixel(3, 0), img.get_pixel(3, 9));
+
+        // 180 degrees runs top to bottom
+        let bg = Background::LinearGradient {
+            angle: 180.0,
+            stops: vec![(0.0, BLACK), (1.0, WHITE)],
+        };
+        bg.paint(&mut img);
+        assert!(img.get_pixel(5, 0)[0] < img.get_pixel(5, 9)[0]);
+    }
+
+    #[test]
+    fn test_background_radial_gradient() {
+        let mut img = RgbaImage::new(11, 11);
+        let bg = Background::RadialGradient {
+            center: (0.5, 0.5),
+            stops: vec![(0.0, WHITE), (1.0, BLACK)],
+        };
+        bg.paint(&mut img);
+        assert!(img.get_pixel(5, 5)[0] > 240);
+        assert!(img.get_pixel(0, 0)[0] < 40);
+    }
+
+    #[test]
+    fn test_background_image_fits() {
+        let src = RgbaImage::from_pixel(2, 1, WHITE);
+        let mut img = RgbaImage::from_pixel(8, 8, BLACK);
+        Background::Image {
+            image: src.clone(),
+            fit: Fit::Contain,
+        }
+        .paint(&mut img);
+        assert_eq!(*img.get_pixel(4, 4), WHITE);
+        assert_eq!(*img.get_pixel(4, 0), BLACK);
+
+        let mut img = RgbaImage::from_pixel(8, 8, BLACK);
+        Background::Image {
+            image: src,
+            fit: Fit::Cover,
+        }
+        .paint(&mut img);
+        assert!(img.pixels().all(|p| *p == WHITE));
+    }
+
+    #[test]
+    fn test_sample_stops() {
+        let stops = [(0.25, BLACK), (0.75, WHITE)];
+        assert_eq!(sample_stops(&stops, 0.0), BLACK);
+        assert_eq!(sample_stops(&stops, 0.5), Rgba([128, 128, 128, 255]));
+        assert_eq!(sample_stops(&stops, 1.0), WHITE);
+        assert_eq!(sample_stops(&[], 0.5), Rgba([0, 0, 0, 0]));
+    }
+}
@@ -0,0 +1,139 @@
+//! Gaussian blur with a selectable speed/quality trade-off.
+
+use image::RgbaImage;
+use image::imageops;
+
+/// How [`blur`] approximates a Gaussian.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlurQuality {
+    /// Three successive box blurs sized to match the Gaussian's variance.
+    ///
+    /// Cost is independent of `sigma`, a few additions per pixel, which
+    /// keeps large blurs interactive on Raspberry Pi-class hosts. Across
+    /// hard edges results can differ from the true Gaussian by up to about
+    /// 10% (26 of 255 levels), most at small `sigma`; smooth gradients come
+    /// out much closer.
+    Fast,
+    /// A true Gaussian kernel. Cost grows with `sigma`.
+    #[default]
+    High,
+}
+
+/// Blur all four channels of `src` with standard deviation `sigma` pixels.
+///
+/// Pixels beyond the edges repeat the nearest edge pixel. A `sigma` of zero
+/// or less returns an unchanged copy.
+pub fn blur(src: &RgbaImage, sigma: f32, quality: BlurQuality) -> RgbaImage {
+    if sigma <= 0.0 || src.width() == 0 || src.height() == 0 {
+        return src.clone();
+    }
+    match quality {
+        BlurQuality::High => imageops::blur(src, sigma),
+        BlurQuality::Fast => {
+            let (width, height) = (src.width() as usize, src.height() as usize);
+            let mut a = src.as_raw().clone();
+            let mut b = vec![0; a.len()];
+            for size in box_sizes(sigma) {
+                let radius = size / 2;
+                box_pass(&a, &mut b, width, height, 1, width, radius);
+                box_pass(&b, &mut a, height, width, width, 1, radius);
+            }
+            RgbaImage::from_raw(src.width(), src.height(), a)
+                .expect("buffer length matches dimensions")
+        }
+    }
+}
+
+/// Widths of three box filters whose combined variance approximates a
+/// Gaussian of `sigma` (after Kutskir, "Fastest Gaussian blur").
+fn box_sizes(sigma: f32) -> [usize; 3] {
+    const PASSES: f32 = 3.0;
+    let ideal = (12.0 * sigma * sigma / PASSES + 1.0).sqrt();
+    let mut lower = ideal.floor() as i32;
+    if lower % 2 == 0 {
+        lower -= 1;
+    }
+    let lower = lower.max(1);
+    let upper = lower + 2;
+    let (l, s) = (lower as f32, sigma);
+    let small_count = ((12.0 * s * s - PASSES * l * l - 4.0 * PASSES * l - 3.0 * PASSES)
+        / (-4.0 * l - 4.0))
+        .round() as usize;
+    std::array::from_fn(|i| if i < small_count { lower } else { upper } as usize)
+}
+
+/// One running-sum box blur along `lines` lines of `len` pixels each.
+///
+/// Pixel `i` of line `line` is at `line * line_step + i * step` (in pixels).
+fn box_pass(
+    src: &[u8],
+    dst: &mut [u8],
+    len: usize,
+    lines: usize,
+    step: usize,
+    line_step: usize,
+    radius: usize,
+) {
+    let window = (2 * radius + 1) as u32;
+    let last = len as isize - 1;
+    for line in 0..lines {
+        let offset = |i: isize| (line * line_step + i.clamp(0, last) as usize * step) * 4;
+        for c in 0..4 {
+            let value = |i: isize| src[offset(i) + c] as u32;
+            let r = radius as isize;
+            let mut sum: u32 = (-r..=r).map(value).sum();
+            for i in 0..len as isize {
+                dst[offset(i) + c] = ((sum + window / 2) / window) as u8;
+                sum = sum + value(i + r + 1) - value(i - r);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    #[test]
+    fn test_box_sizes_match_gaussian_variance() {
+        for sigma in [1.0f32, 2.5, 6.0, 15.0] {
+            let variance: f32 = box_sizes(sigma)
+                .iter()
+                .map(|&w| ((w * w) as f32 - 1.0) / 12.0)
+                .sum();
+            assert!((variance.sqrt() - sigma).abs() < 0.5, "sigma {sigma}");
+        }
+    }
+
+    #[test]
+    fn test_fast_blur_approximates_gaussian() {
+        let src = RgbaImage::from_fn(40, 40, |x, y| {
+            if (x / 10 + y / 10) % 2 == 0 {
+                Rgba([255, 200, 0, 255])
+            } else {
+                Rgba([0, 40, 255, 128])
+            }
+        });
+        for sigma in [1.0, 2.0, 3.0, 6.0, 10.0] {
+            let fast = blur(&src, sigma, BlurQuality::Fast);
+            let high = blur(&src, sigma, BlurQuality::High);
+            let max_diff = fast
+                .as_raw()
+                .iter()
+                .zip(high.as_raw())
+                .map(|(a, b)| a.abs_diff(*b))
+                .max()
+                .unwrap();
+            assert!(max_diff <= 26, "sigma {sigma}: max difference {max_diff}");
+        }
+    }
+
+    #[test]
+    fn test_blur_keeps_flat_images_and_zero_sigma() {
+        let flat = RgbaImage::from_pixel(7, 3, Rgba([10, 20, 30, 40]));
+        assert_eq!(blur(&flat, 4.0, BlurQuality::Fast), flat);
+        let src = RgbaImage::from_fn(5, 5, |x, _| Rgba([x as u8 * 50, 0, 0, 255]));
+        assert_eq!(blur(&src, 0.0, BlurQuality::Fast), src);
+    }
+}
@@ -0,0 +1,166 @@
+//! Memoized image scaling.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+
+use image::RgbImage;
+
+use super::scale_image;
+
+/// Cache key: hash of the source pixels plus the requested size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct CacheKey {
+    source: u64,
+    width: u32,
+    height: u32,
+}
+
+/// Memoizing wrapper around [`scale_image`] with LRU eviction.
+///
+/// Plugins typically rescale the same icon to the same tile size every
+/// frame; with this cache only the first frame pays for the resample.
+/// Sources are identified by a hash of their dimensions and pixel data, so
+/// callers don't need to track identity themselves.
+#[derive(Debug)]
+pub struct ScaledImageCache {
+    capacity: usize,
+    entries: HashMap<CacheKey, RgbImage>,
+    /// Keys from least to most recently used.
+    order: VecDeque<CacheKey>,
+    hits: u64,
+    misses: u64,
+}
+
+impl ScaledImageCache {
+    /// Create a cache holding at most `capacity` scaled images.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Return `src` scaled to the target size, scaling only on a cache miss.
+    pub fn get_or_scale(
+        &mut self,
+        src: &RgbImage,
+        target_width: u32,
+        target_height: u32,
+    ) -> &RgbImage {
+        let key = CacheKey {
+            source: hash_image(src),
+            width: target_width,
+            height: target_height,
+        };
+
+        if self.entries.contains_key(&key) {
+            self.hits += 1;
+            self.touch(key);
+        } else {
+            self.misses += 1;
+            if self.entries.len() >= self.capacity
+                && let Some(evicted) = self.order.pop_front()
+            {
+                self.entries.remove(&evicted);
+            }
+            let scaled = scale_image(src, target_width, target_height);
+            self.entries.insert(key, scaled);
+            self.order.push_back(key);
+        }
+
+        &self.entries[&key]
+    }
+
+    /// Number of cached images.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Number of lookups served from the cache and number that required scaling.
+    pub fn stats(&self) -> (u64, u64) {
+        (self.hits, self.misses)
+    }
+
+    /// Remove all cached images.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    /// Mark `key` as most recently used.
+    fn touch(&mut self, key: CacheKey) {
+        if let Some(pos) = self.order.iter().position(|k| *k == key) {
+            self.order.remove(pos);
+        }
+        self.order.push_back(key);
+    }
+}
+
+fn hash_image(img: &RgbImage) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    img.dimensions().hash(&mut hasher);
+    img.as_raw().hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    #[test]
+    fn test_cache_hit_after_first_scale() {
+        let mut cache = ScaledImageCache::new(4);
+        let img = RgbImage::from_pixel(10, 10, Rgb([1, 2, 3]));
+        assert_eq!(cache.get_or_scale(&img, 20, 20).dimensions(), (20, 20));
+        assert_eq!(cache.get_or_scale(&img, 20, 20).dimensions(), (20, 20));
+        assert_eq!(cache.stats(), (1, 1));
+    }
+
+    #[test]
+    fn test_cache_keys_on_content_and_size() {
+        let mut cache = ScaledImageCache::new(4);
+        let a = RgbImage::from_pixel(10, 10, Rgb([1, 2, 3]));
+        let b = RgbImage::from_pixel(10, 10, Rgb([4, 5, 6]));
+        cache.get_or_scale(&a, 20, 20);
+        cache.get_or_scale(&b, 20, 20);
+        cache.get_or_scale(&a, 30, 30);
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.stats(), (0, 3));
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let mut cache = ScaledImageCache::new(2);
+        let img = RgbImage::from_pixel(4, 4, Rgb([9, 9, 9]));
+        cache.get_or_scale(&img, 8, 8);
+        cache.get_or_scale(&img, 12, 12);
+        // Touch 8x8 so 12x12 becomes least recently used
+        cache.get_or_scale(&img, 8, 8);
+        cache.get_or_scale(&img, 16, 16);
+        assert_eq!(cache.len(), 2);
+
+        cache.get_or_scale(&img, 8, 8);
+        assert_eq!(cache.stats(), (2, 3));
+        cache.get_or_scale(&img, 12, 12);
+        assert_eq!(cache.stats(), (2, 4));
+    }
+
+    #[test]
+    fn test_cache_clear() {
+        let mut cache = ScaledImageCache::new(2);
+        let img = RgbImage::from_pixel(4, 4, Rgb([9, 9, 9]));
+        cache.get_or_scale(&img, 8, 8);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
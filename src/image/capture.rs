@@ -0,0 +1,99 @@
+//! Ingest of padded 32-bit frames from screen capture APIs.
+
+use image::{Rgba, RgbaImage};
+
+/// Byte order of a 4-byte-per-pixel capture buffer with an unused padding
+/// byte (`X`).
+///
+/// Variants name the bytes in memory order. Capture APIs often name formats
+/// by 32-bit word instead, which reverses the order on little-endian hosts:
+/// DRM/KMS `XRGB8888`, PipeWire `BGRx` and X11 24-bit `ZPixmap` images all
+/// arrive as [`CaptureFormat::Bgrx`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureFormat {
+    /// B G R X, e.g. DRM `XRGB8888`, PipeWire `BGRx`, X11 on little-endian.
+    Bgrx,
+    /// R G B X, e.g. DRM `XBGR8888`, PipeWire `RGBx`.
+    Rgbx,
+    /// X R G B, e.g. DRM `BGRX8888`, PipeWire `xRGB`.
+    Xrgb,
+    /// X B G R, e.g. DRM `RGBX8888`, PipeWire `xBGR`.
+    Xbgr,
+}
+
+impl CaptureFormat {
+    /// Offsets of the red, green and blue bytes within a pixel.
+    const fn offsets(self) -> [usize; 3] {
+        match self {
+            CaptureFormat::Bgrx => [2, 1, 0],
+            CaptureFormat::Rgbx => [0, 1, 2],
+            CaptureFormat::Xrgb => [1, 2, 3],
+            CaptureFormat::Xbgr => [3, 2, 1],
+        }
+    }
+}
+
+/// Convert a padded 4-byte-per-pixel capture buffer to an RgbaImage with
+/// full opacity.
+///
+/// `stride` is the number of bytes from the start of one row to the next,
+/// which capture APIs often round up beyond `width * 4`; a `stride` smaller
+/// than that is treated as `width * 4`. The padding byte is ignored, since
+/// capture sources leave it undefined. Pixels past the end of a short
+/// `data` are black, as with [`bytes_to_rgba`](super::bytes_to_rgba).
+///
+/// ```ignore
+/// // Mirror a PipeWire BGRx stream into a tile
+/// let frame = capture_to_rgba(w, h, chunk.stride, &chunk.data, CaptureFormat::Bgrx);
+/// ```
+pub fn capture_to_rgba(
+    width: u32,
+    height: u32,
+    stride: usize,
+    data: &[u8],
+    format: CaptureFormat,
+) -> RgbaImage {
+    let stride = stride.max(width as usize * 4);
+    let [r, g, b] = format.offsets();
+    RgbaImage::from_fn(width, height, |x, y| {
+        let idx = y as usize * stride + x as usize * 4;
+        match data.get(idx..idx + 4) {
+            Some(px) => Rgba([px[r], px[g], px[b], 255]),
+            None => Rgba([0, 0, 0, 255]),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_to_rgba_formats() {
+        let px = [10, 20, 30, 99];
+        let cases = [
+            (CaptureFormat::Bgrx, [30, 20, 10]),
+            (CaptureFormat::Rgbx, [10, 20, 30]),
+            (CaptureFormat::Xrgb, [20, 30, 99]),
+            (CaptureFormat::Xbgr, [99, 30, 20]),
+        ];
+        for (format, [r, g, b]) in cases {
+            let img = capture_to_rgba(1, 1, 4, &px, format);
+            assert_eq!(*img.get_pixel(0, 0), Rgba([r, g, b, 255]), "{format:?}");
+        }
+    }
+
+    #[test]
+    fn test_capture_to_rgba_stride_and_short_buffer() {
+        // 2x2 image with 4 bytes of row padding; second row is cut short
+        let data = [
+            0, 0, 255, 0, 0, 255, 0, 0, 7, 7, 7, 7, //
+            255, 0, 0, 0,
+        ];
+        let img = capture_to_rgba(2, 2, 12, &data, CaptureFormat::Bgrx);
+        assert_eq!(*img.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+        assert_eq!(*img.get_pixel(1, 0), Rgba([0, 255, 0, 255]));
+        assert_eq!(*img.get_pixel(0, 1), Rgba([0, 0, 255, 255]));
+        assert_eq!(*img.get_pixel(1, 1), Rgba([0, 0, 0, 255]));
+    }
+}
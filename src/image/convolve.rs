@@ -0,0 +1,259 @@
+//! Generic 2D convolution.
+
+use std::fmt;
+
+use image::{Rgba, RgbaImage};
+
+/// Why a kernel passed to [`convolve`] can't be applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KernelError {
+    /// The kernel has no weights, or its width is zero.
+    Empty,
+    /// The kernel's length is not a multiple of its width.
+    Ragged {
+        /// Number of weights given.
+        len: usize,
+        /// Columns per row.
+        width: usize,
+    },
+    /// The kernel has an even number of rows or columns, so it has no
+    /// center pixel to anchor on.
+    EvenSize {
+        /// Columns per row.
+        width: usize,
+        /// Number of rows.
+        height: usize,
+    },
+}
+
+impl fmt::Display for KernelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KernelError::Empty => write!(f, "kernel is empty"),
+            KernelError::Ragged { len, width } => {
+                write!(
+                    f,
+                    "kernel length {len} is not a multiple of its width {width}"
+                )
+            }
+            KernelError::EvenSize { width, height } => {
+                write!(f, "kernel is {width}x{height}; both sides must be odd")
+            }
+        }
+    }
+}
+
+impl std::error::Error for KernelError {}
+
+/// How pixels beyond the image edge are sampled during convolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EdgeMode {
+    /// Repeat the nearest edge pixel.
+    #[default]
+    Clamp,
+    /// Reflect across the edge (without repeating the edge pixel).
+    Mirror,
+    /// Wrap around to the opposite edge, for tileable textures.
+    Wrap,
+    /// Treat pixels outside the image as black.
+    Zero,
+}
+
+impl EdgeMode {
+    /// Map a possibly out-of-range coordinate into `0..len`, or `None` for `Zero`.
+    fn resolve(self, i: i64, len: u32) -> Option<u32> {
+        let n = len as i64;
+        if (0..n).contains(&i) {
+            return Some(i as u32);
+        }
+        match self {
+            EdgeMode::Clamp => Some(i.clamp(0, n - 1) as u32),
+            EdgeMode::Mirror => {
+                if n == 1 {
+                    return Some(0);
+                }
+                let period = 2 * (n - 1);
+                let m = i.rem_euclid(period);
+                Some(if m < n { m } else { period - m } as u32)
+            }
+            EdgeMode::Wrap => Some(i.rem_euclid(n) as u32),
+            EdgeMode::Zero => None,
+        }
+    }
+}
+
+/// Convolve an image with a custom kernel.
+///
+/// `kernel` is given in row-major order with `width` columns; its height is
+/// `kernel.len() / width`, and both must be odd. The kernel is anchored at
+/// its center and applied without flipping, so the weights line up with
+/// the pixels they multiply. Results are clamped to 0-255.
+///
+/// Color channels are convolved; alpha is preserved, so sharpen, edge and
+/// emboss kernels keep the image's shape.
+///
+/// Returns an error for an empty kernel, one whose length is not a
+/// multiple of `width`, or one with an even number of rows or columns.
+pub fn convolve(
+    src: &RgbaImage,
+    kernel: &[f32],
+    width: usize,
+    edges: EdgeMode,
+) -> Result<RgbaImage, KernelError> {
+    let mut out = RgbaImage::new(src.width(), src.height());
+    convolve_into(src, kernel, width, edges, &mut out)?;
+    Ok(out)
+}
+
+/// Convolve an image with a custom kernel, writing into `out`.
+///
+/// `out` is resized to match `src` if necessary. Pair with a
+/// [`FramePool`](super::FramePool) buffer to avoid per-frame allocation.
+/// See [`convolve`]; on error `out` is left untouched.
+pub fn convolve_into(
+    src: &RgbaImage,
+    kernel: &[f32],
+    width: usize,
+    edges: EdgeMode,
+    out: &mut RgbaImage,
+) -> Result<(), KernelError> {
+    if width == 0 || kernel.is_empty() {
+        return Err(KernelError::Empty);
+    }
+    if !kernel.len().is_multiple_of(width) {
+        return Err(KernelError::Ragged {
+            len: kernel.len(),
+            width,
+        });
+    }
+    let height = kernel.len() / width;
+    if width.is_multiple_of(2) || height.is_multiple_of(2) {
+        return Err(KernelError::EvenSize { width, height });
+    }
+    let ax = (width / 2) as i64;
+    let ay = (height / 2) as i64;
+    let (w, h) = src.dimensions();
+    if out.dimensions() != (w, h) {
+        *out = RgbaImage::new(w, h);
+    }
+
+    for (x, y, pixel) in out.enumerate_pixels_mut() {
+        let mut acc = [0.0f32; 3];
+        for (k, weight) in kernel.iter().enumerate() {
+            if *weight == 0.0 {
+                continue;
+            }
+            let kx = (k % width) as i64 - ax;
+            let ky = (k / width) as i64 - ay;
+            let (Some(sx), Some(sy)) = (
+                edges.resolve(x as i64 + kx, w),
+                edges.resolve(y as i64 + ky, h),
+            ) else {
+                continue;
+            };
+            let s = src.get_pixel(sx, sy);
+            for c in 0..3 {
+                acc[c] += s[c] as f32 * weight;
+            }
+        }
+        let clamp = |v: f32| v.round().clamp(0.0, 255.0) as u8;
+        *pixel = Rgba([
+            clamp(acc[0]),
+            clamp(acc[1]),
+            clamp(acc[2]),
+            src.get_pixel(x, y)[3],
+        ]);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient() -> RgbaImage {
+        RgbaImage::from_fn(4, 1, |x, _| {
+            let v = (30 + x * 60) as u8;
+            Rgba([v, v, v, 200])
+        })
+    }
+
+    #[test]
+    fn test_convolve_identity() {
+        let src = gradient();
+        let out = convolve(
+            &src,
+            &[0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0],
+            3,
+            EdgeMode::Clamp,
+        )
+        .unwrap();
+        assert_eq!(out, src);
+    }
+
+    #[test]
+    fn test_convolve_box_blur_preserves_flat_and_alpha() {
+        let src = RgbaImage::from_pixel(5, 5, Rgba([100, 50, 25, 77]));
+        let out = convolve(&src, &[1.0 / 9.0; 9], 3, EdgeMode::Clamp).unwrap();
+        assert_eq!(*out.get_pixel(0, 0), Rgba([100, 50, 25, 77]));
+    }
+
+    #[test]
+    fn test_convolve_edge_modes() {
+        let src = gradient();
+        // Shift right by one: each output pixel takes its left neighbour,
+        // so the first pixel samples just beyond the left edge
+        let kernel = [1.0, 0.0, 0.0];
+        let first = |edges| convolve(&src, &kernel, 3, edges).unwrap().get_pixel(0, 0)[0];
+        assert_eq!(first(EdgeMode::Clamp), 30);
+        assert_eq!(first(EdgeMode::Mirror), 90);
+        assert_eq!(first(EdgeMode::Wrap), 210);
+        assert_eq!(first(EdgeMode::Zero), 0);
+    }
+
+    #[test]
+    fn test_convolve_non_square_kernel() {
+        let src = gradient();
+        // A 3x1 kernel averaging each pixel's horizontal neighbours
+        let out = convolve(&src, &[0.5, 0.0, 0.5], 3, EdgeMode::Clamp).unwrap();
+        assert_eq!(out.get_pixel(1, 0)[0], 90);
+        assert_eq!(out.get_pixel(0, 0)[0], 60);
+    }
+
+    #[test]
+    fn test_convolve_rejects_bad_kernels() {
+        let src = gradient();
+        let bad = |kernel: &[f32], width| convolve(&src, kernel, width, EdgeMode::Clamp).err();
+        assert_eq!(bad(&[], 3), Some(KernelError::Empty));
+        assert_eq!(bad(&[1.0], 0), Some(KernelError::Empty));
+        assert_eq!(
+            bad(&[1.0, 2.0, 3.0], 2),
+            Some(KernelError::Ragged { len: 3, width: 2 })
+        );
+        assert_eq!(
+            bad(&[0.5, 0.5], 2),
+            Some(KernelError::EvenSize {
+                width: 2,
+                height: 1
+            })
+        );
+        assert_eq!(
+            bad(&[0.25; 6], 3),
+            Some(KernelError::EvenSize {
+                width: 3,
+                height: 2
+            })
+        );
+
+        let mut out = RgbaImage::new(1, 1);
+        assert!(convolve_into(&src, &[], 1, EdgeMode::Clamp, &mut out).is_err());
+        assert_eq!(out.dimensions(), (1, 1));
+    }
+
+    #[test]
+    fn test_edge_mode_resolve_mirror() {
+        assert_eq!(EdgeMode::Mirror.resolve(-1, 4), Some(1));
+        assert_eq!(EdgeMode::Mirror.resolve(4, 4), Some(2));
+        assert_eq!(EdgeMode::Mirror.resolve(-3, 1), Some(0));
+    }
+}
@@ -0,0 +1,92 @@
+//! Gradient-mapped duotone and tritone effects.
+//!
+//! Maps each pixel's luminance through a ramp between theme colors, so
+//! arbitrary artwork (album covers, avatars) can be recoloured to match a
+//! deck theme.
+
+use image::{Rgba, RgbaImage};
+
+use super::pixel_math::Factor;
+use super::to_greyscale;
+
+/// Recolor an image through a two-stop ramp from `shadow` to `highlight`.
+///
+/// Black maps to `shadow`, white to `highlight`. The ramp colors' alpha is
+/// ignored; the image's own alpha is preserved.
+pub fn duotone(rgba: &mut RgbaImage, shadow: Rgba<u8>, highlight: Rgba<u8>) {
+    let lut: [Rgba<u8>; 256] = std::array::from_fn(|level| {
+        let t = Factor::new(level as f32 / 255.0);
+        ramp(shadow, highlight, t)
+    });
+    apply_lut(rgba, &lut);
+}
+
+/// Recolor an image through a three-stop ramp: `shadow`, `midtone`, `highlight`.
+///
+/// Black maps to `shadow`, mid-grey to `midtone` and white to `highlight`.
+/// Alpha is preserved.
+pub fn tritone(rgba: &mut RgbaImage, shadow: Rgba<u8>, midtone: Rgba<u8>, highlight: Rgba<u8>) {
+    let lut: [Rgba<u8>; 256] = std::array::from_fn(|level| {
+        let t = level as f32 / 255.0;
+        if t < 0.5 {
+            ramp(shadow, midtone, Factor::new(t * 2.0))
+        } else {
+            ramp(midtone, highlight, Factor::new((t - 0.5) * 2.0))
+        }
+    });
+    apply_lut(rgba, &lut);
+}
+
+fn ramp(a: Rgba<u8>, b: Rgba<u8>, t: Factor) -> Rgba<u8> {
+    Rgba([
+        t.blend(a[0], b[0]),
+        t.blend(a[1], b[1]),
+        t.blend(a[2], b[2]),
+        255,
+    ])
+}
+
+fn apply_lut(rgba: &mut RgbaImage, lut: &[Rgba<u8>; 256]) {
+    for pixel in rgba.pixels_mut() {
+        let level = to_greyscale(pixel[0], pixel[1], pixel[2]);
+        let mapped = lut[level as usize];
+        *pixel = Rgba([mapped[0], mapped[1], mapped[2], pixel[3]]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAVY: Rgba<u8> = Rgba([0, 0, 128, 255]);
+    const GOLD: Rgba<u8> = Rgba([255, 200, 0, 255]);
+
+    fn close(a: Rgba<u8>, b: Rgba<u8>) -> bool {
+        a.0.iter()
+            .zip(b.0)
+            .all(|(x, y)| (*x as i32 - y as i32).abs() <= 2)
+    }
+
+    #[test]
+    fn test_duotone_maps_extremes() {
+        let mut img = RgbaImage::from_fn(2, 1, |x, _| {
+            if x == 0 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 77])
+            }
+        });
+        duotone(&mut img, NAVY, GOLD);
+        assert_eq!(*img.get_pixel(0, 0), NAVY);
+        assert!(close(*img.get_pixel(1, 0), Rgba([255, 200, 0, 77])));
+        assert_eq!(img.get_pixel(1, 0)[3], 77);
+    }
+
+    #[test]
+    fn test_tritone_midtone() {
+        let red = Rgba([255, 0, 0, 255]);
+        let mut img = RgbaImage::from_pixel(1, 1, Rgba([128, 128, 128, 255]));
+        tritone(&mut img, NAVY, red, GOLD);
+        assert!(close(*img.get_pixel(0, 0), red));
+    }
+}
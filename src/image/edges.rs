@@ -0,0 +1,123 @@
+//! Edge detection and emboss filters.
+//!
+//! Edges are computed from alpha-weighted luminance, so the silhouette of an
+//! icon on a transparent background produces edges as well as its interior
+//! detail.
+
+use image::{GrayImage, Luma, Rgba, RgbaImage};
+use imageproc::filter::filter3x3;
+use imageproc::gradients::sobel_gradients;
+
+use super::to_greyscale;
+
+/// Alpha-weighted luminance of each pixel.
+fn luma_over_alpha(src: &RgbaImage) -> GrayImage {
+    GrayImage::from_fn(src.width(), src.height(), |x, y| {
+        let p = src.get_pixel(x, y);
+        let luma = to_greyscale(p[0], p[1], p[2]) as u32;
+        Luma([(luma * p[3] as u32 / 255) as u8])
+    })
+}
+
+/// Sobel edge magnitude, scaled to 0-255.
+pub fn sobel_edges(src: &RgbaImage) -> GrayImage {
+    let gradients = sobel_gradients(&luma_over_alpha(src));
+    GrayImage::from_fn(src.width(), src.height(), |x, y| {
+        // Maximum Sobel magnitude for 8-bit input is ~1442; a quarter keeps
+        // ordinary edges visible while clamping only the hardest ones
+        Luma([(gradients.get_pixel(x, y)[0] / 4).min(255) as u8])
+    })
+}
+
+/// Extract edges as a tinted outline on a transparent background.
+///
+/// Pixels whose edge strength is at least `threshold` are drawn in `tint`,
+/// with opacity ramping up with edge strength above the threshold.
+pub fn outline(src: &RgbaImage, tint: Rgba<u8>, threshold: u8) -> RgbaImage {
+    let edges = sobel_edges(src);
+    let range = (255 - threshold as u32).max(1);
+    RgbaImage::from_fn(src.width(), src.height(), |x, y| {
+        let e = edges.get_pixel(x, y)[0];
+        if e < threshold {
+            return Rgba([0, 0, 0, 0]);
+        }
+        // Ramp opacity over the lower quarter of the range above threshold
+        let strength = ((e - threshold) as u32 * 4).min(range) * 255 / range;
+        let alpha = (strength.max(64) * tint[3] as u32 / 255) as u8;
+        Rgba([tint[0], tint[1], tint[2], alpha])
+    })
+}
+
+/// Emboss an image into a grey relief, lit from the top-left.
+///
+/// Flat areas become mid-grey; edges facing the light are brighter and
+/// those facing away darker. Alpha is preserved.
+pub fn emboss(src: &RgbaImage) -> RgbaImage {
+    #[rustfmt::skip]
+    const KERNEL: [f32; 9] = [
+        -1.0, -1.0, 0.0,
+        -1.0,  0.0, 1.0,
+         0.0,  1.0, 1.0,
+    ];
+    let relief = filter3x3::<_, f32, f32>(&luma_over_alpha(src), &KERNEL);
+    RgbaImage::from_fn(src.width(), src.height(), |x, y| {
+        let v = (128.0 + relief.get_pixel(x, y)[0]).clamp(0.0, 255.0) as u8;
+        Rgba([v, v, v, src.get_pixel(x, y)[3]])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Opaque white square on a transparent background.
+    fn square() -> RgbaImage {
+        RgbaImage::from_fn(16, 16, |x, y| {
+            if (4..12).contains(&x) && (4..12).contains(&y) {
+                Rgba([255, 255, 255, 255])
+            } else {
+                Rgba([0, 0, 0, 0])
+            }
+        })
+    }
+
+    #[test]
+    fn test_sobel_edges_flat_is_zero() {
+        let img = RgbaImage::from_pixel(8, 8, Rgba([90, 90, 90, 255]));
+        assert!(sobel_edges(&img).pixels().all(|p| p[0] == 0));
+    }
+
+    #[test]
+    fn test_sobel_edges_detects_silhouette() {
+        let edges = sobel_edges(&square());
+        assert!(edges.get_pixel(4, 8)[0] > 100);
+        assert_eq!(edges.get_pixel(8, 8)[0], 0);
+        assert_eq!(edges.get_pixel(0, 0)[0], 0);
+    }
+
+    #[test]
+    fn test_outline_tints_edges_only() {
+        let tint = Rgba([0, 200, 255, 255]);
+        let out = outline(&square(), tint, 32);
+        let edge = out.get_pixel(4, 8);
+        assert_eq!(&edge.0[..3], &tint.0[..3]);
+        assert!(edge[3] > 0);
+        assert_eq!(out.get_pixel(8, 8)[3], 0);
+        assert_eq!(out.get_pixel(0, 0)[3], 0);
+    }
+
+    #[test]
+    fn test_emboss_flat_is_mid_grey() {
+        let img = RgbaImage::from_pixel(8, 8, Rgba([200, 10, 10, 77]));
+        let out = emboss(&img);
+        assert_eq!(*out.get_pixel(4, 4), Rgba([128, 128, 128, 77]));
+    }
+
+    #[test]
+    fn test_emboss_lit_from_top_left() {
+        let out = emboss(&square());
+        // Top-left edge faces the light, bottom-right edge faces away
+        assert!(out.get_pixel(4, 4)[0] > 128);
+        assert!(out.get_pixel(11, 11)[0] < 128);
+    }
+}
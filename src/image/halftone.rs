@@ -0,0 +1,115 @@
+//! Halftone stylization.
+
+use image::{Rgba, RgbaImage};
+
+use super::to_greyscale;
+
+/// Convert an image to a newspaper-style halftone.
+///
+/// Luminance is sampled on a grid of `dot_size` pixel cells rotated by
+/// `angle` degrees, and each cell is drawn as a black dot whose area is
+/// proportional to the darkness at its center. The result is black ink on
+/// a white, opaque background, suitable for monochrome targets. Transparent
+/// source pixels count as paper.
+pub fn halftone(src: &RgbaImage, dot_size: f32, angle: f32) -> RgbaImage {
+    const INK: f32 = 0.0;
+    const PAPER: f32 = 255.0;
+
+    let (width, height) = src.dimensions();
+    let cell = dot_size.max(1.0);
+    let (sin, cos) = angle.to_radians().sin_cos();
+
+    // Darkness (0.0 to 1.0) at a source position, clamped to the image
+    let darkness = |x: f32, y: f32| {
+        if width == 0 || height == 0 {
+            return 0.0;
+        }
+        let sx = (x.round().max(0.0) as u32).min(width - 1);
+        let sy = (y.round().max(0.0) as u32).min(height - 1);
+        let p = src.get_pixel(sx, sy);
+        let luma = to_greyscale(p[0], p[1], p[2]) as f32 / 255.0;
+        (1.0 - luma) * p[3] as f32 / 255.0
+    };
+
+    RgbaImage::from_fn(width, height, |x, y| {
+        let px = x as f32 + 0.5;
+        let py = y as f32 + 0.5;
+        // Rotate into grid space
+        let u = (px * cos + py * sin) / cell;
+        let v = (-px * sin + py * cos) / cell;
+
+        // Dots may grow past their cell, so check the neighbouring cells too
+        let mut coverage: f32 = 0.0;
+        for dv in -1..=1 {
+            for du in -1..=1 {
+                let cu = u.floor() + 0.5 + du as f32;
+                let cv = v.floor() + 0.5 + dv as f32;
+                // Cell center back in image space
+                let cx = (cu * cos - cv * sin) * cell;
+                let cy = (cu * sin + cv * cos) * cell;
+                let d = darkness(cx - 0.5, cy - 0.5);
+                if d <= 0.0 {
+                    continue;
+                }
+                let radius = dot_radius(d, cell);
+                let dist = ((u - cu).powi(2) + (v - cv).powi(2)).sqrt() * cell;
+                coverage = coverage.max((radius - dist + 0.5).clamp(0.0, 1.0));
+            }
+        }
+        let level = (PAPER + (INK - PAPER) * coverage).round() as u8;
+        Rgba([level, level, level, 255])
+    })
+}
+
+/// Radius of a dot whose area matches `darkness` within a cell of size `cell`.
+///
+/// Once the dot touches the cell edges (darkness π/4) the radius grows
+/// linearly to cover the corners, padded by half a pixel so solid areas have
+/// no antialiased seams.
+fn dot_radius(darkness: f32, cell: f32) -> f32 {
+    use std::f32::consts::{FRAC_1_SQRT_2, FRAC_PI_4, PI};
+
+    if darkness <= FRAC_PI_4 {
+        cell * (darkness / PI).sqrt()
+    } else {
+        let t = (darkness - FRAC_PI_4) / (1.0 - FRAC_PI_4);
+        let full = cell * FRAC_1_SQRT_2 + 0.5;
+        cell * 0.5 + (full - cell * 0.5) * t
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_halftone_white_stays_white() {
+        let src = RgbaImage::from_pixel(16, 16, Rgba([255, 255, 255, 255]));
+        let out = halftone(&src, 4.0, 45.0);
+        assert!(out.pixels().all(|p| *p == Rgba([255, 255, 255, 255])));
+    }
+
+    #[test]
+    fn test_halftone_black_is_solid() {
+        let src = RgbaImage::from_pixel(16, 16, Rgba([0, 0, 0, 255]));
+        let out = halftone(&src, 4.0, 15.0);
+        assert!(out.pixels().all(|p| *p == Rgba([0, 0, 0, 255])));
+    }
+
+    #[test]
+    fn test_halftone_mid_grey_is_partial() {
+        let src = RgbaImage::from_pixel(32, 32, Rgba([128, 128, 128, 255]));
+        let out = halftone(&src, 6.0, 0.0);
+        let ink = out.pixels().filter(|p| p[0] < 128).count();
+        let total = (32 * 32) as f32;
+        let fraction = ink as f32 / total;
+        assert!(fraction > 0.3 && fraction < 0.7, "ink fraction {fraction}");
+    }
+
+    #[test]
+    fn test_halftone_transparent_is_paper() {
+        let src = RgbaImage::from_pixel(8, 8, Rgba([0, 0, 0, 0]));
+        let out = halftone(&src, 4.0, 0.0);
+        assert!(out.pixels().all(|p| *p == Rgba([255, 255, 255, 255])));
+    }
+}
@@ -0,0 +1,235 @@
+//! High dynamic range accumulation and tone mapping.
+//!
+//! Additive effects (glows, sparks, bloom) clip harshly when summed
+//! directly in 8-bit. [`HdrSurface`] accumulates in linear-light `f32` with
+//! headroom above 1.0, and [`HdrSurface::tone_map`] compresses the result
+//! back into displayable 8-bit.
+
+use image::{Rgba, RgbaImage};
+
+use crate::colors::{srgb_decode, srgb_encode};
+
+/// Operator used to bring HDR values back into 0.0-1.0.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ToneMap {
+    /// Hard clip at 1.0 (what direct 8-bit accumulation does).
+    Clip,
+    /// Reinhard `L / (1 + L)` on luminance. Compresses everything, so even
+    /// full-white input is dimmed; best for purely additive layers.
+    Reinhard,
+    /// Extended Reinhard with a white point: luminance at or above `white`
+    /// maps to 1.0 and values well below it are nearly unchanged.
+    ReinhardExtended { white: f32 },
+    /// Extended Reinhard using the brightest luminance on the surface as
+    /// the white point.
+    #[default]
+    Auto,
+}
+
+/// Linear-light RGBA accumulation buffer with headroom above 1.0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HdrSurface {
+    width: u32,
+    height: u32,
+    /// Linear RGB plus alpha, row-major.
+    data: Vec<[f32; 4]>,
+}
+
+impl HdrSurface {
+    /// Create a transparent black surface.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![[0.0; 4]; width as usize * height as usize],
+        }
+    }
+
+    /// Create a surface from an sRGB image, converting to linear light.
+    pub fn from_image(rgba: &RgbaImage) -> Self {
+        let lut: [f32; 256] = std::array::from_fn(|i| srgb_decode(i as f32 / 255.0));
+        Self {
+            width: rgba.width(),
+            height: rgba.height(),
+            data: rgba
+                .pixels()
+                .map(|p| {
+                    [
+                        lut[p[0] as usize],
+                        lut[p[1] as usize],
+                        lut[p[2] as usize],
+                        p[3] as f32 / 255.0,
+                    ]
+                })
+                .collect(),
+        }
+    }
+
+    /// Surface dimensions as `(width, height)`.
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Linear RGBA value at (x, y).
+    ///
+    /// # Panics
+    /// Panics if (x, y) is out of bounds.
+    pub fn get(&self, x: u32, y: u32) -> [f32; 4] {
+        self.data[self.index(x, y)]
+    }
+
+    /// Add linear light `rgb * intensity` at (x, y). Out-of-bounds writes are ignored.
+    ///
+    /// Alpha is raised to fully opaque where light is added.
+    pub fn add(&mut self, x: i32, y: i32, rgb: [f32; 3], intensity: f32) {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return;
+        }
+        let i = self.index(x as u32, y as u32);
+        let p = &mut self.data[i];
+        for c in 0..3 {
+            p[c] += rgb[c] * intensity;
+        }
+        p[3] = 1.0;
+    }
+
+    /// Add an sRGB image as a light layer, weighted by its alpha and `intensity`.
+    ///
+    /// The layer is positioned with its top-left corner at (x, y).
+    pub fn add_image(&mut self, layer: &RgbaImage, x: i32, y: i32, intensity: f32) {
+        let lut: [f32; 256] = std::array::from_fn(|i| srgb_decode(i as f32 / 255.0));
+        for (lx, ly, p) in layer.enumerate_pixels() {
+            if p[3] == 0 {
+                continue;
+            }
+            let a = p[3] as f32 / 255.0;
+            let rgb = [lut[p[0] as usize], lut[p[1] as usize], lut[p[2] as usize]];
+            self.add(x + lx as i32, y + ly as i32, rgb, intensity * a);
+        }
+    }
+
+    /// Add a soft radial glow centered at (cx, cy).
+    ///
+    /// Intensity falls off smoothly to zero at `radius`.
+    pub fn add_glow(&mut self, cx: f32, cy: f32, radius: f32, color: Rgba<u8>, intensity: f32) {
+        let radius = radius.max(0.5);
+        let rgb = [0, 1, 2].map(|c| srgb_decode(color[c] as f32 / 255.0));
+        let x0 = (cx - radius).floor() as i32;
+        let x1 = (cx + radius).ceil() as i32;
+        let y0 = (cy - radius).floor() as i32;
+        let y1 = (cy + radius).ceil() as i32;
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                let d = ((x as f32 + 0.5 - cx).powi(2) + (y as f32 + 0.5 - cy).powi(2)).sqrt();
+                let falloff = (1.0 - d / radius).max(0.0);
+                if falloff > 0.0 {
+                    self.add(x, y, rgb, intensity * falloff * falloff);
+                }
+            }
+        }
+    }
+
+    /// Brightest luminance on the surface.
+    pub fn max_luminance(&self) -> f32 {
+        self.data.iter().map(luminance).fold(0.0, f32::max)
+    }
+
+    /// Tone map to an 8-bit sRGB image.
+    pub fn tone_map(&self, op: ToneMap) -> RgbaImage {
+        let white = match op {
+            ToneMap::Auto => Some(self.max_luminance().max(1.0)),
+            ToneMap::ReinhardExtended { white } => Some(white.max(f32::EPSILON)),
+            _ => None,
+        };
+        let mut out = RgbaImage::new(self.width, self.height);
+        for (pixel, p) in out.pixels_mut().zip(&self.data) {
+            let l = luminance(p);
+            let scale = if l <= 0.0 {
+                1.0
+            } else {
+                let mapped = match (op, white) {
+                    (ToneMap::Clip, _) => l,
+                    (_, Some(w)) => l * (1.0 + l / (w * w)) / (1.0 + l),
+                    _ => l / (1.0 + l),
+                };
+                mapped / l
+            };
+            let enc = |v: f32| (srgb_encode((v * scale).clamp(0.0, 1.0)) * 255.0).round() as u8;
+            *pixel = Rgba([
+                enc(p[0]),
+                enc(p[1]),
+                enc(p[2]),
+                (p[3].clamp(0.0, 1.0) * 255.0).round() as u8,
+            ]);
+        }
+        out
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        assert!(x < self.width && y < self.height, "pixel out of bounds");
+        y as usize * self.width as usize + x as usize
+    }
+}
+
+/// Rec. 709 luminance of linear RGB.
+fn luminance(p: &[f32; 4]) -> f32 {
+    0.2126 * p[0] + 0.7152 * p[1] + 0.0722 * p[2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_with_clip() {
+        let img = RgbaImage::from_fn(4, 1, |x, _| Rgba([x as u8 * 60, 30, 200, 128]));
+        let out = HdrSurface::from_image(&img).tone_map(ToneMap::Clip);
+        assert_eq!(out, img);
+    }
+
+    #[test]
+    fn test_reinhard_compresses_overexposure() {
+        let mut s = HdrSurface::new(2, 1);
+        s.add(0, 0, [1.0, 1.0, 1.0], 1.0);
+        s.add(1, 0, [1.0, 1.0, 1.0], 4.0);
+        let clipped = s.tone_map(ToneMap::Clip);
+        // Clip loses the difference between the two
+        assert_eq!(clipped.get_pixel(0, 0), clipped.get_pixel(1, 0));
+
+        let mapped = s.tone_map(ToneMap::Auto);
+        assert!(mapped.get_pixel(0, 0)[0] < mapped.get_pixel(1, 0)[0]);
+        assert_eq!(mapped.get_pixel(1, 0)[0], 255);
+    }
+
+    #[test]
+    fn test_reinhard_extended_preserves_shadows() {
+        let mut s = HdrSurface::new(1, 1);
+        s.add(0, 0, [0.01, 0.01, 0.01], 1.0);
+        let basic = s.tone_map(ToneMap::Reinhard).get_pixel(0, 0)[0];
+        let ext = s
+            .tone_map(ToneMap::ReinhardExtended { white: 8.0 })
+            .get_pixel(0, 0)[0];
+        let clip = s.tone_map(ToneMap::Clip).get_pixel(0, 0)[0];
+        assert!((ext as i32 - clip as i32).abs() <= 1);
+        assert!(basic <= ext);
+    }
+
+    #[test]
+    fn test_add_glow_falls_off() {
+        let mut s = HdrSurface::new(21, 21);
+        s.add_glow(10.5, 10.5, 8.0, Rgba([255, 200, 100, 255]), 3.0);
+        assert!(s.get(10, 10)[0] > s.get(14, 10)[0]);
+        assert_eq!(s.get(0, 0), [0.0; 4]);
+    }
+
+    #[test]
+    fn test_add_image_weights_alpha_and_clips_bounds() {
+        let mut s = HdrSurface::new(2, 2);
+        let layer = RgbaImage::from_pixel(3, 3, Rgba([255, 255, 255, 0]));
+        s.add_image(&layer, -1, -1, 1.0);
+        assert_eq!(s.get(0, 0), [0.0; 4]);
+        let layer = RgbaImage::from_pixel(1, 1, Rgba([255, 255, 255, 255]));
+        s.add_image(&layer, 1, 1, 2.0);
+        assert_eq!(s.get(1, 1), [2.0, 2.0, 2.0, 1.0]);
+    }
+}
@@ -0,0 +1,171 @@
+//! Aspect-preserving scaling, with letterbox bars or cropping.
+
+use image::imageops::{self, FilterType};
+use image::{GenericImageView, Rgba, RgbaImage};
+
+/// What fills the bars left over when an image is letterboxed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Letterbox {
+    /// Leave the bars transparent.
+    #[default]
+    Transparent,
+    /// Fill the bars with a fixed color.
+    Solid(Rgba<u8>),
+    /// Fill each bar with the [`average_color`] of the image edge it
+    /// touches, so album art and photos blend into the tile instead of
+    /// sitting between black bands.
+    EdgeAverage,
+}
+
+/// Scale `src` to fit inside `width` x `height`, preserving its aspect
+/// ratio, centered, with the uncovered bars filled according to `bars`.
+pub fn scale_with_letterbox(
+    src: &RgbaImage,
+    width: u32,
+    height: u32,
+    bars: Letterbox,
+) -> RgbaImage {
+    let mut frame = RgbaImage::new(width, height);
+    if src.width() == 0 || src.height() == 0 || width == 0 || height == 0 {
+        return frame;
+    }
+    let scale = (width as f32 / src.width() as f32).min(height as f32 / src.height() as f32);
+    let w = ((src.width() as f32 * scale).round() as u32).clamp(1, width);
+    let h = ((src.height() as f32 * scale).round() as u32).clamp(1, height);
+    let scaled = resize(src, w, h);
+    let (x, y) = ((width - w) / 2, (height - h) / 2);
+
+    let (first, second) = match bars {
+        Letterbox::Transparent => (Rgba([0, 0, 0, 0]), Rgba([0, 0, 0, 0])),
+        Letterbox::Solid(color) => (color, color),
+        Letterbox::EdgeAverage if w < width => {
+            let (sw, sh) = src.dimensions();
+            (
+                average_color(&*src.view(0, 0, 1, sh)),
+                average_color(&*src.view(sw - 1, 0, 1, sh)),
+            )
+        }
+        Letterbox::EdgeAverage => {
+            let (sw, sh) = src.dimensions();
+            (
+                average_color(&*src.view(0, 0, sw, 1)),
+                average_color(&*src.view(0, sh - 1, sw, 1)),
+            )
+        }
+    };
+    // Bars run left/right when the image is narrower than the frame,
+    // top/bottom otherwise
+    for (px, py, pixel) in frame.enumerate_pixels_mut() {
+        let before = if w < width { px < x } else { py < y };
+        *pixel = if before { first } else { second };
+    }
+    imageops::replace(&mut frame, &scaled, x as i64, y as i64);
+    frame
+}
+
+/// Scale `src` to cover `width` x `height`, preserving its aspect ratio,
+/// and crop the overflow evenly from both sides. An empty `src` gives a
+/// transparent frame.
+pub fn scale_to_cover(src: &RgbaImage, width: u32, height: u32) -> RgbaImage {
+    if src.width() == 0 || src.height() == 0 || width == 0 || height == 0 {
+        return RgbaImage::new(width, height);
+    }
+    let scale = (width as f32 / src.width() as f32).max(height as f32 / src.height() as f32);
+    let w = ((src.width() as f32 * scale).round() as u32).max(width);
+    let h = ((src.height() as f32 * scale).round() as u32).max(height);
+    let scaled = resize(src, w, h);
+    let (x, y) = ((w - width) / 2, (h - height) / 2);
+    imageops::crop_imm(&scaled, x, y, width, height).to_image()
+}
+
+/// Resize `src` to exactly `width` x `height`, copying it if it is already
+/// that size.
+fn resize(src: &RgbaImage, width: u32, height: u32) -> RgbaImage {
+    if src.dimensions() == (width, height) {
+        return src.clone();
+    }
+    imageops::resize(src, width, height, FilterType::Lanczos3)
+}
+
+/// Mean color of `img`, weighting each pixel by its alpha.
+///
+/// The result's alpha is the mean alpha. Fully transparent (or empty)
+/// images give transparent black.
+pub fn average_color<I>(img: &I) -> Rgba<u8>
+where
+    I: GenericImageView<Pixel = Rgba<u8>>,
+{
+    let mut sums = [0u64; 4];
+    for (_, _, Rgba([r, g, b, a])) in img.pixels() {
+        let a = a as u64;
+        sums[0] += r as u64 * a;
+        sums[1] += g as u64 * a;
+        sums[2] += b as u64 * a;
+        sums[3] += a;
+    }
+    let count = img.width() as u64 * img.height() as u64;
+    if sums[3] == 0 {
+        return Rgba([0, 0, 0, 0]);
+    }
+    let channel = |sum: u64| ((sum + sums[3] / 2) / sums[3]) as u8;
+    Rgba([
+        channel(sums[0]),
+        channel(sums[1]),
+        channel(sums[2]),
+        ((sums[3] + count / 2) / count) as u8,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+
+    #[test]
+    fn test_average_color_weights_by_alpha() {
+        let img = RgbaImage::from_fn(2, 1, |x, _| if x == 0 { RED } else { Rgba([0, 255, 0, 0]) });
+        assert_eq!(average_color(&img), Rgba([255, 0, 0, 128]));
+        assert_eq!(average_color(&RgbaImage::new(0, 0)), Rgba([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_letterbox_bars() {
+        // Wide image: red top edge, blue bottom edge
+        let img = RgbaImage::from_fn(40, 20, |_, y| if y < 10 { RED } else { BLUE });
+        let out = scale_with_letterbox(&img, 20, 20, Letterbox::Transparent);
+        assert_eq!(out.get_pixel(10, 0)[3], 0);
+
+        let grey = Rgba([50, 50, 50, 255]);
+        let out = scale_with_letterbox(&img, 20, 20, Letterbox::Solid(grey));
+        assert_eq!(*out.get_pixel(10, 19), grey);
+
+        let out = scale_with_letterbox(&img, 20, 20, Letterbox::EdgeAverage);
+        assert_eq!(*out.get_pixel(10, 0), RED);
+        assert_eq!(*out.get_pixel(10, 19), BLUE);
+    }
+
+    #[test]
+    fn test_scale_to_cover_crops_overflow() {
+        // Wide image: red left half, blue right half
+        let img = RgbaImage::from_fn(40, 20, |x, _| if x < 20 { RED } else { BLUE });
+        let out = scale_to_cover(&img, 10, 10);
+        assert_eq!(out.dimensions(), (10, 10));
+        assert!(out.pixels().all(|p| p[3] == 255));
+        assert_eq!(*out.get_pixel(0, 5), RED);
+        assert_eq!(*out.get_pixel(9, 5), BLUE);
+        assert_eq!(
+            scale_to_cover(&RgbaImage::new(0, 0), 4, 4),
+            RgbaImage::new(4, 4)
+        );
+    }
+
+    #[test]
+    fn test_letterbox_tall_image_uses_side_edges() {
+        let img = RgbaImage::from_fn(10, 40, |x, _| if x < 5 { RED } else { BLUE });
+        let out = scale_with_letterbox(&img, 20, 20, Letterbox::EdgeAverage);
+        assert_eq!(*out.get_pixel(0, 10), RED);
+        assert_eq!(*out.get_pixel(19, 10), BLUE);
+    }
+}
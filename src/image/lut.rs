@@ -0,0 +1,243 @@
+//! 3D color lookup tables for color grading.
+
+use std::fmt;
+#[cfg(feature = "cube-lut")]
+use std::path::Path;
+
+use image::RgbaImage;
+
+/// Error building or loading a [`Lut3d`].
+#[derive(Debug)]
+pub enum LutError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The table is not valid.
+    Parse(String),
+}
+
+impl fmt::Display for LutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LutError::Io(e) => write!(f, "LUT I/O error: {e}"),
+            LutError::Parse(e) => write!(f, "invalid LUT: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for LutError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LutError::Io(e) => Some(e),
+            LutError::Parse(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for LutError {
+    fn from(e: std::io::Error) -> Self {
+        LutError::Io(e)
+    }
+}
+
+/// A 3D color lookup table, as exported by grading tools.
+///
+/// Maps every input RGB color to an output color through a `size` x `size`
+/// x `size` lattice, interpolating trilinearly between lattice points, so
+/// the same grade used on photos or video elsewhere can be applied to
+/// widget imagery with [`apply_lut`]. With the `cube-lut` feature, tables
+/// load from Adobe/Resolve `.cube` files:
+///
+/// ```ignore
+/// let grade = Lut3d::load("teal-orange.cube")?;
+/// apply_lut(&mut album_art, &grade);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lut3d {
+    size: usize,
+    /// Output colors with red varying fastest, then green, then blue.
+    table: Vec<[f32; 3]>,
+    domain_min: [f32; 3],
+    domain_max: [f32; 3],
+}
+
+impl Lut3d {
+    /// Create a table from `size`³ output colors (0.0 to 1.0) with red
+    /// varying fastest, then green, then blue, over the input domain 0.0
+    /// to 1.0.
+    ///
+    /// Fails if `size` is less than 2 or `table` has the wrong length.
+    pub fn new(size: usize, table: Vec<[f32; 3]>) -> Result<Self, LutError> {
+        if size < 2 {
+            return Err(LutError::Parse(format!("size {size} is less than 2")));
+        }
+        if size.checked_pow(3) != Some(table.len()) {
+            return Err(LutError::Parse(format!(
+                "expected {size}^3 entries, found {}",
+                table.len()
+            )));
+        }
+        Ok(Self {
+            size,
+            table,
+            domain_min: [0.0; 3],
+            domain_max: [1.0; 3],
+        })
+    }
+
+    /// A table that leaves colors unchanged.
+    pub fn identity(size: usize) -> Self {
+        let size = size.max(2);
+        let step = 1.0 / (size - 1) as f32;
+        let table = (0..size.pow(3))
+            .map(|i| [i % size, i / size % size, i / (size * size)].map(|v| v as f32 * step))
+            .collect();
+        Self {
+            size,
+            table,
+            domain_min: [0.0; 3],
+            domain_max: [1.0; 3],
+        }
+    }
+
+    /// Parse a `.cube` file's contents.
+    ///
+    /// Supports `LUT_3D_SIZE`, `DOMAIN_MIN`, `DOMAIN_MAX`, `TITLE` and `#`
+    /// comments. 1D tables (`LUT_1D_SIZE`) are rejected.
+    #[cfg(feature = "cube-lut")]
+    pub fn from_cube(text: &str) -> Result<Self, LutError> {
+        let parse_err = |line: usize, msg: &str| LutError::Parse(format!("line {line}: {msg}"));
+        let mut size = None;
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+        let mut table = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let n = i + 1;
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let keyword = words.next().unwrap_or("");
+            let triple = |words: std::str::SplitWhitespace<'_>| -> Result<[f32; 3], LutError> {
+                let values: Vec<f32> = words
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| parse_err(n, "invalid number"))?;
+                values
+                    .try_into()
+                    .map_err(|_| parse_err(n, "expected three values"))
+            };
+            match keyword {
+                "TITLE" => {}
+                "LUT_1D_SIZE" => return Err(parse_err(n, "1D LUTs are not supported")),
+                "LUT_3D_SIZE" => {
+                    let value = words.next().and_then(|w| w.parse().ok());
+                    size = Some(value.ok_or_else(|| parse_err(n, "invalid LUT_3D_SIZE"))?);
+                }
+                "DOMAIN_MIN" => domain_min = triple(words)?,
+                "DOMAIN_MAX" => domain_max = triple(words)?,
+                _ if keyword.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                    tracing::debug!(keyword, "Ignoring unknown .cube keyword");
+                }
+                _ => table.push(triple(line.split_whitespace())?),
+            }
+        }
+        let size = size.ok_or_else(|| LutError::Parse("missing LUT_3D_SIZE".into()))?;
+        if (0..3).any(|c| domain_max[c] <= domain_min[c]) {
+            return Err(LutError::Parse("empty domain".into()));
+        }
+        Ok(Self {
+            domain_min,
+            domain_max,
+            ..Self::new(size, table)?
+        })
+    }
+
+    /// Load a `.cube` file.
+    #[cfg(feature = "cube-lut")]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LutError> {
+        Self::from_cube(&std::fs::read_to_string(path)?)
+    }
+
+    /// Number of lattice points along each axis.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Look up an RGB color (0.0 to 1.0), interpolating trilinearly.
+    pub fn sample(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let max = (self.size - 1) as f32;
+        let pos: [f32; 3] = std::array::from_fn(|c| {
+            let t = (rgb[c] - self.domain_min[c]) / (self.domain_max[c] - self.domain_min[c]);
+            t.clamp(0.0, 1.0) * max
+        });
+        let lo = pos.map(|p| (p.floor() as usize).min(self.size - 2));
+        let frac: [f32; 3] = std::array::from_fn(|c| pos[c] - lo[c] as f32);
+        let at = |r: usize, g: usize, b: usize| {
+            self.table[(lo[0] + r) + (lo[1] + g) * self.size + (lo[2] + b) * self.size * self.size]
+        };
+        let lerp =
+            |a: [f32; 3], b: [f32; 3], t: f32| std::array::from_fn(|c| a[c] + (b[c] - a[c]) * t);
+        let c00 = lerp(at(0, 0, 0), at(1, 0, 0), frac[0]);
+        let c10 = lerp(at(0, 1, 0), at(1, 1, 0), frac[0]);
+        let c01 = lerp(at(0, 0, 1), at(1, 0, 1), frac[0]);
+        let c11 = lerp(at(0, 1, 1), at(1, 1, 1), frac[0]);
+        let c0 = lerp(c00, c10, frac[1]);
+        let c1 = lerp(c01, c11, frac[1]);
+        lerp(c0, c1, frac[2])
+    }
+}
+
+/// Grade an image through a 3D LUT. Alpha is preserved.
+pub fn apply_lut(rgba: &mut RgbaImage, lut: &Lut3d) {
+    for pixel in rgba.pixels_mut() {
+        let rgb = [0, 1, 2].map(|c| pixel[c] as f32 / 255.0);
+        let out = lut.sample(rgb);
+        for c in 0..3 {
+            pixel[c] = (out[c].clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    #[test]
+    fn test_identity_lut_leaves_image_unchanged() {
+        let src = RgbaImage::from_fn(16, 16, |x, y| Rgba([x as u8 * 16, y as u8 * 16, 200, 99]));
+        let mut img = src.clone();
+        apply_lut(&mut img, &Lut3d::identity(17));
+        assert_eq!(img, src);
+    }
+
+    #[test]
+    fn test_lut_interpolates_between_points() {
+        // 2-point LUT that inverts every channel
+        let table = (0..8)
+            .map(|i| [i & 1, (i >> 1) & 1, i >> 2].map(|v| 1.0 - v as f32))
+            .collect();
+        let lut = Lut3d::new(2, table).unwrap();
+        let mut img = RgbaImage::from_pixel(1, 1, Rgba([0, 64, 255, 10]));
+        apply_lut(&mut img, &lut);
+        assert_eq!(*img.get_pixel(0, 0), Rgba([255, 191, 0, 10]));
+        assert!(Lut3d::new(2, vec![[0.0; 3]; 7]).is_err());
+    }
+
+    #[cfg(feature = "cube-lut")]
+    #[test]
+    fn test_from_cube() {
+        let cube = "# warm\nTITLE \"Warm\"\nLUT_3D_SIZE 2\nDOMAIN_MIN 0 0 0\nDOMAIN_MAX 1 1 1\n\
+                    0 0 0\n1 0 0\n0 1 0\n1 1 0\n0 0 0.5\n1 0 0.5\n0 1 0.5\n1 1 0.5\n";
+        let lut = Lut3d::from_cube(cube).unwrap();
+        assert_eq!(lut.size(), 2);
+        assert_eq!(lut.sample([1.0, 1.0, 1.0]), [1.0, 1.0, 0.5]);
+
+        let short = Lut3d::from_cube("LUT_3D_SIZE 2\n0 0 0\n");
+        assert!(matches!(short, Err(LutError::Parse(_))));
+        let bad = Lut3d::from_cube("LUT_3D_SIZE 2\n0 0 x\n").unwrap_err();
+        assert_eq!(bad.to_string(), "invalid LUT: line 2: invalid number");
+        assert!(Lut3d::from_cube("LUT_1D_SIZE 4\n").is_err());
+    }
+}
@@ -0,0 +1,186 @@
+//! Seeded procedural noise.
+//!
+//! Provides white, value and Perlin noise for texture backgrounds,
+//! static/"no signal" effects and dithering masks. All noise is
+//! deterministic for a given seed, so frames are stable across renders.
+
+use image::{GrayImage, Luma, Rgba, RgbaImage};
+
+/// Noise algorithm and its parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Noise {
+    /// Independent random value per pixel (TV static).
+    White,
+    /// Smoothly interpolated random values on a lattice of `cell` pixels.
+    Value { cell: f32 },
+    /// Gradient noise with lattice spacing `cell` pixels, summed over
+    /// `octaves` (each octave doubles frequency and halves amplitude).
+    Perlin { cell: f32, octaves: u32 },
+}
+
+impl Noise {
+    /// Sample the noise at pixel (x, y), returning a value from 0.0 to 1.0.
+    pub fn sample(&self, x: u32, y: u32, seed: u64) -> f32 {
+        match *self {
+            Noise::White => white(x as i64, y as i64, seed),
+            Noise::Value { cell } => {
+                let cell = cell.max(1.0);
+                value(x as f32 / cell, y as f32 / cell, seed)
+            }
+            Noise::Perlin { cell, octaves } => {
+                let cell = cell.max(1.0);
+                let mut sum = 0.0;
+                let mut amplitude = 1.0;
+                let mut frequency = 1.0 / cell;
+                let mut total = 0.0;
+                for octave in 0..octaves.max(1) {
+                    let s = seed.wrapping_add(octave as u64);
+                    sum += perlin(x as f32 * frequency, y as f32 * frequency, s) * amplitude;
+                    total += amplitude;
+                    amplitude *= 0.5;
+                    frequency *= 2.0;
+                }
+                sum / total
+            }
+        }
+    }
+}
+
+/// Fill an image with noise, mapping 0.0 to `low` and 1.0 to `high`.
+pub fn fill(rgba: &mut RgbaImage, noise: Noise, seed: u64, low: Rgba<u8>, high: Rgba<u8>) {
+    for (x, y, pixel) in rgba.enumerate_pixels_mut() {
+        let t = noise.sample(x, y, seed);
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        *pixel = Rgba([
+            mix(low[0], high[0]),
+            mix(low[1], high[1]),
+            mix(low[2], high[2]),
+            mix(low[3], high[3]),
+        ]);
+    }
+}
+
+/// Generate a greyscale noise mask, e.g. for dithering or masked effects.
+pub fn mask(width: u32, height: u32, noise: Noise, seed: u64) -> GrayImage {
+    GrayImage::from_fn(width, height, |x, y| {
+        Luma([(noise.sample(x, y, seed) * 255.0).round() as u8])
+    })
+}
+
+/// Hash lattice coordinates and seed to a 64-bit value (SplitMix64 finalizer).
+fn hash(x: i64, y: i64, seed: u64) -> u64 {
+    let mut z = seed
+        ^ (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// White noise value at integer coordinates, from 0.0 to 1.0.
+pub fn white(x: i64, y: i64, seed: u64) -> f32 {
+    (hash(x, y, seed) >> 40) as f32 / (1u64 << 24) as f32
+}
+
+/// Quintic smoothstep used to ease lattice interpolation.
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Value noise at continuous coordinates (lattice units), from 0.0 to 1.0.
+pub fn value(x: f32, y: f32, seed: u64) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (tx, ty) = (fade(x - x0), fade(y - y0));
+    let (ix, iy) = (x0 as i64, y0 as i64);
+    let top = lerp(white(ix, iy, seed), white(ix + 1, iy, seed), tx);
+    let bottom = lerp(white(ix, iy + 1, seed), white(ix + 1, iy + 1, seed), tx);
+    lerp(top, bottom, ty)
+}
+
+/// Perlin gradient noise at continuous coordinates (lattice units), from 0.0 to 1.0.
+pub fn perlin(x: f32, y: f32, seed: u64) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (ix, iy) = (x0 as i64, y0 as i64);
+
+    // Dot product of a pseudo-random unit gradient with the offset vector
+    let grad = |gx: i64, gy: i64, dx: f32, dy: f32| {
+        let angle = white(gx, gy, seed) * std::f32::consts::TAU;
+        angle.cos() * dx + angle.sin() * dy
+    };
+
+    let (u, v) = (fade(fx), fade(fy));
+    let top = lerp(grad(ix, iy, fx, fy), grad(ix + 1, iy, fx - 1.0, fy), u);
+    let bottom = lerp(
+        grad(ix, iy + 1, fx, fy - 1.0),
+        grad(ix + 1, iy + 1, fx - 1.0, fy - 1.0),
+        u,
+    );
+    // 2D Perlin output lies within ±√½; rescale to 0..1
+    (lerp(top, bottom, v) * std::f32::consts::FRAC_1_SQRT_2 + 0.5).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_white_is_deterministic_and_seeded() {
+        assert_eq!(white(3, 4, 1), white(3, 4, 1));
+        assert_ne!(white(3, 4, 1), white(3, 4, 2));
+    }
+
+    #[test]
+    fn test_noise_in_range() {
+        for noise in [
+            Noise::White,
+            Noise::Value { cell: 8.0 },
+            Noise::Perlin {
+                cell: 8.0,
+                octaves: 3,
+            },
+        ] {
+            for y in 0..32 {
+                for x in 0..32 {
+                    let v = noise.sample(x, y, 42);
+                    assert!((0.0..=1.0).contains(&v), "{noise:?} out of range: {v}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_value_noise_matches_lattice() {
+        // At lattice points value noise equals the white noise value
+        assert_eq!(value(2.0, 5.0, 7), white(2, 5, 7));
+    }
+
+    #[test]
+    fn test_perlin_zero_at_lattice() {
+        // Gradient noise is zero (0.5 after rescaling) at lattice points
+        assert!((perlin(3.0, 9.0, 11) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_fill_maps_colors() {
+        let mut img = RgbaImage::new(8, 8);
+        fill(
+            &mut img,
+            Noise::White,
+            1,
+            Rgba([0, 0, 0, 255]),
+            Rgba([0, 0, 0, 255]),
+        );
+        assert!(img.pixels().all(|p| *p == Rgba([0, 0, 0, 255])));
+    }
+
+    #[test]
+    fn test_mask_dimensions() {
+        let m = mask(5, 3, Noise::Value { cell: 2.0 }, 0);
+        assert_eq!(m.dimensions(), (5, 3));
+    }
+}
@@ -0,0 +1,246 @@
+//! Per-channel pixel arithmetic in floating-point and fixed-point forms.
+//!
+//! Effects in this crate route their per-pixel math through here. By default
+//! the `f32` forms are used; enabling the `fixed-point` feature switches to
+//! integer Q8 arithmetic, which is considerably faster on hosts with weak
+//! floating-point units (e.g. Raspberry Pi Zero). Both forms are always
+//! available so they can be compared directly (see `benches/pixel_math.rs`).
+//!
+//! The `_linear` forms scale and blend in linear light rather than on sRGB
+//! bytes. Both convert through lookup tables, so neither evaluates the sRGB
+//! transfer curve per pixel; the fixed-point form keeps linear light in Q12
+//! (4096 = 1.0).
+
+use std::sync::LazyLock;
+
+use crate::colors::{srgb_encode, srgb_to_linear};
+
+/// Fixed-point linear light for 1.0.
+const LINEAR_ONE: u32 = 4096;
+
+/// sRGB byte to Q12 linear light.
+static DECODE_Q12: LazyLock<[u16; 256]> = LazyLock::new(|| {
+    std::array::from_fn(|c| (srgb_to_linear(c as u8) * LINEAR_ONE as f32).round() as u16)
+});
+
+/// Q12 linear light to sRGB byte. Every byte survives a round trip through
+/// [`DECODE_Q12`].
+static ENCODE_Q12: LazyLock<[u8; LINEAR_ONE as usize + 1]> = LazyLock::new(|| {
+    std::array::from_fn(|q| (srgb_encode(q as f32 / LINEAR_ONE as f32) * 255.0).round() as u8)
+});
+
+/// Encode linear light (clamped to 0.0 to 1.0) through the Q12 table.
+#[inline]
+fn encode_f32(v: f32) -> u8 {
+    ENCODE_Q12[(v.clamp(0.0, 1.0) * LINEAR_ONE as f32).round() as usize]
+}
+
+/// Whether effects use the fixed-point implementations.
+pub const FIXED_POINT: bool = cfg!(feature = "fixed-point");
+
+/// Convert a factor to Q8 fixed point (256 = 1.0), rounding to nearest.
+#[inline]
+pub fn to_q8(factor: f32) -> u32 {
+    (factor.max(0.0) * 256.0).round() as u32
+}
+
+/// Greyscale via the luminosity method, in floating point.
+#[inline]
+pub fn greyscale_f32(r: u8, g: u8, b: u8) -> u8 {
+    ((0.299 * r as f32) + (0.587 * g as f32) + (0.114 * b as f32)) as u8
+}
+
+/// Greyscale via the luminosity method, in Q8 fixed point.
+///
+/// Coefficients 77/256, 150/256 and 29/256 sum to exactly 1.0, so white
+/// maps to 255.
+#[inline]
+pub fn greyscale_fixed(r: u8, g: u8, b: u8) -> u8 {
+    ((77 * r as u32 + 150 * g as u32 + 29 * b as u32) >> 8) as u8
+}
+
+/// Scale a channel by `factor`, in floating point. Saturates at 255.
+#[inline]
+pub fn scale_f32(c: u8, factor: f32) -> u8 {
+    (c as f32 * factor) as u8
+}
+
+/// Scale a channel by a Q8 factor (see [`to_q8`]). Saturates at 255.
+#[inline]
+pub fn scale_fixed(c: u8, q8: u32) -> u8 {
+    ((c as u32 * q8) >> 8).min(255) as u8
+}
+
+/// Blend from `a` to `b` by `t` (0.0 to 1.0), in floating point.
+#[inline]
+pub fn blend_f32(a: u8, b: u8, t: f32) -> u8 {
+    (a as f32 + (b as f32 - a as f32) * t).round() as u8
+}
+
+/// Blend from `a` to `b` by a Q8 weight (0 to 256), in fixed point.
+#[inline]
+pub fn blend_fixed(a: u8, b: u8, q8: u32) -> u8 {
+    let w = q8.min(256);
+    ((a as u32 * (256 - w) + b as u32 * w + 128) >> 8) as u8
+}
+
+/// Scale the light of a channel by `factor`, in floating point. Saturates at
+/// 255.
+#[inline]
+pub fn scale_linear_f32(c: u8, factor: f32) -> u8 {
+    encode_f32(srgb_to_linear(c) * factor)
+}
+
+/// Scale the light of a channel by a Q8 factor (see [`to_q8`]), in fixed
+/// point. Saturates at 255.
+#[inline]
+pub fn scale_linear_fixed(c: u8, q8: u32) -> u8 {
+    let v = (DECODE_Q12[c as usize] as u32 * q8) >> 8;
+    ENCODE_Q12[v.min(LINEAR_ONE) as usize]
+}
+
+/// Blend from `a` to `b` by `t` (0.0 to 1.0) in linear light, in floating
+/// point.
+#[inline]
+pub fn blend_linear_f32(a: u8, b: u8, t: f32) -> u8 {
+    let (a, b) = (srgb_to_linear(a), srgb_to_linear(b));
+    encode_f32(a + (b - a) * t)
+}
+
+/// Blend from `a` to `b` by a Q8 weight (0 to 256) in linear light, in
+/// fixed point.
+#[inline]
+pub fn blend_linear_fixed(a: u8, b: u8, q8: u32) -> u8 {
+    let w = q8.min(256);
+    let (a, b) = (DECODE_Q12[a as usize] as u32, DECODE_Q12[b as usize] as u32);
+    ENCODE_Q12[((a * (256 - w) + b * w + 128) >> 8) as usize]
+}
+
+/// Greyscale using the active pixel math mode.
+#[inline]
+pub(crate) fn greyscale(r: u8, g: u8, b: u8) -> u8 {
+    if FIXED_POINT {
+        greyscale_fixed(r, g, b)
+    } else {
+        greyscale_f32(r, g, b)
+    }
+}
+
+/// A factor prepared once per frame for the active pixel math mode.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Factor {
+    f: f32,
+    q8: u32,
+}
+
+impl Factor {
+    #[inline]
+    pub(crate) fn new(f: f32) -> Self {
+        Self { f, q8: to_q8(f) }
+    }
+
+    /// Scale a channel by this factor.
+    #[inline]
+    pub(crate) fn scale(self, c: u8) -> u8 {
+        if FIXED_POINT {
+            scale_fixed(c, self.q8)
+        } else {
+            scale_f32(c, self.f)
+        }
+    }
+
+    /// Blend from `a` to `b` using this factor as the weight of `b`.
+    #[inline]
+    pub(crate) fn blend(self, a: u8, b: u8) -> u8 {
+        if FIXED_POINT {
+            blend_fixed(a, b, self.q8)
+        } else {
+            blend_f32(a, b, self.f)
+        }
+    }
+
+    /// Scale the light of a channel by this factor.
+    #[inline]
+    pub(crate) fn scale_linear(self, c: u8) -> u8 {
+        if FIXED_POINT {
+            scale_linear_fixed(c, self.q8)
+        } else {
+            scale_linear_f32(c, self.f)
+        }
+    }
+
+    /// Blend from `a` to `b` in linear light using this factor as the
+    /// weight of `b`.
+    #[inline]
+    pub(crate) fn blend_linear(self, a: u8, b: u8) -> u8 {
+        if FIXED_POINT {
+            blend_linear_fixed(a, b, self.q8)
+        } else {
+            blend_linear_f32(a, b, self.f)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_greyscale_fixed_matches_float() {
+        for (r, g, b) in [
+            (0, 0, 0),
+            (255, 255, 255),
+            (255, 0, 0),
+            (0, 255, 0),
+            (12, 200, 99),
+        ] {
+            let diff = greyscale_f32(r, g, b) as i32 - greyscale_fixed(r, g, b) as i32;
+            assert!(diff.abs() <= 1, "({r}, {g}, {b}) differs by {diff}");
+        }
+        assert_eq!(greyscale_fixed(255, 255, 255), 255);
+    }
+
+    #[test]
+    fn test_scale_fixed_matches_float() {
+        for factor in [0.0, 0.1, 0.5, 0.6, 1.0] {
+            for c in [0, 1, 50, 128, 200, 255] {
+                let diff = scale_f32(c, factor) as i32 - scale_fixed(c, to_q8(factor)) as i32;
+                assert!(diff.abs() <= 1, "{c} * {factor} differs by {diff}");
+            }
+        }
+        assert_eq!(scale_fixed(200, to_q8(2.0)), 255);
+    }
+
+    #[test]
+    fn test_blend_fixed_endpoints_exact() {
+        assert_eq!(blend_fixed(10, 250, 0), 10);
+        assert_eq!(blend_fixed(10, 250, 256), 250);
+        assert_eq!(blend_fixed(255, 0, 128), blend_f32(255, 0, 0.5));
+    }
+
+    #[test]
+    fn test_linear_tables_round_trip() {
+        for c in 0..=255u8 {
+            assert_eq!(ENCODE_Q12[DECODE_Q12[c as usize] as usize], c);
+            assert_eq!(scale_linear_fixed(c, 256), c);
+            assert_eq!(scale_linear_f32(c, 1.0), c);
+        }
+    }
+
+    #[test]
+    fn test_linear_fixed_matches_float() {
+        for t in [0.0, 0.1, 0.5, 0.6, 1.0] {
+            for (a, b) in [(0, 255), (255, 0), (200, 50), (3, 9), (128, 128)] {
+                let diff =
+                    blend_linear_f32(a, b, t) as i32 - blend_linear_fixed(a, b, to_q8(t)) as i32;
+                assert!(diff.abs() <= 1, "{a} -> {b} by {t} differs by {diff}");
+                let diff = scale_linear_f32(a, t) as i32 - scale_linear_fixed(a, to_q8(t)) as i32;
+                assert!(diff.abs() <= 1, "{a} * {t} differs by {diff}");
+            }
+        }
+        // Half the light of white is sRGB 188, not 128
+        assert_eq!(blend_linear_fixed(0, 255, 128), 188);
+        assert_eq!(scale_linear_f32(255, 0.5), 188);
+        assert_eq!(scale_linear_fixed(200, to_q8(2.0)), 255);
+    }
+}
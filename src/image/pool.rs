@@ -0,0 +1,136 @@
+//! Reusable per-frame image buffers.
+
+use image::RgbaImage;
+
+/// A pool of reusable `RgbaImage` buffers.
+///
+/// Effects that need temporary images each frame (transitions, convolution
+/// outputs, shadow layers) can [`take`](Self::take) a buffer and
+/// [`give`](Self::give) it back when done, so steady-state rendering does
+/// not allocate. Buffers are matched by exact dimensions; at most
+/// `capacity` idle buffers are retained.
+///
+/// The crate's renderers that need temporary images accept a pool:
+/// [`Pipeline`](crate::pipeline::Pipeline) keeps its own,
+/// [`render_pooled`](crate::widget::render_pooled) draws spec frames and
+/// element layers, [`Carousel::render_pooled`](crate::anim::Carousel::render_pooled)
+/// and [`draw_flip_digit_pooled`](crate::anim::draw_flip_digit_pooled)
+/// compose transitions, and [`convolve_into`](super::convolve_into) writes
+/// into a pooled buffer. Charts draw straight into the target image and
+/// need none.
+#[derive(Debug)]
+pub struct FramePool {
+    capacity: usize,
+    free: Vec<RgbaImage>,
+}
+
+impl FramePool {
+    /// Default number of idle buffers retained.
+    pub const DEFAULT_CAPACITY: usize = 8;
+
+    /// Create a pool retaining up to [`DEFAULT_CAPACITY`](Self::DEFAULT_CAPACITY) idle buffers.
+    pub fn new() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+
+    /// Create a pool retaining up to `capacity` idle buffers.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            free: Vec::new(),
+        }
+    }
+
+    /// Take a transparent buffer of the given size, reusing an idle one if available.
+    pub fn take(&mut self, width: u32, height: u32) -> RgbaImage {
+        let mut img = self.take_uncleared(width, height);
+        img.fill(0);
+        img
+    }
+
+    /// Take a buffer of the given size without clearing its contents.
+    ///
+    /// Cheaper than [`take`](Self::take) when the caller overwrites every pixel.
+    pub fn take_uncleared(&mut self, width: u32, height: u32) -> RgbaImage {
+        match self
+            .free
+            .iter()
+            .position(|img| img.dimensions() == (width, height))
+        {
+            Some(i) => self.free.swap_remove(i),
+            None => RgbaImage::new(width, height),
+        }
+    }
+
+    /// Return a buffer to the pool for reuse.
+    ///
+    /// If the pool is full, the oldest idle buffer is dropped.
+    pub fn give(&mut self, img: RgbaImage) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.free.len() >= self.capacity {
+            self.free.remove(0);
+        }
+        self.free.push(img);
+    }
+
+    /// Number of idle buffers held.
+    pub fn idle(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Drop all idle buffers.
+    pub fn clear(&mut self) {
+        self.free.clear();
+    }
+}
+
+impl Default for FramePool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    #[test]
+    fn test_take_reuses_matching_buffer() {
+        let mut pool = FramePool::new();
+        let mut img = pool.take(8, 8);
+        img.put_pixel(0, 0, Rgba([1, 2, 3, 4]));
+        let ptr = img.as_raw().as_ptr();
+        pool.give(img);
+        assert_eq!(pool.idle(), 1);
+
+        let again = pool.take(8, 8);
+        assert_eq!(again.as_raw().as_ptr(), ptr);
+        assert_eq!(*again.get_pixel(0, 0), Rgba([0, 0, 0, 0]));
+        assert_eq!(pool.idle(), 0);
+    }
+
+    #[test]
+    fn test_take_allocates_for_other_sizes() {
+        let mut pool = FramePool::new();
+        pool.give(RgbaImage::new(8, 8));
+        let img = pool.take(4, 4);
+        assert_eq!(img.dimensions(), (4, 4));
+        assert_eq!(pool.idle(), 1);
+    }
+
+    #[test]
+    fn test_give_respects_capacity() {
+        let mut pool = FramePool::with_capacity(2);
+        for _ in 0..5 {
+            pool.give(RgbaImage::new(2, 2));
+        }
+        assert_eq!(pool.idle(), 2);
+
+        let mut none = FramePool::with_capacity(0);
+        none.give(RgbaImage::new(2, 2));
+        assert_eq!(none.idle(), 0);
+    }
+}
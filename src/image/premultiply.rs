@@ -0,0 +1,53 @@
+//! Whole-image conversion between straight and premultiplied alpha.
+
+use image::RgbaImage;
+
+use crate::colors::{premultiply, unpremultiply};
+
+/// Convert every pixel of a straight-alpha image to premultiplied alpha in
+/// place, for compositors that expect premultiplied buffers.
+///
+/// Fully opaque pixels are unchanged. Convert a copy just before handing
+/// the buffer over, and keep drawing on the straight-alpha original: the
+/// crate's drawing functions all assume straight alpha.
+pub fn premultiply_image(rgba: &mut RgbaImage) {
+    for pixel in rgba.pixels_mut() {
+        if pixel[3] != 255 {
+            *pixel = premultiply(*pixel);
+        }
+    }
+}
+
+/// Convert every pixel of a premultiplied-alpha image back to straight
+/// alpha in place, e.g. for a buffer read back from a compositor.
+pub fn unpremultiply_image(rgba: &mut RgbaImage) {
+    for pixel in rgba.pixels_mut() {
+        if pixel[3] != 255 {
+            *pixel = unpremultiply(*pixel);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    #[test]
+    fn test_premultiply_image_round_trip() {
+        let mut img = RgbaImage::from_fn(3, 1, |x, _| match x {
+            0 => Rgba([200, 100, 50, 255]),
+            1 => Rgba([200, 100, 50, 128]),
+            _ => Rgba([200, 100, 50, 0]),
+        });
+        premultiply_image(&mut img);
+        assert_eq!(*img.get_pixel(0, 0), Rgba([200, 100, 50, 255]));
+        assert_eq!(*img.get_pixel(1, 0), Rgba([100, 50, 25, 128]));
+        assert_eq!(*img.get_pixel(2, 0), Rgba([0, 0, 0, 0]));
+
+        unpremultiply_image(&mut img);
+        assert_eq!(*img.get_pixel(0, 0), Rgba([200, 100, 50, 255]));
+        assert_eq!(*img.get_pixel(1, 0), Rgba([199, 100, 50, 128]));
+        assert_eq!(*img.get_pixel(2, 0), Rgba([0, 0, 0, 0]));
+    }
+}
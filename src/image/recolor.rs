@@ -0,0 +1,104 @@
+//! Selective color replacement.
+
+use std::collections::HashMap;
+
+use image::{Rgba, RgbaImage};
+
+use crate::colors::{Oklab, from_oklab, to_oklab};
+
+/// Recolor pixels close to `from` so they become `to`, e.g. to re-theme a
+/// stock icon with a baked-in brand color.
+///
+/// Closeness is measured as OKLab distance; `tolerance` of 0.05 catches
+/// only near-exact matches, 0.2 also catches shaded and anti-aliased
+/// variants. The shift is applied fully to exact matches and fades out
+/// smoothly towards the tolerance edge, so edges don't show a hard seam.
+/// Matched pixels move by the difference between `from` and `to`, so a
+/// darker shade of `from` becomes the same darker shade of `to`. Alpha is
+/// preserved. A `tolerance` of zero or less replaces exact matches only.
+///
+/// ```ignore
+/// replace_color(&mut icon, hex("#1DB954"), theme.accent, 0.15);
+/// ```
+pub fn replace_color(rgba: &mut RgbaImage, from: Rgba<u8>, to: Rgba<u8>, tolerance: f32) {
+    let source = to_oklab(from);
+    let target = to_oklab(to);
+    let shift = [
+        target.l - source.l,
+        target.a - source.a,
+        target.b - source.b,
+    ];
+    // Icons use few distinct colors, so convert each only once
+    let mut cache: HashMap<[u8; 3], [u8; 3]> = HashMap::new();
+    for pixel in rgba.pixels_mut() {
+        let rgb = [pixel[0], pixel[1], pixel[2]];
+        let out = *cache.entry(rgb).or_insert_with(|| {
+            let lab = to_oklab(Rgba([rgb[0], rgb[1], rgb[2], 255]));
+            let distance = ((lab.l - source.l).powi(2)
+                + (lab.a - source.a).powi(2)
+                + (lab.b - source.b).powi(2))
+            .sqrt();
+            let weight = falloff(distance, tolerance);
+            if weight <= 0.0 {
+                return rgb;
+            }
+            let moved = from_oklab(Oklab {
+                l: lab.l + shift[0] * weight,
+                a: lab.a + shift[1] * weight,
+                b: lab.b + shift[2] * weight,
+                alpha: 1.0,
+            });
+            [moved[0], moved[1], moved[2]]
+        });
+        pixel[0] = out[0];
+        pixel[1] = out[1];
+        pixel[2] = out[2];
+    }
+}
+
+/// 1.0 at distance zero, easing to 0.0 at `tolerance`.
+fn falloff(distance: f32, tolerance: f32) -> f32 {
+    if tolerance <= 0.0 {
+        return if distance <= 1e-6 { 1.0 } else { 0.0 };
+    }
+    let t = (distance / tolerance).clamp(0.0, 1.0);
+    1.0 - t * t * (3.0 - 2.0 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GREEN: Rgba<u8> = Rgba([29, 185, 84, 255]);
+    const PURPLE: Rgba<u8> = Rgba([128, 0, 255, 200]);
+
+    #[test]
+    fn test_replace_color_exact_and_untouched() {
+        let mut img = RgbaImage::from_fn(2, 1, |x, _| {
+            if x == 0 {
+                GREEN
+            } else {
+                Rgba([255, 255, 255, 77])
+            }
+        });
+        replace_color(&mut img, GREEN, PURPLE, 0.1);
+        // Alpha comes from the image, not from `to`
+        assert_eq!(*img.get_pixel(0, 0), Rgba([128, 0, 255, 255]));
+        assert_eq!(*img.get_pixel(1, 0), Rgba([255, 255, 255, 77]));
+    }
+
+    #[test]
+    fn test_replace_color_falls_off_smoothly() {
+        let near = Rgba([40, 170, 80, 255]);
+        let mut img = RgbaImage::from_pixel(1, 1, near);
+        replace_color(&mut img, GREEN, PURPLE, 0.2);
+        let moved = *img.get_pixel(0, 0);
+        // Partially shifted towards purple
+        assert!(moved[2] > near[2] && moved[1] < near[1]);
+        assert_ne!(moved, Rgba([128, 0, 255, 255]));
+
+        let mut exact_only = RgbaImage::from_pixel(1, 1, near);
+        replace_color(&mut exact_only, GREEN, PURPLE, 0.0);
+        assert_eq!(*exact_only.get_pixel(0, 0), near);
+    }
+}
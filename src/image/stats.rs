@@ -0,0 +1,141 @@
+//! Summary statistics for adapting overlays to arbitrary backgrounds.
+
+use image::RgbaImage;
+
+use super::{sobel_edges, to_greyscale};
+
+/// Sobel strength (see [`sobel_edges`]) at which a pixel counts as an edge.
+const EDGE_THRESHOLD: u8 = 32;
+
+/// Brightness and busyness of an image, from [`stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ImageStats {
+    /// Mean luma, 0.0 (black) to 1.0 (white).
+    pub mean_luma: f32,
+    /// RMS contrast: the standard deviation of luma, 0.0 (flat) to 0.5
+    /// (half black, half white).
+    pub contrast: f32,
+    /// Whether more than half the image is darker than mid-grey, i.e.
+    /// light text will read better than dark text.
+    pub is_mostly_dark: bool,
+    /// Fraction of visible pixels on an edge, 0.0 (smooth) to 1.0. Busy
+    /// backgrounds above roughly 0.15 call for outlined or backed text.
+    pub edge_density: f32,
+}
+
+/// Measure `img` so overlays can adapt to it, e.g. choosing light or dark
+/// text and how strong an outline to draw over a user's wallpaper.
+///
+/// Pixels are weighted by alpha, so transparent areas don't count. A fully
+/// transparent or empty image gives all-zero statistics.
+///
+/// ```ignore
+/// let s = stats(&wallpaper);
+/// let fg = if s.is_mostly_dark { css::WHITE } else { css::BLACK };
+/// let width = if s.edge_density > 0.15 { 2 } else { 1 };
+/// let legibility = TextLegibility::Outline { color: readable_on(fg), width };
+/// ```
+pub fn stats(img: &RgbaImage) -> ImageStats {
+    let mut weight = 0.0;
+    let mut sum = 0.0;
+    let mut sum_sq = 0.0;
+    let mut dark = 0.0;
+    for p in img.pixels() {
+        let a = p[3] as f32 / 255.0;
+        let luma = to_greyscale(p[0], p[1], p[2]) as f32 / 255.0;
+        weight += a;
+        sum += luma * a;
+        sum_sq += luma * luma * a;
+        if luma < 0.5 {
+            dark += a;
+        }
+    }
+    if weight <= 0.0 {
+        return ImageStats::default();
+    }
+    let mean = sum / weight;
+    // Only visible pixels count; weight > 0 means there is at least one
+    let edges = sobel_edges(img);
+    let (visible, edge_pixels) = img
+        .pixels()
+        .zip(edges.pixels())
+        .filter(|(p, _)| p[3] > 0)
+        .fold((0, 0), |(visible, on_edge), (_, e)| {
+            (visible + 1, on_edge + usize::from(e[0] >= EDGE_THRESHOLD))
+        });
+    ImageStats {
+        mean_luma: mean,
+        contrast: (sum_sq / weight - mean * mean).max(0.0).sqrt(),
+        is_mostly_dark: dark > weight / 2.0,
+        edge_density: edge_pixels as f32 / visible as f32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    #[test]
+    fn test_stats_flat_images() {
+        let black = stats(&RgbaImage::from_pixel(8, 8, Rgba([0, 0, 0, 255])));
+        assert_eq!(black.mean_luma, 0.0);
+        assert_eq!(black.contrast, 0.0);
+        assert!(black.is_mostly_dark);
+        assert_eq!(black.edge_density, 0.0);
+
+        let white = stats(&RgbaImage::from_pixel(8, 8, Rgba([255, 255, 255, 255])));
+        assert_eq!(white.mean_luma, 1.0);
+        assert!(!white.is_mostly_dark);
+        assert_eq!(stats(&RgbaImage::new(4, 4)), ImageStats::default());
+    }
+
+    #[test]
+    fn test_stats_contrast_and_edges() {
+        let checker = RgbaImage::from_fn(16, 16, |x, y| {
+            if (x / 2 + y / 2) % 2 == 0 {
+                Rgba([255, 255, 255, 255])
+            } else {
+                Rgba([0, 0, 0, 255])
+            }
+        });
+        let s = stats(&checker);
+        assert!((s.mean_luma - 0.5).abs() < 0.01);
+        assert!((s.contrast - 0.5).abs() < 0.01);
+        assert!(s.edge_density > 0.5);
+
+        // Transparent white pixels don't lighten a dark image
+        let mostly_clear = RgbaImage::from_fn(4, 4, |x, _| {
+            if x == 0 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 0])
+            }
+        });
+        assert!(stats(&mostly_clear).is_mostly_dark);
+    }
+
+    #[test]
+    fn test_stats_edge_density_ignores_transparent_border() {
+        let checker = |x: u32, y: u32| {
+            let v = if (x / 2 + y / 2).is_multiple_of(2) {
+                255
+            } else {
+                0
+            };
+            Rgba([v, v, v, 255])
+        };
+        let full = stats(&RgbaImage::from_fn(8, 8, checker));
+        // The same checker centered in a transparent 4px border
+        let padded = RgbaImage::from_fn(16, 16, |x, y| {
+            if (4..12).contains(&x) && (4..12).contains(&y) {
+                checker(x - 4, y - 4)
+            } else {
+                Rgba([0, 0, 0, 0])
+            }
+        });
+        let padded = stats(&padded);
+        assert!(full.edge_density > 0.5);
+        assert!(padded.edge_density >= full.edge_density);
+    }
+}
@@ -0,0 +1,110 @@
+//! Geometric warps for skeuomorphic effects.
+
+use image::{Rgba, RgbaImage};
+use imageproc::geometric_transformations::{Interpolation, Projection, warp, warp_with};
+
+/// Map the corners of `src` onto an arbitrary quadrilateral.
+///
+/// `quad` gives the destination of the source's top-left, top-right,
+/// bottom-right and bottom-left corners, in output pixel coordinates. The
+/// output has the same dimensions as `src`; areas outside the quad are
+/// transparent. Useful for tilted-card effects.
+///
+/// Returns `None` if the quad is degenerate (e.g. three collinear corners).
+pub fn warp_perspective(src: &RgbaImage, quad: [(f32, f32); 4]) -> Option<RgbaImage> {
+    let (w, h) = (src.width() as f32, src.height() as f32);
+    let corners = [(0.0, 0.0), (w, 0.0), (w, h), (0.0, h)];
+    let projection = Projection::from_control_points(corners, quad)?;
+    Some(warp(
+        src,
+        &projection,
+        Interpolation::Bilinear,
+        Rgba([0, 0, 0, 0]),
+    ))
+}
+
+/// Apply radial lens distortion around the image center.
+///
+/// Positive `k` produces barrel distortion (the center bulges outwards, as on
+/// a curved screen); negative `k` produces pincushion distortion. Values
+/// around ±0.1 to ±0.5 give a visible but subtle effect. Areas that map
+/// outside the source are transparent.
+pub fn barrel(src: &RgbaImage, k: f32) -> RgbaImage {
+    let cx = src.width() as f32 / 2.0;
+    let cy = src.height() as f32 / 2.0;
+    // Normalize so the corners sit at radius 1.0
+    let norm = (cx * cx + cy * cy).sqrt().max(1.0);
+
+    warp_with(
+        src,
+        |x, y| {
+            let dx = (x - cx) / norm;
+            let dy = (y - cy) / norm;
+            let factor = 1.0 + k * (dx * dx + dy * dy);
+            (cx + dx * factor * norm, cy + dy * factor * norm)
+        },
+        Interpolation::Bilinear,
+        Rgba([0, 0, 0, 0]),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checker() -> RgbaImage {
+        RgbaImage::from_fn(16, 16, |x, y| {
+            if (x + y) % 2 == 0 {
+                Rgba([255, 255, 255, 255])
+            } else {
+                Rgba([0, 0, 0, 255])
+            }
+        })
+    }
+
+    #[test]
+    fn test_warp_perspective_identity() {
+        let src = checker();
+        let out =
+            warp_perspective(&src, [(0.0, 0.0), (16.0, 0.0), (16.0, 16.0), (0.0, 16.0)]).unwrap();
+        assert_eq!(out.get_pixel(5, 5), src.get_pixel(5, 5));
+        assert_eq!(out.get_pixel(6, 5), src.get_pixel(6, 5));
+    }
+
+    #[test]
+    fn test_warp_perspective_outside_quad_transparent() {
+        let src = RgbaImage::from_pixel(16, 16, Rgba([255, 0, 0, 255]));
+        let out =
+            warp_perspective(&src, [(4.0, 0.0), (12.0, 0.0), (16.0, 16.0), (0.0, 16.0)]).unwrap();
+        assert_eq!(out.get_pixel(0, 0)[3], 0);
+        assert_eq!(*out.get_pixel(8, 8), Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_warp_perspective_degenerate() {
+        let src = checker();
+        assert!(warp_perspective(&src, [(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (3.0, 3.0)]).is_none());
+    }
+
+    #[test]
+    fn test_barrel_zero_is_identity() {
+        let src = checker();
+        let out = barrel(&src, 0.0);
+        assert_eq!(out.get_pixel(3, 7), src.get_pixel(3, 7));
+    }
+
+    #[test]
+    fn test_barrel_positive_clears_corners() {
+        let src = RgbaImage::from_pixel(16, 16, Rgba([255, 0, 0, 255]));
+        let out = barrel(&src, 0.5);
+        assert_eq!(out.get_pixel(0, 0)[3], 0);
+        assert_eq!(*out.get_pixel(8, 8), Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_pincushion_keeps_corners() {
+        let src = RgbaImage::from_pixel(16, 16, Rgba([255, 0, 0, 255]));
+        let out = barrel(&src, -0.3);
+        assert_eq!(out.get_pixel(0, 0)[3], 255);
+    }
+}
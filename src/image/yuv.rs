@@ -0,0 +1,229 @@
+//! Planar YUV 4:2:0 to RGB conversion for camera and video frames.
+
+use image::{Rgba, RgbaImage};
+
+/// Which YUV to RGB matrix a source was encoded with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum YuvMatrix {
+    /// ITU-R BT.601, used by standard-definition video, most webcams and
+    /// JPEG/MJPEG.
+    #[default]
+    Bt601,
+    /// ITU-R BT.709, used by HD video (720p and up).
+    Bt709,
+}
+
+impl YuvMatrix {
+    /// Luma weights of red and blue (Kr, Kb).
+    const fn weights(self) -> (f32, f32) {
+        match self {
+            YuvMatrix::Bt601 => (0.299, 0.114),
+            YuvMatrix::Bt709 => (0.2126, 0.0722),
+        }
+    }
+}
+
+/// Value range of the Y, U and V samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum YuvRange {
+    /// "TV" range: Y from 16 to 235, U and V from 16 to 240. Usual for
+    /// video decoders and V4L2 raw formats.
+    #[default]
+    Limited,
+    /// "PC" range: all samples 0 to 255. Usual for MJPEG webcams.
+    Full,
+}
+
+/// Convert an NV12 frame to an RgbaImage with full opacity.
+///
+/// NV12 is a `width * height` luma plane followed by one plane of
+/// interleaved U and V samples at half resolution in each direction
+/// (rounded up for odd sizes), with no row padding. Pixels past the end of
+/// a short `data` are black, as with [`bytes_to_rgba`](super::bytes_to_rgba).
+/// For frames with padded rows, use [`nv12_to_rgba_with_stride`].
+///
+/// ```ignore
+/// let frame = nv12_to_rgba(640, 480, &buffer, YuvMatrix::Bt601, YuvRange::Limited);
+/// ```
+pub fn nv12_to_rgba(
+    width: u32,
+    height: u32,
+    data: &[u8],
+    matrix: YuvMatrix,
+    range: YuvRange,
+) -> RgbaImage {
+    nv12_to_rgba_with_stride(width, height, data, 0, 0, matrix, range)
+}
+
+/// Like [`nv12_to_rgba`], for frames whose rows are padded.
+///
+/// `y_stride` and `uv_stride` are the bytes from the start of one row to
+/// the next in the luma and chroma planes; the chroma plane starts right
+/// after `height` luma rows. Strides smaller than a tightly packed row
+/// (e.g. 0) are treated as tightly packed, as in
+/// [`capture_to_rgba`](super::capture_to_rgba).
+///
+/// ```ignore
+/// // V4L2 reports the luma stride as bytesperline; NV12 shares it
+/// let frame = nv12_to_rgba_with_stride(w, h, &buffer, bpl, bpl, YuvMatrix::Bt601, YuvRange::Limited);
+/// ```
+pub fn nv12_to_rgba_with_stride(
+    width: u32,
+    height: u32,
+    data: &[u8],
+    y_stride: usize,
+    uv_stride: usize,
+    matrix: YuvMatrix,
+    range: YuvRange,
+) -> RgbaImage {
+    let (w, h) = (width as usize, height as usize);
+    let y_stride = y_stride.max(w);
+    let uv_stride = uv_stride.max(w.div_ceil(2) * 2);
+    let uv = y_stride * h;
+    convert(width, height, matrix, range, |x, y| {
+        let c = uv + y / 2 * uv_stride + x / 2 * 2;
+        Some([
+            *data.get(y * y_stride + x)?,
+            *data.get(c)?,
+            *data.get(c + 1)?,
+        ])
+    })
+}
+
+/// Convert an I420 (YUV 4:2:0 planar) frame to an RgbaImage with full
+/// opacity.
+///
+/// I420 is a `width * height` luma plane followed by a U plane and then a
+/// V plane, each at half resolution in each direction (rounded up for odd
+/// sizes), with no row padding. Pixels past the end of a short `data` are
+/// black. For frames with padded rows, use [`i420_to_rgba_with_stride`].
+pub fn i420_to_rgba(
+    width: u32,
+    height: u32,
+    data: &[u8],
+    matrix: YuvMatrix,
+    range: YuvRange,
+) -> RgbaImage {
+    i420_to_rgba_with_stride(width, height, data, 0, 0, matrix, range)
+}
+
+/// Like [`i420_to_rgba`], for frames whose rows are padded.
+///
+/// `y_stride` is the bytes from the start of one luma row to the next, and
+/// `chroma_stride` the same for the U and V planes, which each start right
+/// after the previous plane's last row. Strides smaller than a tightly
+/// packed row (e.g. 0) are treated as tightly packed.
+pub fn i420_to_rgba_with_stride(
+    width: u32,
+    height: u32,
+    data: &[u8],
+    y_stride: usize,
+    chroma_stride: usize,
+    matrix: YuvMatrix,
+    range: YuvRange,
+) -> RgbaImage {
+    let (w, h) = (width as usize, height as usize);
+    let y_stride = y_stride.max(w);
+    let chroma_stride = chroma_stride.max(w.div_ceil(2));
+    let u = y_stride * h;
+    let v = u + chroma_stride * h.div_ceil(2);
+    convert(width, height, matrix, range, |x, y| {
+        let c = y / 2 * chroma_stride + x / 2;
+        Some([
+            *data.get(y * y_stride + x)?,
+            *data.get(u + c)?,
+            *data.get(v + c)?,
+        ])
+    })
+}
+
+/// Build an image from per-pixel `[y, u, v]` samples (`None` for black).
+fn convert(
+    width: u32,
+    height: u32,
+    matrix: YuvMatrix,
+    range: YuvRange,
+    sample: impl Fn(usize, usize) -> Option<[u8; 3]>,
+) -> RgbaImage {
+    let (kr, kb) = matrix.weights();
+    let kg = 1.0 - kr - kb;
+    let (y_offset, y_scale, c_scale) = match range {
+        YuvRange::Limited => (16.0, 255.0 / 219.0, 255.0 / 224.0),
+        YuvRange::Full => (0.0, 1.0, 1.0),
+    };
+    RgbaImage::from_fn(width, height, |x, y| {
+        let Some([luma, u, v]) = sample(x as usize, y as usize) else {
+            return Rgba([0, 0, 0, 255]);
+        };
+        let luma = (luma as f32 - y_offset) * y_scale;
+        let cb = (u as f32 - 128.0) * c_scale;
+        let cr = (v as f32 - 128.0) * c_scale;
+        let r = luma + 2.0 * (1.0 - kr) * cr;
+        let b = luma + 2.0 * (1.0 - kb) * cb;
+        let g = luma - (2.0 * kb * (1.0 - kb) * cb + 2.0 * kr * (1.0 - kr) * cr) / kg;
+        let to_u8 = |c: f32| c.round().clamp(0.0, 255.0) as u8;
+        Rgba([to_u8(r), to_u8(g), to_u8(b), 255])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nv12_and_i420_agree() {
+        // 2x2 frame: grey luma ramp with one reddish chroma sample
+        let luma = [16, 82, 145, 235];
+        let nv12 = [&luma[..], &[90, 240]].concat();
+        let i420 = [&luma[..], &[90], &[240]].concat();
+        let a = nv12_to_rgba(2, 2, &nv12, YuvMatrix::Bt601, YuvRange::Limited);
+        let b = i420_to_rgba(2, 2, &i420, YuvMatrix::Bt601, YuvRange::Limited);
+        assert_eq!(a, b);
+        assert_eq!(*a.get_pixel(0, 0), Rgba([179, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_yuv_ranges_and_matrices() {
+        let grey = |range| i420_to_rgba(1, 1, &[235, 128, 128], YuvMatrix::Bt709, range);
+        assert_eq!(*grey(YuvRange::Limited).get_pixel(0, 0), Rgba([255; 4]));
+        assert_eq!(
+            *grey(YuvRange::Full).get_pixel(0, 0),
+            Rgba([235, 235, 235, 255])
+        );
+
+        // Full-range pure red under each matrix
+        let red_601 = i420_to_rgba(1, 1, &[76, 85, 255], YuvMatrix::Bt601, YuvRange::Full);
+        assert_eq!(*red_601.get_pixel(0, 0), Rgba([254, 0, 0, 255]));
+        let red_709 = i420_to_rgba(1, 1, &[54, 99, 255], YuvMatrix::Bt709, YuvRange::Full);
+        assert_eq!(*red_709.get_pixel(0, 0), Rgba([254, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_yuv_padded_rows() {
+        // 2x2 frame with each row padded to 4 bytes (padding is 0xEE)
+        let luma = [16, 82, 145, 235];
+        let tight = i420_to_rgba(
+            2,
+            2,
+            &[&luma[..], &[90], &[240]].concat(),
+            YuvMatrix::Bt601,
+            YuvRange::Limited,
+        );
+        let pad = 0xEE;
+        let nv12 = [16, 82, pad, pad, 145, 235, pad, pad, 90, 240, pad, pad];
+        let a = nv12_to_rgba_with_stride(2, 2, &nv12, 4, 4, YuvMatrix::Bt601, YuvRange::Limited);
+        assert_eq!(a, tight);
+        let i420 = [16, 82, pad, pad, 145, 235, pad, pad, 90, pad, 240, pad];
+        let b = i420_to_rgba_with_stride(2, 2, &i420, 4, 2, YuvMatrix::Bt601, YuvRange::Limited);
+        assert_eq!(b, tight);
+    }
+
+    #[test]
+    fn test_yuv_odd_size_and_short_buffer() {
+        // 3x1 frame has 2 chroma samples per row
+        let frame = nv12_to_rgba(3, 1, &[128; 7], YuvMatrix::Bt601, YuvRange::Full);
+        assert!(frame.pixels().all(|p| *p == Rgba([128, 128, 128, 255])));
+        let short = nv12_to_rgba(2, 2, &[128; 4], YuvMatrix::Bt601, YuvRange::Full);
+        assert_eq!(*short.get_pixel(1, 1), Rgba([0, 0, 0, 255]));
+    }
+}
@@ -3,10 +3,29 @@
 //! This crate provides shared functionality for building verandah plugins,
 //! including:
 //!
-//! - **colors**: CSS color parsing (named colors and hex formats)
+//! - **accessibility**: Crate-wide high-contrast and reduced-motion modes
+//! - **anim**: Animation clock and time-driven helpers (carousel, tweens, odometer, flip digits, shake, particles)
+//! - **cancel**: Cancellation tokens for stopping superseded renders early
+//! - **chart**: Chart primitives (gauges, radar, donut, candlesticks, timelines, stacked areas) with automatic tick layout
+//! - **colors**: CSS color parsing (named colors, hex and functional formats)
+//! - **debug**: Runtime-toggleable overlay outlining layout slots, text boxes, baselines and safe areas
+//! - **desktop**: Theme colors from the desktop's settings portal, GTK or KDE color scheme (`desktop-theme` feature)
+//! - **device**: Target device profiles (tile sizes, safe areas, pixel formats)
+//! - **flag**: Simplified country flag rendering
 //! - **font**: System font loading via fontconfig
+//! - **gallery**: Sample renders of every built-in primitive in a theme, for palette design
+//! - **icons**: Built-in vector icons for media controls and status marks (`icons` feature)
+//! - **locale**: Locale-aware number, date and time formatting
+//! - **pipeline**: Per-frame composition pipeline (background, content, overlays, effects)
+//! - **preview**: Render specs or drawing closures to PNG files for offline iteration, and record animation frames
+//! - **reload**: Polling file watcher for hot-reloading assets (`hot-reload` feature)
 //! - **text**: Text measurement and rendering utilities
-//! - **image**: Image effects (brightness pulse) and format conversions
+//! - **time**: Time zone aware clocks for world-clock widgets (`tz` feature)
+//! - **tile**: Const-size tiles for allocation-free rendering
+//! - **units**: Temperature, speed, pressure and data-rate conversion and formatting
+//! - **widget**: Widget primitives (progress bars, loading skeletons) and declarative specs
+//! - **worker**: Background render thread returning finished frames by ID, with cancellation
+//! - **image**: Image effects (brightness pulse, warps, noise), icon atlases and format conversions
 //!
 //! # Example
 //!
@@ -25,10 +44,33 @@
 //! apply_brightness_pulse(&mut img);
 //! ```
 
+pub mod accessibility;
+pub mod anim;
+pub mod cancel;
+pub mod chart;
 pub mod colors;
+pub mod debug;
+#[cfg(feature = "desktop-theme")]
+pub mod desktop;
+pub mod device;
+pub mod flag;
 pub mod font;
+pub mod gallery;
+#[cfg(feature = "icons")]
+pub mod icons;
 pub mod image;
+pub mod locale;
+pub mod pipeline;
+pub mod preview;
+#[cfg(feature = "hot-reload")]
+pub mod reload;
 pub mod text;
+pub mod tile;
+#[cfg(feature = "tz")]
+pub mod time;
+pub mod units;
+pub mod widget;
+pub mod worker;
 
 /// Prelude module for convenient imports.
 ///
@@ -39,22 +81,77 @@ pub mod text;
 pub mod prelude {
     // Re-export image types that plugins commonly use
     pub use ::image::{Rgb, RgbImage, Rgba, RgbaImage};
+    pub use ::imageproc::rect::Rect;
+
+    // Compile-time color literals
+    pub use crate::rgba;
+
+    // Animation
+    pub use crate::anim::{
+        Carousel, Clock, Easing, FlipStyle, Odometer, Particles, Slide, Transition, ValueTween,
+        apply_shake, draw_flip_digit, shake,
+    };
+
+    // Charts
+    pub use crate::chart::{
+        AreaStyle, CandleStyle, DonutStyle, GaugeStyle, NiceRange, RadarStyle, TickFormat,
+        TimelineStyle, draw_candlesticks, draw_donut, draw_gauge, draw_radar, draw_stacked_area,
+        draw_timeline, nice_range,
+    };
 
     // Colors
-    pub use crate::colors::{get_color, hex as rgb, lookup as lookup_color, parse_colors};
+    pub use crate::colors::{
+        Color, ColorMap, ColorResolver, Gradient, Scale, Theme, blend_linear, get_color,
+        hex as rgb, linear_to_srgb, lookup as lookup_color, lookup_with_current, mix_linear,
+        parse_colors, srgb_to_linear, try_parse_colors,
+    };
+
+    // Devices
+    pub use crate::device::DeviceProfile;
+
+    // Flags
+    pub use crate::flag::draw_region_flag;
 
     // Font
     pub use crate::font::get_system_monospace_font;
 
+    // Icons
+    #[cfg(feature = "icons")]
+    pub use crate::icons::{BuiltinIcon, draw_icon};
+
+    // Locale
+    pub use crate::locale::{DateTime, Locale, locale, set_locale};
+
+    // Pipeline
+    pub use crate::pipeline::{FrameContext, Pipeline};
+
     // Text
+    #[cfg(feature = "sanitize")]
+    pub use crate::text::sanitize_for_display;
     pub use crate::text::{
-        draw_centered_text, draw_centered_text_with_reserved, find_optimal_scale,
-        measure_text_width,
+        CachedTextLine, GlyphCache, TextLegibility, TextReveal, draw_centered_text,
+        draw_centered_text_legible, draw_centered_text_reveal, draw_centered_text_with_reserved,
+        find_optimal_scale, format_kv, measure_text_width,
     };
 
+    // Tiles
+    pub use crate::tile::{Tile, Tile72, Tile96, tile_len};
+
+    // Widgets
+    pub use crate::widget::{
+        Element, Progress, ProgressStyle, Spec, draw_progress_bar, draw_skeleton,
+        render as render_spec,
+    };
+
+    // Off-thread rendering and cancellation
+    pub use crate::cancel::CancelToken;
+    pub use crate::worker::{RenderWorker, RenderedFrame};
+
     // Image utilities
     pub use crate::image::{
-        apply_brightness_pulse, bytes_to_rgb, bytes_to_rgba, rgb_to_rgba, rgba_to_rgb, scale_image,
-        to_greyscale,
+        Background, Fit, Pulse, WidgetState, apply_brightness_pulse,
+        apply_brightness_pulse_in_rect, apply_brightness_pulse_masked, apply_brightness_pulse_with,
+        apply_state_filter, bytes_to_rgb, bytes_to_rgba, feather_edges, flatten_onto, rgb_to_rgba,
+        rgba_to_rgb, scale_image, to_greyscale,
     };
 }
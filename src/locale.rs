@@ -0,0 +1,594 @@
+//! Locale-aware number, date and time formatting.
+//!
+//! A [`Locale`] is a small table of separators, clock style and English or
+//! native weekday and month names; a handful of common locales are built
+//! in and selected by tag (`"de-DE"`, `"en_GB.UTF-8"`, ...), so plugins can
+//! expose the choice in their config. The process-wide locale set with
+//! [`set_locale`] is used by renderers that draw numbers, such as
+//! [`ValueTween::render`](crate::anim::ValueTween::render) and chart axis
+//! labels. The default is [`Locale::POSIX`], which formats as plain Rust
+//! does.
+//!
+//! Dates and times are formatted from a [`DateTime`] broken out of a Unix
+//! timestamp, either with the locale's own patterns or with a small
+//! strftime-style subset:
+//!
+//! ```ignore
+//! set_locale("de-DE".parse()?);
+//! let now = DateTime::now_utc();
+//! let time = locale().format_time(&now); // "14:05"
+//! let date = locale().format_date(&now); // "Mo., 5. Jan."
+//! let custom = locale().format(&now, "%A, %-d. %B %Y");
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::chart::format_fixed;
+
+static LOCALE: RwLock<Locale> = RwLock::new(Locale::POSIX);
+
+/// Set the locale used by the crate's renderers for the whole process.
+pub fn set_locale(locale: Locale) {
+    *LOCALE.write().unwrap_or_else(|e| e.into_inner()) = locale;
+}
+
+/// The locale used by the crate's renderers.
+pub fn locale() -> Locale {
+    *LOCALE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Separators, clock style and names for formatting in one locale.
+///
+/// Weekday tables start on Monday; month tables start on January.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    /// BCP 47 style tag, e.g. "en-US".
+    pub tag: &'static str,
+    /// Decimal separator.
+    pub decimal: char,
+    /// Thousands separator, or None for no grouping.
+    pub group: Option<char>,
+    /// Whether times use a 12-hour clock with AM/PM.
+    pub hour12: bool,
+    /// AM and PM markers.
+    pub am_pm: [&'static str; 2],
+    /// Pattern for [`format_date`](Self::format_date).
+    pub date_pattern: &'static str,
+    /// Full weekday names.
+    pub weekdays: [&'static str; 7],
+    /// Abbreviated weekday names.
+    pub weekdays_short: [&'static str; 7],
+    /// Full month names.
+    pub months: [&'static str; 12],
+    /// Abbreviated month names.
+    pub months_short: [&'static str; 12],
+}
+
+const EN_WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+const EN_WEEKDAYS_SHORT: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const EN_MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const EN_MONTHS_SHORT: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+impl Locale {
+    /// Plain formatting: "." decimals, no grouping, 24-hour ISO-style times
+    /// and English names.
+    pub const POSIX: Locale = Locale {
+        tag: "C",
+        decimal: '.',
+        group: None,
+        hour12: false,
+        am_pm: ["AM", "PM"],
+        date_pattern: "%Y-%m-%d",
+        weekdays: EN_WEEKDAYS,
+        weekdays_short: EN_WEEKDAYS_SHORT,
+        months: EN_MONTHS,
+        months_short: EN_MONTHS_SHORT,
+    };
+
+    /// English (United States).
+    pub const EN_US: Locale = Locale {
+        tag: "en-US",
+        decimal: '.',
+        group: Some(','),
+        hour12: true,
+        date_pattern: "%a, %b %-d",
+        ..Self::POSIX
+    };
+
+    /// English (United Kingdom).
+    pub const EN_GB: Locale = Locale {
+        tag: "en-GB",
+        decimal: '.',
+        group: Some(','),
+        hour12: false,
+        date_pattern: "%a %-d %b",
+        ..Self::POSIX
+    };
+
+    /// German (Germany).
+    pub const DE_DE: Locale = Locale {
+        tag: "de-DE",
+        decimal: ',',
+        group: Some('.'),
+        hour12: false,
+        am_pm: ["AM", "PM"],
+        date_pattern: "%a, %-d. %b",
+        weekdays: [
+            "Montag",
+            "Dienstag",
+            "Mittwoch",
+            "Donnerstag",
+            "Freitag",
+            "Samstag",
+            "Sonntag",
+        ],
+        weekdays_short: ["Mo.", "Di.", "Mi.", "Do.", "Fr.", "Sa.", "So."],
+        months: [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ],
+        months_short: [
+            "Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sept.", "Okt.", "Nov.",
+            "Dez.",
+        ],
+    };
+
+    /// French (France).
+    pub const FR_FR: Locale = Locale {
+        tag: "fr-FR",
+        decimal: ',',
+        group: Some(' '),
+        hour12: false,
+        am_pm: ["AM", "PM"],
+        date_pattern: "%a %-d %b",
+        weekdays: [
+            "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+        ],
+        weekdays_short: ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+        months: [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ],
+        months_short: [
+            "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.",
+            "nov.", "déc.",
+        ],
+    };
+
+    /// Spanish (Spain).
+    pub const ES_ES: Locale = Locale {
+        tag: "es-ES",
+        decimal: ',',
+        group: Some('.'),
+        hour12: false,
+        am_pm: ["a. m.", "p. m."],
+        date_pattern: "%a, %-d %b",
+        weekdays: [
+            "lunes",
+            "martes",
+            "miércoles",
+            "jueves",
+            "viernes",
+            "sábado",
+            "domingo",
+        ],
+        weekdays_short: ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+        months: [
+            "enero",
+            "febrero",
+            "marzo",
+            "abril",
+            "mayo",
+            "junio",
+            "julio",
+            "agosto",
+            "septiembre",
+            "octubre",
+            "noviembre",
+            "diciembre",
+        ],
+        months_short: [
+            "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sept", "oct", "nov", "dic",
+        ],
+    };
+
+    /// All built-in locales.
+    pub const BUILT_IN: [Locale; 6] = [
+        Self::POSIX,
+        Self::EN_US,
+        Self::EN_GB,
+        Self::DE_DE,
+        Self::FR_FR,
+        Self::ES_ES,
+    ];
+
+    /// Look up a built-in locale by tag.
+    ///
+    /// Accepts BCP 47 tags ("de-DE") and POSIX locale names
+    /// ("de_DE.UTF-8"), case-insensitively. An unknown region falls back to
+    /// the first locale for the language, so "de-AT" gives German.
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let tag = tag.split(['.', '@']).next().unwrap_or_default();
+        if tag.eq_ignore_ascii_case("C") || tag.eq_ignore_ascii_case("POSIX") {
+            return Some(Self::POSIX);
+        }
+        let mut parts = tag.split(['-', '_']);
+        let language = parts.next().filter(|l| !l.is_empty())?;
+        let region = parts.next();
+        let language_of = |l: &Locale| l.tag.split('-').next().unwrap_or_default();
+        let same_language = || {
+            Self::BUILT_IN
+                .into_iter()
+                .filter(move |l| language_of(l).eq_ignore_ascii_case(language))
+        };
+        region
+            .and_then(|region| {
+                same_language().find(|l| {
+                    l.tag
+                        .rsplit('-')
+                        .next()
+                        .is_some_and(|r| r.eq_ignore_ascii_case(region))
+                })
+            })
+            .or_else(|| same_language().next())
+    }
+
+    /// The built-in locale named by the `LC_ALL`, `LC_TIME` or `LANG`
+    /// environment variables, checked in that order.
+    pub fn from_env() -> Option<Locale> {
+        ["LC_ALL", "LC_TIME", "LANG"]
+            .into_iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::from_tag(&value))
+    }
+
+    /// Format `value` with exactly `decimals` decimal places and this
+    /// locale's separators, never as "-0".
+    pub fn format_number(&self, value: f64, decimals: usize) -> String {
+        self.localize_number(&format_fixed(value, decimals))
+    }
+
+    /// Apply this locale's separators to a number already formatted by
+    /// Rust, such as "-1234.5" or "1.5k". Non-numeric text is unchanged.
+    pub fn localize_number(&self, text: &str) -> String {
+        let (sign, rest) = match text.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", text),
+        };
+        let int_len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if int_len == 0 {
+            return text.to_string();
+        }
+        let (int, tail) = rest.split_at(int_len);
+        let mut out = String::with_capacity(text.len() + int_len / 3);
+        out.push_str(sign);
+        for (i, digit) in int.chars().enumerate() {
+            if let Some(group) = self.group
+                && i > 0
+                && (int_len - i) % 3 == 0
+            {
+                out.push(group);
+            }
+            out.push(digit);
+        }
+        match tail.strip_prefix('.') {
+            Some(fraction) => {
+                out.push(self.decimal);
+                out.push_str(fraction);
+            }
+            None => out.push_str(tail),
+        }
+        out
+    }
+
+    /// Full name of `weekday` (0 is Monday).
+    pub fn weekday_name(&self, weekday: u8) -> &'static str {
+        self.weekdays[weekday as usize % 7]
+    }
+
+    /// Abbreviated name of `weekday` (0 is Monday).
+    pub fn weekday_abbr(&self, weekday: u8) -> &'static str {
+        self.weekdays_short[weekday as usize % 7]
+    }
+
+    /// Full name of `month` (1 is January).
+    pub fn month_name(&self, month: u8) -> &'static str {
+        self.months[(month as usize + 11) % 12]
+    }
+
+    /// Abbreviated name of `month` (1 is January).
+    pub fn month_abbr(&self, month: u8) -> &'static str {
+        self.months_short[(month as usize + 11) % 12]
+    }
+
+    /// Hours and minutes, e.g. "2:05 PM" or "14:05".
+    pub fn format_time(&self, time: &DateTime) -> String {
+        let pattern = if self.hour12 { "%-I:%M %p" } else { "%H:%M" };
+        self.format(time, pattern)
+    }
+
+    /// Hours, minutes and seconds, e.g. "2:05:09 PM" or "14:05:09".
+    pub fn format_time_with_seconds(&self, time: &DateTime) -> String {
+        let pattern = if self.hour12 {
+            "%-I:%M:%S %p"
+        } else {
+            "%H:%M:%S"
+        };
+        self.format(time, pattern)
+    }
+
+    /// Short date using [`date_pattern`](Self::date_pattern), e.g.
+    /// "Mon, Jan 5" or "Mo., 5. Jan.".
+    pub fn format_date(&self, date: &DateTime) -> String {
+        self.format(date, self.date_pattern)
+    }
+
+    /// Format `time` with a strftime-style `pattern`.
+    ///
+    /// Supports `%Y`, `%y`, `%m`, `%d`, `%H`, `%I`, `%M`, `%S`, `%p`, `%a`,
+    /// `%A`, `%b`, `%B` and `%%`. A `-` flag (`%-d`) drops zero padding.
+    /// Unknown specifiers are copied through.
+    pub fn format(&self, time: &DateTime, pattern: &str) -> String {
+        let mut out = String::with_capacity(pattern.len() + 8);
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+            let mut spec = chars.next();
+            let pad = spec != Some('-');
+            if !pad {
+                spec = chars.next();
+            }
+            let number = |out: &mut String, n: u32| {
+                if pad {
+                    out.push_str(&format!("{n:02}"));
+                } else {
+                    out.push_str(&n.to_string());
+                }
+            };
+            match spec {
+                Some('Y') => out.push_str(&time.year.to_string()),
+                Some('y') => number(&mut out, time.year.rem_euclid(100) as u32),
+                Some('m') => number(&mut out, time.month as u32),
+                Some('d') => number(&mut out, time.day as u32),
+                Some('H') => number(&mut out, time.hour as u32),
+                Some('I') => number(&mut out, (time.hour as u32 + 11) % 12 + 1),
+                Some('M') => number(&mut out, time.minute as u32),
+                Some('S') => number(&mut out, time.second as u32),
+                Some('p') => out.push_str(self.am_pm[usize::from(time.hour >= 12)]),
+                Some('a') => out.push_str(self.weekday_abbr(time.weekday)),
+                Some('A') => out.push_str(self.weekday_name(time.weekday)),
+                Some('b') => out.push_str(self.month_abbr(time.month)),
+                Some('B') => out.push_str(self.month_name(time.month)),
+                Some('%') => out.push('%'),
+                Some(other) => {
+                    out.push('%');
+                    if !pad {
+                        out.push('-');
+                    }
+                    out.push(other);
+                }
+                None => out.push('%'),
+            }
+        }
+        out
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::POSIX
+    }
+}
+
+/// Error returned when a string does not name a built-in locale.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLocaleError(String);
+
+impl fmt::Display for ParseLocaleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown locale: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseLocaleError {}
+
+impl FromStr for Locale {
+    type Err = ParseLocaleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_tag(s.trim()).ok_or_else(|| ParseLocaleError(s.to_string()))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Locale {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Locale {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.tag)
+    }
+}
+
+/// A calendar date and time of day, broken out for formatting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DateTime {
+    /// Year, e.g. 2024.
+    pub year: i32,
+    /// Month, 1 to 12.
+    pub month: u8,
+    /// Day of the month, 1 to 31.
+    pub day: u8,
+    /// Hour, 0 to 23.
+    pub hour: u8,
+    /// Minute, 0 to 59.
+    pub minute: u8,
+    /// Second, 0 to 59.
+    pub second: u8,
+    /// Day of the week, 0 (Monday) to 6 (Sunday).
+    pub weekday: u8,
+}
+
+impl DateTime {
+    /// Break a Unix timestamp (seconds since 1970-01-01 UTC) into UTC
+    /// calendar fields.
+    pub fn from_unix(secs: i64) -> Self {
+        let days = secs.div_euclid(86_400);
+        let time = secs.rem_euclid(86_400) as u32;
+        // Civil-from-days (Howard Hinnant), with eras of 400 years
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+        Self {
+            year,
+            month,
+            day,
+            hour: (time / 3600) as u8,
+            minute: (time / 60 % 60) as u8,
+            second: (time % 60) as u8,
+            // 1970-01-01 was a Thursday
+            weekday: (days + 3).rem_euclid(7) as u8,
+        }
+    }
+
+    /// The current time in UTC.
+    pub fn now_utc() -> Self {
+        let secs = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_secs() as i64,
+            Err(e) => -(e.duration().as_secs() as i64),
+        };
+        Self::from_unix(secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-02-29 14:05:09 UTC, a Thursday
+    const LEAP_DAY: i64 = 1_709_215_509;
+
+    #[test]
+    fn test_date_time_from_unix() {
+        let t = DateTime::from_unix(LEAP_DAY);
+        assert_eq!((t.year, t.month, t.day), (2024, 2, 29));
+        assert_eq!((t.hour, t.minute, t.second), (14, 5, 9));
+        assert_eq!(t.weekday, 3);
+
+        let epoch = DateTime::from_unix(0);
+        assert_eq!(
+            (epoch.year, epoch.month, epoch.day, epoch.weekday),
+            (1970, 1, 1, 3)
+        );
+        let before = DateTime::from_unix(-1);
+        assert_eq!((before.year, before.month, before.day), (1969, 12, 31));
+        assert_eq!((before.hour, before.minute, before.second), (23, 59, 59));
+    }
+
+    #[test]
+    fn test_locale_numbers() {
+        assert_eq!(Locale::POSIX.format_number(-1234567.891, 2), "-1234567.89");
+        assert_eq!(Locale::EN_US.format_number(1234567.891, 2), "1,234,567.89");
+        assert_eq!(Locale::DE_DE.format_number(-1234.5, 1), "-1.234,5");
+        assert_eq!(Locale::DE_DE.format_number(-0.01, 1), "0,0");
+        assert_eq!(Locale::FR_FR.localize_number("1500.5k"), "1 500,5k");
+        assert_eq!(Locale::DE_DE.localize_number("n/a"), "n/a");
+    }
+
+    #[test]
+    fn test_locale_times_and_dates() {
+        let t = DateTime::from_unix(LEAP_DAY);
+        assert_eq!(Locale::EN_US.format_time(&t), "2:05 PM");
+        assert_eq!(Locale::EN_GB.format_time_with_seconds(&t), "14:05:09");
+        assert_eq!(Locale::EN_US.format_date(&t), "Thu, Feb 29");
+        assert_eq!(Locale::DE_DE.format_date(&t), "Do., 29. Feb.");
+        assert_eq!(Locale::POSIX.format_date(&t), "2024-02-29");
+        assert_eq!(
+            Locale::FR_FR.format(&t, "%A %-d %B %y, 100%% %q"),
+            "jeudi 29 février 24, 100% %q"
+        );
+
+        let midnight = DateTime::from_unix(0);
+        assert_eq!(Locale::EN_US.format_time(&midnight), "12:00 AM");
+    }
+
+    #[test]
+    fn test_locale_from_tag() {
+        assert_eq!(Locale::from_tag("de_DE.UTF-8"), Some(Locale::DE_DE));
+        assert_eq!(Locale::from_tag("en-gb"), Some(Locale::EN_GB));
+        assert_eq!(Locale::from_tag("en"), Some(Locale::EN_US));
+        assert_eq!(Locale::from_tag("de-AT"), Some(Locale::DE_DE));
+        assert_eq!(Locale::from_tag("C.UTF-8"), Some(Locale::POSIX));
+        assert_eq!(Locale::from_tag("xx-YY"), None);
+        assert!("klingon".parse::<Locale>().is_err());
+    }
+}
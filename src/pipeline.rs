@@ -0,0 +1,257 @@
+//! Per-frame composition pipeline.
+//!
+//! A [`Pipeline`] runs a fixed sequence of stages each frame:
+//!
+//! 1. background ([`Background`])
+//! 2. content closures, drawn onto a transparent layer
+//! 3. overlay closures, drawn onto a second transparent layer
+//! 4. effect closures, which modify the composed frame in place
+//! 5. the [debug overlay](crate::debug), when enabled, including the
+//!    device safe area
+//! 6. the device output transform, when built with [`Pipeline::for_device`]
+//!
+//! Layers are drawn into buffers borrowed from a [`FramePool`], so steady-state
+//! rendering does not allocate.
+//!
+//! ```ignore
+//! let mut pipeline = Pipeline::for_device(&TILE_72)
+//!     .background(Background::Solid(rgb("#202020")))
+//!     .content(|img, frame| draw_centered_text(img, &format!("{}", frame.index), fg, 0.1))
+//!     .effect(|img, _| apply_brightness_pulse(img));
+//!
+//! let frame = pipeline.render(clock.elapsed());
+//! host.present(&frame);
+//! pipeline.recycle(frame);
+//! ```
+
+use std::time::Duration;
+
+use image::RgbaImage;
+use image::imageops;
+use imageproc::rect::Rect;
+
+use crate::debug::{self, DebugMark};
+use crate::device::DeviceProfile;
+use crate::image::{Background, FramePool};
+
+/// Per-frame information passed to every stage.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameContext {
+    /// Time passed to [`Pipeline::render`].
+    pub elapsed: Duration,
+    /// Number of frames rendered before this one.
+    pub index: u64,
+    /// Area guaranteed to be visible; the whole frame without a device profile.
+    pub safe_rect: Rect,
+}
+
+type Stage = Box<dyn FnMut(&mut RgbaImage, &FrameContext)>;
+
+/// Builder and runner for a fixed sequence of per-frame render stages.
+///
+/// See the [module documentation](self) for the stage order.
+pub struct Pipeline {
+    width: u32,
+    height: u32,
+    device: Option<DeviceProfile>,
+    background: Background,
+    content: Vec<Stage>,
+    overlays: Vec<Stage>,
+    effects: Vec<Stage>,
+    pool: FramePool,
+    index: u64,
+}
+
+impl Pipeline {
+    /// Create an empty pipeline producing frames of the given size.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            device: None,
+            background: Background::None,
+            content: Vec::new(),
+            overlays: Vec::new(),
+            effects: Vec::new(),
+            pool: FramePool::new(),
+            index: 0,
+        }
+    }
+
+    /// Create a pipeline sized for `device` that finalizes each frame with
+    /// the device's output transform.
+    pub fn for_device(device: &DeviceProfile) -> Self {
+        let mut pipeline = Self::new(device.width, device.height);
+        pipeline.device = Some(*device);
+        pipeline
+    }
+
+    /// Set the background painted at the start of every frame.
+    pub fn background(mut self, background: Background) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Add a content stage. Content stages share one transparent layer,
+    /// composited over the background in registration order.
+    pub fn content(mut self, stage: impl FnMut(&mut RgbaImage, &FrameContext) + 'static) -> Self {
+        self.content.push(Box::new(stage));
+        self
+    }
+
+    /// Add an overlay stage, drawn on a layer above all content.
+    pub fn overlay(mut self, stage: impl FnMut(&mut RgbaImage, &FrameContext) + 'static) -> Self {
+        self.overlays.push(Box::new(stage));
+        self
+    }
+
+    /// Add an effect stage, applied in place to the composed frame.
+    pub fn effect(mut self, stage: impl FnMut(&mut RgbaImage, &FrameContext) + 'static) -> Self {
+        self.effects.push(Box::new(stage));
+        self
+    }
+
+    /// Frame dimensions as `(width, height)`.
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Number of frames rendered so far.
+    pub fn frames(&self) -> u64 {
+        self.index
+    }
+
+    /// Render a frame into a pooled buffer.
+    ///
+    /// Pass the frame to [`recycle`](Self::recycle) once presented so the
+    /// next render can reuse it.
+    pub fn render(&mut self, elapsed: Duration) -> RgbaImage {
+        let mut frame = self.pool.take(self.width, self.height);
+        self.render_into(&mut frame, elapsed);
+        frame
+    }
+
+    /// Render a frame into `out`, which must match the pipeline size.
+    ///
+    /// The background is painted over the existing contents of `out`, so a
+    /// [`Background::BlurBehind`] blurs whatever was there.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` does not match [`size`](Self::size).
+    pub fn render_into(&mut self, out: &mut RgbaImage, elapsed: Duration) {
+        assert_eq!(out.dimensions(), self.size(), "frame size mismatch");
+        let ctx = FrameContext {
+            elapsed,
+            index: self.index,
+            safe_rect: match &self.device {
+                Some(device) => device.safe_rect(),
+                None => Rect::at(0, 0).of_size(self.width.max(1), self.height.max(1)),
+            },
+        };
+        self.index += 1;
+
+        self.background.paint(out);
+        for stages in [&mut self.content, &mut self.overlays] {
+            if stages.is_empty() {
+                continue;
+            }
+            let mut layer = self.pool.take(self.width, self.height);
+            for stage in stages.iter_mut() {
+                stage(&mut layer, &ctx);
+            }
+            imageops::overlay(out, &layer, 0, 0);
+            self.pool.give(layer);
+        }
+        for effect in &mut self.effects {
+            effect(out, &ctx);
+        }
+        if self.device.is_some() {
+            debug::mark(DebugMark::SafeArea, ctx.safe_rect);
+        }
+        debug::draw_overlay(out);
+        if let Some(device) = &self.device {
+            device.finalize(out);
+        }
+    }
+
+    /// Return a frame from [`render`](Self::render) for reuse.
+    pub fn recycle(&mut self, frame: RgbaImage) {
+        self.pool.give(frame);
+    }
+}
+
+impl std::fmt::Debug for Pipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("device", &self.device.map(|d| d.name))
+            .field("background", &self.background)
+            .field("content", &self.content.len())
+            .field("overlays", &self.overlays.len())
+            .field("effects", &self.effects.len())
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::{OutputTransform, TILE_72};
+    use image::Rgba;
+
+    #[test]
+    fn test_stage_order() {
+        let mut pipeline = Pipeline::new(4, 4)
+            .background(Background::Solid(Rgba([0, 0, 255, 255])))
+            .content(|img, _| img.put_pixel(0, 0, Rgba([255, 0, 0, 255])))
+            .overlay(|img, _| {
+                img.put_pixel(0, 0, Rgba([0, 255, 0, 255]));
+                img.put_pixel(1, 0, Rgba([0, 255, 0, 255]));
+            })
+            .effect(|img, _| img.put_pixel(1, 0, Rgba([9, 9, 9, 255])));
+
+        let frame = pipeline.render(Duration::ZERO);
+        assert_eq!(*frame.get_pixel(0, 0), Rgba([0, 255, 0, 255]));
+        assert_eq!(*frame.get_pixel(1, 0), Rgba([9, 9, 9, 255]));
+        assert_eq!(*frame.get_pixel(3, 3), Rgba([0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn test_content_layer_is_cleared_each_frame() {
+        let mut pipeline = Pipeline::new(2, 1)
+            .background(Background::Solid(Rgba([0, 0, 0, 255])))
+            .content(|img, frame| {
+                let x = (frame.index % 2) as u32;
+                img.put_pixel(x, 0, Rgba([255, 255, 255, 255]));
+            });
+
+        let first = pipeline.render(Duration::ZERO);
+        pipeline.recycle(first);
+        let second = pipeline.render(Duration::from_millis(16));
+        assert_eq!(*second.get_pixel(0, 0), Rgba([0, 0, 0, 255]));
+        assert_eq!(*second.get_pixel(1, 0), Rgba([255, 255, 255, 255]));
+        assert_eq!(pipeline.frames(), 2);
+    }
+
+    #[test]
+    fn test_for_device_applies_output_transform() {
+        let device = TILE_72.with_output(OutputTransform::Gamma(2.0));
+        let mut pipeline =
+            Pipeline::for_device(&device).background(Background::Solid(Rgba([128, 128, 128, 255])));
+        let frame = pipeline.render(Duration::ZERO);
+        assert_eq!(frame.dimensions(), (72, 72));
+        assert!(frame.get_pixel(10, 10)[0] < 128);
+    }
+
+    #[test]
+    fn test_context_safe_rect() {
+        let expected = TILE_72.safe_rect();
+        let mut pipeline = Pipeline::for_device(&TILE_72).content(move |_, frame| {
+            assert_eq!(frame.safe_rect, expected);
+        });
+        pipeline.render(Duration::ZERO);
+    }
+}
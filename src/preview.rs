@@ -0,0 +1,293 @@
+//! Offline previews of widget visuals.
+//!
+//! Renders a [`Spec`] or a drawing closure to a PNG file, so plugin
+//! authors can iterate on visuals from a small binary without a running
+//! verandah host:
+//!
+//! ```ignore
+//! fn main() -> Result<(), image::ImageError> {
+//!     let theme = Theme::default();
+//!     render_to_png(&my_plugin_spec(), (72, 72), &theme, "preview.png")?;
+//!     render_to_png(
+//!         |img: &mut RgbaImage, theme: &Theme| draw_centered_text(img, "42", theme.fg, 0.1),
+//!         (72, 72),
+//!         &theme,
+//!         "text.png",
+//!     )
+//! }
+//! ```
+//!
+//! See `examples/preview.rs` for a runnable version.
+//!
+//! For animations, a [`FrameRecorder`] keeps every N-th frame a running
+//! plugin renders and dumps them as numbered PNGs or an animated GIF, to
+//! step through janky transitions frame by frame.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, ImageFormat, RgbaImage};
+
+use crate::colors::Theme;
+use crate::widget::{Spec, render};
+
+/// Something that can draw a preview frame.
+///
+/// Implemented for [`Spec`] and for closures taking the frame (already
+/// filled with the theme background) and the theme.
+pub trait Preview {
+    /// Render a frame of `size` with `theme`.
+    fn render_preview(&mut self, size: (u32, u32), theme: &Theme) -> RgbaImage;
+}
+
+impl Preview for Spec {
+    fn render_preview(&mut self, size: (u32, u32), theme: &Theme) -> RgbaImage {
+        render(self, size, theme)
+    }
+}
+
+impl Preview for &Spec {
+    fn render_preview(&mut self, size: (u32, u32), theme: &Theme) -> RgbaImage {
+        render(self, size, theme)
+    }
+}
+
+impl<F> Preview for F
+where
+    F: FnMut(&mut RgbaImage, &Theme),
+{
+    fn render_preview(&mut self, size: (u32, u32), theme: &Theme) -> RgbaImage {
+        let mut rgba = RgbaImage::from_pixel(size.0, size.1, theme.bg);
+        self(&mut rgba, theme);
+        rgba
+    }
+}
+
+/// Render `source` and save it as a PNG at `path`.
+pub fn render_to_png(
+    mut source: impl Preview,
+    size: (u32, u32),
+    theme: &Theme,
+    path: impl AsRef<Path>,
+) -> Result<(), image::ImageError> {
+    source
+        .render_preview(size, theme)
+        .save_with_format(path, ImageFormat::Png)
+}
+
+/// A frame kept by a [`FrameRecorder`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedFrame {
+    /// Number of frames passed to [`FrameRecorder::record`] before this one.
+    pub index: u64,
+    /// Animation time the frame was rendered for.
+    pub elapsed: Duration,
+    /// Copy of the frame as it was passed to [`FrameRecorder::record`].
+    pub image: RgbaImage,
+}
+
+/// Captures rendered frames for offline inspection.
+///
+/// Pass every frame to [`record`](Self::record); every N-th is copied and
+/// kept. Beyond a capacity the oldest frames are dropped, so a recorder can
+/// be left running and dumped when a glitch is noticed.
+///
+/// ```ignore
+/// let mut recorder = FrameRecorder::new(2).with_capacity(120);
+///
+/// // Each frame:
+/// let frame = pipeline.render(elapsed);
+/// recorder.record(&frame, elapsed);
+///
+/// // On a debug key press:
+/// recorder.save_gif("jank.gif")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrameRecorder {
+    every: u64,
+    capacity: usize,
+    seen: u64,
+    frames: VecDeque<RecordedFrame>,
+}
+
+impl FrameRecorder {
+    /// Frames kept by default, 10 seconds at 30 fps.
+    pub const DEFAULT_CAPACITY: usize = 300;
+
+    /// Keep every `every`-th frame (every frame for 0 or 1), up to
+    /// [`DEFAULT_CAPACITY`](Self::DEFAULT_CAPACITY).
+    pub fn new(every: u64) -> Self {
+        Self {
+            every: every.max(1),
+            capacity: Self::DEFAULT_CAPACITY,
+            seen: 0,
+            frames: VecDeque::new(),
+        }
+    }
+
+    /// Keep at most `frames` frames (at least 1), dropping the oldest.
+    pub fn with_capacity(mut self, frames: usize) -> Self {
+        self.capacity = frames.max(1);
+        self.trim();
+        self
+    }
+
+    /// Offer a rendered frame; it is copied if it is due.
+    pub fn record(&mut self, frame: &RgbaImage, elapsed: Duration) {
+        let index = self.seen;
+        self.seen += 1;
+        if !index.is_multiple_of(self.every) {
+            return;
+        }
+        self.frames.push_back(RecordedFrame {
+            index,
+            elapsed,
+            image: frame.clone(),
+        });
+        self.trim();
+    }
+
+    /// The kept frames, oldest first.
+    pub fn frames(&self) -> impl Iterator<Item = &RecordedFrame> {
+        self.frames.iter()
+    }
+
+    /// Number of kept frames.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Whether no frames are kept.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Drop all kept frames. Frame numbering continues.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// Save the kept frames as PNGs in `dir`, creating it if needed.
+    ///
+    /// Files are named `frame-<index>-<elapsed ms>ms.png`, zero-padded so
+    /// they sort in order. Returns the paths written.
+    pub fn save_pngs(&self, dir: impl AsRef<Path>) -> Result<Vec<PathBuf>, image::ImageError> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        self.frames
+            .iter()
+            .map(|frame| {
+                let name = format!(
+                    "frame-{:06}-{:07}ms.png",
+                    frame.index,
+                    frame.elapsed.as_millis()
+                );
+                let path = dir.join(name);
+                frame.image.save_with_format(&path, ImageFormat::Png)?;
+                Ok(path)
+            })
+            .collect()
+    }
+
+    /// Save the kept frames as a looping animated GIF at `path`.
+    ///
+    /// Each frame is shown until the next one's timestamp, so playback runs
+    /// at the recorded speed; the last frame repeats the previous delay.
+    /// GIF has 256 colors per frame and 1-bit transparency, which is fine
+    /// for spotting timing problems but not for judging colors.
+    pub fn save_gif(&self, path: impl AsRef<Path>) -> Result<(), image::ImageError> {
+        let mut encoder = GifEncoder::new(BufWriter::new(File::create(path)?));
+        encoder.set_repeat(Repeat::Infinite)?;
+        let mut delay = Duration::from_millis(100);
+        for (i, frame) in self.frames.iter().enumerate() {
+            if let Some(next) = self.frames.get(i + 1) {
+                delay = next.elapsed.saturating_sub(frame.elapsed);
+            }
+            encoder.encode_frame(Frame::from_parts(
+                frame.image.clone(),
+                0,
+                0,
+                Delay::from_saturating_duration(delay),
+            ))?;
+        }
+        Ok(())
+    }
+
+    fn trim(&mut self) {
+        while self.frames.len() > self.capacity {
+            self.frames.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::Element;
+
+    #[test]
+    fn test_closure_preview_starts_from_background() {
+        let theme = Theme::default();
+        let img = (|img: &mut RgbaImage, theme: &Theme| img.put_pixel(0, 0, theme.accent))
+            .render_preview((4, 4), &theme);
+        assert_eq!(*img.get_pixel(0, 0), theme.accent);
+        assert_eq!(*img.get_pixel(3, 3), theme.bg);
+    }
+
+    #[test]
+    fn test_render_to_png_round_trip() {
+        let path = std::env::temp_dir().join(format!("vpu-preview-{}.png", std::process::id()));
+        let spec = Spec::column().with_padding(0).with(Element::bar(1.0));
+        let theme = Theme::default();
+        render_to_png(&spec, (20, 20), &theme, &path).unwrap();
+
+        let saved = image::open(&path).unwrap().to_rgba8();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(saved, render(&spec, (20, 20), &theme));
+        assert!(saved.pixels().any(|p| *p == theme.accent));
+    }
+
+    #[test]
+    fn test_frame_recorder_keeps_every_nth_up_to_capacity() {
+        let mut recorder = FrameRecorder::new(3).with_capacity(3);
+        for i in 0..12u64 {
+            let frame = RgbaImage::from_pixel(2, 2, image::Rgba([i as u8, 0, 0, 255]));
+            recorder.record(&frame, Duration::from_millis(i * 20));
+        }
+        let kept: Vec<_> = recorder.frames().map(|f| (f.index, f.elapsed)).collect();
+        assert_eq!(kept, [3, 6, 9].map(|i| (i, Duration::from_millis(i * 20))));
+        assert_eq!(
+            recorder.frames().last().unwrap().image.get_pixel(0, 0)[0],
+            9
+        );
+    }
+
+    #[test]
+    fn test_frame_recorder_saves_pngs_and_gif() {
+        use image::AnimationDecoder;
+        use image::codecs::gif::GifDecoder;
+
+        let dir = std::env::temp_dir().join(format!("vpu-recorder-{}", std::process::id()));
+        let mut recorder = FrameRecorder::new(1);
+        for i in 0..3u8 {
+            let frame = RgbaImage::from_pixel(4, 4, image::Rgba([i * 100, 50, 0, 255]));
+            recorder.record(&frame, Duration::from_millis(i as u64 * 40));
+        }
+        let paths = recorder.save_pngs(&dir).unwrap();
+        assert_eq!(paths[2], dir.join("frame-000002-0000080ms.png"));
+        let saved = image::open(&paths[1]).unwrap().to_rgba8();
+        assert_eq!(saved, recorder.frames().nth(1).unwrap().image);
+
+        let gif = dir.join("anim.gif");
+        recorder.save_gif(&gif).unwrap();
+        let decoder = GifDecoder::new(std::io::BufReader::new(File::open(&gif).unwrap())).unwrap();
+        let frames = decoder.into_frames().collect_frames().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].delay().numer_denom_ms(), (40, 1));
+    }
+}
@@ -0,0 +1,321 @@
+//! Hot reloading of file-backed assets during development.
+//!
+//! Files are watched by polling their modification times, so no platform
+//! notification service is needed. Polls are rate-limited by an interval
+//! (250ms by default), making it cheap to poll every frame.
+//!
+//! [`HotAsset`] wraps a single loaded value (a theme, font, image or
+//! template) and reloads it when its file changes. [`AssetWatcher`] tracks
+//! a set of files and exposes a "changed" flag for triggering re-renders;
+//! pair it with cache invalidation such as
+//! [`ScaledImageCache::clear`](crate::image::ScaledImageCache::clear).
+//!
+//! ```ignore
+//! let mut template = HotAsset::new("widget.json", |p| Template::load(p))?;
+//! let mut icon = HotAsset::new("icon.png", |p| image::open(p).map(|i| i.to_rgba8()))?;
+//!
+//! // Each frame:
+//! if template.poll() | icon.poll() {
+//!     needs_render = true;
+//! }
+//! let img = template.get_mut().render(&values, (72, 72), &theme)?;
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Default minimum time between file system checks.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Rate limit for file system checks.
+#[derive(Debug, Clone)]
+struct Throttle {
+    interval: Duration,
+    last: Option<Instant>,
+}
+
+impl Throttle {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last: None,
+        }
+    }
+
+    /// Whether a check is due now; records the check if so.
+    fn ready(&mut self) -> bool {
+        let now = Instant::now();
+        if self.last.is_some_and(|last| now - last < self.interval) {
+            return false;
+        }
+        self.last = Some(now);
+        true
+    }
+}
+
+/// Watches a set of files and raises a flag when any of them changes.
+///
+/// A file counts as changed when its modification time differs from the
+/// last check, including when it is created or deleted.
+#[derive(Debug, Clone)]
+pub struct AssetWatcher {
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+    changed: bool,
+    throttle: Throttle,
+}
+
+impl AssetWatcher {
+    /// Create a watcher polling at most every [`DEFAULT_POLL_INTERVAL`].
+    pub fn new() -> Self {
+        Self::with_interval(DEFAULT_POLL_INTERVAL)
+    }
+
+    /// Create a watcher polling at most once per `interval`.
+    pub fn with_interval(interval: Duration) -> Self {
+        Self {
+            files: Vec::new(),
+            changed: false,
+            throttle: Throttle::new(interval),
+        }
+    }
+
+    /// Start watching `path`. Watching the same path twice has no effect.
+    pub fn watch(&mut self, path: impl Into<PathBuf>) {
+        let path = path.into();
+        if !self.files.iter().any(|(p, _)| *p == path) {
+            let mtime = modified(&path);
+            self.files.push((path, mtime));
+        }
+    }
+
+    /// Stop watching `path`.
+    pub fn unwatch(&mut self, path: impl AsRef<Path>) {
+        self.files.retain(|(p, _)| p != path.as_ref());
+    }
+
+    /// Watched paths.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|(p, _)| p.as_path())
+    }
+
+    /// Check the watched files, returning the paths that changed since the
+    /// last check. Returns nothing if called again within the poll interval.
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        if !self.throttle.ready() {
+            return Vec::new();
+        }
+        let mut changed = Vec::new();
+        for (path, mtime) in &mut self.files {
+            let now = modified(path);
+            if now != *mtime {
+                *mtime = now;
+                changed.push(path.clone());
+            }
+        }
+        self.changed |= !changed.is_empty();
+        changed
+    }
+
+    /// Whether any file has changed since the flag was last cleared.
+    ///
+    /// Polls first, so this is all a plugin needs to call each frame.
+    pub fn changed(&mut self) -> bool {
+        self.poll();
+        self.changed
+    }
+
+    /// Return the changed flag and clear it.
+    pub fn take_changed(&mut self) -> bool {
+        self.poll();
+        std::mem::take(&mut self.changed)
+    }
+}
+
+impl Default for AssetWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+type Loader<T, E> = Box<dyn FnMut(&Path) -> Result<T, E>>;
+
+/// A value loaded from a file and reloaded when the file changes.
+///
+/// If a reload fails (e.g. a half-saved file), the previous value is kept
+/// and the error is available from [`last_error`](Self::last_error) until
+/// the next successful load.
+pub struct HotAsset<T, E> {
+    path: PathBuf,
+    loader: Loader<T, E>,
+    value: T,
+    mtime: Option<SystemTime>,
+    error: Option<E>,
+    throttle: Throttle,
+}
+
+impl<T, E> HotAsset<T, E> {
+    /// Load `path` with `loader`, polling for changes at most every
+    /// [`DEFAULT_POLL_INTERVAL`].
+    pub fn new(
+        path: impl Into<PathBuf>,
+        loader: impl FnMut(&Path) -> Result<T, E> + 'static,
+    ) -> Result<Self, E> {
+        Self::with_interval(path, loader, DEFAULT_POLL_INTERVAL)
+    }
+
+    /// Load `path` with `loader`, polling for changes at most once per `interval`.
+    pub fn with_interval(
+        path: impl Into<PathBuf>,
+        mut loader: impl FnMut(&Path) -> Result<T, E> + 'static,
+        interval: Duration,
+    ) -> Result<Self, E> {
+        let path = path.into();
+        let mtime = modified(&path);
+        let value = loader(&path)?;
+        Ok(Self {
+            path,
+            loader: Box::new(loader),
+            value,
+            mtime,
+            error: None,
+            throttle: Throttle::new(interval),
+        })
+    }
+
+    /// Reload if the file has changed. Returns `true` if the value was replaced.
+    pub fn poll(&mut self) -> bool {
+        if !self.throttle.ready() {
+            return false;
+        }
+        let mtime = modified(&self.path);
+        if mtime == self.mtime {
+            return false;
+        }
+        self.mtime = mtime;
+        self.reload()
+    }
+
+    /// Reload unconditionally. Returns `true` on success.
+    pub fn reload(&mut self) -> bool {
+        match (self.loader)(&self.path) {
+            Ok(value) => {
+                self.value = value;
+                self.error = None;
+                true
+            }
+            Err(e) => {
+                self.error = Some(e);
+                false
+            }
+        }
+    }
+
+    /// The current value.
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// The current value, mutably (e.g. for assets with internal caches).
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    /// The watched file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The error from the most recent failed reload, if any.
+    pub fn last_error(&self) -> Option<&E> {
+        self.error.as_ref()
+    }
+}
+
+impl<T: std::fmt::Debug, E: std::fmt::Debug> std::fmt::Debug for HotAsset<T, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HotAsset")
+            .field("path", &self.path)
+            .field("value", &self.value)
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("vpu-reload-{}-{name}", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    /// Rewrite a file with a modification time that is guaranteed to differ.
+    fn touch(path: &Path, contents: &str, secs: u64) {
+        std::fs::write(path, contents).unwrap();
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_watcher_flags_changes() {
+        let path = temp_file("watch.txt", "a");
+        let mut watcher = AssetWatcher::with_interval(Duration::ZERO);
+        watcher.watch(&path);
+        watcher.watch(&path);
+        assert_eq!(watcher.paths().count(), 1);
+        assert!(!watcher.take_changed());
+
+        touch(&path, "b", 1_000);
+        assert!(watcher.changed());
+        assert!(watcher.take_changed());
+        assert!(!watcher.changed());
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(watcher.poll(), vec![path]);
+    }
+
+    #[test]
+    fn test_watcher_respects_interval() {
+        let path = temp_file("interval.txt", "a");
+        let mut watcher = AssetWatcher::with_interval(Duration::from_secs(3600));
+        watcher.watch(&path);
+        assert!(watcher.poll().is_empty());
+        touch(&path, "b", 2_000);
+        // Second check falls inside the interval
+        assert!(watcher.poll().is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_hot_asset_reloads_and_keeps_last_good_value() {
+        let path = temp_file("asset.txt", "1");
+        let mut asset = HotAsset::with_interval(
+            &path,
+            |p| {
+                let text = std::fs::read_to_string(p).map_err(|e| e.to_string())?;
+                text.trim().parse::<u32>().map_err(|e| e.to_string())
+            },
+            Duration::ZERO,
+        )
+        .unwrap();
+        assert_eq!(*asset.get(), 1);
+        assert!(!asset.poll());
+
+        touch(&path, "2", 1_000);
+        assert!(asset.poll());
+        assert_eq!(*asset.get(), 2);
+
+        touch(&path, "oops", 2_000);
+        assert!(!asset.poll());
+        assert_eq!(*asset.get(), 2);
+        assert!(asset.last_error().is_some());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
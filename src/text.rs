@@ -1,11 +1,24 @@
 //! Text rendering utilities.
 //!
-//! Provides functions for measuring and drawing text on images.
+//! Provides functions for measuring and drawing text on images, and for
+//! preparing strings to draw: sanitizing untrusted input (`sanitize`
+//! feature) and laying out key/value lists for the monospace font.
+
+use std::collections::HashMap;
+use std::ops::Range;
+#[cfg(feature = "sanitize")]
+use std::sync::LazyLock;
 
 use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
-use image::{Rgba, RgbaImage};
-use imageproc::drawing::draw_text_mut;
+use image::{GrayImage, Luma, Rgba, RgbaImage};
+use imageproc::drawing::{draw_filled_rect_mut, draw_text_mut};
+use imageproc::rect::Rect;
+#[cfg(feature = "sanitize")]
+use unicode_normalization::UnicodeNormalization;
 
+use crate::accessibility::{HIGH_CONTRAST_RATIO, high_contrast, motion_progress, show_decoration};
+use crate::colors::{luminance_contrast, relative_luminance};
+use crate::debug::{self, DebugMark, debug_overlay};
 use crate::font::get_system_monospace_font;
 
 /// Calculate the width of a line of text using actual font metrics.
@@ -53,6 +66,244 @@ where
     scale_for_width.min(scale_for_height).clamp(8.0, 96.0)
 }
 
+/// Placeholder [`sanitize_for_display`] puts in place of characters the font
+/// has no glyph for.
+#[cfg(feature = "sanitize")]
+pub const DEFAULT_PLACEHOLDER: char = '?';
+
+/// Clean an untrusted string (a song title, a notification body) for
+/// drawing with the system monospace font.
+///
+/// See [`sanitize_for_display_with`]; characters the font cannot draw become
+/// [`DEFAULT_PLACEHOLDER`]. If the system font is unavailable, glyphs are
+/// not checked.
+#[cfg(feature = "sanitize")]
+pub fn sanitize_for_display(s: &str) -> String {
+    // Parsed once; the font's bytes are already cached for the process
+    static FONT: LazyLock<Option<FontRef<'static>>> = LazyLock::new(|| {
+        get_system_monospace_font().and_then(|bytes| FontRef::try_from_slice(bytes).ok())
+    });
+    sanitize(
+        s,
+        |c| FONT.as_ref().is_none_or(|f| f.glyph_id(c).0 != 0),
+        DEFAULT_PLACEHOLDER,
+    )
+}
+
+/// Clean an untrusted string for drawing with `font`.
+///
+/// - Normalizes to Unicode NFC, so decomposed accents render as one glyph
+/// - Drops control and invisible formatting characters (including bidi
+///   overrides and zero-width spaces), which would otherwise draw as boxes
+///   or reorder the text
+/// - Collapses each run of whitespace, line breaks included, to one space
+///   and trims both ends
+/// - Replaces characters `font` has no glyph for with `placeholder`
+#[cfg(feature = "sanitize")]
+pub fn sanitize_for_display_with<F: Font>(s: &str, font: &F, placeholder: char) -> String {
+    sanitize(s, |c| font.glyph_id(c).0 != 0, placeholder)
+}
+
+#[cfg(feature = "sanitize")]
+fn sanitize(s: &str, supported: impl Fn(char) -> bool, placeholder: char) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut space = false;
+    for c in s.nfc() {
+        if c.is_whitespace() {
+            space = !out.is_empty();
+            continue;
+        }
+        if c.is_control() || is_invisible_format(c) {
+            continue;
+        }
+        if std::mem::take(&mut space) {
+            out.push(' ');
+        }
+        out.push(if supported(c) { c } else { placeholder });
+    }
+    out
+}
+
+/// Zero-width and directional formatting characters.
+#[cfg(feature = "sanitize")]
+fn is_invisible_format(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}' | '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}'..='\u{206F}'
+            | '\u{FEFF}' | '\u{FFF9}'..='\u{FFFB}'
+    )
+}
+
+/// Spaces between the label and value columns in [`format_kv`].
+pub const KV_GAP: usize = 2;
+
+/// Marks text cut short by [`format_kv`].
+const ELLIPSIS: char = '…';
+
+/// Format `(label, value)` pairs as aligned `label  value` lines at most
+/// `width_chars` characters wide, for settings and status lists drawn with
+/// the monospace font.
+///
+/// Labels are padded to a common column and values start [`KV_GAP`]
+/// spaces after it. When a line would be too wide:
+///
+/// - labels are shortened first, down to a third of the width, since the
+///   values are usually what the list is for
+/// - anything still too long is cut and ends with `…`
+///
+/// Lines have no trailing spaces. Below `KV_GAP + 2` characters there is
+/// no room for both columns, and each line is just its (shortened) label.
+///
+/// ```ignore
+/// let lines = format_kv(&[("CPU", "42%"), ("Memory", "3.1 GiB"), ("Uptime", "5d 2h")], 16);
+/// // "CPU     42%"
+/// // "Memory  3.1 GiB"
+/// // "Uptime  5d 2h"
+/// draw_centered_text(&mut img, &lines.join("\n"), theme.fg, 0.05);
+/// ```
+pub fn format_kv(entries: &[(&str, &str)], width_chars: usize) -> Vec<String> {
+    let longest =
+        |column: &dyn Fn(&(&str, &str)) -> usize| entries.iter().map(column).max().unwrap_or(0);
+    let longest_label = longest(&|(label, _)| label.chars().count());
+    let longest_value = longest(&|(_, value)| value.chars().count());
+    if width_chars < KV_GAP + 2 {
+        return entries
+            .iter()
+            .map(|(label, _)| truncate_chars(label, width_chars))
+            .collect();
+    }
+    let available = width_chars - KV_GAP;
+    let label_width = longest_label.min(available.saturating_sub(longest_value).max(available / 3));
+    let value_width = available - label_width;
+    entries
+        .iter()
+        .map(|(label, value)| {
+            let label = truncate_chars(label, label_width);
+            let value = truncate_chars(value, value_width);
+            let line = format!("{label:<label_width$}{:KV_GAP$}{value}", "");
+            line.trim_end().to_string()
+        })
+        .collect()
+}
+
+/// `s` cut to at most `max` characters, ending with [`ELLIPSIS`] if cut.
+fn truncate_chars(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        return s.to_string();
+    }
+    let mut cut: String = s.chars().take(max.saturating_sub(1)).collect();
+    if max > 0 {
+        cut.push(ELLIPSIS);
+    }
+    cut
+}
+
+/// A single positioned line of text.
+struct PlacedLine<'a> {
+    text: &'a str,
+    x: i32,
+    y: i32,
+    width: f32,
+}
+
+/// Layout of centered multi-line text at a chosen scale.
+struct CenteredLayout<'a> {
+    scale: PxScale,
+    line_height: f32,
+    lines: Vec<PlacedLine<'a>>,
+}
+
+impl CenteredLayout<'_> {
+    /// Bounding box of all lines, clipped to the image, as (x0, y0, x1, y1).
+    fn bounds(&self, width: u32, height: u32) -> Option<(u32, u32, u32, u32)> {
+        let x0 = self.lines.iter().map(|l| l.x).min()?.max(0);
+        let y0 = self.lines.iter().map(|l| l.y).min()?.max(0);
+        let x1 = self
+            .lines
+            .iter()
+            .map(|l| l.x + l.width.ceil() as i32)
+            .max()?
+            .min(width as i32);
+        let y1 = self
+            .lines
+            .iter()
+            .map(|l| l.y + self.line_height.ceil() as i32)
+            .max()?
+            .min(height as i32);
+        (x1 > x0 && y1 > y0).then_some((x0 as u32, y0 as u32, x1 as u32, y1 as u32))
+    }
+}
+
+/// Record the text box and line baselines for the debug overlay.
+fn mark_layout<F: Font>(layout: &CenteredLayout<'_>, font: &F, width: u32, height: u32) {
+    if let Some((x0, y0, x1, y1)) = layout.bounds(width, height) {
+        debug::mark(
+            DebugMark::TextBounds,
+            Rect::at(x0 as i32, y0 as i32).of_size(x1 - x0, y1 - y0),
+        );
+    }
+    let ascent = font.as_scaled(layout.scale).ascent().round() as i32;
+    for line in &layout.lines {
+        let baseline =
+            Rect::at(line.x, line.y + ascent).of_size(line.width.ceil().max(1.0) as u32, 1);
+        debug::mark(DebugMark::Baseline, baseline);
+    }
+}
+
+/// Compute the centered layout for `lines` within an image of the given size.
+fn layout_centered<'a, F>(
+    font: &F,
+    lines: &[&'a str],
+    width: u32,
+    height: u32,
+    padding: f32,
+) -> CenteredLayout<'a>
+where
+    F: Font,
+{
+    // Find optimal scale to fill the image with specified padding on each side
+    let content_fraction = 1.0 - (2.0 * padding);
+    let target_width = width as f32 * content_fraction;
+    let target_height = height as f32 * content_fraction;
+    let scale_value = find_optimal_scale(font, lines, target_width, target_height);
+    let scale = PxScale::from(scale_value);
+
+    // Get actual metrics at the chosen scale
+    let scaled_font = font.as_scaled(scale);
+    let line_height = scaled_font.height();
+    let num_lines = lines.len() as f32;
+    let total_height = num_lines * line_height;
+
+    // Center vertically
+    let start_y = (height as f32 - total_height) / 2.0;
+
+    let lines = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            // Calculate actual line width using font metrics
+            let line_width: f32 = line
+                .chars()
+                .map(|c| scaled_font.h_advance(font.glyph_id(c)))
+                .sum();
+
+            // Center horizontally
+            PlacedLine {
+                text: line,
+                x: ((width as f32 - line_width) / 2.0).max(0.0) as i32,
+                y: (start_y + i as f32 * line_height) as i32,
+                width: line_width,
+            }
+        })
+        .collect();
+
+    CenteredLayout {
+        scale,
+        line_height,
+        lines,
+    }
+}
+
 /// Draw text centered on an image.
 ///
 /// # Arguments
@@ -61,6 +312,123 @@ where
 /// * `fg_color` - The foreground (text) color
 /// * `padding` - Padding as a fraction of image size (0.0 to 0.4)
 pub fn draw_centered_text(rgba: &mut RgbaImage, text: &str, fg_color: Rgba<u8>, padding: f32) {
+    draw_centered_text_legible(rgba, text, fg_color, padding, TextLegibility::None);
+}
+
+/// Treatments that keep text readable over arbitrary backgrounds.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TextLegibility {
+    /// Draw the text as-is.
+    #[default]
+    None,
+    /// Surround glyphs with an outline `width` pixels thick.
+    Outline { color: Rgba<u8>, width: u32 },
+    /// Draw a drop shadow offset by `offset` pixels.
+    Shadow { color: Rgba<u8>, offset: (i32, i32) },
+    /// Fill a box behind the text, blended using the color's alpha.
+    Backing { color: Rgba<u8>, padding: u32 },
+    /// Inspect the background behind the text and apply the minimal
+    /// combination of outline, shadow and backing box needed to reach
+    /// [`AUTO_CONTRAST_TARGET`].
+    Auto,
+}
+
+/// Contrast ratio targeted by [`TextLegibility::Auto`] (WCAG AA for normal text).
+pub const AUTO_CONTRAST_TARGET: f32 = 4.5;
+
+/// Concrete combination of treatments applied when drawing.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+struct Treatment {
+    backing: Option<(Rgba<u8>, u32)>,
+    shadow: Option<(Rgba<u8>, (i32, i32))>,
+    outline: Option<(Rgba<u8>, u32)>,
+}
+
+/// Luminance statistics of a background region.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BackgroundStats {
+    mean: f32,
+    low: f32,
+    high: f32,
+}
+
+impl BackgroundStats {
+    /// Sample the region (x0, y0)..(x1, y1), using 10th/90th percentiles as extremes.
+    fn sample(rgba: &RgbaImage, (x0, y0, x1, y1): (u32, u32, u32, u32)) -> Option<Self> {
+        let mut lums: Vec<f32> = (y0..y1)
+            .flat_map(|y| (x0..x1).map(move |x| (x, y)))
+            .map(|(x, y)| relative_luminance(*rgba.get_pixel(x, y)))
+            .collect();
+        if lums.is_empty() {
+            return None;
+        }
+        lums.sort_by(f32::total_cmp);
+        let n = lums.len();
+        Some(Self {
+            mean: lums.iter().sum::<f32>() / n as f32,
+            low: lums[n / 10],
+            high: lums[(n * 9 / 10).min(n - 1)],
+        })
+    }
+}
+
+/// Pick the minimal treatment for `fg` over a background with `stats`.
+///
+/// With `high_contrast` the target is [`HIGH_CONTRAST_RATIO`] and any
+/// backing box is opaque.
+fn auto_treatment(fg: Rgba<u8>, stats: BackgroundStats, high_contrast: bool) -> Treatment {
+    let target = if high_contrast {
+        HIGH_CONTRAST_RATIO
+    } else {
+        AUTO_CONTRAST_TARGET
+    };
+    let fg_lum = relative_luminance(fg);
+    let worst = luminance_contrast(fg_lum, stats.low).min(luminance_contrast(fg_lum, stats.high));
+    if worst >= target {
+        return Treatment::default();
+    }
+
+    // Halo is whichever of black or white contrasts best with the text
+    let halo = if luminance_contrast(fg_lum, 0.0) > luminance_contrast(fg_lum, 1.0) {
+        Rgba([0, 0, 0, 255])
+    } else {
+        Rgba([255, 255, 255, 255])
+    };
+    let mean = luminance_contrast(fg_lum, stats.mean);
+    let backing_alpha = if high_contrast { 255 } else { 180 };
+
+    if mean >= target {
+        // Only parts of a busy background clash; a thin outline is enough
+        Treatment {
+            outline: Some((halo, 1)),
+            ..Default::default()
+        }
+    } else if mean >= target / 2.0 {
+        Treatment {
+            outline: Some((halo, 1)),
+            shadow: Some((halo, (1, 1))),
+            ..Default::default()
+        }
+    } else {
+        Treatment {
+            backing: Some((Rgba([halo[0], halo[1], halo[2], backing_alpha]), 2)),
+            ..Default::default()
+        }
+    }
+}
+
+/// Draw text centered on an image with a legibility treatment.
+///
+/// Layout matches [`draw_centered_text`]; the treatment is drawn beneath
+/// the text. In [high-contrast mode](crate::accessibility) untreated text
+/// and faint shadows or backings are upgraded to [`TextLegibility::Auto`].
+pub fn draw_centered_text_legible(
+    rgba: &mut RgbaImage,
+    text: &str,
+    fg_color: Rgba<u8>,
+    padding: f32,
+    legibility: TextLegibility,
+) {
     let Some(font_bytes) = get_system_monospace_font() else {
         return;
     };
@@ -76,34 +444,200 @@ pub fn draw_centered_text(rgba: &mut RgbaImage, text: &str, fg_color: Rgba<u8>,
         return;
     }
 
-    // Find optimal scale to fill the image with specified padding on each side
-    let content_fraction = 1.0 - (2.0 * padding);
-    let target_width = width as f32 * content_fraction;
-    let target_height = height as f32 * content_fraction;
-    let scale_value = find_optimal_scale(&font, &lines, target_width, target_height);
-    let scale = PxScale::from(scale_value);
+    let layout = layout_centered(&font, &lines, width, height, padding);
+    if debug_overlay() {
+        mark_layout(&layout, &font, width, height);
+    }
 
-    // Get actual metrics at the chosen scale
-    let scaled_font = font.as_scaled(scale);
-    let line_height = scaled_font.height();
-    let num_lines = lines.len() as f32;
-    let total_height = num_lines * line_height;
+    // High-contrast mode always checks the background and replaces faint
+    // decorations with whatever treatment the background needs
+    let legibility = match legibility {
+        TextLegibility::None if high_contrast() => TextLegibility::Auto,
+        TextLegibility::Shadow { color, .. } | TextLegibility::Backing { color, .. }
+            if !show_decoration(color[3]) =>
+        {
+            TextLegibility::Auto
+        }
+        other => other,
+    };
+    let treatment = match legibility {
+        TextLegibility::None => Treatment::default(),
+        TextLegibility::Outline { color, width } => Treatment {
+            outline: Some((color, width)),
+            ..Default::default()
+        },
+        TextLegibility::Shadow { color, offset } => Treatment {
+            shadow: Some((color, offset)),
+            ..Default::default()
+        },
+        TextLegibility::Backing { color, padding } => Treatment {
+            backing: Some((color, padding)),
+            ..Default::default()
+        },
+        TextLegibility::Auto => layout
+            .bounds(width, height)
+            .and_then(|b| BackgroundStats::sample(rgba, b))
+            .map(|stats| auto_treatment(fg_color, stats, high_contrast()))
+            .unwrap_or_default(),
+    };
 
-    // Center vertically
-    let start_y = (height as f32 - total_height) / 2.0;
+    if let Some((color, pad)) = treatment.backing
+        && let Some((x0, y0, x1, y1)) = layout.bounds(width, height)
+    {
+        let x0 = x0.saturating_sub(pad);
+        let y0 = y0.saturating_sub(pad);
+        let x1 = (x1 + pad).min(width);
+        let y1 = (y1 + pad).min(height);
+        let a = color[3] as f32 / 255.0;
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let p = rgba.get_pixel_mut(x, y);
+                for c in 0..3 {
+                    p[c] = (p[c] as f32 * (1.0 - a) + color[c] as f32 * a).round() as u8;
+                }
+                p[3] = p[3].max(color[3]);
+            }
+        }
+    }
+
+    for line in &layout.lines {
+        if let Some((color, (dx, dy))) = treatment.shadow {
+            draw_text_mut(
+                rgba,
+                color,
+                line.x + dx,
+                line.y + dy,
+                layout.scale,
+                &font,
+                line.text,
+            );
+        }
+        if let Some((color, w)) = treatment.outline {
+            let w = w as i32;
+            for dy in -w..=w {
+                for dx in -w..=w {
+                    if dx != 0 || dy != 0 {
+                        draw_text_mut(
+                            rgba,
+                            color,
+                            line.x + dx,
+                            line.y + dy,
+                            layout.scale,
+                            &font,
+                            line.text,
+                        );
+                    }
+                }
+            }
+        }
+    }
+    for line in &layout.lines {
+        draw_text_mut(
+            rgba,
+            fg_color,
+            line.x,
+            line.y,
+            layout.scale,
+            &font,
+            line.text,
+        );
+    }
+}
+
+/// How [`draw_centered_text_reveal`] brings text into view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextReveal {
+    /// Characters appear one at a time, in reading order.
+    #[default]
+    Typewriter,
+    /// A mask sweeps left to right across the text, with a soft leading
+    /// edge `softness` pixels wide.
+    Wipe { softness: u32 },
+}
+
+/// Draw centered text partially revealed, for animated announcements.
+///
+/// `t` runs from 0.0 (nothing shown) to 1.0 (fully drawn) and is typically
+/// derived from the animation clock. Layout is computed for the full text,
+/// so characters stay in place as they appear. In [reduced-motion
+/// mode](crate::accessibility::set_reduced_motion) the text is drawn in
+/// full as soon as `t` passes 0.0.
+pub fn draw_centered_text_reveal(
+    rgba: &mut RgbaImage,
+    text: &str,
+    fg_color: Rgba<u8>,
+    padding: f32,
+    reveal: TextReveal,
+    t: f32,
+) {
+    let Some(font_bytes) = get_system_monospace_font() else {
+        return;
+    };
+    let Ok(font) = FontRef::try_from_slice(font_bytes) else {
+        return;
+    };
+
+    let width = rgba.width();
+    let height = rgba.height();
 
-    for (i, line) in lines.iter().enumerate() {
-        // Calculate actual line width using font metrics
-        let line_width: f32 = line
-            .chars()
-            .map(|c| scaled_font.h_advance(font.glyph_id(c)))
-            .sum();
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.is_empty() {
+        return;
+    }
+    let t = motion_progress(t.clamp(0.0, 1.0));
 
-        // Center horizontally
-        let text_x = ((width as f32 - line_width) / 2.0).max(0.0) as i32;
-        let text_y = (start_y + i as f32 * line_height) as i32;
+    let layout = layout_centered(&font, &lines, width, height, padding);
 
-        draw_text_mut(rgba, fg_color, text_x, text_y, scale, &font, line);
+    match reveal {
+        TextReveal::Typewriter => {
+            let total: usize = layout.lines.iter().map(|l| l.text.chars().count()).sum();
+            let mut remaining = (t * total as f32).floor() as usize;
+            for line in &layout.lines {
+                if remaining == 0 {
+                    break;
+                }
+                let visible = match line.text.char_indices().nth(remaining) {
+                    Some((end, _)) => &line.text[..end],
+                    None => line.text,
+                };
+                remaining -= visible.chars().count();
+                draw_text_mut(rgba, fg_color, line.x, line.y, layout.scale, &font, visible);
+            }
+        }
+        TextReveal::Wipe { softness } => {
+            let Some((x0, _, x1, _)) = layout.bounds(width, height) else {
+                return;
+            };
+            // Render coverage to a mask so the sweep can weight each pixel
+            let mut coverage = GrayImage::new(width, height);
+            for line in &layout.lines {
+                draw_text_mut(
+                    &mut coverage,
+                    Luma([255]),
+                    line.x,
+                    line.y,
+                    layout.scale,
+                    &font,
+                    line.text,
+                );
+            }
+            let softness = softness.max(1) as f32;
+            let edge = x0 as f32 + t * ((x1 - x0) as f32 + softness);
+            for (x, y, c) in coverage.enumerate_pixels() {
+                if c[0] == 0 {
+                    continue;
+                }
+                let sweep = ((edge - x as f32) / softness).clamp(0.0, 1.0);
+                let a = c[0] as f32 / 255.0 * sweep * fg_color[3] as f32 / 255.0;
+                if a > 0.0 {
+                    let p = rgba.get_pixel_mut(x, y);
+                    for i in 0..3 {
+                        p[i] = (p[i] as f32 * (1.0 - a) + fg_color[i] as f32 * a).round() as u8;
+                    }
+                    p[3] = p[3].max((a * 255.0).round() as u8);
+                }
+            }
+        }
     }
 }
 
@@ -164,6 +698,226 @@ pub fn draw_centered_text_with_reserved(
     draw_text_mut(rgba, fg_color, x, y, scale, &font, text);
 }
 
+/// A single line of monospace text that redraws only the characters that change.
+///
+/// Designed for clocks and counters where most of the line stays the same
+/// between frames. Each character occupies a fixed-width cell (the advance
+/// of '0' in the system monospace font); [`update`](Self::update) compares
+/// the new string against the previous one, clears and redraws only the
+/// changed cells, and reports the regions it touched.
+#[derive(Debug, Clone)]
+pub struct CachedTextLine {
+    x: i32,
+    y: i32,
+    scale: PxScale,
+    fg: Rgba<u8>,
+    bg: Rgba<u8>,
+    previous: Option<Vec<char>>,
+}
+
+impl CachedTextLine {
+    /// Create a line with its top-left corner at (`x`, `y`) and the given font scale.
+    ///
+    /// Changed cells are cleared to `bg` before the new glyph is drawn in `fg`.
+    pub fn new(x: i32, y: i32, scale: f32, fg: Rgba<u8>, bg: Rgba<u8>) -> Self {
+        Self {
+            x,
+            y,
+            scale: PxScale::from(scale),
+            fg,
+            bg,
+            previous: None,
+        }
+    }
+
+    /// Force the next update to redraw the whole line.
+    pub fn invalidate(&mut self) {
+        self.previous = None;
+    }
+
+    /// Draw `text`, redrawing only cells that differ from the previous update.
+    ///
+    /// Returns the dirty rectangles (one per run of adjacent changed cells),
+    /// clipped to the image. The first update, or the first after
+    /// [`invalidate`](Self::invalidate), redraws every cell. Returns no rects
+    /// if the system font is unavailable.
+    pub fn update(&mut self, rgba: &mut RgbaImage, text: &str) -> Vec<Rect> {
+        let Some(font_bytes) = get_system_monospace_font() else {
+            return Vec::new();
+        };
+        let Ok(font) = FontRef::try_from_slice(font_bytes) else {
+            return Vec::new();
+        };
+        if rgba.width() == 0 || rgba.height() == 0 {
+            return Vec::new();
+        }
+
+        let scaled_font = font.as_scaled(self.scale);
+        let cell_width = scaled_font.h_advance(font.glyph_id('0')).ceil().max(1.0) as u32;
+        let line_height = scaled_font.height().ceil().max(1.0) as u32;
+        let bounds = Rect::at(0, 0).of_size(rgba.width(), rgba.height());
+
+        let chars: Vec<char> = text.chars().collect();
+        // With nothing drawn yet, diffing against an empty line marks every cell
+        let previous = self.previous.as_deref().unwrap_or_default();
+        let runs = changed_runs(previous, &chars);
+
+        let mut dirty = Vec::with_capacity(runs.len());
+        for run in runs {
+            let left = self.x + (run.start as u32 * cell_width) as i32;
+            let rect = Rect::at(left, self.y).of_size(run.len() as u32 * cell_width, line_height);
+            let Some(clipped) = rect.intersect(bounds) else {
+                continue;
+            };
+            draw_filled_rect_mut(rgba, clipped, self.bg);
+            for i in run {
+                let Some(c) = chars.get(i) else {
+                    continue;
+                };
+                let cx = self.x + (i as u32 * cell_width) as i32;
+                let mut buf = [0u8; 4];
+                draw_text_mut(
+                    rgba,
+                    self.fg,
+                    cx,
+                    self.y,
+                    self.scale,
+                    &font,
+                    c.encode_utf8(&mut buf),
+                );
+            }
+            dirty.push(clipped);
+        }
+
+        self.previous = Some(chars);
+        dirty
+    }
+}
+
+/// Pre-rendered glyph coverage masks for the system monospace font at a fixed scale.
+///
+/// Glyphs are rasterized once on first use and then blitted from the cache,
+/// which keeps per-frame cost low for animated counters and clocks. Every
+/// glyph occupies a fixed-size cell (the advance of '0' by the line height).
+#[derive(Debug, Clone)]
+pub struct GlyphCache {
+    scale: PxScale,
+    cell_width: u32,
+    line_height: u32,
+    glyphs: HashMap<char, GrayImage>,
+}
+
+impl GlyphCache {
+    /// Create an empty cache for the given font scale.
+    ///
+    /// Returns None if the system font is unavailable.
+    pub fn new(scale: f32) -> Option<Self> {
+        let font = FontRef::try_from_slice(get_system_monospace_font()?).ok()?;
+        let scale = PxScale::from(scale);
+        let scaled_font = font.as_scaled(scale);
+        Some(Self {
+            scale,
+            cell_width: scaled_font.h_advance(font.glyph_id('0')).ceil().max(1.0) as u32,
+            line_height: scaled_font.height().ceil().max(1.0) as u32,
+            glyphs: HashMap::new(),
+        })
+    }
+
+    /// Cell size as `(width, height)`.
+    pub fn cell_size(&self) -> (u32, u32) {
+        (self.cell_width, self.line_height)
+    }
+
+    /// Number of glyphs rasterized so far.
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    /// Whether no glyphs have been rasterized yet.
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+
+    /// Coverage mask for `c`, rasterizing it on first use.
+    pub fn glyph(&mut self, c: char) -> &GrayImage {
+        let (scale, w, h) = (self.scale, self.cell_width, self.line_height);
+        self.glyphs.entry(c).or_insert_with(|| {
+            let mut mask = GrayImage::new(w, h);
+            if let Some(font) =
+                get_system_monospace_font().and_then(|b| FontRef::try_from_slice(b).ok())
+            {
+                let mut buf = [0u8; 4];
+                draw_text_mut(
+                    &mut mask,
+                    Luma([255]),
+                    0,
+                    0,
+                    scale,
+                    &font,
+                    c.encode_utf8(&mut buf),
+                );
+            }
+            mask
+        })
+    }
+
+    /// Draw `c` with its cell's top-left corner at (x, y), clipped to `clip`.
+    pub fn draw_clipped(
+        &mut self,
+        rgba: &mut RgbaImage,
+        c: char,
+        x: i32,
+        y: i32,
+        color: Rgba<u8>,
+        clip: Rect,
+    ) {
+        if rgba.width() == 0 || rgba.height() == 0 {
+            return;
+        }
+        let bounds = Rect::at(0, 0).of_size(rgba.width(), rgba.height());
+        let Some(clip) = clip.intersect(bounds) else {
+            return;
+        };
+        let mask = self.glyph(c);
+        for (gx, gy, m) in mask.enumerate_pixels() {
+            let px = x + gx as i32;
+            let py = y + gy as i32;
+            if m[0] == 0
+                || px < clip.left()
+                || px > clip.right()
+                || py < clip.top()
+                || py > clip.bottom()
+            {
+                continue;
+            }
+            let a = m[0] as f32 / 255.0 * color[3] as f32 / 255.0;
+            let p = rgba.get_pixel_mut(px as u32, py as u32);
+            for i in 0..3 {
+                p[i] = (p[i] as f32 * (1.0 - a) + color[i] as f32 * a).round() as u8;
+            }
+            p[3] = p[3].max((a * 255.0).round() as u8);
+        }
+    }
+}
+
+/// Runs of character cells that differ between `old` and `new`.
+///
+/// Cells beyond the end of the shorter string count as changed.
+fn changed_runs(old: &[char], new: &[char]) -> Vec<Range<usize>> {
+    let len = old.len().max(new.len());
+    let mut runs: Vec<Range<usize>> = Vec::new();
+    for i in 0..len {
+        if old.get(i) == new.get(i) {
+            continue;
+        }
+        match runs.last_mut() {
+            Some(run) if run.end == i => run.end = i + 1,
+            _ => runs.push(i..i + 1),
+        }
+    }
+    runs
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,6 +929,57 @@ mod tests {
         FontRef::try_from_slice(font_bytes).ok()
     }
 
+    #[test]
+    #[cfg(feature = "sanitize")]
+    fn test_sanitize_cleans_untrusted_text() {
+        let all = |_| true;
+        assert_eq!(sanitize("  Song\t\r\n  Title  ", all, '?'), "Song Title");
+        assert_eq!(
+            sanitize("a\u{0007}b\u{202E}c\u{200B}d\u{FEFF}", all, '?'),
+            "abcd"
+        );
+        // Decomposed e + combining acute composes to a single char
+        assert_eq!(sanitize("Cafe\u{0301}", all, '?'), "Caf\u{00E9}");
+        assert_eq!(sanitize("\u{0000}\n ", all, '?'), "");
+        assert_eq!(sanitize("ok 🎵", |c| c.is_ascii(), '_'), "ok _");
+    }
+
+    #[test]
+    fn test_format_kv_aligns_columns() {
+        let entries = [("CPU", "42%"), ("Memory", "3.1 GiB"), ("Uptime", "")];
+        assert_eq!(
+            format_kv(&entries, 20),
+            ["CPU     42%", "Memory  3.1 GiB", "Uptime"]
+        );
+        assert!(format_kv(&[], 20).is_empty());
+    }
+
+    #[test]
+    fn test_format_kv_truncates_labels_then_values() {
+        let entries = [("Temperature", "21.5 °C"), ("Wind", "12 km/h NW")];
+        // Labels shrink to fit the longest value
+        assert_eq!(
+            format_kv(&entries, 16),
+            ["Tem…  21.5 °C", "Wind  12 km/h NW"]
+        );
+        // Labels keep a third of the line; values are cut after that
+        assert_eq!(format_kv(&entries, 11), ["Te…  21.5 …", "Wi…  12 km…"]);
+        for line in format_kv(&entries, 11) {
+            assert!(line.chars().count() <= 11);
+        }
+        assert_eq!(format_kv(&entries, 3), ["Te…", "Wi…"]);
+        assert_eq!(format_kv(&entries, 0), ["", ""]);
+    }
+
+    #[test]
+    #[cfg(feature = "sanitize")]
+    fn test_sanitize_for_display_with_font() {
+        if let Some(font) = get_test_font() {
+            assert_eq!(sanitize_for_display_with("A\u{10FFFD}b", &font, '*'), "A*b");
+            assert_eq!(sanitize_for_display(" x\u{0085}y "), "x y");
+        }
+    }
+
     #[test]
     fn test_measure_text_width_empty() {
         if let Some(font) = get_test_font() {
@@ -210,4 +1015,182 @@ mod tests {
             assert!(scale >= 8.0);
         }
     }
+
+    fn stats(mean: f32, low: f32, high: f32) -> BackgroundStats {
+        BackgroundStats { mean, low, high }
+    }
+
+    #[test]
+    fn test_auto_treatment_none_on_contrasting_background() {
+        let t = auto_treatment(Rgba([255, 255, 255, 255]), stats(0.0, 0.0, 0.01), false);
+        assert_eq!(t, Treatment::default());
+    }
+
+    #[test]
+    fn test_auto_treatment_outline_on_busy_background() {
+        // Mostly dark background with a few bright patches
+        let t = auto_treatment(Rgba([255, 255, 255, 255]), stats(0.05, 0.0, 0.8), false);
+        assert_eq!(t.outline, Some((Rgba([0, 0, 0, 255]), 1)));
+        assert!(t.backing.is_none());
+    }
+
+    #[test]
+    fn test_auto_treatment_backing_on_matching_background() {
+        let t = auto_treatment(Rgba([255, 255, 255, 255]), stats(0.95, 0.9, 1.0), false);
+        assert!(t.backing.is_some());
+    }
+
+    #[test]
+    fn test_auto_treatment_high_contrast_target() {
+        // Mid grey on black passes AA but not the high-contrast target
+        let grey = Rgba([130, 130, 130, 255]);
+        assert_eq!(
+            auto_treatment(grey, stats(0.0, 0.0, 0.0), false),
+            Treatment::default()
+        );
+        let t = auto_treatment(grey, stats(0.0, 0.0, 0.0), true);
+        let backed = auto_treatment(Rgba([255, 255, 255, 255]), stats(0.95, 0.9, 1.0), true);
+        assert_ne!(t, Treatment::default());
+        assert_eq!(backed.backing.map(|(c, _)| c[3]), Some(255));
+    }
+
+    #[test]
+    fn test_background_stats_sample() {
+        let img = RgbaImage::from_pixel(4, 4, Rgba([255, 255, 255, 255]));
+        let s = BackgroundStats::sample(&img, (0, 0, 4, 4)).unwrap();
+        assert!((s.mean - 1.0).abs() < 1e-4);
+        assert!(BackgroundStats::sample(&img, (2, 2, 2, 2)).is_none());
+    }
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    fn lit_pixels(img: &RgbaImage) -> usize {
+        img.pixels().filter(|p| p[3] > 0).count()
+    }
+
+    #[test]
+    fn test_draw_centered_text_reveal_typewriter_progresses() {
+        if get_system_monospace_font().is_none() {
+            return;
+        }
+        let counts: Vec<usize> = [0.0, 0.5, 1.0]
+            .iter()
+            .map(|t| {
+                let mut img = RgbaImage::new(96, 32);
+                let color = Rgba([255, 255, 255, 255]);
+                draw_centered_text_reveal(
+                    &mut img,
+                    "ABCDEF",
+                    color,
+                    0.1,
+                    TextReveal::Typewriter,
+                    *t,
+                );
+                lit_pixels(&img)
+            })
+            .collect();
+        assert_eq!(counts[0], 0);
+        assert!(counts[0] < counts[1] && counts[1] < counts[2]);
+
+        let mut full = RgbaImage::new(96, 32);
+        draw_centered_text(&mut full, "ABCDEF", Rgba([255, 255, 255, 255]), 0.1);
+        assert_eq!(counts[2], lit_pixels(&full));
+    }
+
+    #[test]
+    fn test_draw_centered_text_reveal_wipe_left_first() {
+        if get_system_monospace_font().is_none() {
+            return;
+        }
+        let mut img = RgbaImage::new(96, 32);
+        let reveal = TextReveal::Wipe { softness: 4 };
+        draw_centered_text_reveal(
+            &mut img,
+            "MMMMMM",
+            Rgba([255, 255, 255, 255]),
+            0.1,
+            reveal,
+            0.5,
+        );
+        let lit_in = |xs: std::ops::Range<u32>| {
+            xs.flat_map(|x| (0..32).map(move |y| (x, y)))
+                .filter(|&(x, y)| img.get_pixel(x, y)[3] > 0)
+                .count()
+        };
+        // The sweep edge sits just past the middle of the text
+        assert!(lit_in(0..40) > 0);
+        assert_eq!(lit_in(60..96), 0);
+    }
+
+    #[test]
+    fn test_glyph_cache_rasterizes_once() {
+        let Some(mut cache) = GlyphCache::new(16.0) else {
+            return;
+        };
+        let (w, h) = cache.cell_size();
+        assert!(w > 0 && h > 0);
+        assert!(cache.glyph('8').pixels().any(|p| p[0] > 0));
+        assert!(cache.glyph(' ').pixels().all(|p| p[0] == 0));
+        cache.glyph('8');
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_glyph_cache_draw_clipped() {
+        let Some(mut cache) = GlyphCache::new(16.0) else {
+            return;
+        };
+        let mut img = RgbaImage::new(32, 32);
+        let clip = Rect::at(0, 0).of_size(32, 4);
+        cache.draw_clipped(&mut img, '#', 0, 0, Rgba([255, 255, 255, 255]), clip);
+        assert!(img.enumerate_pixels().all(|(_, y, p)| y < 4 || p[3] == 0));
+
+        // An empty image has nothing to draw into
+        let mut empty = RgbaImage::new(0, 0);
+        cache.draw_clipped(&mut empty, '#', 0, 0, Rgba([255, 255, 255, 255]), clip);
+    }
+
+    #[test]
+    fn test_changed_runs_single_digit() {
+        assert_eq!(changed_runs(&chars("12:34"), &chars("12:35")), vec![4..5]);
+    }
+
+    #[test]
+    fn test_changed_runs_merges_adjacent() {
+        assert_eq!(
+            changed_runs(&chars("12:59"), &chars("13:00")),
+            vec![1..2, 3..5]
+        );
+    }
+
+    #[test]
+    fn test_changed_runs_length_change() {
+        assert_eq!(changed_runs(&chars("99"), &chars("100")), vec![0..3]);
+        assert_eq!(changed_runs(&chars("100"), &chars("10")), vec![2..3]);
+        assert!(changed_runs(&chars("same"), &chars("same")).is_empty());
+    }
+
+    #[test]
+    fn test_cached_text_line_reports_dirty_rects() {
+        if get_test_font().is_none() {
+            return;
+        }
+        let mut img = RgbaImage::new(200, 40);
+        let mut line = CachedTextLine::new(0, 0, 20.0, Rgba([255; 4]), Rgba([0, 0, 0, 255]));
+
+        let first = line.update(&mut img, "12:34");
+        assert_eq!(first.len(), 1);
+
+        let second = line.update(&mut img, "12:35");
+        assert_eq!(second.len(), 1);
+        assert!(second[0].width() < first[0].width());
+        assert!(second[0].left() > first[0].left());
+
+        assert!(line.update(&mut img, "12:35").is_empty());
+
+        line.invalidate();
+        assert_eq!(line.update(&mut img, "12:35"), first);
+    }
 }
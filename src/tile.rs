@@ -0,0 +1,232 @@
+//! Fixed-size tiles.
+//!
+//! Most verandah keys are a fixed 72×72 or 96×96 pixels. [`Tile`] fixes
+//! its size in the type and stores its pixels inline, so a tile lives on the
+//! stack or inside whatever owns it, drawing into it never touches the heap,
+//! and pixel access needs no runtime size bookkeeping.
+//!
+//! Stable Rust cannot size an array from `W * H`, so the pixel count is a
+//! third parameter `N`, which must equal [`tile_len(W, H)`](tile_len). The
+//! [`Tile72`] and [`Tile96`] aliases fill it in.
+//!
+//! `Tile` implements [`GenericImage`], so imageproc's drawing functions
+//! (`draw_filled_rect_mut`, `draw_text_mut`, ...) work on it directly.
+
+use image::{GenericImage, GenericImageView, Rgba, RgbaImage};
+
+/// Pixel count of a `w`×`h` tile, for [`Tile`]'s `N` parameter.
+pub const fn tile_len(w: u32, h: u32) -> usize {
+    w as usize * h as usize
+}
+
+/// A fixed-size RGBA image of `W`×`H` pixels, stored inline.
+///
+/// `N` must be [`tile_len(W, H)`](tile_len); other values fail to compile
+/// when the tile is created.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Tile<const W: u32, const H: u32, const N: usize> {
+    /// Pixels in row-major order.
+    pixels: [Rgba<u8>; N],
+}
+
+/// 72×72 key tile.
+pub type Tile72 = Tile<72, 72, { tile_len(72, 72) }>;
+
+/// 96×96 key tile.
+pub type Tile96 = Tile<96, 96, { tile_len(96, 96) }>;
+
+impl<const W: u32, const H: u32, const N: usize> Tile<W, H, N> {
+    /// Tile width in pixels.
+    pub const WIDTH: u32 = W;
+    /// Tile height in pixels.
+    pub const HEIGHT: u32 = H;
+
+    /// Create a fully transparent tile.
+    pub const fn new() -> Self {
+        Self::filled(Rgba([0, 0, 0, 0]))
+    }
+
+    /// Create a tile filled with `color`.
+    pub const fn filled(color: Rgba<u8>) -> Self {
+        const { assert!(N == tile_len(W, H), "Tile pixel count must be W * H") };
+        Self { pixels: [color; N] }
+    }
+
+    /// Set every pixel to `color`.
+    pub fn fill(&mut self, color: Rgba<u8>) {
+        self.pixels.fill(color);
+    }
+
+    /// Pixels in row-major order.
+    pub fn pixels(&self) -> &[Rgba<u8>] {
+        &self.pixels
+    }
+
+    /// Row `y`, left to right.
+    ///
+    /// # Panics
+    /// Panics if `y` is not less than `H`.
+    pub fn row(&self, y: u32) -> &[Rgba<u8>] {
+        let start = y as usize * W as usize;
+        &self.pixels[start..start + W as usize]
+    }
+
+    /// Mutable row `y`, left to right.
+    ///
+    /// # Panics
+    /// Panics if `y` is not less than `H`.
+    pub fn row_mut(&mut self, y: u32) -> &mut [Rgba<u8>] {
+        let start = y as usize * W as usize;
+        &mut self.pixels[start..start + W as usize]
+    }
+
+    /// Iterate over all pixels mutably, in row-major order.
+    pub fn pixels_mut(&mut self) -> impl Iterator<Item = &mut Rgba<u8>> {
+        self.pixels.iter_mut()
+    }
+
+    /// Index of pixel (x, y) in `pixels`.
+    ///
+    /// # Panics
+    /// Panics if (x, y) is outside the tile.
+    fn index(x: u32, y: u32) -> usize {
+        assert!(x < W && y < H, "pixel ({x}, {y}) outside Tile<{W}, {H}>");
+        y as usize * W as usize + x as usize
+    }
+
+    /// Copy from an `RgbaImage` of exactly the tile's size.
+    ///
+    /// Returns `None` if the dimensions differ.
+    pub fn from_image(img: &RgbaImage) -> Option<Self> {
+        if img.dimensions() != (Self::WIDTH, Self::HEIGHT) {
+            return None;
+        }
+        let mut tile = Self::new();
+        for (dst, src) in tile.pixels_mut().zip(img.pixels()) {
+            *dst = *src;
+        }
+        Some(tile)
+    }
+
+    /// Copy into a newly allocated `RgbaImage`.
+    pub fn to_image(&self) -> RgbaImage {
+        let mut img = RgbaImage::new(Self::WIDTH, Self::HEIGHT);
+        self.write_to(&mut img);
+        img
+    }
+
+    /// Copy into an existing `RgbaImage`, resizing it if necessary.
+    pub fn write_to(&self, img: &mut RgbaImage) {
+        if img.dimensions() != (Self::WIDTH, Self::HEIGHT) {
+            *img = RgbaImage::new(Self::WIDTH, Self::HEIGHT);
+        }
+        for (dst, src) in img.pixels_mut().zip(self.pixels.iter()) {
+            *dst = *src;
+        }
+    }
+}
+
+impl<const W: u32, const H: u32, const N: usize> Default for Tile<W, H, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: u32, const H: u32, const N: usize> std::fmt::Debug for Tile<W, H, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Tile<{W}, {H}>")
+    }
+}
+
+impl<const W: u32, const H: u32, const N: usize> GenericImageView for Tile<W, H, N> {
+    type Pixel = Rgba<u8>;
+
+    fn dimensions(&self) -> (u32, u32) {
+        (Self::WIDTH, Self::HEIGHT)
+    }
+
+    fn get_pixel(&self, x: u32, y: u32) -> Rgba<u8> {
+        self.pixels[Self::index(x, y)]
+    }
+}
+
+impl<const W: u32, const H: u32, const N: usize> GenericImage for Tile<W, H, N> {
+    fn get_pixel_mut(&mut self, x: u32, y: u32) -> &mut Rgba<u8> {
+        &mut self.pixels[Self::index(x, y)]
+    }
+
+    fn put_pixel(&mut self, x: u32, y: u32, pixel: Rgba<u8>) {
+        self.pixels[Self::index(x, y)] = pixel;
+    }
+
+    fn blend_pixel(&mut self, x: u32, y: u32, pixel: Rgba<u8>) {
+        use image::Pixel;
+        self.pixels[Self::index(x, y)].blend(&pixel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imageproc::drawing::draw_filled_rect_mut;
+    use imageproc::rect::Rect;
+
+    #[test]
+    fn test_tile_dimensions() {
+        let tile = Tile72::new();
+        assert_eq!(tile.dimensions(), (72, 72));
+        assert_eq!(Tile96::WIDTH, 96);
+    }
+
+    #[test]
+    fn test_tile_imageproc_drawing() {
+        let mut tile = Tile::<8, 4, 32>::new();
+        draw_filled_rect_mut(
+            &mut tile,
+            Rect::at(2, 1).of_size(3, 2),
+            Rgba([255, 0, 0, 255]),
+        );
+        assert_eq!(tile.get_pixel(2, 1), Rgba([255, 0, 0, 255]));
+        assert_eq!(tile.get_pixel(0, 0), Rgba([0, 0, 0, 0]));
+        // Out-of-bounds parts are clipped by the drawing function
+        draw_filled_rect_mut(
+            &mut tile,
+            Rect::at(6, 2).of_size(10, 10),
+            Rgba([1, 1, 1, 1]),
+        );
+        assert_eq!(tile.get_pixel(7, 3), Rgba([1, 1, 1, 1]));
+    }
+
+    #[test]
+    fn test_tile_image_round_trip() {
+        let img = RgbaImage::from_fn(4, 3, |x, y| Rgba([x as u8, y as u8, 0, 255]));
+        let tile = Tile::<4, 3, 12>::from_image(&img).unwrap();
+        assert_eq!(tile.get_pixel(3, 2), Rgba([3, 2, 0, 255]));
+        assert_eq!(tile.to_image(), img);
+        assert!(Tile::<3, 3, 9>::from_image(&img).is_none());
+    }
+
+    #[test]
+    fn test_tile_fill() {
+        let mut tile = Tile::<2, 2, 4>::filled(Rgba([9, 9, 9, 9]));
+        assert!(tile.pixels_mut().all(|p| *p == Rgba([9, 9, 9, 9])));
+        tile.fill(Rgba([0, 0, 0, 255]));
+        assert_eq!(tile.get_pixel(1, 1), Rgba([0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_tile_rows() {
+        let mut tile = Tile::<3, 2, 6>::new();
+        tile.row_mut(1)[2] = Rgba([7, 7, 7, 7]);
+        assert_eq!(tile.get_pixel(2, 1), Rgba([7, 7, 7, 7]));
+        assert_eq!(tile.row(1).len(), 3);
+        assert_eq!(tile.pixels()[5], Rgba([7, 7, 7, 7]));
+    }
+
+    #[test]
+    #[should_panic(expected = "pixel (3, 0) outside Tile<3, 2>")]
+    fn test_tile_put_pixel_out_of_bounds_panics() {
+        // Would land on (0, 1) if the bounds were not checked
+        Tile::<3, 2, 6>::new().put_pixel(3, 0, Rgba([1, 1, 1, 1]));
+    }
+}
@@ -0,0 +1,205 @@
+//! Time zone aware clocks for world-clock widgets (`tz` feature).
+//!
+//! Looks up IANA zones ("Europe/Berlin", "America/New_York", ...) in the
+//! system time zone database (`/usr/share/zoneinfo` on Unix) via jiff, so
+//! DST rules stay current with OS updates. The result is a [`ZonedTime`]
+//! whose [`DateTime`] feeds straight into [`Locale`] formatting and
+//! [`hand_angles`] for analog faces:
+//!
+//! ```ignore
+//! let tokyo = now_in("Asia/Tokyo")?;
+//! let label = tokyo.format(&locale(), "%H:%M %Z"); // "23:05 JST"
+//! let (hour, minute, second) = hand_angles(&tokyo.datetime);
+//! ```
+
+use std::fmt;
+
+use jiff::Timestamp;
+use jiff::tz::TimeZone;
+
+use crate::locale::{DateTime, Locale};
+
+/// Error returned when a time can't be placed in a zone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeError {
+    /// The time zone name is not in the database.
+    UnknownZone(String),
+    /// The Unix timestamp is outside the range of supported dates.
+    OutOfRange(i64),
+}
+
+impl fmt::Display for TimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeError::UnknownZone(tz) => write!(f, "unknown time zone: {tz:?}"),
+            TimeError::OutOfRange(timestamp) => {
+                write!(f, "timestamp out of range: {timestamp}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TimeError {}
+
+/// A moment broken out into calendar fields in a particular time zone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZonedTime {
+    /// Local calendar date and time of day.
+    pub datetime: DateTime,
+    /// Offset from UTC in seconds, positive east of Greenwich.
+    pub offset: i32,
+    /// Zone abbreviation in effect, e.g. "CEST", or a numeric offset such
+    /// as "+0530" for zones without one.
+    pub abbreviation: String,
+}
+
+impl ZonedTime {
+    /// The offset as a label such as "UTC+2", "UTC-3:30" or "UTC".
+    pub fn offset_label(&self) -> String {
+        if self.offset == 0 {
+            return "UTC".to_string();
+        }
+        let sign = if self.offset < 0 { '-' } else { '+' };
+        let minutes = self.offset.unsigned_abs() / 60;
+        match minutes % 60 {
+            0 => format!("UTC{sign}{}", minutes / 60),
+            m => format!("UTC{sign}{}:{m:02}", minutes / 60),
+        }
+    }
+
+    /// Format with `locale` and a [`Locale::format`] pattern, which may
+    /// also use `%Z` for the zone abbreviation and `%z` for the offset as
+    /// `+hhmm`.
+    pub fn format(&self, locale: &Locale, pattern: &str) -> String {
+        let mut expanded = String::with_capacity(pattern.len());
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                expanded.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('Z') => expanded.push_str(&self.abbreviation.replace('%', "%%")),
+                Some('z') => {
+                    let sign = if self.offset < 0 { '-' } else { '+' };
+                    let minutes = self.offset.unsigned_abs() / 60;
+                    expanded.push_str(&format!("{sign}{:02}{:02}", minutes / 60, minutes % 60));
+                }
+                Some(other) => {
+                    expanded.push('%');
+                    expanded.push(other);
+                }
+                None => expanded.push('%'),
+            }
+        }
+        locale.format(&self.datetime, &expanded)
+    }
+}
+
+/// The current time in the IANA zone `tz`.
+pub fn now_in(tz: &str) -> Result<ZonedTime, TimeError> {
+    Ok(zoned(Timestamp::now(), &lookup(tz)?))
+}
+
+/// The time in zone `tz` at a Unix timestamp (seconds since 1970-01-01 UTC).
+///
+/// Timestamps outside years -9999 to 9999 are rejected with
+/// [`TimeError::OutOfRange`].
+pub fn at_in(timestamp: i64, tz: &str) -> Result<ZonedTime, TimeError> {
+    let zone = lookup(tz)?;
+    let timestamp =
+        Timestamp::from_second(timestamp).map_err(|_| TimeError::OutOfRange(timestamp))?;
+    Ok(zoned(timestamp, &zone))
+}
+
+/// The current time in the system's local time zone.
+///
+/// Falls back to UTC if the local zone cannot be determined.
+pub fn now_local() -> ZonedTime {
+    zoned(Timestamp::now(), &TimeZone::system())
+}
+
+/// Angles of the hour, minute and second hands for `time`, in degrees
+/// clockwise from 12 o'clock.
+///
+/// Hands sweep smoothly: the hour hand advances with the minutes and the
+/// minute hand with the seconds.
+pub fn hand_angles(time: &DateTime) -> (f32, f32, f32) {
+    let second = time.second as f32;
+    let minute = time.minute as f32 + second / 60.0;
+    let hour = (time.hour % 12) as f32 + minute / 60.0;
+    (hour * 30.0, minute * 6.0, second * 6.0)
+}
+
+fn lookup(tz: &str) -> Result<TimeZone, TimeError> {
+    TimeZone::get(tz.trim()).map_err(|_| TimeError::UnknownZone(tz.to_string()))
+}
+
+fn zoned(timestamp: Timestamp, zone: &TimeZone) -> ZonedTime {
+    let info = zone.to_offset_info(timestamp);
+    let offset = info.offset().seconds();
+    ZonedTime {
+        datetime: DateTime::from_unix(timestamp.as_second() + i64::from(offset)),
+        offset,
+        abbreviation: info.abbreviation().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-07-01 12:00:00 UTC
+    const SUMMER: i64 = 1_719_835_200;
+
+    #[test]
+    fn test_at_in_applies_zone_offset() {
+        let berlin = at_in(SUMMER, "Europe/Berlin").unwrap();
+        assert_eq!((berlin.datetime.hour, berlin.datetime.minute), (14, 0));
+        assert_eq!(berlin.offset, 7200);
+        assert_eq!(berlin.abbreviation, "CEST");
+        assert_eq!(berlin.offset_label(), "UTC+2");
+
+        let kolkata = at_in(SUMMER, "Asia/Kolkata").unwrap();
+        assert_eq!((kolkata.datetime.hour, kolkata.datetime.minute), (17, 30));
+        assert_eq!(kolkata.offset_label(), "UTC+5:30");
+
+        // Crossing midnight moves the date back too
+        let la = at_in(SUMMER - 12 * 3600, "America/Los_Angeles").unwrap();
+        assert_eq!(
+            (la.datetime.month, la.datetime.day, la.datetime.hour),
+            (6, 30, 17)
+        );
+        assert_eq!(la.datetime.weekday, 6);
+        assert_eq!(at_in(SUMMER, "UTC").unwrap().offset_label(), "UTC");
+    }
+
+    #[test]
+    fn test_unknown_time_zone() {
+        let err = now_in("Mars/Olympus_Mons").unwrap_err();
+        assert_eq!(err.to_string(), "unknown time zone: \"Mars/Olympus_Mons\"");
+    }
+
+    #[test]
+    fn test_at_in_rejects_out_of_range() {
+        assert_eq!(
+            at_in(i64::MAX, "UTC").unwrap_err(),
+            TimeError::OutOfRange(i64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_zoned_format() {
+        let ny = at_in(SUMMER, "America/New_York").unwrap();
+        assert_eq!(
+            ny.format(&Locale::EN_US, "%-I:%M %p %Z (%z) 100%%"),
+            "8:00 AM EDT (-0400) 100%"
+        );
+    }
+
+    #[test]
+    fn test_hand_angles() {
+        let time = DateTime::from_unix(3 * 3600 + 30 * 60);
+        assert_eq!(hand_angles(&time), (105.0, 180.0, 0.0));
+    }
+}
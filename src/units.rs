@@ -0,0 +1,503 @@
+//! Measurement unit conversion and formatting for sensor widgets.
+//!
+//! Covers the quantities weather and monitoring plugins display:
+//! [`Temperature`], [`Speed`], [`Pressure`] and [`DataRate`]. Each unit
+//! enum implements [`Unit`], parses from its symbol or a common alias
+//! (`"°F"`, `"mph"`, `"inHg"`, `"MB/s"`, ...) and, with the `serde`
+//! feature, deserializes from the same strings so users can pick units in
+//! their config:
+//!
+//! ```ignore
+//! let unit: Temperature = config.temperature_unit.parse()?;
+//! let text = format(convert(celsius, Temperature::Celsius, unit), unit, 1); // "70.3°F"
+//! let rate = format_data_rate(12_500_000.0, RateScale::Bits, 1); // "100.0 Mbit/s"
+//! ```
+//!
+//! Numbers are formatted with the current [`locale`]'s separators.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::locale::locale;
+
+/// A unit of some physical quantity.
+pub trait Unit: Copy + PartialEq + 'static {
+    /// Every unit of this quantity.
+    const ALL: &'static [Self];
+
+    /// Convert `value` in this unit to the quantity's base unit.
+    fn to_base(self, value: f64) -> f64;
+
+    /// Convert `value` in the quantity's base unit to this unit.
+    fn to_unit(self, value: f64) -> f64;
+
+    /// Symbol followed by any aliases accepted when parsing.
+    fn names(self) -> &'static [&'static str];
+
+    /// Symbol used when formatting, e.g. "km/h".
+    fn symbol(self) -> &'static str {
+        self.names()[0]
+    }
+}
+
+/// Convert `value` from one unit to another.
+pub fn convert<U: Unit>(value: f64, from: U, to: U) -> f64 {
+    if from == to {
+        return value;
+    }
+    to.to_unit(from.to_base(value))
+}
+
+/// Format `value` with `decimals` places followed by the unit symbol.
+///
+/// Degree symbols attach directly ("21.5°C"); other symbols are separated
+/// by a space ("12 km/h").
+pub fn format<U: Unit>(value: f64, unit: U, decimals: usize) -> String {
+    let number = locale().format_number(value, decimals);
+    let symbol = unit.symbol();
+    if symbol.starts_with('°') {
+        format!("{number}{symbol}")
+    } else {
+        format!("{number} {symbol}")
+    }
+}
+
+/// Temperature units. The base unit is degrees Celsius.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Temperature {
+    /// Degrees Celsius.
+    #[default]
+    Celsius,
+    /// Degrees Fahrenheit.
+    Fahrenheit,
+    /// Kelvin.
+    Kelvin,
+}
+
+impl Unit for Temperature {
+    const ALL: &'static [Self] = &[Self::Celsius, Self::Fahrenheit, Self::Kelvin];
+
+    fn to_base(self, value: f64) -> f64 {
+        match self {
+            Self::Celsius => value,
+            Self::Fahrenheit => (value - 32.0) * 5.0 / 9.0,
+            Self::Kelvin => value - 273.15,
+        }
+    }
+
+    fn to_unit(self, value: f64) -> f64 {
+        match self {
+            Self::Celsius => value,
+            Self::Fahrenheit => value * 9.0 / 5.0 + 32.0,
+            Self::Kelvin => value + 273.15,
+        }
+    }
+
+    fn names(self) -> &'static [&'static str] {
+        match self {
+            Self::Celsius => &["°C", "C", "celsius"],
+            Self::Fahrenheit => &["°F", "F", "fahrenheit"],
+            Self::Kelvin => &["K", "kelvin"],
+        }
+    }
+}
+
+/// Speed units. The base unit is metres per second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Speed {
+    /// Metres per second.
+    MetersPerSecond,
+    /// Kilometres per hour.
+    #[default]
+    KilometersPerHour,
+    /// Miles per hour.
+    MilesPerHour,
+    /// Nautical miles per hour.
+    Knots,
+}
+
+impl Unit for Speed {
+    const ALL: &'static [Self] = &[
+        Self::MetersPerSecond,
+        Self::KilometersPerHour,
+        Self::MilesPerHour,
+        Self::Knots,
+    ];
+
+    fn to_base(self, value: f64) -> f64 {
+        value * self.meters_per_second()
+    }
+
+    fn to_unit(self, value: f64) -> f64 {
+        value / self.meters_per_second()
+    }
+
+    fn names(self) -> &'static [&'static str] {
+        match self {
+            Self::MetersPerSecond => &["m/s", "mps"],
+            Self::KilometersPerHour => &["km/h", "kph", "kmh"],
+            Self::MilesPerHour => &["mph"],
+            Self::Knots => &["kn", "kt", "knots"],
+        }
+    }
+}
+
+impl Speed {
+    fn meters_per_second(self) -> f64 {
+        match self {
+            Self::MetersPerSecond => 1.0,
+            Self::KilometersPerHour => 1.0 / 3.6,
+            Self::MilesPerHour => 0.44704,
+            Self::Knots => 1852.0 / 3600.0,
+        }
+    }
+}
+
+/// Pressure units. The base unit is the pascal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Pressure {
+    /// Hectopascals, the usual unit for barometric pressure.
+    #[default]
+    Hectopascal,
+    /// Kilopascals.
+    Kilopascal,
+    /// Millibars, equal to hectopascals.
+    Millibar,
+    /// Inches of mercury.
+    InchesOfMercury,
+    /// Millimetres of mercury.
+    MillimetersOfMercury,
+    /// Pounds per square inch.
+    Psi,
+}
+
+impl Unit for Pressure {
+    const ALL: &'static [Self] = &[
+        Self::Hectopascal,
+        Self::Kilopascal,
+        Self::Millibar,
+        Self::InchesOfMercury,
+        Self::MillimetersOfMercury,
+        Self::Psi,
+    ];
+
+    fn to_base(self, value: f64) -> f64 {
+        value * self.pascals()
+    }
+
+    fn to_unit(self, value: f64) -> f64 {
+        value / self.pascals()
+    }
+
+    fn names(self) -> &'static [&'static str] {
+        match self {
+            Self::Hectopascal => &["hPa"],
+            Self::Kilopascal => &["kPa"],
+            Self::Millibar => &["mbar", "mb"],
+            Self::InchesOfMercury => &["inHg"],
+            Self::MillimetersOfMercury => &["mmHg"],
+            Self::Psi => &["psi"],
+        }
+    }
+}
+
+impl Pressure {
+    fn pascals(self) -> f64 {
+        match self {
+            Self::Hectopascal | Self::Millibar => 100.0,
+            Self::Kilopascal => 1000.0,
+            Self::InchesOfMercury => 3386.389,
+            Self::MillimetersOfMercury => 133.322_387_415,
+            Self::Psi => 6_894.757_293_168,
+        }
+    }
+}
+
+/// Data rate units. The base unit is bytes per second.
+///
+/// Decimal units (kB/s, Mbit/s) are powers of 1000; binary units (KiB/s)
+/// are powers of 1024.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DataRate {
+    /// Bits per second.
+    BitsPerSecond,
+    /// Kilobits per second.
+    KilobitsPerSecond,
+    /// Megabits per second.
+    MegabitsPerSecond,
+    /// Gigabits per second.
+    GigabitsPerSecond,
+    /// Bytes per second.
+    #[default]
+    BytesPerSecond,
+    /// Kilobytes per second.
+    KilobytesPerSecond,
+    /// Megabytes per second.
+    MegabytesPerSecond,
+    /// Gigabytes per second.
+    GigabytesPerSecond,
+    /// Kibibytes per second.
+    KibibytesPerSecond,
+    /// Mebibytes per second.
+    MebibytesPerSecond,
+    /// Gibibytes per second.
+    GibibytesPerSecond,
+}
+
+impl Unit for DataRate {
+    const ALL: &'static [Self] = &[
+        Self::BitsPerSecond,
+        Self::KilobitsPerSecond,
+        Self::MegabitsPerSecond,
+        Self::GigabitsPerSecond,
+        Self::BytesPerSecond,
+        Self::KilobytesPerSecond,
+        Self::MegabytesPerSecond,
+        Self::GigabytesPerSecond,
+        Self::KibibytesPerSecond,
+        Self::MebibytesPerSecond,
+        Self::GibibytesPerSecond,
+    ];
+
+    fn to_base(self, value: f64) -> f64 {
+        value * self.bytes_per_second()
+    }
+
+    fn to_unit(self, value: f64) -> f64 {
+        value / self.bytes_per_second()
+    }
+
+    fn names(self) -> &'static [&'static str] {
+        match self {
+            Self::BitsPerSecond => &["bit/s", "bps"],
+            Self::KilobitsPerSecond => &["kbit/s", "kbps"],
+            Self::MegabitsPerSecond => &["Mbit/s", "Mbps"],
+            Self::GigabitsPerSecond => &["Gbit/s", "Gbps"],
+            Self::BytesPerSecond => &["B/s"],
+            Self::KilobytesPerSecond => &["kB/s", "KB/s"],
+            Self::MegabytesPerSecond => &["MB/s"],
+            Self::GigabytesPerSecond => &["GB/s"],
+            Self::KibibytesPerSecond => &["KiB/s"],
+            Self::MebibytesPerSecond => &["MiB/s"],
+            Self::GibibytesPerSecond => &["GiB/s"],
+        }
+    }
+}
+
+impl DataRate {
+    fn bytes_per_second(self) -> f64 {
+        match self {
+            Self::BitsPerSecond => 1.0 / 8.0,
+            Self::KilobitsPerSecond => 1e3 / 8.0,
+            Self::MegabitsPerSecond => 1e6 / 8.0,
+            Self::GigabitsPerSecond => 1e9 / 8.0,
+            Self::BytesPerSecond => 1.0,
+            Self::KilobytesPerSecond => 1e3,
+            Self::MegabytesPerSecond => 1e6,
+            Self::GigabytesPerSecond => 1e9,
+            Self::KibibytesPerSecond => 1024.0,
+            Self::MebibytesPerSecond => 1024.0 * 1024.0,
+            Self::GibibytesPerSecond => 1024.0 * 1024.0 * 1024.0,
+        }
+    }
+}
+
+/// Family of units [`format_data_rate`] picks from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RateScale {
+    /// B/s, kB/s, MB/s, GB/s.
+    #[default]
+    Bytes,
+    /// B/s, KiB/s, MiB/s, GiB/s.
+    Binary,
+    /// bit/s, kbit/s, Mbit/s, Gbit/s.
+    Bits,
+}
+
+/// Format a rate in bytes per second using the largest unit in `scale`
+/// that keeps the number, once rounded to `decimals`, at or above 1, e.g.
+/// "3.2 MB/s".
+pub fn format_data_rate(bytes_per_second: f64, scale: RateScale, decimals: usize) -> String {
+    use DataRate::*;
+    let units = match scale {
+        RateScale::Bytes => [
+            BytesPerSecond,
+            KilobytesPerSecond,
+            MegabytesPerSecond,
+            GigabytesPerSecond,
+        ],
+        RateScale::Binary => [
+            BytesPerSecond,
+            KibibytesPerSecond,
+            MebibytesPerSecond,
+            GibibytesPerSecond,
+        ],
+        RateScale::Bits => [
+            BitsPerSecond,
+            KilobitsPerSecond,
+            MegabitsPerSecond,
+            GigabitsPerSecond,
+        ],
+    };
+    let magnitude = bytes_per_second.abs();
+    let mut i = units
+        .iter()
+        .rposition(|u| u.to_unit(magnitude) >= 1.0)
+        .unwrap_or(0);
+    // Step up if rounding would print a full next unit, e.g. "1000.0 B/s"
+    let pow = 10f64.powi(decimals.min(16) as i32);
+    if let Some(next) = units.get(i + 1) {
+        let ratio = units[i].to_unit(1.0) / next.to_unit(1.0);
+        if (units[i].to_unit(magnitude) * pow).round() / pow >= ratio {
+            i += 1;
+        }
+    }
+    format(units[i].to_unit(bytes_per_second), units[i], decimals)
+}
+
+/// Error returned when a string is not a recognized unit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseUnitError(String);
+
+impl fmt::Display for ParseUnitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown unit: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseUnitError {}
+
+/// Find the unit named `s`, preferring an exact match so "Mbps" and "MB/s"
+/// stay distinct, then ignoring ASCII case.
+fn parse_unit<U: Unit>(s: &str) -> Result<U, ParseUnitError> {
+    let s = s.trim();
+    let find = |matches: &dyn Fn(&str) -> bool| {
+        U::ALL
+            .iter()
+            .copied()
+            .find(|u| u.names().iter().any(|n| matches(n)))
+    };
+    find(&|n| n == s)
+        .or_else(|| find(&|n| n.eq_ignore_ascii_case(s)))
+        .ok_or_else(|| ParseUnitError(s.to_string()))
+}
+
+macro_rules! unit_traits {
+    ($($unit:ty),*) => {$(
+        impl fmt::Display for $unit {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.symbol())
+            }
+        }
+
+        impl FromStr for $unit {
+            type Err = ParseUnitError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                parse_unit(s)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for $unit {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+                s.parse().map_err(serde::de::Error::custom)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for $unit {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_str(self.symbol())
+            }
+        }
+    )*};
+}
+
+unit_traits!(Temperature, Speed, Pressure, DataRate);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6 * b.abs().max(1.0)
+    }
+
+    #[test]
+    fn test_convert_temperature() {
+        use Temperature::*;
+        assert!(close(convert(100.0, Celsius, Fahrenheit), 212.0));
+        assert!(close(convert(-40.0, Fahrenheit, Celsius), -40.0));
+        assert!(close(convert(0.0, Kelvin, Fahrenheit), -459.67));
+    }
+
+    #[test]
+    fn test_convert_speed_and_pressure() {
+        assert!(close(
+            convert(100.0, Speed::KilometersPerHour, Speed::MilesPerHour),
+            62.137_119
+        ));
+        assert!(close(
+            convert(1.0, Speed::Knots, Speed::KilometersPerHour),
+            1.852
+        ));
+        assert!(close(
+            convert(1013.25, Pressure::Hectopascal, Pressure::InchesOfMercury),
+            29.92126
+        ));
+        assert!(close(
+            convert(1.0, Pressure::Millibar, Pressure::Hectopascal),
+            1.0
+        ));
+    }
+
+    #[test]
+    fn test_format_units() {
+        assert_eq!(format(21.46, Temperature::Celsius, 1), "21.5°C");
+        assert_eq!(format(12.0, Speed::KilometersPerHour, 0), "12 km/h");
+        assert_eq!(format(-0.04, Temperature::Fahrenheit, 1), "0.0°F");
+    }
+
+    #[test]
+    fn test_format_data_rate() {
+        assert_eq!(format_data_rate(512.0, RateScale::Bytes, 0), "512 B/s");
+        assert_eq!(
+            format_data_rate(3_200_000.0, RateScale::Bytes, 1),
+            "3.2 MB/s"
+        );
+        assert_eq!(format_data_rate(1536.0, RateScale::Binary, 1), "1.5 KiB/s");
+        assert_eq!(
+            format_data_rate(12_500_000.0, RateScale::Bits, 0),
+            "100 Mbit/s"
+        );
+        assert_eq!(format_data_rate(0.0, RateScale::Bytes, 0), "0 B/s");
+        // Units follow the rounded value
+        assert_eq!(format_data_rate(999.96, RateScale::Bytes, 1), "1.0 kB/s");
+        assert_eq!(format_data_rate(999.94, RateScale::Bytes, 1), "999.9 B/s");
+        assert_eq!(format_data_rate(-999.6, RateScale::Bytes, 0), "-1 kB/s");
+        assert_eq!(
+            format_data_rate(1023.99 * 1024.0, RateScale::Binary, 1),
+            "1.0 MiB/s"
+        );
+    }
+
+    #[test]
+    fn test_parse_units() {
+        assert_eq!("°F".parse(), Ok(Temperature::Fahrenheit));
+        assert_eq!("celsius".parse(), Ok(Temperature::Celsius));
+        assert_eq!(" MPH ".parse(), Ok(Speed::MilesPerHour));
+        assert_eq!("inhg".parse(), Ok(Pressure::InchesOfMercury));
+        assert_eq!("Mbps".parse(), Ok(DataRate::MegabitsPerSecond));
+        assert_eq!("MB/s".parse(), Ok(DataRate::MegabytesPerSecond));
+        assert!("furlongs".parse::<Speed>().is_err());
+        assert_eq!(Speed::Knots.to_string(), "kn");
+    }
+}
@@ -0,0 +1,265 @@
+//! Reusable widget primitives.
+//!
+//! Drawing helpers for common tile elements such as progress bars and loading
+//! placeholders, plus a declarative [`Spec`] that lays out and renders a
+//! stack of these primitives (and, with the `templates` feature,
+//! [`Template`]s that load specs from data files). Animated variants take an
+//! elapsed [`Duration`] from the plugin's [`Clock`](crate::anim::Clock).
+
+use std::time::Duration;
+
+use image::{Rgba, RgbaImage};
+use imageproc::drawing::draw_filled_rect_mut;
+use imageproc::rect::Rect;
+
+use crate::accessibility::reduced_motion;
+use crate::anim::phase;
+use crate::colors::hex;
+use crate::image::pixel_math::Factor;
+
+mod spec;
+#[cfg(feature = "templates")]
+mod template;
+
+pub use spec::{Direction, Element, Item, Spec, render, render_cancelable, render_pooled};
+#[cfg(feature = "templates")]
+pub use template::{Template, TemplateError};
+
+/// Progress shown by [`draw_progress_bar`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Progress {
+    /// Known completion, from 0.0 to 1.0.
+    Determinate(f32),
+    /// Unknown duration, shown as diagonal stripes scrolling along the bar.
+    Stripes,
+    /// Unknown duration, shown as a segment bouncing between the ends.
+    Bounce,
+}
+
+/// Colors and timing for [`draw_progress_bar`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressStyle {
+    /// Unfilled track color.
+    pub track: Rgba<u8>,
+    /// Filled portion, stripe and segment color.
+    pub fill: Rgba<u8>,
+    /// Width of each diagonal stripe in pixels.
+    pub stripe_width: u32,
+    /// Width of the bouncing segment as a fraction of the bar.
+    pub segment: f32,
+    /// Time for one stripe repeat or one full bounce cycle.
+    pub period: Duration,
+}
+
+impl Default for ProgressStyle {
+    fn default() -> Self {
+        Self {
+            track: hex("#333333"),
+            fill: hex("#3DA5FF"),
+            stripe_width: 6,
+            segment: 0.3,
+            period: Duration::from_millis(1200),
+        }
+    }
+}
+
+/// Draw a horizontal progress bar into `rect`.
+///
+/// Indeterminate modes animate with `elapsed`; determinate progress ignores
+/// it. In [reduced-motion mode](crate::accessibility::set_reduced_motion)
+/// stripes stand still and the bouncing segment rests in the middle.
+/// Drawing is clipped to the image bounds.
+pub fn draw_progress_bar(
+    rgba: &mut RgbaImage,
+    rect: Rect,
+    progress: Progress,
+    style: &ProgressStyle,
+    elapsed: Duration,
+) {
+    if rgba.width() == 0 || rgba.height() == 0 {
+        return;
+    }
+    let bounds = Rect::at(0, 0).of_size(rgba.width(), rgba.height());
+    let Some(clipped) = rect.intersect(bounds) else {
+        return;
+    };
+    draw_filled_rect_mut(rgba, clipped, style.track);
+
+    let width = rect.width() as f32;
+    match progress {
+        Progress::Determinate(value) => {
+            let filled = (value.clamp(0.0, 1.0) * width).round() as u32;
+            fill_span(rgba, rect, bounds, 0, filled, style.fill);
+        }
+        Progress::Bounce => {
+            let segment = (style.segment.clamp(0.0, 1.0) * width).round() as u32;
+            let travel = rect.width().saturating_sub(segment) as f32;
+            // Triangle wave so the segment reverses at each end
+            let t = if reduced_motion() {
+                0.5
+            } else {
+                let p = phase(elapsed, style.period);
+                1.0 - (2.0 * p - 1.0).abs()
+            };
+            let start = (t * travel).round() as u32;
+            fill_span(rgba, rect, bounds, start, start + segment, style.fill);
+        }
+        Progress::Stripes => {
+            let stripe = style.stripe_width.max(1) as i32;
+            let repeat = stripe * 2;
+            let offset = if reduced_motion() {
+                0
+            } else {
+                (phase(elapsed, style.period) * repeat as f32) as i32
+            };
+            for y in clipped.top()..=clipped.bottom() {
+                for x in clipped.left()..=clipped.right() {
+                    // 45 degree stripes moving right over time
+                    let d = (x - rect.left()) + (y - rect.top()) - offset;
+                    if d.rem_euclid(repeat) < stripe {
+                        rgba.put_pixel(x as u32, y as u32, style.fill);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Fill columns `start..end` (relative to `rect`) across the full bar height.
+fn fill_span(
+    rgba: &mut RgbaImage,
+    rect: Rect,
+    bounds: Rect,
+    start: u32,
+    end: u32,
+    color: Rgba<u8>,
+) {
+    let end = end.min(rect.width());
+    if end <= start {
+        return;
+    }
+    let span = Rect::at(rect.left() + start as i32, rect.top()).of_size(end - start, rect.height());
+    if let Some(span) = span.intersect(bounds) {
+        draw_filled_rect_mut(rgba, span, color);
+    }
+}
+
+/// Placeholder block color for [`draw_skeleton`].
+pub const SKELETON_BASE: Rgba<u8> = hex("#3A3A3A");
+
+/// Peak shimmer color for [`draw_skeleton`].
+pub const SKELETON_HIGHLIGHT: Rgba<u8> = hex("#5A5A5A");
+
+/// Draw grey loading placeholder blocks with a moving shimmer highlight.
+///
+/// `t` is the shimmer position from 0.0 to 1.0, typically
+/// [`phase`](crate::anim::phase) of the elapsed time over a ~1.5s period.
+/// The highlight is a soft diagonal band sweeping left to right across the
+/// whole image, so it stays continuous between blocks. In [reduced-motion
+/// mode](crate::accessibility::set_reduced_motion) the blocks are drawn
+/// without the highlight. Drawing is clipped to the image bounds.
+pub fn draw_skeleton(rgba: &mut RgbaImage, rects: &[Rect], t: f32) {
+    if rgba.width() == 0 || rgba.height() == 0 {
+        return;
+    }
+    let bounds = Rect::at(0, 0).of_size(rgba.width(), rgba.height());
+    let band = (rgba.width() as f32 * 0.3).max(1.0);
+    // Start and end fully off-image so the sweep enters and leaves cleanly
+    let travel = rgba.width() as f32 + rgba.height() as f32 * 0.5 + 2.0 * band;
+    let t = if reduced_motion() {
+        0.0
+    } else {
+        t.clamp(0.0, 1.0)
+    };
+    let center = -band + t * travel;
+    for rect in rects {
+        let Some(clipped) = rect.intersect(bounds) else {
+            continue;
+        };
+        for y in clipped.top()..=clipped.bottom() {
+            for x in clipped.left()..=clipped.right() {
+                let d = (x as f32 + y as f32 * 0.5 - center).abs() / band;
+                let k = (1.0 - d).max(0.0);
+                let k = Factor::new(k * k * (3.0 - 2.0 * k));
+                let p = Rgba(std::array::from_fn(|c| {
+                    k.blend(SKELETON_BASE[c], SKELETON_HIGHLIGHT[c])
+                }));
+                rgba.put_pixel(x as u32, y as u32, p);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(progress: Progress, elapsed: Duration) -> RgbaImage {
+        let mut img = RgbaImage::new(40, 6);
+        let rect = Rect::at(0, 1).of_size(40, 4);
+        draw_progress_bar(&mut img, rect, progress, &ProgressStyle::default(), elapsed);
+        img
+    }
+
+    fn filled_columns(img: &RgbaImage) -> Vec<u32> {
+        let fill = ProgressStyle::default().fill;
+        (0..img.width())
+            .filter(|&x| *img.get_pixel(x, 2) == fill)
+            .collect()
+    }
+
+    #[test]
+    fn test_progress_determinate_fills_fraction() {
+        let img = bar(Progress::Determinate(0.25), Duration::ZERO);
+        assert_eq!(filled_columns(&img), (0..10).collect::<Vec<_>>());
+        assert_eq!(img.get_pixel(0, 0)[3], 0);
+        assert_eq!(
+            filled_columns(&bar(Progress::Determinate(2.0), Duration::ZERO)).len(),
+            40
+        );
+    }
+
+    #[test]
+    fn test_progress_bounce_moves_and_returns() {
+        let period = ProgressStyle::default().period;
+        let start = filled_columns(&bar(Progress::Bounce, Duration::ZERO));
+        let mid = filled_columns(&bar(Progress::Bounce, period / 2));
+        assert_eq!(start.first(), Some(&0));
+        assert_eq!(mid.last(), Some(&39));
+        assert_eq!(filled_columns(&bar(Progress::Bounce, period)), start);
+    }
+
+    #[test]
+    fn test_progress_stripes_scroll() {
+        let period = ProgressStyle::default().period;
+        let a = bar(Progress::Stripes, Duration::ZERO);
+        let b = bar(Progress::Stripes, period / 4);
+        assert_ne!(a, b);
+        assert_eq!(a, bar(Progress::Stripes, period));
+        assert!(!filled_columns(&a).is_empty());
+    }
+
+    #[test]
+    fn test_skeleton_fills_only_rects() {
+        let mut img = RgbaImage::new(40, 20);
+        let rects = [
+            Rect::at(2, 2).of_size(30, 4),
+            Rect::at(2, 10).of_size(20, 4),
+        ];
+        draw_skeleton(&mut img, &rects, 0.0);
+        assert_eq!(*img.get_pixel(5, 3), SKELETON_BASE);
+        assert_eq!(img.get_pixel(5, 7)[3], 0);
+        assert_eq!(img.get_pixel(35, 3)[3], 0);
+    }
+
+    #[test]
+    fn test_skeleton_shimmer_moves() {
+        let rects = [Rect::at(0, 0).of_size(40, 4)];
+        let brightest = |t: f32| {
+            let mut img = RgbaImage::new(40, 4);
+            draw_skeleton(&mut img, &rects, t);
+            (0..40).max_by_key(|&x| img.get_pixel(x, 0)[0]).unwrap()
+        };
+        assert!(brightest(0.3) < brightest(0.6));
+    }
+}
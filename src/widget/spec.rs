@@ -0,0 +1,550 @@
+//! Declarative widget descriptions.
+
+use std::time::Duration;
+
+use image::{GenericImageView, Rgba, RgbaImage, imageops};
+use imageproc::drawing::draw_line_segment_mut;
+use imageproc::rect::Rect;
+
+use super::{Progress, ProgressStyle, draw_progress_bar};
+use crate::accessibility::{high_contrast, stroke_width};
+use crate::cancel::CancelToken;
+use crate::chart::{DonutStyle, draw_donut};
+use crate::colors::Theme;
+use crate::debug::{self, DebugMark};
+use crate::image::{Fit, FramePool, apply_tint, paint_image};
+use crate::text::draw_centered_text;
+
+/// Axis along which a [`Spec`] stacks its items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Direction {
+    /// Top to bottom.
+    #[default]
+    Column,
+    /// Left to right.
+    Row,
+}
+
+/// A drawable primitive in a [`Spec`].
+///
+/// Colors left as `None` come from the [`Theme`] passed to [`render`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Element {
+    /// Text scaled to fit its slot, centered. May contain newlines.
+    Text {
+        text: String,
+        color: Option<Rgba<u8>>,
+    },
+    /// An image scaled to fit its slot, optionally recolored to `tint`
+    /// (keeping the image's alpha).
+    Icon {
+        image: RgbaImage,
+        tint: Option<Rgba<u8>>,
+    },
+    /// A horizontal progress bar, `value` from 0.0 to 1.0.
+    Bar { value: f32, color: Option<Rgba<u8>> },
+    /// A circular progress ring, `value` from 0.0 to 1.0.
+    Ring { value: f32, color: Option<Rgba<u8>> },
+    /// A line through `values`, scaled to their min and max.
+    Sparkline {
+        values: Vec<f32>,
+        color: Option<Rgba<u8>>,
+    },
+    /// A nested stack.
+    Stack(Spec),
+    /// Empty space.
+    Spacer,
+}
+
+impl Element {
+    /// Text in the theme's foreground color.
+    pub fn text(text: impl Into<String>) -> Self {
+        Element::Text {
+            text: text.into(),
+            color: None,
+        }
+    }
+
+    /// An untinted icon.
+    pub fn icon(image: RgbaImage) -> Self {
+        Element::Icon { image, tint: None }
+    }
+
+    /// A progress bar in the theme's accent color.
+    pub fn bar(value: f32) -> Self {
+        Element::Bar { value, color: None }
+    }
+
+    /// A progress ring in the theme's accent color.
+    pub fn ring(value: f32) -> Self {
+        Element::Ring { value, color: None }
+    }
+
+    /// A sparkline in the theme's accent color.
+    pub fn sparkline(values: impl Into<Vec<f32>>) -> Self {
+        Element::Sparkline {
+            values: values.into(),
+            color: None,
+        }
+    }
+
+    /// Override the theme color (or icon tint). Has no effect on stacks and spacers.
+    pub fn with_color(mut self, new: Rgba<u8>) -> Self {
+        match &mut self {
+            Element::Text { color, .. }
+            | Element::Bar { color, .. }
+            | Element::Ring { color, .. }
+            | Element::Sparkline { color, .. } => *color = Some(new),
+            Element::Icon { tint, .. } => *tint = Some(new),
+            Element::Stack(_) | Element::Spacer => {}
+        }
+        self
+    }
+}
+
+/// An [`Element`] with its layout hint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub element: Element,
+    /// Share of the stack's length relative to its siblings.
+    pub weight: f32,
+}
+
+/// A stack of primitives, rendered by [`render`].
+///
+/// ```ignore
+/// let spec = Spec::column()
+///     .with(Element::text("CPU"))
+///     .with_weighted(Element::sparkline(history), 2.0)
+///     .with(Element::bar(load));
+/// let img = render(&spec, (72, 72), &Theme::default());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spec {
+    pub direction: Direction,
+    /// Space in pixels around the items.
+    pub padding: u32,
+    /// Space in pixels between items.
+    pub gap: u32,
+    pub items: Vec<Item>,
+}
+
+impl Default for Spec {
+    fn default() -> Self {
+        Self {
+            direction: Direction::Column,
+            padding: 4,
+            gap: 2,
+            items: Vec::new(),
+        }
+    }
+}
+
+impl Spec {
+    /// An empty top-to-bottom stack.
+    pub fn column() -> Self {
+        Self::default()
+    }
+
+    /// An empty left-to-right stack.
+    pub fn row() -> Self {
+        Self {
+            direction: Direction::Row,
+            ..Self::default()
+        }
+    }
+
+    /// Set the padding around the items.
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Set the gap between items.
+    pub fn with_gap(mut self, gap: u32) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Append an element with weight 1.0.
+    pub fn with(self, element: Element) -> Self {
+        self.with_weighted(element, 1.0)
+    }
+
+    /// Append an element taking `weight` shares of the stack's length.
+    pub fn with_weighted(mut self, element: Element, weight: f32) -> Self {
+        self.items.push(Item { element, weight });
+        self
+    }
+}
+
+/// Render `spec` onto a new image of `size`, filled with the theme background.
+///
+/// In [high-contrast mode](crate::accessibility) the theme's
+/// [`effective`](Theme::effective) colors are used and strokes are thicker.
+/// With the [debug overlay](crate::debug) enabled, element slots and text
+/// boxes are outlined.
+pub fn render(spec: &Spec, size: (u32, u32), theme: &Theme) -> RgbaImage {
+    render_pooled(spec, size, theme, &mut FramePool::with_capacity(0))
+}
+
+/// Like [`render`], but takes the frame and the temporary layers for text
+/// and icon elements from `pool`.
+///
+/// Give the finished frame back to the pool once it has been presented,
+/// and steady-state rendering of a spec does not allocate.
+pub fn render_pooled(
+    spec: &Spec,
+    size: (u32, u32),
+    theme: &Theme,
+    pool: &mut FramePool,
+) -> RgbaImage {
+    draw_spec(spec, size, theme, &CancelToken::new(), pool)
+}
+
+/// Like [`render`], but stops early and returns `None` once `cancel` is
+/// canceled, e.g. because newer data has superseded this frame.
+///
+/// The token is checked before each element and between sparkline
+/// segments, so a large spec stops within one element's drawing time.
+pub fn render_cancelable(
+    spec: &Spec,
+    size: (u32, u32),
+    theme: &Theme,
+    cancel: &CancelToken,
+) -> Option<RgbaImage> {
+    let rgba = draw_spec(spec, size, theme, cancel, &mut FramePool::with_capacity(0));
+    (!cancel.is_canceled()).then_some(rgba)
+}
+
+/// Render `spec`, leaving the image partly drawn if `cancel` fires.
+fn draw_spec(
+    spec: &Spec,
+    size: (u32, u32),
+    theme: &Theme,
+    cancel: &CancelToken,
+    pool: &mut FramePool,
+) -> RgbaImage {
+    let theme = &theme.effective();
+    let (width, height) = size;
+    let mut rgba = pool.take_uncleared(width, height);
+    for p in rgba.pixels_mut() {
+        *p = theme.bg;
+    }
+    if width > 0 && height > 0 {
+        draw_stack(
+            &mut rgba,
+            spec,
+            Rect::at(0, 0).of_size(width, height),
+            theme,
+            cancel,
+            pool,
+        );
+    }
+    debug::draw_overlay(&mut rgba);
+    rgba
+}
+
+fn draw_stack(
+    rgba: &mut RgbaImage,
+    spec: &Spec,
+    rect: Rect,
+    theme: &Theme,
+    cancel: &CancelToken,
+    pool: &mut FramePool,
+) {
+    let pad = spec.padding as i32;
+    let (w, h) = (
+        rect.width() as i32 - 2 * pad,
+        rect.height() as i32 - 2 * pad,
+    );
+    let total_weight: f32 = spec.items.iter().map(|item| item.weight.max(0.0)).sum();
+    if w <= 0 || h <= 0 || total_weight <= 0.0 {
+        return;
+    }
+    let (x0, y0) = (rect.left() + pad, rect.top() + pad);
+    let length = match spec.direction {
+        Direction::Column => h,
+        Direction::Row => w,
+    };
+    let gaps = spec.gap as f32 * (spec.items.len() - 1) as f32;
+    let available = (length as f32 - gaps).max(0.0);
+
+    let mut pos = 0.0f32;
+    for item in &spec.items {
+        if cancel.is_canceled() {
+            return;
+        }
+        let len = available * item.weight.max(0.0) / total_weight;
+        let (start, end) = (pos.round() as i32, (pos + len).round() as i32);
+        pos += len + spec.gap as f32;
+        if end <= start {
+            continue;
+        }
+        let slot = match spec.direction {
+            Direction::Column => Rect::at(x0, y0 + start).of_size(w as u32, (end - start) as u32),
+            Direction::Row => Rect::at(x0 + start, y0).of_size((end - start) as u32, h as u32),
+        };
+        debug::mark(DebugMark::Layout, slot);
+        draw_element(rgba, &item.element, slot, theme, cancel, pool);
+    }
+}
+
+fn draw_element(
+    rgba: &mut RgbaImage,
+    element: &Element,
+    rect: Rect,
+    theme: &Theme,
+    cancel: &CancelToken,
+    pool: &mut FramePool,
+) {
+    let (x, y) = (rect.left() as i64, rect.top() as i64);
+    match element {
+        Element::Text { text, color } => {
+            // Draw on a copy of the slot so legibility checks see the background
+            let slot = imageops::crop_imm(rgba, x as u32, y as u32, rect.width(), rect.height());
+            let mut layer = pool.take_uncleared(slot.width(), slot.height());
+            for (px, py, p) in slot.pixels() {
+                layer.put_pixel(px, py, p);
+            }
+            debug::with_origin(x as i32, y as i32, || {
+                draw_centered_text(&mut layer, text, color.unwrap_or(theme.fg), 0.1)
+            });
+            imageops::replace(rgba, &layer, x, y);
+            pool.give(layer);
+        }
+        Element::Icon { image, tint } => {
+            let mut layer = pool.take(rect.width(), rect.height());
+            paint_image(&mut layer, image, Fit::Contain);
+            if let Some(color) = tint {
+                apply_tint(&mut layer, *color);
+            }
+            imageops::overlay(rgba, &layer, x, y);
+            pool.give(layer);
+        }
+        Element::Bar { value, color } => {
+            // A third of the slot's height, centered, so bars read as bars
+            let thickness = stroke_width((rect.height() / 3).max(2)).min(rect.height());
+            let top = rect.top() + (rect.height() - thickness) as i32 / 2;
+            let style = ProgressStyle {
+                track: theme.muted,
+                fill: color.unwrap_or(theme.accent),
+                ..ProgressStyle::default()
+            };
+            draw_progress_bar(
+                rgba,
+                Rect::at(rect.left(), top).of_size(rect.width(), thickness),
+                Progress::Determinate(*value),
+                &style,
+                Duration::ZERO,
+            );
+        }
+        Element::Ring { value, color } => {
+            let value = value.clamp(0.0, 1.0);
+            let segments = [
+                (value, color.unwrap_or(theme.accent)),
+                (1.0 - value, theme.muted),
+            ];
+            let style = DonutStyle {
+                hole: if high_contrast() { 0.5 } else { 0.7 },
+                gap: 0.0,
+            };
+            draw_donut(rgba, rect, &segments, &style);
+        }
+        Element::Sparkline { values, color } => {
+            draw_sparkline(rgba, rect, values, color.unwrap_or(theme.accent), cancel);
+        }
+        Element::Stack(spec) => draw_stack(rgba, spec, rect, theme, cancel, pool),
+        Element::Spacer => {}
+    }
+}
+
+fn draw_sparkline(
+    rgba: &mut RgbaImage,
+    rect: Rect,
+    values: &[f32],
+    color: Rgba<u8>,
+    cancel: &CancelToken,
+) {
+    let (min, max) = values
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &v| {
+            (lo.min(v), hi.max(v))
+        });
+    if values.is_empty() || !min.is_finite() || !max.is_finite() {
+        return;
+    }
+    let span = max - min;
+    let (left, top) = (rect.left() as f32, rect.top() as f32);
+    let (w, h) = ((rect.width() - 1) as f32, (rect.height() - 1) as f32);
+    let point = |i: usize, v: f32| {
+        let x = if values.len() > 1 {
+            i as f32 / (values.len() - 1) as f32
+        } else {
+            0.5
+        };
+        // Flat series sit on the middle line
+        let y = if span > 0.0 { (v - min) / span } else { 0.5 };
+        (left + x * w, top + (1.0 - y) * h)
+    };
+    // Thicker strokes are drawn as vertically offset copies of the line
+    let width = stroke_width(1);
+    for offset in 0..width {
+        let dy = offset as f32 - (width - 1) as f32 / 2.0;
+        let shift = |(x, y): (f32, f32)| (x, y + dy);
+        if values.len() == 1 {
+            let (_, y) = point(0, values[0]);
+            draw_line_segment_mut(rgba, (left, y + dy), (left + w, y + dy), color);
+            continue;
+        }
+        for (i, pair) in values.windows(2).enumerate() {
+            if cancel.is_canceled() {
+                return;
+            }
+            draw_line_segment_mut(
+                rgba,
+                shift(point(i, pair[0])),
+                shift(point(i + 1, pair[1])),
+                color,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::font::get_system_monospace_font;
+
+    fn theme() -> Theme {
+        Theme {
+            fg: Rgba([255, 255, 255, 255]),
+            bg: Rgba([0, 0, 0, 255]),
+            accent: Rgba([255, 0, 0, 255]),
+            muted: Rgba([0, 0, 255, 255]),
+            ..Theme::dark()
+        }
+    }
+
+    fn has_color(img: &RgbaImage, rect: Rect, color: Rgba<u8>) -> bool {
+        (rect.top()..=rect.bottom()).any(|y| {
+            (rect.left()..=rect.right()).any(|x| *img.get_pixel(x as u32, y as u32) == color)
+        })
+    }
+
+    #[test]
+    fn test_render_empty_fills_background() {
+        let img = render(&Spec::column(), (16, 8), &theme());
+        assert_eq!(img.dimensions(), (16, 8));
+        assert!(img.pixels().all(|p| *p == Rgba([0, 0, 0, 255])));
+    }
+
+    #[test]
+    fn test_render_weighted_column() {
+        let spec = Spec::column()
+            .with_padding(0)
+            .with_gap(0)
+            .with_weighted(Element::bar(1.0), 1.0)
+            .with_weighted(Element::Spacer, 3.0);
+        let img = render(&spec, (40, 40), &theme());
+        // The bar occupies the top quarter only
+        assert!(has_color(
+            &img,
+            Rect::at(0, 0).of_size(40, 10),
+            theme().accent
+        ));
+        assert!(!has_color(
+            &img,
+            Rect::at(0, 10).of_size(40, 30),
+            theme().accent
+        ));
+    }
+
+    #[test]
+    fn test_render_cancelable() {
+        let spec = Spec::column().with(Element::sparkline(vec![0.5; 1000]));
+        let cancel = CancelToken::new();
+        let img = render_cancelable(&spec, (8, 8), &theme(), &cancel).unwrap();
+        assert_eq!(img, render(&spec, (8, 8), &theme()));
+        cancel.cancel();
+        assert!(render_cancelable(&spec, (8, 8), &theme(), &cancel).is_none());
+    }
+
+    #[test]
+    fn test_render_pooled_matches_render() {
+        let spec =
+            Spec::column()
+                .with(Element::text("42"))
+                .with(Element::icon(RgbaImage::from_pixel(
+                    4,
+                    4,
+                    Rgba([0, 255, 0, 255]),
+                )));
+        let mut pool = FramePool::new();
+        let frame = render_pooled(&spec, (16, 16), &theme(), &mut pool);
+        assert_eq!(frame, render(&spec, (16, 16), &theme()));
+        // The element layers went back to the pool, and so can the frame
+        let layers = pool.idle();
+        assert!(layers > 0);
+        pool.give(frame);
+        let again = render_pooled(&spec, (16, 16), &theme(), &mut pool);
+        assert_eq!(again, render(&spec, (16, 16), &theme()));
+        assert_eq!(pool.idle(), layers);
+    }
+
+    #[test]
+    fn test_render_bar_in_one_pixel_slot() {
+        let spec = Spec::column().with_padding(0).with(Element::bar(0.5));
+        let img = render(&spec, (10, 1), &theme());
+        assert_eq!(*img.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+        assert_eq!(*img.get_pixel(9, 0), Rgba([0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn test_render_row_ring_and_sparkline() {
+        let spec = Spec::row()
+            .with_padding(0)
+            .with_gap(0)
+            .with(Element::ring(0.5))
+            .with(Element::sparkline([1.0, 3.0, 2.0]).with_color(Rgba([0, 255, 0, 255])));
+        let img = render(&spec, (40, 20), &theme());
+        let left = Rect::at(0, 0).of_size(20, 20);
+        let right = Rect::at(20, 0).of_size(20, 20);
+        assert!(has_color(&img, left, theme().accent));
+        assert!(has_color(&img, left, theme().muted));
+        assert!(has_color(&img, right, Rgba([0, 255, 0, 255])));
+        assert!(!has_color(&img, right, theme().accent));
+    }
+
+    #[test]
+    fn test_render_icon_tint() {
+        let icon = RgbaImage::from_pixel(4, 4, Rgba([10, 20, 30, 255]));
+        let tint = Rgba([0, 255, 0, 255]);
+        let spec = Spec::column()
+            .with_padding(0)
+            .with(Element::icon(icon).with_color(tint));
+        let img = render(&spec, (8, 8), &theme());
+        assert_eq!(*img.get_pixel(4, 4), tint);
+    }
+
+    #[test]
+    fn test_render_nested_text() {
+        if get_system_monospace_font().is_none() {
+            return;
+        }
+        let spec = Spec::column().with(Element::Stack(
+            Spec::row().with(Element::text("42")).with(Element::Spacer),
+        ));
+        let img = render(&spec, (72, 72), &theme());
+        // Text lands in the left half only
+        let lit =
+            |x0: u32, x1: u32| (x0..x1).any(|x| (0..72).any(|y| img.get_pixel(x, y)[0] > 128));
+        assert!(lit(0, 36));
+        assert!(!lit(36, 72));
+    }
+}
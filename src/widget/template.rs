@@ -0,0 +1,461 @@
+//! Widget templates loaded from data files.
+//!
+//! A template is a serialized [`Spec`] whose strings may contain
+//! `{placeholders}`, filled from a key/value map each time it is rendered.
+//! Users can re-skin a plugin by editing the template file, with no
+//! recompilation. `{{` and `}}` produce literal braces.
+//!
+//! ```json
+//! {
+//!   "direction": "column",
+//!   "items": [
+//!     { "text": "{label}", "color": "{fg}" },
+//!     { "sparkline": "{history}", "weight": 2 },
+//!     { "icon": "icons/{state}.png", "tint": "#ffcc00" },
+//!     { "stack": { "direction": "row", "items": [{ "ring": "{cpu}" }, { "spacer": true }] } },
+//!     { "bar": 0.5 }
+//!   ]
+//! }
+//! ```
+//!
+//! Numbers may be given directly or as placeholder strings; sparkline
+//! values are a comma or space separated list. [`Template`] implements
+//! `Deserialize`, so formats other than JSON (e.g. TOML) work through
+//! their own serde crates.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use image::RgbaImage;
+use serde::Deserialize;
+
+use super::{Direction, Element, Item, Spec, render};
+use crate::colors::{Theme, lookup};
+
+/// Error loading or filling a [`Template`].
+#[derive(Debug)]
+pub enum TemplateError {
+    /// The template or an icon file could not be read.
+    Io(std::io::Error),
+    /// The template file is not valid.
+    Parse(String),
+    /// The file extension is not a format this crate reads.
+    UnsupportedFormat(PathBuf),
+    /// An icon could not be decoded.
+    Image(image::ImageError),
+    /// A `{placeholder}` has no value in the map.
+    MissingValue(String),
+    /// A filled-in field could not be interpreted.
+    InvalidValue { field: &'static str, value: String },
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::Io(e) => write!(f, "template I/O error: {e}"),
+            TemplateError::Parse(e) => write!(f, "invalid template: {e}"),
+            TemplateError::UnsupportedFormat(path) => {
+                write!(f, "unsupported template format: {}", path.display())
+            }
+            TemplateError::Image(e) => write!(f, "invalid template icon: {e}"),
+            TemplateError::MissingValue(key) => write!(f, "no value for placeholder {{{key}}}"),
+            TemplateError::InvalidValue { field, value } => {
+                write!(f, "invalid {field} value: {value:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TemplateError::Io(e) => Some(e),
+            TemplateError::Image(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for TemplateError {
+    fn from(e: std::io::Error) -> Self {
+        TemplateError::Io(e)
+    }
+}
+
+impl From<image::ImageError> for TemplateError {
+    fn from(e: image::ImageError) -> Self {
+        TemplateError::Image(e)
+    }
+}
+
+/// A number written directly or as a (placeholder) string.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum Field {
+    Number(f32),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ElementTemplate {
+    Text(String),
+    Icon(String),
+    Bar(Field),
+    Ring(Field),
+    Sparkline(Field),
+    Stack(StackTemplate),
+    Spacer(serde::de::IgnoredAny),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ItemTemplate {
+    #[serde(flatten)]
+    element: ElementTemplate,
+    #[serde(default = "default_weight")]
+    weight: f32,
+    color: Option<String>,
+    tint: Option<String>,
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+struct StackTemplate {
+    direction: Direction,
+    padding: u32,
+    gap: u32,
+    items: Vec<ItemTemplate>,
+}
+
+impl Default for StackTemplate {
+    fn default() -> Self {
+        let spec = Spec::default();
+        Self {
+            direction: spec.direction,
+            padding: spec.padding,
+            gap: spec.gap,
+            items: Vec::new(),
+        }
+    }
+}
+
+/// A [`Spec`] with `{placeholders}`, loaded from a data file.
+///
+/// Icon paths are resolved against the template's directory and decoded
+/// images are cached, so filling the template each frame only touches the
+/// disk when an icon path changes.
+#[derive(Debug, Clone)]
+pub struct Template {
+    root: StackTemplate,
+    base_dir: PathBuf,
+    icons: HashMap<PathBuf, RgbaImage>,
+}
+
+impl<'de> Deserialize<'de> for Template {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        StackTemplate::deserialize(deserializer).map(|root| Template {
+            root,
+            base_dir: PathBuf::new(),
+            icons: HashMap::new(),
+        })
+    }
+}
+
+impl Template {
+    /// Parse a template from a JSON string.
+    pub fn from_json(json: &str) -> Result<Self, TemplateError> {
+        serde_json::from_str(json).map_err(|e| TemplateError::Parse(e.to_string()))
+    }
+
+    /// Load a template file. Icon paths are resolved relative to its directory.
+    ///
+    /// Only `.json` files are read here; other formats can be deserialized
+    /// with their serde crate and given a base directory with
+    /// [`with_base_dir`](Self::with_base_dir).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TemplateError> {
+        let path = path.as_ref();
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        if !is_json {
+            return Err(TemplateError::UnsupportedFormat(path.to_path_buf()));
+        }
+        let template = Self::from_json(&std::fs::read_to_string(path)?)?;
+        Ok(template.with_base_dir(path.parent().unwrap_or(Path::new(""))))
+    }
+
+    /// Set the directory icon paths are resolved against.
+    pub fn with_base_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = dir.into();
+        self.clear_cache();
+        self
+    }
+
+    /// Drop cached icons so they are read from disk again, e.g. after an
+    /// icon file changes.
+    pub fn clear_cache(&mut self) {
+        self.icons.clear();
+    }
+
+    /// Fill placeholders from `values` to produce a [`Spec`].
+    pub fn to_spec(&mut self, values: &HashMap<String, String>) -> Result<Spec, TemplateError> {
+        let root = self.root.clone();
+        self.fill_stack(&root, values)
+    }
+
+    /// Fill placeholders and render, as [`render`] does for a [`Spec`].
+    pub fn render(
+        &mut self,
+        values: &HashMap<String, String>,
+        size: (u32, u32),
+        theme: &Theme,
+    ) -> Result<RgbaImage, TemplateError> {
+        Ok(render(&self.to_spec(values)?, size, theme))
+    }
+
+    fn fill_stack(
+        &mut self,
+        stack: &StackTemplate,
+        values: &HashMap<String, String>,
+    ) -> Result<Spec, TemplateError> {
+        let mut items = Vec::with_capacity(stack.items.len());
+        for item in &stack.items {
+            let color = item
+                .color
+                .as_ref()
+                .or(item.tint.as_ref())
+                .map(|c| parse_color(&substitute(c, values)?))
+                .transpose()?;
+            let element = match &item.element {
+                ElementTemplate::Text(text) => Element::Text {
+                    text: substitute(text, values)?,
+                    color,
+                },
+                ElementTemplate::Icon(path) => Element::Icon {
+                    image: self.icon(&substitute(path, values)?)?,
+                    tint: color,
+                },
+                ElementTemplate::Bar(value) => Element::Bar {
+                    value: fill_number(value, values, "bar")?,
+                    color,
+                },
+                ElementTemplate::Ring(value) => Element::Ring {
+                    value: fill_number(value, values, "ring")?,
+                    color,
+                },
+                ElementTemplate::Sparkline(series) => Element::Sparkline {
+                    values: fill_series(series, values)?,
+                    color,
+                },
+                ElementTemplate::Stack(nested) => Element::Stack(self.fill_stack(nested, values)?),
+                ElementTemplate::Spacer(_) => Element::Spacer,
+            };
+            items.push(Item {
+                element,
+                weight: item.weight,
+            });
+        }
+        Ok(Spec {
+            direction: stack.direction,
+            padding: stack.padding,
+            gap: stack.gap,
+            items,
+        })
+    }
+
+    fn icon(&mut self, path: &str) -> Result<RgbaImage, TemplateError> {
+        let path = self.base_dir.join(path);
+        if let Some(image) = self.icons.get(&path) {
+            return Ok(image.clone());
+        }
+        let image = image::open(&path)?.to_rgba8();
+        self.icons.insert(path, image.clone());
+        Ok(image)
+    }
+}
+
+/// Replace `{key}` with its value from `values`; `{{` and `}}` are literal braces.
+fn substitute(s: &str, values: &HashMap<String, String>) -> Result<String, TemplateError> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find(['{', '}']) {
+        out.push_str(&rest[..i]);
+        let tail = &rest[i..];
+        if let Some(after) = tail.strip_prefix("{{") {
+            out.push('{');
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix("}}") {
+            out.push('}');
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix('{')
+            && let Some((key, after)) = after.split_once('}')
+        {
+            let value = values
+                .get(key.trim())
+                .ok_or_else(|| TemplateError::MissingValue(key.trim().to_string()))?;
+            out.push_str(value);
+            rest = after;
+        } else {
+            // Unmatched brace, kept as written
+            out.push_str(&tail[..1]);
+            rest = &tail[1..];
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn parse_color(s: &str) -> Result<image::Rgba<u8>, TemplateError> {
+    lookup(s).ok_or_else(|| TemplateError::InvalidValue {
+        field: "color",
+        value: s.to_string(),
+    })
+}
+
+fn fill_number(
+    field: &Field,
+    values: &HashMap<String, String>,
+    name: &'static str,
+) -> Result<f32, TemplateError> {
+    match field {
+        Field::Number(n) => Ok(*n),
+        Field::Text(text) => {
+            let filled = substitute(text, values)?;
+            filled
+                .trim()
+                .parse()
+                .map_err(|_| TemplateError::InvalidValue {
+                    field: name,
+                    value: filled,
+                })
+        }
+    }
+}
+
+fn fill_series(field: &Field, values: &HashMap<String, String>) -> Result<Vec<f32>, TemplateError> {
+    match field {
+        Field::Number(n) => Ok(vec![*n]),
+        Field::Text(text) => {
+            let filled = substitute(text, values)?;
+            filled
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|v| !v.is_empty())
+                .map(|v| {
+                    v.parse().map_err(|_| TemplateError::InvalidValue {
+                        field: "sparkline",
+                        value: filled.clone(),
+                    })
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_substitute() {
+        let v = values(&[("name", "cpu"), ("n", "3")]);
+        assert_eq!(substitute("{name} x{ n }", &v).unwrap(), "cpu x3");
+        assert_eq!(substitute("{{literal}} }", &v).unwrap(), "{literal} }");
+        assert!(matches!(
+            substitute("{missing}", &v),
+            Err(TemplateError::MissingValue(key)) if key == "missing"
+        ));
+    }
+
+    #[test]
+    fn test_template_to_spec() {
+        let mut template = Template::from_json(
+            r##"{
+                "direction": "row",
+                "gap": 0,
+                "items": [
+                    { "text": "{label}", "color": "{fg}" },
+                    { "bar": "{load}", "weight": 2 },
+                    { "ring": 0.25 },
+                    { "sparkline": "{history}" },
+                    { "stack": { "items": [{ "spacer": true }] } }
+                ]
+            }"##,
+        )
+        .unwrap();
+        let spec = template
+            .to_spec(&values(&[
+                ("label", "CPU"),
+                ("fg", "#ff0000"),
+                ("load", "0.5"),
+                ("history", "1, 2 3"),
+            ]))
+            .unwrap();
+
+        let expected = Spec::row()
+            .with_gap(0)
+            .with(Element::text("CPU").with_color(Rgba([255, 0, 0, 255])))
+            .with_weighted(Element::bar(0.5), 2.0)
+            .with(Element::ring(0.25))
+            .with(Element::sparkline([1.0, 2.0, 3.0]))
+            .with(Element::Stack(Spec::column().with(Element::Spacer)));
+        assert_eq!(spec, expected);
+    }
+
+    #[test]
+    fn test_template_errors() {
+        assert!(matches!(
+            Template::from_json(r#"{ "items": [{ "dial": 1 }] }"#),
+            Err(TemplateError::Parse(_))
+        ));
+        let mut template = Template::from_json(r#"{ "items": [{ "bar": "{v}" }] }"#).unwrap();
+        assert!(matches!(
+            template.to_spec(&values(&[("v", "lots")])),
+            Err(TemplateError::InvalidValue { field: "bar", .. })
+        ));
+        assert!(matches!(
+            Template::load("widget.yaml"),
+            Err(TemplateError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_template_load_with_icon() {
+        let dir = std::env::temp_dir().join(format!("vpu-template-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("icons")).unwrap();
+        RgbaImage::from_pixel(2, 2, Rgba([0, 0, 0, 255]))
+            .save(dir.join("icons/ok.png"))
+            .unwrap();
+        std::fs::write(
+            dir.join("widget.json"),
+            r##"{ "padding": 0, "items": [{ "icon": "icons/{state}.png", "tint": "#00ff00" }] }"##,
+        )
+        .unwrap();
+
+        let mut template = Template::load(dir.join("widget.json")).unwrap();
+        let img = template
+            .render(&values(&[("state", "ok")]), (8, 8), &Theme::default())
+            .unwrap();
+        assert_eq!(*img.get_pixel(4, 4), Rgba([0, 255, 0, 255]));
+        assert!(matches!(
+            template.to_spec(&values(&[("state", "gone")])),
+            Err(TemplateError::Image(_))
+        ));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,338 @@
+//! Off-thread rendering.
+//!
+//! A [`RenderWorker`] owns a background thread that runs render jobs in
+//! submission order, so a plugin's event loop stays responsive while heavy
+//! frames (blurred backgrounds, charts, large specs) are drawn. Each job gets
+//! a frame ID, returned with the finished image, so stale results can be
+//! recognized and dropped. Jobs submitted with a [`CancelToken`] can be
+//! abandoned part-way once newer data makes them obsolete.
+//!
+//! ```ignore
+//! let mut worker = RenderWorker::new();
+//!
+//! // On input or a timer tick, replacing any frame still in progress:
+//! worker.cancel_pending();
+//! worker.submit_spec(build_spec(&state), (72, 72), theme);
+//!
+//! // In the event loop, without blocking:
+//! if let Some(frame) = worker.latest() {
+//!     host.present(&frame.image);
+//! }
+//! ```
+
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use image::RgbaImage;
+
+pub use crate::cancel::CancelToken;
+use crate::colors::Theme;
+use crate::widget::{Spec, render_cancelable};
+
+type Job = (
+    u64,
+    CancelToken,
+    Box<dyn FnOnce(&CancelToken) -> Option<RgbaImage> + Send>,
+);
+
+/// What the render thread reports for every job: its ID and frame, or
+/// `None` if it was skipped, stopped early or panicked.
+type Outcome = (u64, Option<RgbaImage>);
+
+/// A finished frame from a [`RenderWorker`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedFrame {
+    /// ID returned by the `submit` call that produced this frame.
+    pub id: u64,
+    /// The finished image, at the size the job rendered.
+    pub image: RgbaImage,
+}
+
+/// Runs render jobs on a dedicated thread.
+///
+/// Jobs run one at a time in the order submitted. A job that panics is
+/// logged and produces no frame; the worker keeps running. So does a job
+/// canceled through [`cancel_pending`](Self::cancel_pending): queued jobs
+/// are skipped, and running ones stop at their next check of the token.
+/// Dropping the worker finishes any queued jobs and joins the thread.
+pub struct RenderWorker {
+    jobs: Option<Sender<Job>>,
+    outcomes: Receiver<Outcome>,
+    thread: Option<JoinHandle<()>>,
+    next_id: u64,
+    pending: usize,
+    tokens: Vec<(u64, CancelToken)>,
+}
+
+impl RenderWorker {
+    /// Start a worker thread.
+    ///
+    /// If the thread can't be spawned the error is logged and the worker
+    /// drops every job it is given; use [`try_new`](Self::try_new) to
+    /// handle the error instead.
+    pub fn new() -> Self {
+        Self::try_new().unwrap_or_else(|e| {
+            tracing::error!(error = %e, "Failed to spawn render thread");
+            let (_, outcomes) = mpsc::channel();
+            Self {
+                jobs: None,
+                outcomes,
+                thread: None,
+                next_id: 0,
+                pending: 0,
+                tokens: Vec::new(),
+            }
+        })
+    }
+
+    /// Start a worker thread, or return the error from spawning it.
+    pub fn try_new() -> std::io::Result<Self> {
+        let (jobs, job_rx) = mpsc::channel::<Job>();
+        let (outcome_tx, outcomes) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("verandah-render".into())
+            .spawn(move || {
+                for (id, cancel, job) in job_rx {
+                    let image = if cancel.is_canceled() {
+                        tracing::debug!(id, "Render job skipped");
+                        None
+                    } else {
+                        match catch_unwind(AssertUnwindSafe(|| job(&cancel))) {
+                            Ok(Some(image)) => Some(image),
+                            Ok(None) => {
+                                tracing::debug!(id, "Render job canceled");
+                                None
+                            }
+                            Err(_) => {
+                                tracing::error!(id, "Render job panicked");
+                                None
+                            }
+                        }
+                    };
+                    if outcome_tx.send((id, image)).is_err() {
+                        break;
+                    }
+                }
+            })?;
+        Ok(Self {
+            jobs: Some(jobs),
+            outcomes,
+            thread: Some(thread),
+            next_id: 0,
+            pending: 0,
+            tokens: Vec::new(),
+        })
+    }
+
+    /// Queue a render closure, returning its frame ID.
+    ///
+    /// IDs increase by one per submission, starting at 0.
+    pub fn submit(&mut self, render: impl FnOnce() -> RgbaImage + Send + 'static) -> u64 {
+        self.submit_cancelable(move |_| Some(render()))
+    }
+
+    /// Queue a render closure that polls a [`CancelToken`] and returns
+    /// `None` if it stopped early, returning its frame ID.
+    ///
+    /// The token is canceled by [`cancel_pending`](Self::cancel_pending).
+    pub fn submit_cancelable(
+        &mut self,
+        render: impl FnOnce(&CancelToken) -> Option<RgbaImage> + Send + 'static,
+    ) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        let cancel = CancelToken::new();
+        let sent = self
+            .jobs
+            .as_ref()
+            .is_some_and(|jobs| jobs.send((id, cancel.clone(), Box::new(render))).is_ok());
+        if sent {
+            self.pending += 1;
+            self.tokens.push((id, cancel));
+        } else {
+            tracing::warn!(id, "Render thread has exited; job dropped");
+        }
+        id
+    }
+
+    /// Queue a [`Spec`] to be rendered with
+    /// [`render_cancelable`](crate::widget::render_cancelable), returning
+    /// its frame ID.
+    pub fn submit_spec(&mut self, spec: Spec, size: (u32, u32), theme: Theme) -> u64 {
+        self.submit_cancelable(move |cancel| render_cancelable(&spec, size, &theme, cancel))
+    }
+
+    /// Cancel every job submitted so far that has not produced a frame.
+    ///
+    /// Frames already finished can still be received.
+    pub fn cancel_pending(&mut self) {
+        for (_, cancel) in self.tokens.drain(..) {
+            cancel.cancel();
+        }
+    }
+
+    /// A finished frame, if one is ready. Never blocks.
+    pub fn try_recv(&mut self) -> Option<RenderedFrame> {
+        while let Ok(outcome) = self.outcomes.try_recv() {
+            if let Some(frame) = self.received(outcome) {
+                return Some(frame);
+            }
+        }
+        None
+    }
+
+    /// Wait up to `timeout` for the next finished frame.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Option<RenderedFrame> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let wait = deadline.saturating_duration_since(Instant::now());
+            let outcome = self.outcomes.recv_timeout(wait).ok()?;
+            if let Some(frame) = self.received(outcome) {
+                return Some(frame);
+            }
+        }
+    }
+
+    /// The most recent finished frame, discarding any older ones that are
+    /// also ready. Never blocks.
+    pub fn latest(&mut self) -> Option<RenderedFrame> {
+        std::iter::from_fn(|| self.try_recv()).last()
+    }
+
+    /// Number of submitted jobs not yet accounted for.
+    ///
+    /// A job stops counting once its frame is received, or, for jobs that
+    /// panicked, returned `None` or were canceled, once a receiving call
+    /// ([`try_recv`](Self::try_recv), [`recv_timeout`](Self::recv_timeout)
+    /// or [`latest`](Self::latest)) finds that it finished without one.
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
+    /// Account for one job's outcome, returning its frame if it has one.
+    fn received(&mut self, (id, image): Outcome) -> Option<RenderedFrame> {
+        self.pending = self.pending.saturating_sub(1);
+        // Outcomes arrive in order, so no token up to this ID is needed
+        self.tokens.retain(|(token, _)| *token > id);
+        image.map(|image| RenderedFrame { id, image })
+    }
+}
+
+impl Default for RenderWorker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for RenderWorker {
+    fn drop(&mut self) {
+        // Closing the channel ends the thread's loop once the queue drains
+        self.jobs.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl std::fmt::Debug for RenderWorker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RenderWorker")
+            .field("next_id", &self.next_id)
+            .field("pending", &self.pending)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    const WAIT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn test_worker_returns_frames_in_order() {
+        let mut worker = RenderWorker::new();
+        let a = worker.submit(|| RgbaImage::from_pixel(1, 1, Rgba([1, 0, 0, 255])));
+        let b = worker.submit_spec(Spec::default(), (4, 2), Theme::dark());
+        assert_eq!((a, b), (0, 1));
+        assert_eq!(worker.pending(), 2);
+
+        let first = worker.recv_timeout(WAIT).unwrap();
+        assert_eq!(first.id, a);
+        assert_eq!(first.image.get_pixel(0, 0)[0], 1);
+        let second = worker.recv_timeout(WAIT).unwrap();
+        assert_eq!((second.id, second.image.dimensions()), (b, (4, 2)));
+        assert_eq!(worker.pending(), 0);
+        assert!(worker.try_recv().is_none());
+    }
+
+    #[test]
+    fn test_worker_survives_panicking_job() {
+        let mut worker = RenderWorker::new();
+        worker.submit(|| panic!("bad frame"));
+        let ok = worker.submit(|| RgbaImage::new(2, 2));
+        let frame = worker.recv_timeout(WAIT).unwrap();
+        assert_eq!(frame.id, ok);
+        assert_eq!(worker.pending(), 0);
+    }
+
+    #[test]
+    fn test_pending_counts_jobs_without_frames() {
+        let mut worker = RenderWorker::new();
+        worker.submit(|| RgbaImage::new(1, 1));
+        worker.submit(|| panic!("bad frame"));
+        worker.submit_cancelable(|_| None);
+        assert_eq!(worker.pending(), 3);
+        assert!(worker.recv_timeout(WAIT).is_some());
+        let start = Instant::now();
+        while worker.pending() > 0 && start.elapsed() < WAIT {
+            assert!(worker.try_recv().is_none());
+        }
+        assert_eq!(worker.pending(), 0);
+    }
+
+    #[test]
+    fn test_worker_latest_skips_stale_frames() {
+        let mut worker = RenderWorker::new();
+        for size in 1..5 {
+            worker.submit(move || RgbaImage::new(size, size));
+        }
+        // Once this job starts, all earlier frames have been sent; it then
+        // waits so its own frame isn't ready yet
+        let (started_tx, started) = mpsc::channel();
+        let (release, released) = mpsc::channel::<()>();
+        worker.submit(move || {
+            started_tx.send(()).unwrap();
+            let _ = released.recv_timeout(WAIT);
+            RgbaImage::new(1, 1)
+        });
+        started.recv_timeout(WAIT).unwrap();
+        let frame = worker.latest().unwrap();
+        assert_eq!((frame.id, frame.image.width()), (3, 4));
+        assert_eq!(worker.pending(), 1);
+        drop(release);
+    }
+
+    #[test]
+    fn test_cancel_pending_skips_and_stops_jobs() {
+        let mut worker = RenderWorker::new();
+        let (started_tx, started) = mpsc::channel();
+        let (release, released) = mpsc::channel::<()>();
+        worker.submit_cancelable(move |cancel| {
+            started_tx.send(()).unwrap();
+            let _ = released.recv_timeout(WAIT);
+            (!cancel.is_canceled()).then(|| RgbaImage::new(1, 1))
+        });
+        worker.submit(|| RgbaImage::new(2, 2));
+        started.recv_timeout(WAIT).unwrap();
+        worker.cancel_pending();
+        drop(release);
+        let fresh = worker.submit(|| RgbaImage::new(3, 3));
+        let frame = worker.recv_timeout(WAIT).unwrap();
+        assert_eq!((frame.id, frame.image.width()), (fresh, 3));
+        assert_eq!(worker.pending(), 0);
+    }
+}
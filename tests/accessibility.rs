@@ -0,0 +1,152 @@
+//! High-contrast and reduced-motion modes are process-wide, so tests that
+//! read or switch them live in their own test binary and hold [`LOCK`] for
+//! their whole body.
+
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use verandah_plugin_utils::accessibility::{
+    HIGH_CONTRAST_RATIO, high_contrast, motion_progress, reduced_motion, set_high_contrast,
+    set_reduced_motion, show_decoration, stroke_width,
+};
+use verandah_plugin_utils::colors::contrast_ratio;
+use verandah_plugin_utils::prelude::*;
+
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// Take [`LOCK`] before touching either mode, including baseline reads.
+fn lock() -> MutexGuard<'static, ()> {
+    LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Turns a mode on until dropped, switching it back off even if the test
+/// panics. Borrowing the [`lock`] guard keeps the lock held for as long.
+struct Mode<'a> {
+    set: fn(bool),
+    _lock: &'a MutexGuard<'static, ()>,
+}
+
+impl<'a> Mode<'a> {
+    fn on(lock: &'a MutexGuard<'static, ()>, set: fn(bool)) -> Self {
+        set(true);
+        Mode { set, _lock: lock }
+    }
+}
+
+impl Drop for Mode<'_> {
+    fn drop(&mut self) {
+        (self.set)(false);
+    }
+}
+
+#[test]
+fn test_high_contrast_helpers() {
+    let lock = lock();
+    let _mode = Mode::on(&lock, set_high_contrast);
+    assert!(high_contrast());
+    assert_eq!(stroke_width(2), 4);
+    assert!(!show_decoration(40));
+    assert!(show_decoration(200));
+}
+
+#[test]
+fn test_theme_effective_follows_high_contrast() {
+    let lock = lock();
+    let normal = Theme::default().effective();
+    let theme = {
+        let _mode = Mode::on(&lock, set_high_contrast);
+        Theme::default().effective()
+    };
+    assert_eq!(normal, Theme::default());
+    assert!(contrast_ratio(theme.accent, theme.bg) >= HIGH_CONTRAST_RATIO);
+}
+
+#[test]
+fn test_render_high_contrast_thickens_strokes() {
+    let lock = lock();
+    let theme = Theme {
+        fg: Rgba([255, 255, 255, 255]),
+        bg: Rgba([0, 0, 0, 255]),
+        ..Theme::dark()
+    };
+    let spec = Spec::column()
+        .with_padding(0)
+        .with(Element::sparkline([1.0, 1.0]).with_color(Rgba([255, 255, 255, 255])));
+    let count = |img: &RgbaImage| img.pixels().filter(|p| p[0] == 255).count();
+    let normal = count(&render_spec(&spec, (20, 20), &theme));
+    let thick = {
+        let _mode = Mode::on(&lock, set_high_contrast);
+        count(&render_spec(&spec, (20, 20), &theme))
+    };
+    assert_eq!(thick, normal * 2);
+}
+
+#[test]
+fn test_reduced_motion_helpers() {
+    let lock = lock();
+    assert_eq!(motion_progress(0.3), 0.3);
+    let _mode = Mode::on(&lock, set_reduced_motion);
+    assert!(reduced_motion());
+    assert_eq!(motion_progress(0.0), 0.0);
+    assert_eq!(motion_progress(0.3), 1.0);
+}
+
+#[test]
+fn test_progress_reduced_motion_is_static() {
+    let lock = lock();
+    let style = ProgressStyle::default();
+    let bar = |progress: Progress, elapsed: Duration| {
+        let mut img = RgbaImage::new(40, 6);
+        let rect = Rect::at(0, 1).of_size(40, 4);
+        draw_progress_bar(&mut img, rect, progress, &style, elapsed);
+        img
+    };
+    let (stripes, bounce) = {
+        let _mode = Mode::on(&lock, set_reduced_motion);
+        (
+            [Duration::ZERO, style.period / 4].map(|t| bar(Progress::Stripes, t)),
+            [Duration::ZERO, style.period / 2].map(|t| bar(Progress::Bounce, t)),
+        )
+    };
+    assert_eq!(stripes[0], stripes[1]);
+    assert_eq!(bounce[0], bounce[1]);
+    // Segment rests in the middle of the bar
+    let filled: Vec<u32> = (0..40)
+        .filter(|&x| *bounce[0].get_pixel(x, 2) == style.fill)
+        .collect();
+    assert_eq!(filled, (14..26).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_pulse_reduced_motion_holds_max() {
+    let lock = lock();
+    let _mode = Mode::on(&lock, set_reduced_motion);
+    assert_eq!(Pulse::default().level_at(Duration::from_millis(1125)), 1.0);
+}
+
+#[test]
+fn test_value_tween_reduced_motion_jumps() {
+    let lock = lock();
+    let mut tween = ValueTween::new(10.0, Duration::from_secs(1));
+    let _mode = Mode::on(&lock, set_reduced_motion);
+    tween.set(20.0, Duration::from_secs(5));
+    assert_eq!(tween.value(Duration::from_secs(5)), 20.0);
+}
+
+#[test]
+fn test_carousel_and_shake_reduced_motion() {
+    let lock = lock();
+    let solid = |color| Slide::Image(RgbaImage::from_pixel(4, 4, color));
+    let carousel = Carousel::new(
+        vec![solid(Rgba([255, 0, 0, 255])), solid(Rgba([0, 0, 255, 255]))],
+        Duration::from_secs(2),
+    )
+    .with_transition(Transition::Crossfade, Duration::from_secs(1));
+    let mut img = RgbaImage::new(4, 4);
+    let _mode = Mode::on(&lock, set_reduced_motion);
+    // Transitions collapse, so 2.5s falls in the second slide's dwell
+    assert_eq!(carousel.position(Duration::from_millis(2500)), (1, None));
+    carousel.render(&mut img, Duration::from_millis(2500));
+    assert_eq!(*img.get_pixel(0, 0), Rgba([0, 0, 255, 255]));
+    assert_eq!(shake(0.1, 5.0), 0.0);
+}
@@ -0,0 +1,58 @@
+//! The debug overlay flag is process-wide, so tests that switch it live in
+//! their own test binary and hold [`LOCK`] while it is on.
+
+use std::sync::{Mutex, MutexGuard};
+
+use verandah_plugin_utils::debug::{
+    DebugMark, debug_overlay, draw_overlay, mark, set_debug_overlay,
+};
+use verandah_plugin_utils::prelude::*;
+
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// Enables the overlay until dropped, switching it back off even if the
+/// test panics.
+struct Overlay {
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl Overlay {
+    fn on() -> Self {
+        let lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        set_debug_overlay(true);
+        Overlay { _lock: lock }
+    }
+}
+
+impl Drop for Overlay {
+    fn drop(&mut self) {
+        set_debug_overlay(false);
+    }
+}
+
+#[test]
+fn test_marks_recorded_while_disabled_are_not_drawn() {
+    let lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    assert!(!debug_overlay());
+    mark(DebugMark::Layout, Rect::at(0, 0).of_size(4, 4));
+    drop(lock);
+
+    let _overlay = Overlay::on();
+    let mut img = RgbaImage::new(4, 4);
+    draw_overlay(&mut img);
+    assert!(img.pixels().all(|p| p[3] == 0));
+}
+
+#[test]
+fn test_overlay_draws_and_consumes_marks() {
+    let _overlay = Overlay::on();
+    mark(DebugMark::SafeArea, Rect::at(1, 1).of_size(4, 3));
+    let mut img = RgbaImage::new(8, 8);
+    draw_overlay(&mut img);
+    assert!(img.get_pixel(1, 1)[3] > 0);
+    assert_eq!(img.get_pixel(2, 2)[3], 0, "outline interior is untouched");
+
+    let mut again = RgbaImage::new(8, 8);
+    draw_overlay(&mut again);
+    assert!(again.pixels().all(|p| p[3] == 0));
+}
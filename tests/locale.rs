@@ -0,0 +1,57 @@
+//! The renderers' locale is process-wide, so tests that read or change it
+//! live in their own test binary and hold [`LOCK`] for their whole body.
+
+use std::sync::{Mutex, MutexGuard};
+
+use verandah_plugin_utils::prelude::*;
+use verandah_plugin_utils::units::{Pressure, format};
+
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// Take [`LOCK`] before reading or setting the locale.
+fn lock() -> MutexGuard<'static, ()> {
+    LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Sets the locale until dropped, restoring the default even if the test
+/// panics. Borrowing the [`lock`] guard keeps the lock held for as long.
+struct Scoped<'a> {
+    _lock: &'a MutexGuard<'static, ()>,
+}
+
+impl<'a> Scoped<'a> {
+    fn set(lock: &'a MutexGuard<'static, ()>, locale: Locale) -> Self {
+        set_locale(locale);
+        Scoped { _lock: lock }
+    }
+}
+
+impl Drop for Scoped<'_> {
+    fn drop(&mut self) {
+        set_locale(Locale::default());
+    }
+}
+
+#[test]
+fn test_set_locale() {
+    let lock = lock();
+    assert_eq!(locale(), Locale::POSIX);
+    let _locale = Scoped::set(&lock, Locale::FR_FR);
+    assert_eq!(locale(), Locale::FR_FR);
+}
+
+#[test]
+fn test_tick_labels_follow_locale() {
+    let lock = lock();
+    let _locale = Scoped::set(&lock, Locale::DE_DE);
+    let labels = NiceRange::with_max_ticks(0.0, 2500.0, 3).labels(TickFormat::Fixed(1));
+    assert_eq!(labels, vec!["0,0", "2.000,0", "4.000,0"]);
+    assert_eq!(TickFormat::Si.format(1500.0, 500.0), "1,5k");
+}
+
+#[test]
+fn test_format_units_follows_locale() {
+    let lock = lock();
+    let _locale = Scoped::set(&lock, Locale::DE_DE);
+    assert_eq!(format(1013.27, Pressure::Hectopascal, 1), "1.013,3 hPa");
+}